@@ -1,13 +1,14 @@
 //! Arda client and orchestrator for ledger-backed command emission, receipts, and replay.
 #![deny(missing_docs)]
 
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use anyhow::Context;
 use ed25519_dalek::{Signer, SigningKey};
 use ledger_core::{envelope_hash, signing, AppendError, AppendLog, MerkleReceipt, ReplayValidator};
 use ledger_spec::{
-    Envelope, EnvelopeBody, EnvelopeHeader, SchemaVersion, Timestamp, ValidationError,
+    ChannelState, Envelope, EnvelopeBody, EnvelopeHeader, SchemaVersion, Timestamp, ValidationError,
 };
 use ledger_transport::Transport;
 use parking_lot::RwLock;
@@ -40,7 +41,7 @@ pub struct ArdaOrchestrator {
     schema_version: SchemaVersion,
     channels: Arc<Vec<String>>,
     view_tx: broadcast::Sender<LedgerViewEntry>,
-    tail_cache: Arc<RwLock<Option<[u8; 32]>>>,
+    heads: Arc<RwLock<HashMap<String, ChannelState>>>,
 }
 
 impl ArdaOrchestrator {
@@ -63,7 +64,7 @@ impl ArdaOrchestrator {
             schema_version,
             channels: Arc::new(channels),
             view_tx: tx,
-            tail_cache: Arc::new(RwLock::new(None)),
+            heads: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -101,7 +102,7 @@ impl ArdaOrchestrator {
                 match rx.recv().await {
                     Ok(env) => {
                         let incoming_hash = envelope_hash(&env);
-                        if orchestrator.tail_hash() == Some(incoming_hash) {
+                        if orchestrator.channel_head(&env.header.channel) == Some(incoming_hash) {
                             continue;
                         }
                         match orchestrator.append_local(env) {
@@ -137,12 +138,12 @@ impl ArdaOrchestrator {
         if !self.channels.is_empty() && !self.channels.contains(&channel.to_string()) {
             anyhow::bail!("channel {channel} is not whitelisted for this orchestrator");
         }
-        let prev = self.tail_hash();
+        let prev = self.channel_head(channel);
         let body = EnvelopeBody {
             payload,
             payload_type: Some(payload_type.to_string()),
         };
-        let body_hash = ledger_spec::hash_body(&body);
+        let body_hash = ledger_spec::hash_body_for(&body, self.schema_version);
         let mut envelope = Envelope {
             header: EnvelopeHeader {
                 channel: channel.to_string(),
@@ -168,10 +169,33 @@ impl ArdaOrchestrator {
         Ok(entry)
     }
 
-    /// Verify the local log deterministically.
+    /// Verify the local log deterministically, one channel's chain at a time.
     pub fn replay(&self) -> Result<(), ValidationError> {
-        let all = self.log.read(0, self.log.len());
-        self.validator.validate_sequence(&all)
+        let mut chains: Vec<(String, Vec<Envelope>)> = Vec::new();
+        for env in self.log.read(0, self.log.len()) {
+            match chains
+                .iter_mut()
+                .find(|(channel, _)| *channel == env.header.channel)
+            {
+                Some((_, chain)) => chain.push(env),
+                None => chains.push((env.header.channel.clone(), vec![env])),
+            }
+        }
+        for (_, chain) in chains {
+            self.validator
+                .validate_sequence(&chain)
+                .map_err(|err| err.source)?;
+        }
+        Ok(())
+    }
+
+    /// Hash of the last envelope on `channel`, i.e. the `prev` for its next
+    /// command.
+    pub fn channel_head(&self, channel: &str) -> Option<[u8; 32]> {
+        self.heads
+            .read()
+            .get(channel)
+            .and_then(|state| state.last_hash)
     }
 
     /// Current length of the local log.
@@ -180,31 +204,28 @@ impl ArdaOrchestrator {
     }
 
     fn append_local(&self, env: Envelope) -> Result<LedgerViewEntry, AppendError> {
-        let index = self.log.append_with_index(env.clone(), &self.registry)?;
+        let mut heads = self.heads.write();
+        let chain = heads.get(&env.header.channel).cloned().unwrap_or_default();
+        let index = self
+            .log
+            .append_on_chain_with_index(env.clone(), &self.registry, &chain)?;
         let receipt = self
             .log
             .receipt_for(index)
             .expect("receipt should exist for appended envelope");
-        *self.tail_cache.write() = Some(envelope_hash(&env));
+        heads.insert(
+            env.header.channel.clone(),
+            ChannelState {
+                last_hash: Some(envelope_hash(&env)),
+                last_timestamp: Some(env.header.timestamp),
+            },
+        );
         Ok(LedgerViewEntry {
             index,
             envelope: env,
             receipt,
         })
     }
-
-    fn tail_hash(&self) -> Option<[u8; 32]> {
-        let cached = *self.tail_cache.read();
-        if cached.is_some() {
-            return cached;
-        }
-        let len = self.log.len();
-        if len == 0 {
-            return None;
-        }
-        let last = self.log.read(len - 1, 1);
-        last.first().map(|env| envelope_hash(env))
-    }
 }
 
 /// Simple UI event for rendering.
@@ -272,16 +293,24 @@ mod tests {
     use rand_core::OsRng;
 
     fn registry_for(pk: [u8; 32]) -> ledger_spec::ChannelRegistry {
+        registry_for_channels(pk, &["arda.commands"])
+    }
+
+    fn registry_for_channels(pk: [u8; 32], channels: &[&str]) -> ledger_spec::ChannelRegistry {
         let mut reg = ledger_spec::ChannelRegistry::new();
-        reg.upsert(ChannelSpec {
-            name: "arda.commands".into(),
-            policy: ChannelPolicy {
-                min_signers: 1,
-                allowed_signers: vec![pk],
-                require_attestations: false,
-                enforce_timestamp_ordering: true,
-            },
-        });
+        for channel in channels {
+            reg.upsert(ChannelSpec {
+                name: channel.to_string(),
+                policy: ChannelPolicy {
+                    min_signers: 1,
+                    allowed_signers: vec![pk],
+                    signer_weights: Vec::new(),
+                    require_attestations: false,
+                    enforce_timestamp_ordering: true,
+                },
+                schemas: Default::default(),
+            });
+        }
         reg
     }
 
@@ -312,4 +341,75 @@ mod tests {
         assert!(entry.receipt.verify());
         orchestrator.replay().unwrap();
     }
+    #[tokio::test]
+    async fn interleaved_channels_chain_independently() {
+        let signing_key = SigningKey::generate(&mut OsRng);
+        let channels = ["arda.commands", "arda.telemetry"];
+        let registry = registry_for_channels(signing_key.verifying_key().to_bytes(), &channels);
+        let transport: Arc<dyn Transport> =
+            Arc::new(InVmQueue::with_registry(registry.clone()).unwrap());
+        let orchestrator = ArdaOrchestrator::new(
+            transport.clone(),
+            registry.clone(),
+            signing_key.clone(),
+            channels.iter().map(|c| c.to_string()).collect(),
+            DEFAULT_SCHEMA_VERSION,
+        );
+
+        let mut entries = Vec::new();
+        for (timestamp, channel) in [
+            (1, channels[0]),
+            (2, channels[1]),
+            (3, channels[0]),
+            (4, channels[1]),
+        ] {
+            let entry = orchestrator
+                .submit_command(
+                    channel,
+                    serde_json::json!({"seq": timestamp}),
+                    "ea.event.v1",
+                    timestamp,
+                )
+                .await
+                .unwrap();
+            entries.push(entry);
+        }
+
+        // Each command links to the previous one on its own channel, not
+        // to the log tail.
+        assert_eq!(entries[0].envelope.header.prev, None);
+        assert_eq!(entries[1].envelope.header.prev, None);
+        assert_eq!(
+            entries[2].envelope.header.prev,
+            Some(envelope_hash(&entries[0].envelope))
+        );
+        assert_eq!(
+            entries[3].envelope.header.prev,
+            Some(envelope_hash(&entries[1].envelope))
+        );
+        assert_eq!(
+            orchestrator.channel_head(channels[1]),
+            Some(envelope_hash(&entries[3].envelope))
+        );
+        orchestrator.replay().unwrap();
+
+        // A second orchestrator hydrated from the transport resumes both chains.
+        let mirror = ArdaOrchestrator::new(
+            transport,
+            registry,
+            signing_key,
+            Vec::new(),
+            DEFAULT_SCHEMA_VERSION,
+        );
+        assert_eq!(mirror.hydrate(2).await.unwrap().len(), 4);
+        mirror.replay().unwrap();
+        let next = mirror
+            .submit_command(channels[0], serde_json::json!({"seq": 5}), "ea.event.v1", 5)
+            .await
+            .unwrap();
+        assert_eq!(
+            next.envelope.header.prev,
+            Some(envelope_hash(&entries[2].envelope))
+        );
+    }
 }
@@ -67,9 +67,11 @@ async fn main() -> anyhow::Result<()> {
         policy: ChannelPolicy {
             min_signers: 1,
             allowed_signers: vec![signing_key.verifying_key().to_bytes()],
+            signer_weights: Vec::new(),
             require_attestations: false,
             enforce_timestamp_ordering: true,
         },
+        schemas: Default::default(),
     });
 
     let channels = if cli.channel.is_empty() {
@@ -1,10 +1,41 @@
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
 use ea_lattice_ledger::*;
 use ed25519_dalek::SigningKey;
-use ledger_core::{signing, AppendLog};
+use ledger_core::{signing, AppendLog, CheckpointWriter, MerkleAccumulator};
 use ledger_spec::{ChannelRegistry, Envelope, EnvelopeBody, EnvelopeHeader};
-use ledger_transport::Loopback;
+use ledger_transport::{Loopback, Transport, TrustLevel};
 use rand_core::OsRng;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// System allocator that counts allocations, so scans can report how many
+/// they make alongside their timings.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+// SAFETY: every request is forwarded to the system allocator unchanged.
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Number of allocations `f` performs.
+fn allocations_during(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
 
 fn bench_generate_update(c: &mut Criterion) {
     c.bench_function("generate_update", |b| {
@@ -50,9 +81,11 @@ fn bench_append_latency(c: &mut Criterion) {
         policy: ledger_spec::ChannelPolicy {
             min_signers: 1,
             allowed_signers: vec![signer.verifying_key().to_bytes()],
+            signer_weights: Vec::new(),
             require_attestations: false,
             enforce_timestamp_ordering: true,
         },
+        schemas: Default::default(),
     });
     let mut env = Envelope {
         header: EnvelopeHeader {
@@ -91,9 +124,11 @@ fn bench_receipt_generation(c: &mut Criterion) {
         policy: ledger_spec::ChannelPolicy {
             min_signers: 1,
             allowed_signers: vec![signer.verifying_key().to_bytes()],
+            signer_weights: Vec::new(),
             require_attestations: false,
             enforce_timestamp_ordering: true,
         },
+        schemas: Default::default(),
     });
     let log = AppendLog::new();
     for ts in 0..32u64 {
@@ -125,6 +160,25 @@ fn bench_receipt_generation(c: &mut Criterion) {
     });
 }
 
+fn bench_incremental_merkle_root(c: &mut Criterion) {
+    let leaves: Vec<[u8; 32]> = (0u64..10_000)
+        .map(|i| *blake3::hash(&i.to_le_bytes()).as_bytes())
+        .collect();
+
+    let mut group = c.benchmark_group("merkle_accumulator");
+    group.sample_size(10);
+    group.bench_function("root_after_each_of_10k_appends", |b| {
+        b.iter(|| {
+            let mut acc = MerkleAccumulator::new();
+            for leaf in &leaves {
+                acc.push(*leaf);
+                black_box(acc.root());
+            }
+        });
+    });
+    group.finish();
+}
+
 fn bench_transport_loopback_latency(c: &mut Criterion) {
     let rt = tokio::runtime::Runtime::new().expect("runtime");
     let mut registry = ChannelRegistry::new();
@@ -134,9 +188,11 @@ fn bench_transport_loopback_latency(c: &mut Criterion) {
         policy: ledger_spec::ChannelPolicy {
             min_signers: 1,
             allowed_signers: vec![signer.verifying_key().to_bytes()],
+            signer_weights: Vec::new(),
             require_attestations: false,
             enforce_timestamp_ordering: true,
         },
+        schemas: Default::default(),
     });
     let transport = Loopback::new(registry.clone(), None).expect("loopback");
 
@@ -167,6 +223,136 @@ fn bench_transport_loopback_latency(c: &mut Criterion) {
     });
 }
 
+fn bench_pre_verified_ingest(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().expect("runtime");
+    let mut registry = ChannelRegistry::new();
+    let signer = SigningKey::generate(&mut OsRng);
+    registry.upsert(ledger_spec::ChannelSpec {
+        name: "bench".into(),
+        policy: ledger_spec::ChannelPolicy {
+            min_signers: 1,
+            allowed_signers: vec![signer.verifying_key().to_bytes()],
+            signer_weights: Vec::new(),
+            require_attestations: false,
+            enforce_timestamp_ordering: true,
+        },
+        schemas: Default::default(),
+    });
+    let source = AppendLog::new();
+    let mut prev = None;
+    for ts in 0..256u64 {
+        let mut env = Envelope {
+            header: EnvelopeHeader {
+                channel: "bench".into(),
+                version: 1,
+                prev,
+                body_hash: [0u8; 32],
+                timestamp: ts,
+            },
+            body: EnvelopeBody {
+                payload: serde_json::json!({"ts": ts}),
+                payload_type: Some("bench".into()),
+            },
+            signatures: Vec::new(),
+            attestations: Vec::new(),
+        };
+        env.header.body_hash = ledger_spec::hash_body(&env.body);
+        signing::sign_envelope(&mut env, &signer);
+        prev = Some(ledger_spec::envelope_hash(&env));
+        source.append(env, &registry).expect("append");
+    }
+    let checkpoint = CheckpointWriter::new()
+        .maybe_checkpoint(&source, source.len())
+        .expect("checkpoint");
+    let envs = source.read(0, source.len());
+
+    // PreVerified should beat Validated by the per-envelope signature and
+    // policy checks it skips.
+    let mut group = c.benchmark_group("ingest_256");
+    for trust in [TrustLevel::Validated, TrustLevel::PreVerified] {
+        group.bench_function(format!("{trust:?}"), |b| {
+            b.iter_batched(
+                || {
+                    let loopback =
+                        Loopback::with_log(registry.clone(), None, Arc::new(AppendLog::new()))
+                            .expect("loopback")
+                            .with_trust_level(trust);
+                    (loopback, envs.clone())
+                },
+                |(loopback, envs)| {
+                    rt.block_on(loopback.ingest(envs, &checkpoint))
+                        .expect("ingest")
+                },
+                BatchSize::SmallInput,
+            );
+        });
+    }
+    group.finish();
+}
+
+fn bench_log_scan(c: &mut Criterion) {
+    let mut registry = ChannelRegistry::new();
+    let signer = SigningKey::generate(&mut OsRng);
+    registry.upsert(ledger_spec::ChannelSpec {
+        name: "bench".into(),
+        policy: ledger_spec::ChannelPolicy {
+            min_signers: 1,
+            allowed_signers: vec![signer.verifying_key().to_bytes()],
+            signer_weights: Vec::new(),
+            require_attestations: false,
+            enforce_timestamp_ordering: true,
+        },
+        schemas: Default::default(),
+    });
+    let log = AppendLog::new();
+    let mut prev = None;
+    for ts in 0..10_000u64 {
+        let mut env = Envelope {
+            header: EnvelopeHeader {
+                channel: "bench".into(),
+                version: 1,
+                prev,
+                body_hash: [0u8; 32],
+                timestamp: ts,
+            },
+            body: EnvelopeBody {
+                payload: serde_json::json!({"ts": ts}),
+                payload_type: Some("bench".into()),
+            },
+            signatures: Vec::new(),
+            attestations: Vec::new(),
+        };
+        env.header.body_hash = ledger_spec::hash_body(&env.body);
+        signing::sign_envelope(&mut env, &signer);
+        prev = Some(ledger_spec::envelope_hash(&env));
+        log.append(env, &registry).expect("append");
+    }
+
+    // Both scans sum timestamps; `read` clones every envelope to do it.
+    let read_scan = || {
+        log.read(0, log.len())
+            .iter()
+            .map(|env| env.header.timestamp)
+            .sum::<u64>()
+    };
+    let fold_scan = || log.fold(0, log.len(), 0u64, |sum, _, env| sum + env.header.timestamp);
+    println!(
+        "log_scan_10k allocations: read={} fold={}",
+        allocations_during(|| {
+            black_box(read_scan());
+        }),
+        allocations_during(|| {
+            black_box(fold_scan());
+        })
+    );
+
+    let mut group = c.benchmark_group("log_scan_10k");
+    group.sample_size(10);
+    group.bench_function("read", |b| b.iter(|| black_box(read_scan())));
+    group.bench_function("fold", |b| b.iter(|| black_box(fold_scan())));
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_generate_update,
@@ -174,6 +360,9 @@ criterion_group!(
     bench_square_mod_n,
     bench_append_latency,
     bench_receipt_generation,
+    bench_incremental_merkle_root,
     bench_transport_loopback_latency,
+    bench_pre_verified_ingest,
+    bench_log_scan,
 );
 criterion_main!(benches);
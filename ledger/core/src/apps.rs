@@ -13,12 +13,12 @@ use ledger_spec::events::{
     AgencyEvent, Audience, AuditEvent, ContentRef, DataSensitivity, EventId, EventKind,
     LedgerEvent, LifecycleStage, MuscleEvent, PrivacyAction, PrivacyEvent,
 };
-use ledger_spec::{Attestation, Channel, Hash, PublicKey, SchemaVersion, Timestamp};
+use ledger_spec::{Attestation, Channel, Hash, PublicKey, SchemaVersion};
 
 use crate::brainstem::{Alert, AppendReceipt, Ledger, SliceQuery};
 use crate::lifecycle::MuscleLifecycleManager;
 use crate::signing;
-use crate::MerkleReceipt;
+use crate::{now_millis, MerkleReceipt};
 
 /// Errors returned by orchestrators.
 #[derive(Debug, thiserror::Error)]
@@ -561,14 +561,6 @@ impl AgencyAssistant {
     }
 }
 
-fn now_millis() -> Timestamp {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -585,9 +577,11 @@ mod tests {
             policy: ledger_spec::ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: Vec::new(),
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
         let ledger = Ledger::new(registry);
         let signer = SigningKey::generate(&mut OsRng);
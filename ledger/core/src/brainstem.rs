@@ -4,7 +4,7 @@
 //! This module intentionally keeps all data in-process without dynamic code
 //! loading or shared memory. All mutation is single-writer through `Ledger`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 use blake3::Hasher;
@@ -12,7 +12,19 @@ use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 
-use crate::{envelope_hash, hash_body, AppendLog, ChannelRegistry, Envelope, MerkleReceipt};
+use ledger_spec::events::ContentRef;
+use ledger_spec::EnvelopeBody;
+
+use crate::{
+    hash_body_for, verify_merkle_proof_in, AppendLog, ChannelRegistry, Envelope, LedgerInstance,
+    MerkleReceipt,
+};
+
+/// Payload type tag for logged bodies whose payload lives in the CAS store.
+///
+/// Informational only: the log records which entries are detached, so an
+/// inline body carrying this tag is never mistaken for a reference.
+pub const CAS_REF_PAYLOAD_TYPE: &str = "ea.cas.ref.v1";
 
 /// Content-addressed payload store (blake3 digest).
 #[derive(Debug, Default, Clone)]
@@ -39,6 +51,39 @@ impl ContentStore {
     pub fn get(&self, digest: &[u8; 32]) -> Option<Vec<u8>> {
         self.inner.read().get(digest).cloned()
     }
+
+    /// Number of stored blobs.
+    pub fn len(&self) -> usize {
+        self.inner.read().len()
+    }
+
+    /// Whether the store holds no blobs.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().is_empty()
+    }
+
+    /// Drop every blob whose digest is not in `live`.
+    pub fn gc(&self, live: &HashSet<[u8; 32]>) -> GcReport {
+        let mut report = GcReport::default();
+        self.inner.write().retain(|digest, bytes| {
+            if live.contains(digest) {
+                return true;
+            }
+            report.removed += 1;
+            report.bytes_reclaimed += bytes.len() as u64;
+            false
+        });
+        report
+    }
+}
+
+/// Outcome of a content store garbage collection pass.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GcReport {
+    /// Number of blobs removed.
+    pub removed: usize,
+    /// Total payload bytes released.
+    pub bytes_reclaimed: u64,
 }
 
 /// Indexes for domain/routing lookups.
@@ -46,6 +91,7 @@ impl ContentStore {
 pub struct DomainIndex {
     by_channel: Arc<RwLock<HashMap<String, Vec<usize>>>>,
     by_payload_type: Arc<RwLock<HashMap<String, Vec<usize>>>>,
+    by_timestamp: Arc<RwLock<HashMap<String, BTreeMap<u64, Vec<usize>>>>>,
 }
 
 impl DomainIndex {
@@ -62,6 +108,13 @@ impl DomainIndex {
                 .or_default()
                 .push(idx);
         }
+        self.by_timestamp
+            .write()
+            .entry(env.header.channel.clone())
+            .or_default()
+            .entry(env.header.timestamp)
+            .or_default()
+            .push(idx);
     }
 
     /// Fetch offsets for a channel.
@@ -72,6 +125,24 @@ impl DomainIndex {
             .cloned()
             .unwrap_or_default()
     }
+
+    /// Fetch offsets for a channel whose timestamps fall within
+    /// `start_ts..=end_ts`, ordered by timestamp then offset.
+    pub fn offsets_in_time_range(&self, channel: &str, start_ts: u64, end_ts: u64) -> Vec<usize> {
+        if start_ts > end_ts {
+            return Vec::new();
+        }
+        self.by_timestamp
+            .read()
+            .get(channel)
+            .map(|times| {
+                times
+                    .range(start_ts..=end_ts)
+                    .flat_map(|(_, offsets)| offsets.iter().copied())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 }
 
 /// Receipt bundle emitted after append.
@@ -83,11 +154,36 @@ pub struct AppendReceipt {
     pub merkle: MerkleReceipt,
 }
 
+impl AppendReceipt {
+    /// Check the inclusion proof for `index` against the receipt's root.
+    pub fn verify(&self) -> bool {
+        verify_merkle_proof_in(
+            &self.merkle.instance,
+            self.merkle.leaf,
+            self.index,
+            &self.merkle.sibling_hashes(),
+            self.merkle.root,
+        )
+    }
+}
+
 /// Alert emitted when validation fails.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum Alert {
     /// Validation failure on append.
     ValidationFailed(String),
+    /// A detached payload could not be rehydrated from the CAS store.
+    PayloadUnavailable {
+        /// Body hash of the missing or corrupt payload.
+        body_hash: [u8; 32],
+    },
+    /// An envelope in an all-or-nothing batch failed; nothing was written.
+    BatchRejected {
+        /// Position of the failing envelope within the batch.
+        index: usize,
+        /// Why the envelope was rejected.
+        reason: String,
+    },
     /// Query requested nonexistent slice.
     QueryOutOfRange {
         /// Starting offset requested by the caller.
@@ -97,6 +193,37 @@ pub enum Alert {
     },
 }
 
+/// Receiver for alerts as they are raised, e.g. to forward them to a
+/// webhook or emit them onto the lattice.
+pub trait AlertSink: Send + Sync {
+    /// Called once for every alert the ledger raises.
+    fn alert(&self, alert: &Alert);
+}
+
+/// Sink that discards every alert; the ledger's default.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NoopAlertSink;
+
+impl AlertSink for NoopAlertSink {
+    fn alert(&self, _alert: &Alert) {}
+}
+
+/// Alert sink slot shared by every clone of a ledger.
+#[derive(Clone)]
+struct SharedAlertSink(Arc<RwLock<Box<dyn AlertSink>>>);
+
+impl Default for SharedAlertSink {
+    fn default() -> Self {
+        Self(Arc::new(RwLock::new(Box::new(NoopAlertSink))))
+    }
+}
+
+impl std::fmt::Debug for SharedAlertSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedAlertSink")
+    }
+}
+
 /// Query slice request with proofs.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct SliceQuery {
@@ -126,19 +253,47 @@ pub struct Ledger {
     log: AppendLog,
     store: ContentStore,
     index: DomainIndex,
+    payload_threshold: Option<usize>,
+    alert_sink: SharedAlertSink,
 }
 
 impl Ledger {
     /// Create a new ledger with the given registry.
     pub fn new(registry: ChannelRegistry) -> Self {
+        Self::for_instance(registry, LedgerInstance::default())
+    }
+
+    /// Create a new ledger whose receipts are bound to `instance`.
+    pub fn for_instance(registry: ChannelRegistry, instance: LedgerInstance) -> Self {
         Self {
             registry,
-            log: AppendLog::new(),
+            log: AppendLog::for_instance(instance),
             store: ContentStore::default(),
             index: DomainIndex::default(),
+            payload_threshold: None,
+            alert_sink: SharedAlertSink::default(),
         }
     }
 
+    /// Deliver every subsequently raised alert to `sink` as well as
+    /// returning it to the caller. Applies to all clones of this ledger.
+    pub fn set_alert_sink(&self, sink: Box<dyn AlertSink>) {
+        *self.alert_sink.0.write() = sink;
+    }
+
+    /// Hand `alert` to the alert sink and return it for the caller.
+    fn raise(&self, alert: Alert) -> Alert {
+        self.alert_sink.0.read().alert(&alert);
+        alert
+    }
+
+    /// Log bodies larger than `threshold` bytes as a CAS reference instead of
+    /// inline; queries rehydrate them transparently.
+    pub fn with_payload_threshold(mut self, threshold: usize) -> Self {
+        self.payload_threshold = Some(threshold);
+        self
+    }
+
     /// Access the underlying content-addressed store for attaching blobs.
     pub fn content_store(&self) -> ContentStore {
         self.store.clone()
@@ -146,33 +301,38 @@ impl Ledger {
 
     /// Hash of the most recent envelope, if any.
     pub fn tail_hash(&self) -> Option<[u8; 32]> {
-        let len = self.log.len();
-        if len == 0 {
-            return None;
-        }
-        self.log.read(len - 1, 1).first().map(envelope_hash)
+        self.log.tail_hash()
     }
 
     /// Append an envelope, enforce invariants, and return a receipt.
     pub fn append(&self, env: Envelope) -> Result<AppendReceipt, Alert> {
-        let index = self
-            .log
-            .append_with_index(env.clone(), &self.registry)
-            .map_err(|err| {
-                error!("append validation failed: {err:?}");
-                Alert::ValidationFailed(err.to_string())
-            })?;
-
         // CAS the payload to allow optional retrieval while keeping log small.
         // Store the canonical body encoding under the deterministic digest.
         let body_bytes = serde_json::to_vec(&env.body).map_err(|err| {
-            Alert::ValidationFailed(format!("payload serialization failed: {err}"))
+            self.raise(Alert::ValidationFailed(format!(
+                "payload serialization failed: {err}"
+            )))
         })?;
-        let computed_body_hash = hash_body(&env.body);
+        let computed_body_hash = hash_body_for(&env.body, env.header.version);
         if computed_body_hash != env.header.body_hash {
-            return Err(Alert::ValidationFailed("body hash mismatch".into()));
+            return Err(self.raise(Alert::ValidationFailed("body hash mismatch".into())));
+        }
+        let detached = self.detached_body(&env, body_bytes.len());
+        let body_bytes = self.store_if_detached(&env, detached.is_some(), body_bytes);
+        let appended = match detached {
+            Some(stored_body) => {
+                self.log
+                    .append_detached_with_index(env.clone(), stored_body, &self.registry)
+            }
+            None => self.log.append_with_index(env.clone(), &self.registry),
+        };
+        let index = appended.map_err(|err| {
+            error!("append validation failed: {err:?}");
+            self.raise(Alert::ValidationFailed(err.to_string()))
+        })?;
+        if let Some(body_bytes) = body_bytes {
+            self.store.put_with_digest(env.header.body_hash, body_bytes);
         }
-        self.store.put_with_digest(env.header.body_hash, body_bytes);
 
         self.index.index(&env, index);
 
@@ -190,19 +350,97 @@ impl Ledger {
         })
     }
 
+    /// Append `envs` all-or-nothing and return one receipt per envelope.
+    ///
+    /// Every envelope is validated against the chain as it will stand after
+    /// the envelopes before it in the batch. If any fails, nothing is written
+    /// and the alert names its position in the batch.
+    pub fn append_all(&self, envs: Vec<Envelope>) -> Result<Vec<AppendReceipt>, Alert> {
+        let mut batch = Vec::with_capacity(envs.len());
+        let mut bodies = Vec::with_capacity(envs.len());
+        for (index, env) in envs.iter().enumerate() {
+            let body_bytes = serde_json::to_vec(&env.body).map_err(|err| {
+                self.raise(Alert::BatchRejected {
+                    index,
+                    reason: format!("payload serialization failed: {err}"),
+                })
+            })?;
+            if hash_body_for(&env.body, env.header.version) != env.header.body_hash {
+                return Err(self.raise(Alert::BatchRejected {
+                    index,
+                    reason: "body hash mismatch".into(),
+                }));
+            }
+            let detached = self.detached_body(env, body_bytes.len());
+            bodies.push(self.store_if_detached(env, detached.is_some(), body_bytes));
+            batch.push((env.clone(), detached));
+        }
+
+        let indexes = self
+            .log
+            .append_batch_with_index(batch, &self.registry)
+            .map_err(|(index, err)| {
+                error!("batch append rejected at {index}: {err:?}");
+                self.raise(Alert::BatchRejected {
+                    index,
+                    reason: err.to_string(),
+                })
+            })?;
+
+        let mut receipts = Vec::with_capacity(envs.len());
+        for ((env, body_bytes), index) in envs.iter().zip(bodies).zip(indexes) {
+            if let Some(body_bytes) = body_bytes {
+                self.store.put_with_digest(env.header.body_hash, body_bytes);
+            }
+            self.index.index(env, index);
+            let merkle = self
+                .log
+                .receipt_for(index)
+                .expect("receipt must exist immediately after append");
+            receipts.push(AppendReceipt { index, merkle });
+        }
+        info!("batch append ok count={}", receipts.len());
+        Ok(receipts)
+    }
+
+    /// CAS reference body to log in place of a body of `len` bytes, if it
+    /// exceeds the payload threshold.
+    fn detached_body(&self, env: &Envelope, len: usize) -> Option<EnvelopeBody> {
+        self.payload_threshold
+            .filter(|threshold| len > *threshold)
+            .map(|_| content_ref_body(env, len))
+    }
+
+    /// Store the payload of an envelope about to be logged detached, before
+    /// its reference is visible in the log; a rejected append leaves the
+    /// blob for gc. Inline bodies are handed back to store once logged.
+    fn store_if_detached(
+        &self,
+        env: &Envelope,
+        detached: bool,
+        body_bytes: Vec<u8>,
+    ) -> Option<Vec<u8>> {
+        if !detached {
+            return Some(body_bytes);
+        }
+        self.store.put_with_digest(env.header.body_hash, body_bytes);
+        None
+    }
+
     /// Query a bounded slice with receipts and optional payload blobs.
     pub fn query(&self, req: SliceQuery) -> Result<SliceResponse, Alert> {
         let entries = self.log.read(req.from, req.limit);
         if entries.is_empty() {
             warn!("query out of range from={} limit={}", req.from, req.limit);
-            return Err(Alert::QueryOutOfRange {
+            return Err(self.raise(Alert::QueryOutOfRange {
                 from: req.from,
                 limit: req.limit,
-            });
+            }));
         }
+        let mut envelopes = Vec::with_capacity(entries.len());
         let mut receipts = Vec::with_capacity(entries.len());
         let mut payloads = HashMap::new();
-        for (i, env) in entries.iter().enumerate() {
+        for (i, env) in entries.into_iter().enumerate() {
             let idx = req.from + i;
             if let Some(receipt) = self.log.receipt_for(idx) {
                 receipts.push(receipt);
@@ -212,24 +450,126 @@ impl Ledger {
                     payloads.insert(env.header.body_hash, bytes);
                 }
             }
+            envelopes.push(self.rehydrate(idx, env)?);
         }
         Ok(SliceResponse {
-            envelopes: entries,
+            envelopes,
             receipts,
             payloads,
         })
     }
 
+    /// Re-fetch the envelope with hash `env_hash` together with a receipt
+    /// proving its inclusion under the current root.
+    pub fn get_by_hash(&self, env_hash: &[u8; 32]) -> Option<(Envelope, AppendReceipt)> {
+        let index = self.log.position_of(env_hash)?;
+        let env = self.log.read(index, 1).into_iter().next()?;
+        let merkle = self.log.receipt_for(index)?;
+        let env = self.rehydrate(index, env).ok()?;
+        Some((env, AppendReceipt { index, merkle }))
+    }
+
+    /// Restore the body of the entry at `index` from the CAS store if it
+    /// was logged detached, checking it against the header's body hash.
+    fn rehydrate(&self, index: usize, mut env: Envelope) -> Result<Envelope, Alert> {
+        if !self.log.is_detached(index) {
+            return Ok(env);
+        }
+        let body_hash = env.header.body_hash;
+        let version = env.header.version;
+        let body = self
+            .store
+            .get(&body_hash)
+            .and_then(|bytes| serde_json::from_slice::<EnvelopeBody>(&bytes).ok())
+            .filter(|body| hash_body_for(body, version) == body_hash)
+            .ok_or_else(|| {
+                error!("detached payload unavailable body_hash={body_hash:x?}");
+                self.raise(Alert::PayloadUnavailable { body_hash })
+            })?;
+        env.body = body;
+        Ok(env)
+    }
+
+    /// Remove CAS payloads no longer referenced by any logged envelope,
+    /// either as its body or through a [`ContentRef`] in its payload.
+    pub fn gc_content_store(&self) -> GcReport {
+        let live = self
+            .log
+            .fold(0, self.log.len(), HashSet::new(), |mut live, index, env| {
+                live.insert(env.header.body_hash);
+                collect_content_refs(&env.body.payload, &mut live);
+                if self.log.is_detached(index) {
+                    // The detached body may reference attachments of its own
+                    let stored = self.store.get(&env.header.body_hash);
+                    if let Some(body) =
+                        stored.and_then(|bytes| serde_json::from_slice::<EnvelopeBody>(&bytes).ok())
+                    {
+                        collect_content_refs(&body.payload, &mut live);
+                    }
+                }
+                live
+            });
+        let report = self.store.gc(&live);
+        info!(
+            "content store gc removed={} bytes={}",
+            report.removed, report.bytes_reclaimed
+        );
+        report
+    }
+
     /// Fetch offsets for a channel (domain index).
     pub fn offsets_for_channel(&self, channel: &str) -> Vec<usize> {
         self.index.offsets_for_channel(channel)
     }
+
+    /// Fetch offsets for a channel within an inclusive timestamp window.
+    pub fn offsets_in_time_range(&self, channel: &str, start_ts: u64, end_ts: u64) -> Vec<usize> {
+        self.index.offsets_in_time_range(channel, start_ts, end_ts)
+    }
+}
+
+/// Add the hash of every [`ContentRef`] nested in `value` to `live`.
+fn collect_content_refs(value: &serde_json::Value, live: &mut HashSet<[u8; 32]>) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            if fields.contains_key("locator") {
+                if let Ok(reference) = serde_json::from_value::<ContentRef>(value.clone()) {
+                    live.insert(reference.hash);
+                    return;
+                }
+            }
+            for field in fields.values() {
+                collect_content_refs(field, live);
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                collect_content_refs(item, live);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn content_ref_body(env: &Envelope, len: usize) -> EnvelopeBody {
+    let hash = env.header.body_hash;
+    let hex: String = hash.iter().map(|b| format!("{b:02x}")).collect();
+    let reference = ContentRef {
+        locator: format!("cas:blake3:{hex}"),
+        hash,
+        media_type: env.body.payload_type.clone(),
+        bytes: Some(len as u64),
+    };
+    EnvelopeBody {
+        payload: serde_json::to_value(reference).expect("ContentRef serializes to JSON"),
+        payload_type: Some(CAS_REF_PAYLOAD_TYPE.into()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::envelope_hash;
+    use crate::{envelope_hash, hash_body};
     use ed25519_dalek::{Signer, SigningKey};
     use rand_core::OsRng;
 
@@ -240,9 +580,11 @@ mod tests {
             policy: ledger_spec::ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: vec![pk],
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
         reg
     }
@@ -282,8 +624,8 @@ mod tests {
 
         let (env1, _) = make_envelope(&sk, 1, None);
         let (env2, _) = make_envelope(&sk, 2, Some(envelope_hash(&env1)));
-        ledger.append(env1).expect("append 1");
-        ledger.append(env2).expect("append 2");
+        assert!(ledger.append(env1).expect("append 1").verify());
+        assert!(ledger.append(env2).expect("append 2").verify());
 
         let resp = ledger
             .query(SliceQuery {
@@ -298,6 +640,318 @@ mod tests {
         assert_eq!(resp.payloads.len(), 2);
     }
 
+    #[test]
+    fn get_by_hash_returns_entry_with_receipt() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger = Ledger::new(registry_with(sk.verifying_key().to_bytes()));
+
+        let mut prev = None;
+        let mut captured = None;
+        for ts in 1..=4 {
+            let (env, _) = make_envelope(&sk, ts, prev);
+            let hash = envelope_hash(&env);
+            let receipt = ledger.append(env.clone()).expect("append");
+            if ts == 2 {
+                captured = Some((hash, env, receipt));
+            }
+            prev = Some(hash);
+        }
+        let (hash, env, receipt) = captured.expect("captured receipt");
+
+        let (fetched, fetched_receipt) = ledger.get_by_hash(&hash).expect("indexed by hash");
+        assert_eq!(fetched, env);
+        assert_eq!(fetched_receipt.index, receipt.index);
+        assert_eq!(fetched_receipt.merkle.leaf, receipt.merkle.leaf);
+        assert!(fetched_receipt.verify());
+
+        assert!(ledger.get_by_hash(&[0u8; 32]).is_none());
+    }
+
+    #[test]
+    fn content_store_gc_drops_unreferenced_blobs() {
+        let store = ContentStore::default();
+        let a = store.put(b"alpha".to_vec());
+        let b = store.put(b"beta".to_vec());
+        let c = store.put(b"gamma!".to_vec());
+        let live: HashSet<[u8; 32]> = [a, b].into_iter().collect();
+
+        let report = store.gc(&live);
+        assert_eq!(
+            report,
+            GcReport {
+                removed: 1,
+                bytes_reclaimed: 6
+            }
+        );
+        assert!(store.get(&c).is_none());
+        assert_eq!(store.get(&a), Some(b"alpha".to_vec()));
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.gc(&live), GcReport::default());
+    }
+
+    #[test]
+    fn ledger_gc_keeps_logged_payloads() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger = Ledger::new(registry_with(sk.verifying_key().to_bytes()));
+        let (env, body_hash) = make_envelope(&sk, 1, None);
+        ledger.append(env).expect("append");
+        let orphan = ledger.content_store().put(vec![0u8; 16]);
+
+        let report = ledger.gc_content_store();
+        assert_eq!(report.removed, 1);
+        assert_eq!(report.bytes_reclaimed, 16);
+        assert!(ledger.content_store().get(&orphan).is_none());
+        assert!(ledger.content_store().get(&body_hash).is_some());
+    }
+
+    #[test]
+    fn ledger_gc_keeps_referenced_content() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger =
+            Ledger::new(registry_with(sk.verifying_key().to_bytes())).with_payload_threshold(512);
+        let store = ledger.content_store();
+        let inline = store.put(b"inline attachment".to_vec());
+        let nested = store.put(b"attachment of a detached body".to_vec());
+        let orphan = store.put(vec![0u8; 16]);
+
+        let reference = |hash: [u8; 32]| ContentRef {
+            locator: "cas:test".into(),
+            hash,
+            media_type: None,
+            bytes: None,
+        };
+        let (mut small, _) = make_envelope(&sk, 1, None);
+        small.body.payload = serde_json::json!({"file": {"content_ref": reference(inline)}});
+        small.header.body_hash = hash_body(&small.body);
+        small.signatures.clear();
+        crate::signing::sign_envelope(&mut small, &sk);
+        let (mut large, _) = make_envelope(&sk, 2, Some(envelope_hash(&small)));
+        large.body.payload = serde_json::json!({
+            "pad": "x".repeat(1024),
+            "versions": [{"content": reference(nested)}],
+        });
+        large.header.body_hash = hash_body(&large.body);
+        large.signatures.clear();
+        crate::signing::sign_envelope(&mut large, &sk);
+        ledger.append(small).expect("append small");
+        ledger.append(large).expect("append large");
+
+        let report = ledger.gc_content_store();
+        assert_eq!(report.removed, 1);
+        assert!(store.get(&orphan).is_none());
+        assert!(store.get(&inline).is_some());
+        assert!(store.get(&nested).is_some());
+    }
+
+    #[test]
+    fn domain_index_time_range_handles_out_of_order() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let index = DomainIndex::default();
+        for (idx, ts) in [50u64, 10, 40, 20, 30, 40].into_iter().enumerate() {
+            let (env, _) = make_envelope(&sk, ts, None);
+            index.index(&env, idx);
+        }
+        let (mut other, _) = make_envelope(&sk, 25, None);
+        other.header.channel = "other".into();
+        index.index(&other, 6);
+
+        assert_eq!(
+            index.offsets_in_time_range("test", 20, 40),
+            vec![3, 4, 2, 5]
+        );
+        assert_eq!(
+            index.offsets_in_time_range("test", 41, 49),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            index.offsets_in_time_range("test", 40, 20),
+            Vec::<usize>::new()
+        );
+        assert_eq!(index.offsets_in_time_range("other", 0, u64::MAX), vec![6]);
+        assert!(index
+            .offsets_in_time_range("missing", 0, u64::MAX)
+            .is_empty());
+    }
+
+    #[test]
+    fn ledger_time_range_query() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger = Ledger::new(registry_with(sk.verifying_key().to_bytes()));
+        let mut prev = None;
+        for ts in [5u64, 10, 15, 20] {
+            let (env, _) = make_envelope(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            ledger.append(env).expect("append");
+        }
+        assert_eq!(ledger.offsets_in_time_range("test", 10, 15), vec![1, 2]);
+    }
+
+    #[test]
+    fn large_payload_is_detached_and_rehydrated() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry_with(sk.verifying_key().to_bytes());
+        let ledger = Ledger::new(reg).with_payload_threshold(256);
+
+        let (small, _) = make_envelope(&sk, 1, None);
+        let (mut large, _) = make_envelope(&sk, 2, Some(envelope_hash(&small)));
+        large.body.payload = serde_json::json!({"blob": "x".repeat(4096)});
+        large.header.body_hash = hash_body(&large.body);
+        large.signatures.clear();
+        crate::signing::sign_envelope(&mut large, &sk);
+        let original = large.clone();
+
+        ledger.append(small.clone()).expect("append small");
+        let receipt = ledger.append(large).expect("append large");
+        assert!(receipt.verify());
+
+        let logged = ledger.log.read(0, 2);
+        assert_eq!(logged[0], small);
+        assert_eq!(
+            logged[1].body.payload_type.as_deref(),
+            Some(CAS_REF_PAYLOAD_TYPE)
+        );
+        assert_eq!(logged[1].header, original.header);
+        assert!(!ledger.log.is_detached(0) && ledger.log.is_detached(1));
+        assert!(!logged[1].body.payload.to_string().contains("xxxx"));
+        assert!(ledger
+            .content_store()
+            .get(&original.header.body_hash)
+            .is_some());
+
+        let resp = ledger
+            .query(SliceQuery {
+                from: 0,
+                limit: 2,
+                include_payloads: false,
+            })
+            .expect("query ok");
+        assert_eq!(resp.envelopes[1], original);
+        assert_eq!(
+            hash_body(&resp.envelopes[1].body),
+            original.header.body_hash
+        );
+        assert!(resp.receipts.iter().all(|r| r.verify()));
+        crate::ReplayValidator::new(ledger.registry.clone())
+            .validate_sequence(&resp.envelopes)
+            .expect("rehydrated envelopes replay cleanly");
+    }
+
+    #[test]
+    fn missing_detached_payload_raises_alert() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry_with(sk.verifying_key().to_bytes());
+        let ledger = Ledger::new(reg).with_payload_threshold(0);
+        let (env, body_hash) = make_envelope(&sk, 1, None);
+        ledger.append(env).expect("append");
+        ledger.content_store().gc(&HashSet::new());
+        let err = ledger
+            .query(SliceQuery {
+                from: 0,
+                limit: 1,
+                include_payloads: false,
+            })
+            .unwrap_err();
+        assert_eq!(err, Alert::PayloadUnavailable { body_hash });
+    }
+
+    #[test]
+    fn inline_body_tagged_as_cas_ref_is_not_rehydrated() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger = Ledger::new(registry_with(sk.verifying_key().to_bytes()));
+        let (mut env, _) = make_envelope(&sk, 1, None);
+        env.body.payload_type = Some(CAS_REF_PAYLOAD_TYPE.into());
+        env.header.body_hash = hash_body(&env.body);
+        env.signatures.clear();
+        crate::signing::sign_envelope(&mut env, &sk);
+        ledger.append(env.clone()).expect("append");
+
+        // Only entries the log recorded as detached are looked up in CAS
+        assert!(!ledger.log.is_detached(0));
+        ledger.content_store().gc(&HashSet::new());
+        let resp = ledger
+            .query(SliceQuery {
+                from: 0,
+                limit: 1,
+                include_payloads: false,
+            })
+            .expect("inline body needs no payload");
+        assert_eq!(resp.envelopes, vec![env]);
+    }
+
+    fn signed_chain(
+        sk: &SigningKey,
+        mut prev: Option<[u8; 32]>,
+        timestamps: &[u64],
+    ) -> Vec<Envelope> {
+        timestamps
+            .iter()
+            .map(|&ts| {
+                let (env, _) = make_envelope(sk, ts, prev);
+                prev = Some(envelope_hash(&env));
+                env
+            })
+            .collect()
+    }
+
+    #[test]
+    fn append_all_commits_valid_batch() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger = Ledger::new(registry_with(sk.verifying_key().to_bytes()));
+
+        let receipts = ledger
+            .append_all(signed_chain(&sk, None, &[1, 2, 3, 4]))
+            .expect("batch ok");
+        assert_eq!(
+            receipts.iter().map(|r| r.index).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+        assert!(receipts.iter().all(AppendReceipt::verify));
+        assert_eq!(ledger.offsets_for_channel("test"), vec![0, 1, 2, 3]);
+        assert_eq!(ledger.content_store().len(), 4);
+    }
+
+    #[test]
+    fn append_all_rejects_batch_without_partial_writes() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger = Ledger::new(registry_with(sk.verifying_key().to_bytes()));
+        let (first, _) = make_envelope(&sk, 1, None);
+        ledger.append(first.clone()).expect("append first");
+
+        let mut batch = signed_chain(&sk, Some(envelope_hash(&first)), &[2, 3, 4, 5]);
+        batch[2].signatures.clear();
+
+        let err = ledger.append_all(batch).unwrap_err();
+        assert!(matches!(err, Alert::BatchRejected { index: 2, .. }));
+        assert_eq!(ledger.log.len(), 1);
+        assert_eq!(ledger.tail_hash(), Some(envelope_hash(&first)));
+        assert_eq!(ledger.offsets_for_channel("test"), vec![0]);
+        assert_eq!(ledger.content_store().len(), 1);
+    }
+
+    #[derive(Default)]
+    struct RecordingSink(Arc<parking_lot::Mutex<Vec<Alert>>>);
+
+    impl AlertSink for RecordingSink {
+        fn alert(&self, alert: &Alert) {
+            self.0.lock().push(alert.clone());
+        }
+    }
+
+    #[test]
+    fn alert_sink_receives_body_hash_mismatch() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let ledger = Ledger::new(registry_with(sk.verifying_key().to_bytes()));
+        let recorded = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        ledger.set_alert_sink(Box::new(RecordingSink(recorded.clone())));
+
+        let (mut env, _) = make_envelope(&sk, 1, None);
+        env.body.payload = serde_json::json!({"ts": 2});
+        let err = ledger.clone().append(env).unwrap_err();
+
+        assert!(matches!(err, Alert::ValidationFailed(_)));
+        assert_eq!(*recorded.lock(), vec![err]);
+    }
+
     #[test]
     fn alert_on_invalid_append() {
         let sk = SigningKey::generate(&mut OsRng);
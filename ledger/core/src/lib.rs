@@ -2,9 +2,10 @@
 //! Merkle segmenter, checkpoint writer, and replay validator.
 #![deny(missing_docs)]
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs::{self, File, OpenOptions};
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -13,9 +14,9 @@ use blake3::Hasher;
 use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 
-pub use ledger_spec::{envelope_hash, hash_body};
+pub use ledger_spec::{envelope_hash, hash_body, hash_body_for};
 use ledger_spec::{
-    Attestation, ChannelRegistry, ChannelState, Envelope, Signature, ValidationError,
+    Attestation, ChannelRegistry, ChannelState, Envelope, EnvelopeBody, Signature, ValidationError,
 };
 
 /// Base application orchestrators (audit terminal, privacy analyzer, agency assistant).
@@ -30,6 +31,44 @@ pub mod policy;
 /// Append-only log identifier.
 pub type LogId = String;
 
+/// Ledger deployment a Merkle tree belongs to.
+///
+/// The id is bound into every leaf, so two instances logging identical
+/// envelopes still produce different roots and a receipt from one never
+/// verifies against the other. The default (empty) instance hashes exactly
+/// as logs did before instances existed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LedgerInstance(String);
+
+impl LedgerInstance {
+    /// Instance identified by a deployment or network id.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    /// The instance id; empty for the default instance.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Hasher for `label` under this instance's domain, `ea-ledger:<label>`
+    /// for the default instance.
+    fn hasher(&self, label: &[u8]) -> Hasher {
+        let mut hasher = Hasher::new();
+        if self.0.is_empty() {
+            hasher.update(b"ea-ledger:");
+        } else {
+            // Length-prefixed so no id can spell out another's label.
+            hasher.update(b"ea-ledger/");
+            hasher.update(&(self.0.len() as u64).to_le_bytes());
+            hasher.update(self.0.as_bytes());
+            hasher.update(b":");
+        }
+        hasher.update(label);
+        hasher
+    }
+}
+
 /// Errors emitted by append-only logs (validation + storage).
 #[derive(Debug, thiserror::Error)]
 pub enum AppendError {
@@ -51,34 +90,141 @@ pub trait AppendLogStorage: Send + Sync {
         env: Envelope,
         registry: &ChannelRegistry,
     ) -> Result<usize, AppendError>;
+    /// Append an envelope chained onto `chain` rather than the log tail and
+    /// return its index. Lets callers that interleave several channels in
+    /// one log keep each channel's `prev` linkage separate.
+    fn append_on_chain_with_index(
+        &self,
+        env: Envelope,
+        registry: &ChannelRegistry,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError>;
+    /// Append an envelope validated before it reached this log, e.g. one
+    /// replayed from a checkpoint-verified export, and return its index.
+    /// Only its body hash and linkage onto `chain` are checked; channel
+    /// policy is not re-run.
+    fn append_pre_verified_on_chain(
+        &self,
+        env: Envelope,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError>;
     /// Read a slice of envelopes.
     fn read(&self, offset: usize, limit: usize) -> Vec<Envelope>;
+    /// Visit up to `limit` envelopes from `offset` in log order, passing each
+    /// with its index. The built-in logs lend entries under their read lock
+    /// instead of cloning them, so `f` must not append to this log.
+    fn for_each(&self, offset: usize, limit: usize, f: &mut dyn FnMut(usize, &Envelope)) {
+        for (index, env) in (offset..).zip(self.read(offset, limit).iter()) {
+            f(index, env);
+        }
+    }
+    /// Fold up to `limit` envelopes from `offset` into an accumulator,
+    /// visiting them as [`AppendLogStorage::for_each`] does.
+    fn fold<B, F>(&self, offset: usize, limit: usize, init: B, mut f: F) -> B
+    where
+        Self: Sized,
+        F: FnMut(B, usize, &Envelope) -> B,
+    {
+        let mut acc = Some(init);
+        self.for_each(offset, limit, &mut |index, env| {
+            acc = acc.take().map(|acc| f(acc, index, env));
+        });
+        acc.expect("fold accumulator is restored after every step")
+    }
     /// Return the length.
     fn len(&self) -> usize;
+    /// Instance this log's Merkle tree is bound to.
+    fn instance(&self) -> LedgerInstance;
+    /// Hash of the last appended envelope, i.e. the `prev` for the next one.
+    fn tail_hash(&self) -> Option<[u8; 32]> {
+        let last = self.len().checked_sub(1)?;
+        self.read(last, 1).first().map(envelope_hash)
+    }
+    /// Index of the envelope whose hash is `env_hash`, if it is in the log.
+    fn position_of(&self, env_hash: &[u8; 32]) -> Option<usize> {
+        let mut found = None;
+        self.for_each(0, self.len(), &mut |index, env| {
+            if found.is_none() && envelope_hash(env) == *env_hash {
+                found = Some(index);
+            }
+        });
+        found
+    }
     /// Compute the Merkle root over current entries.
     fn merkle_root(&self) -> Option<[u8; 32]>;
     /// Produce a Merkle receipt for a specific log entry.
     fn receipt_for(&self, index: usize) -> Option<MerkleReceipt>;
+    /// Prove the log at `new_len` extends the log at `old_len`; see
+    /// [`verify_consistency`].
+    fn consistency_proof(&self, old_len: usize, new_len: usize) -> Vec<[u8; 32]>;
     /// Optional storage usage hint (in bytes) for health reporting.
     fn storage_usage_bytes(&self) -> Option<u64> {
         None
     }
 }
 
+/// Wall-clock time in milliseconds since the Unix epoch.
+pub(crate) fn now_millis() -> ledger_spec::Timestamp {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Merkle leaf for `env` appended after `prev_state`. The envelope is
+/// validated against `registry` as of `now` when one is given; without one
+/// it is only checked to extend the chain and to carry the body its header
+/// commits to, since checkpoints cover headers but not bodies.
+fn admit(
+    env: &Envelope,
+    registry: Option<&ChannelRegistry>,
+    prev_state: &ChannelState,
+    now: ledger_spec::Timestamp,
+) -> Result<[u8; 32], AppendError> {
+    let Some(registry) = registry else {
+        if hash_body_for(&env.body, env.header.version) != env.header.body_hash {
+            return Err(ValidationError::BodyHashMismatch.into());
+        }
+        if env.header.prev != prev_state.last_hash {
+            return Err(ValidationError::ChainMismatch.into());
+        }
+        return Ok(envelope_hash(env));
+    };
+    let state = ledger_spec::validate_envelope_at(env, registry, prev_state, now)?;
+    Ok(state.last_hash.unwrap_or_else(|| envelope_hash(env)))
+}
+
 /// In-memory append-only log with hash chaining and Merkle checkpoints.
 #[derive(Debug, Default, Clone)]
 pub struct AppendLog {
     entries: Arc<RwLock<Vec<Envelope>>>,
+    tree: Arc<RwLock<MerkleAccumulator>>,
+    positions: Arc<RwLock<HashMap<[u8; 32], usize>>>,
+    detached: Arc<RwLock<HashSet<usize>>>,
 }
 
 impl AppendLog {
     /// Create a new empty log.
     pub fn new() -> Self {
+        Self::for_instance(LedgerInstance::default())
+    }
+
+    /// Create a new empty log whose Merkle tree is bound to `instance`.
+    pub fn for_instance(instance: LedgerInstance) -> Self {
         Self {
             entries: Arc::new(RwLock::new(Vec::new())),
+            tree: Arc::new(RwLock::new(MerkleAccumulator::for_instance(instance))),
+            positions: Arc::new(RwLock::new(HashMap::new())),
+            detached: Arc::new(RwLock::new(HashSet::new())),
         }
     }
 
+    /// Instance this log's Merkle tree is bound to.
+    pub fn instance(&self) -> LedgerInstance {
+        self.tree.read().instance.clone()
+    }
+
     /// Append an envelope after validation.
     pub fn append(&self, env: Envelope, registry: &ChannelRegistry) -> Result<(), AppendError> {
         self.append_with_index(env, registry).map(|_| ())
@@ -87,28 +233,127 @@ impl AppendLog {
     fn validate_and_append(
         &self,
         mut env: Envelope,
-        registry: &ChannelRegistry,
+        registry: Option<&ChannelRegistry>,
+        stored_body: Option<EnvelopeBody>,
+        chain: Option<&ChannelState>,
     ) -> Result<usize, AppendError> {
         let mut entries = self.entries.write();
-        let prev_hash = entries.last().map(envelope_hash);
-        if env.header.prev.is_none() {
-            env.header.prev = prev_hash;
-        }
-        let prev_state = ChannelState {
-            last_hash: prev_hash,
+        let prev_state = chain.cloned().unwrap_or_else(|| ChannelState {
+            last_hash: entries.last().map(envelope_hash),
             last_timestamp: entries.last().map(|e| e.header.timestamp),
-        };
-        let _ = ledger_spec::validate_envelope(&env, registry, &prev_state)?;
+        });
+        // Per-channel callers chain against a head they supplied, so an
+        // unset `prev` there is a mismatch to re-sign, not a gap to fill.
+        if chain.is_none() && env.header.prev.is_none() {
+            env.header.prev = prev_state.last_hash;
+        }
+        let leaf = admit(&env, registry, &prev_state, now_millis())?;
         let index = entries.len();
+        if let Some(body) = stored_body {
+            env.body = body;
+            self.detached.write().insert(index);
+        }
         entries.push(env);
+        self.tree.write().push(leaf);
+        self.positions.write().entry(leaf).or_insert(index);
         Ok(index)
     }
 
+    /// Validate the full envelope but log it with `stored_body` in place of
+    /// its body. The header (and so the envelope hash) is unchanged; callers
+    /// are responsible for keeping the original body retrievable.
+    pub(crate) fn append_detached_with_index(
+        &self,
+        env: Envelope,
+        stored_body: EnvelopeBody,
+        registry: &ChannelRegistry,
+    ) -> Result<usize, AppendError> {
+        self.traced_append(env, Some(registry), Some(stored_body), None)
+    }
+
+    /// Validate each `(envelope, stored_body)` pair against the chain as it
+    /// will stand after the ones before it, then log them all under one lock.
+    /// Nothing is written unless the whole batch validates; the error carries
+    /// the batch position of the first envelope that failed.
+    pub(crate) fn append_batch_with_index(
+        &self,
+        batch: Vec<(Envelope, Option<EnvelopeBody>)>,
+        registry: &ChannelRegistry,
+    ) -> Result<Range<usize>, (usize, AppendError)> {
+        let mut entries = self.entries.write();
+        let mut prev_state = ChannelState {
+            last_hash: entries.last().map(envelope_hash),
+            last_timestamp: entries.last().map(|e| e.header.timestamp),
+        };
+        let now = now_millis();
+        let mut staged = Vec::with_capacity(batch.len());
+        for (position, (mut env, stored_body)) in batch.into_iter().enumerate() {
+            if env.header.prev.is_none() {
+                env.header.prev = prev_state.last_hash;
+            }
+            let leaf =
+                admit(&env, Some(registry), &prev_state, now).map_err(|err| (position, err))?;
+            prev_state = ChannelState {
+                last_hash: Some(envelope_hash(&env)),
+                last_timestamp: Some(env.header.timestamp),
+            };
+            let detached = stored_body.is_some();
+            if let Some(body) = stored_body {
+                env.body = body;
+            }
+            staged.push((env, leaf, detached));
+        }
+
+        let start = entries.len();
+        let mut tree = self.tree.write();
+        let mut positions = self.positions.write();
+        let mut detached_entries = self.detached.write();
+        for (env, leaf, detached) in staged {
+            positions.entry(leaf).or_insert(entries.len());
+            if detached {
+                detached_entries.insert(entries.len());
+            }
+            entries.push(env);
+            tree.push(leaf);
+        }
+        Ok(start..entries.len())
+    }
+
     /// Append an envelope and return its log index once validated.
     pub fn append_with_index(
         &self,
         env: Envelope,
         registry: &ChannelRegistry,
+    ) -> Result<usize, AppendError> {
+        self.traced_append(env, Some(registry), None, None)
+    }
+
+    /// Append an envelope chained onto `chain` instead of the log tail.
+    pub fn append_on_chain_with_index(
+        &self,
+        env: Envelope,
+        registry: &ChannelRegistry,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError> {
+        self.traced_append(env, Some(registry), None, Some(chain))
+    }
+
+    /// Append an envelope validated elsewhere onto `chain`, checking only
+    /// its linkage.
+    pub fn append_pre_verified_on_chain(
+        &self,
+        env: Envelope,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError> {
+        self.traced_append(env, None, None, Some(chain))
+    }
+
+    fn traced_append(
+        &self,
+        env: Envelope,
+        registry: Option<&ChannelRegistry>,
+        stored_body: Option<EnvelopeBody>,
+        chain: Option<&ChannelState>,
     ) -> Result<usize, AppendError> {
         let span = tracing::info_span!(
             "append_log",
@@ -118,7 +363,7 @@ impl AppendLog {
         );
         let _guard = span.enter();
         let start = std::time::Instant::now();
-        let res = self.validate_and_append(env, registry);
+        let res = self.validate_and_append(env, registry, stored_body, chain);
         let elapsed = start.elapsed().as_millis() as u64;
         span.record("latency_ms", &elapsed);
         match &res {
@@ -149,29 +394,70 @@ impl AppendLog {
         out
     }
 
+    /// Visit up to `limit` envelopes from `offset` by reference, passing
+    /// each with its index.
+    ///
+    /// The read lock is held for the whole scan, so `f` must not append to
+    /// this log.
+    pub fn for_each(&self, offset: usize, limit: usize, mut f: impl FnMut(usize, &Envelope)) {
+        let entries = self.entries.read();
+        for (index, env) in entries.iter().enumerate().skip(offset).take(limit) {
+            f(index, env);
+        }
+    }
+
+    /// Fold up to `limit` envelopes from `offset` into an accumulator
+    /// without cloning them; the same locking rules as [`Self::for_each`] apply.
+    pub fn fold<B>(
+        &self,
+        offset: usize,
+        limit: usize,
+        init: B,
+        mut f: impl FnMut(B, usize, &Envelope) -> B,
+    ) -> B {
+        let entries = self.entries.read();
+        entries
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(limit)
+            .fold(init, |acc, (index, env)| f(acc, index, env))
+    }
+
     /// Return the length.
     pub fn len(&self) -> usize {
         self.entries.read().len()
     }
 
-    /// Compute a Merkle root over current entries.
+    /// Return the hash of the last appended envelope.
+    pub fn tail_hash(&self) -> Option<[u8; 32]> {
+        self.entries.read().last().map(envelope_hash)
+    }
+
+    /// Return the index of the envelope hashing to `env_hash`.
+    pub fn position_of(&self, env_hash: &[u8; 32]) -> Option<usize> {
+        self.positions.read().get(env_hash).copied()
+    }
+
+    /// Whether the entry at `index` was logged with a stored body in place
+    /// of its own.
+    pub(crate) fn is_detached(&self, index: usize) -> bool {
+        self.detached.read().contains(&index)
+    }
+
+    /// Return the Merkle root over current entries.
     pub fn merkle_root(&self) -> Option<[u8; 32]> {
-        let entries = self.entries.read();
-        if entries.is_empty() {
-            return None;
-        }
-        let leaves: Vec<[u8; 32]> = entries.iter().map(envelope_hash).collect();
-        compute_merkle_root(&leaves)
+        self.tree.read().root()
     }
 
     /// Produce a Merkle receipt for a specific log entry.
     pub fn receipt_for(&self, index: usize) -> Option<MerkleReceipt> {
-        let entries = self.entries.read();
-        if index >= entries.len() {
-            return None;
-        }
-        let leaves: Vec<[u8; 32]> = entries.iter().map(envelope_hash).collect();
-        MerkleReceipt::from_leaves(&leaves, index)
+        self.tree.read().receipt(index)
+    }
+
+    /// Prove the log at `new_len` is an append-only extension of `old_len`.
+    pub fn consistency_proof(&self, old_len: usize, new_len: usize) -> Vec<[u8; 32]> {
+        self.tree.read().consistency_proof(old_len, new_len)
     }
 
     /// Estimate storage usage in bytes (approximate based on entry count).
@@ -198,14 +484,54 @@ impl AppendLogStorage for AppendLog {
         AppendLog::append_with_index(self, env, registry)
     }
 
+    fn append_on_chain_with_index(
+        &self,
+        env: Envelope,
+        registry: &ChannelRegistry,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError> {
+        AppendLog::append_on_chain_with_index(self, env, registry, chain)
+    }
+
+    fn append_pre_verified_on_chain(
+        &self,
+        env: Envelope,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError> {
+        AppendLog::append_pre_verified_on_chain(self, env, chain)
+    }
+
     fn read(&self, offset: usize, limit: usize) -> Vec<Envelope> {
         AppendLog::read(self, offset, limit)
     }
 
+    fn for_each(&self, offset: usize, limit: usize, f: &mut dyn FnMut(usize, &Envelope)) {
+        AppendLog::for_each(self, offset, limit, f);
+    }
+
+    fn fold<B, F>(&self, offset: usize, limit: usize, init: B, f: F) -> B
+    where
+        F: FnMut(B, usize, &Envelope) -> B,
+    {
+        AppendLog::fold(self, offset, limit, init, f)
+    }
+
     fn len(&self) -> usize {
         AppendLog::len(self)
     }
 
+    fn instance(&self) -> LedgerInstance {
+        AppendLog::instance(self)
+    }
+
+    fn tail_hash(&self) -> Option<[u8; 32]> {
+        AppendLog::tail_hash(self)
+    }
+
+    fn position_of(&self, env_hash: &[u8; 32]) -> Option<usize> {
+        AppendLog::position_of(self, env_hash)
+    }
+
     fn merkle_root(&self) -> Option<[u8; 32]> {
         AppendLog::merkle_root(self)
     }
@@ -214,20 +540,34 @@ impl AppendLogStorage for AppendLog {
         AppendLog::receipt_for(self, index)
     }
 
+    fn consistency_proof(&self, old_len: usize, new_len: usize) -> Vec<[u8; 32]> {
+        AppendLog::consistency_proof(self, old_len, new_len)
+    }
+
     fn storage_usage_bytes(&self) -> Option<u64> {
         Some(0)
     }
 }
 
+/// Merkle leaf scheme recorded in persisted metadata.
+///
+/// - 0: envelope hashes are used as leaves directly.
+/// - 1: envelope hashes are bound to their index (`ea-ledger:leaf`).
+const MERKLE_LEAF_SCHEME: u32 = 1;
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
 struct PersistentMetadata {
     length: usize,
     root: Option<[u8; 32]>,
+    #[serde(default)]
+    leaf_scheme: u32,
 }
 
 #[derive(Debug)]
 struct PersistentState {
     entries: Vec<Envelope>,
+    tree: MerkleAccumulator,
+    positions: HashMap<[u8; 32], usize>,
     wal_entries: usize,
 }
 
@@ -235,7 +575,8 @@ impl PersistentMetadata {
     fn from_state(state: &PersistentState) -> Self {
         Self {
             length: state.entries.len(),
-            root: merkle_root_for(&state.entries),
+            root: state.tree.root(),
+            leaf_scheme: MERKLE_LEAF_SCHEME,
         }
     }
 }
@@ -271,6 +612,19 @@ impl PersistentAppendLog {
     pub fn open_with_segment_size<P: AsRef<Path>>(
         dir: P,
         segment_size: usize,
+    ) -> Result<Self, AppendError> {
+        Self::open_for_instance(dir, segment_size, LedgerInstance::default())
+    }
+
+    /// Open (or create) a persistent log at `dir` whose Merkle tree is
+    /// bound to `instance`.
+    ///
+    /// Reopening a log under a different instance fails recovery, as its
+    /// recomputed root no longer matches the persisted one.
+    pub fn open_for_instance<P: AsRef<Path>>(
+        dir: P,
+        segment_size: usize,
+        instance: LedgerInstance,
     ) -> Result<Self, AppendError> {
         let dir = dir.as_ref();
         let segment_size = segment_size.max(1);
@@ -283,12 +637,27 @@ impl PersistentAppendLog {
         let mut entries = read_records(&segments_path)?;
         let wal_count = wal_entries.len();
         entries.extend(wal_entries);
+        let leaves: Vec<[u8; 32]> = entries.iter().map(envelope_hash).collect();
+        let mut tree = MerkleAccumulator::for_instance(instance);
+        for leaf in &leaves {
+            tree.push(*leaf);
+        }
+        let mut positions = HashMap::with_capacity(leaves.len());
+        for (index, leaf) in leaves.into_iter().enumerate() {
+            positions.entry(leaf).or_insert(index);
+        }
         let current_meta = PersistentMetadata {
             length: entries.len(),
-            root: merkle_root_for(&entries),
+            root: tree.root(),
+            leaf_scheme: MERKLE_LEAF_SCHEME,
         };
         if let Some(on_disk) = read_metadata_file(&meta_path) {
-            if on_disk != current_meta {
+            // Roots written under an older leaf scheme are not comparable;
+            // the length still is, and the metadata is rewritten below.
+            let mismatch = on_disk.length != current_meta.length
+                || (on_disk.leaf_scheme == current_meta.leaf_scheme
+                    && on_disk.root != current_meta.root);
+            if mismatch {
                 return Err(anyhow::anyhow!("persistent log metadata mismatch during recovery").into());
             }
         }
@@ -312,6 +681,8 @@ impl PersistentAppendLog {
         let log = Self {
             state: Arc::new(RwLock::new(PersistentState {
                 entries,
+                tree,
+                positions,
                 wal_entries: wal_count,
             })),
             wal,
@@ -399,17 +770,14 @@ impl PersistentAppendLog {
     fn metadata(&self) -> Option<PersistentMetadata> {
         read_metadata_file(&self.meta_path)
     }
-}
 
-impl AppendLogStorage for PersistentAppendLog {
-    fn append(&self, env: Envelope, registry: &ChannelRegistry) -> Result<(), AppendError> {
-        self.append_with_index(env, registry).map(|_| ())
-    }
-
-    fn append_with_index(
+    /// Validate against `chain` (the log tail when `None`), then write
+    /// through the WAL. Without a registry only chain linkage is checked.
+    fn append_chained(
         &self,
         mut env: Envelope,
-        registry: &ChannelRegistry,
+        registry: Option<&ChannelRegistry>,
+        chain: Option<&ChannelState>,
     ) -> Result<usize, AppendError> {
         let span = tracing::info_span!(
             "append_persistent_log",
@@ -420,23 +788,23 @@ impl AppendLogStorage for PersistentAppendLog {
         let _guard = span.enter();
         let start = std::time::Instant::now();
         let mut state = self.state.write();
-        let prev_hash = state.entries.last().map(envelope_hash);
-        if env.header.prev.is_none() {
-            env.header.prev = prev_hash;
-        }
-        let prev_state = ChannelState {
-            last_hash: prev_hash,
+        let prev_state = chain.cloned().unwrap_or_else(|| ChannelState {
+            last_hash: state.entries.last().map(envelope_hash),
             last_timestamp: state.entries.last().map(|e| e.header.timestamp),
-        };
-        let _ = ledger_spec::validate_envelope(&env, registry, &prev_state)?;
+        });
+        // Per-channel callers chain against a head they supplied, so an
+        // unset `prev` there is a mismatch to re-sign, not a gap to fill.
+        if chain.is_none() && env.header.prev.is_none() {
+            env.header.prev = prev_state.last_hash;
+        }
+        let leaf = admit(&env, registry, &prev_state, now_millis())?;
         let index = state.entries.len();
         self.write_wal(&env)?;
         state.entries.push(env);
+        state.tree.push(leaf);
+        state.positions.entry(leaf).or_insert(index);
         state.wal_entries += 1;
-        let meta = PersistentMetadata {
-            length: state.entries.len(),
-            root: merkle_root_for(&state.entries),
-        };
+        let meta = PersistentMetadata::from_state(&state);
         drop(state);
         self.persist_metadata(&meta)?;
         if meta.length % self.segment_size == 0 {
@@ -448,6 +816,37 @@ impl AppendLogStorage for PersistentAppendLog {
         tracing::debug!("append committed to wal");
         Ok(index)
     }
+}
+
+impl AppendLogStorage for PersistentAppendLog {
+    fn append(&self, env: Envelope, registry: &ChannelRegistry) -> Result<(), AppendError> {
+        self.append_with_index(env, registry).map(|_| ())
+    }
+
+    fn append_with_index(
+        &self,
+        env: Envelope,
+        registry: &ChannelRegistry,
+    ) -> Result<usize, AppendError> {
+        self.append_chained(env, Some(registry), None)
+    }
+
+    fn append_on_chain_with_index(
+        &self,
+        env: Envelope,
+        registry: &ChannelRegistry,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError> {
+        self.append_chained(env, Some(registry), Some(chain))
+    }
+
+    fn append_pre_verified_on_chain(
+        &self,
+        env: Envelope,
+        chain: &ChannelState,
+    ) -> Result<usize, AppendError> {
+        self.append_chained(env, None, Some(chain))
+    }
 
     fn read(&self, offset: usize, limit: usize) -> Vec<Envelope> {
         let span = tracing::info_span!(
@@ -472,22 +871,39 @@ impl AppendLogStorage for PersistentAppendLog {
         out
     }
 
+    fn for_each(&self, offset: usize, limit: usize, f: &mut dyn FnMut(usize, &Envelope)) {
+        let state = self.state.read();
+        for (index, env) in state.entries.iter().enumerate().skip(offset).take(limit) {
+            f(index, env);
+        }
+    }
+
     fn len(&self) -> usize {
         self.state.read().entries.len()
     }
 
+    fn instance(&self) -> LedgerInstance {
+        self.state.read().tree.instance.clone()
+    }
+
+    fn tail_hash(&self) -> Option<[u8; 32]> {
+        self.state.read().entries.last().map(envelope_hash)
+    }
+
+    fn position_of(&self, env_hash: &[u8; 32]) -> Option<usize> {
+        self.state.read().positions.get(env_hash).copied()
+    }
+
     fn merkle_root(&self) -> Option<[u8; 32]> {
-        let state = self.state.read();
-        merkle_root_for(&state.entries)
+        self.state.read().tree.root()
     }
 
     fn receipt_for(&self, index: usize) -> Option<MerkleReceipt> {
-        let state = self.state.read();
-        if index >= state.entries.len() {
-            return None;
-        }
-        let leaves: Vec<[u8; 32]> = state.entries.iter().map(envelope_hash).collect();
-        MerkleReceipt::from_leaves(&leaves, index)
+        self.state.read().tree.receipt(index)
+    }
+
+    fn consistency_proof(&self, old_len: usize, new_len: usize) -> Vec<[u8; 32]> {
+        self.state.read().tree.consistency_proof(old_len, new_len)
     }
 
     fn storage_usage_bytes(&self) -> Option<u64> {
@@ -548,30 +964,103 @@ pub struct Checkpoint {
     pub length: usize,
     /// Merkle root.
     pub root: [u8; 32],
+    /// Hash of the preceding checkpoint in the chain, if any.
+    #[serde(default)]
+    pub prev: Option<[u8; 32]>,
+}
+
+impl Checkpoint {
+    /// Domain-separated hash committing to length, root, and predecessor.
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Hasher::new();
+        hasher.update(b"ea-ledger:checkpoint");
+        hasher.update(&(self.length as u64).to_le_bytes());
+        hasher.update(&self.root);
+        if let Some(prev) = self.prev {
+            hasher.update(&prev);
+        }
+        *hasher.finalize().as_bytes()
+    }
 }
 
-/// Checkpoint writer produces periodic checkpoints.
+/// Verify that a checkpoint chain links each entry to its predecessor and
+/// that log lengths strictly increase.
+pub fn verify_checkpoint_chain(chain: &[Checkpoint]) -> bool {
+    let mut prev: Option<&Checkpoint> = None;
+    for cp in chain {
+        let linked = match prev {
+            Some(p) => cp.prev == Some(p.hash()) && cp.length > p.length,
+            None => cp.prev.is_none(),
+        };
+        if !linked {
+            return false;
+        }
+        prev = Some(cp);
+    }
+    true
+}
+
+/// Check that `envs` is exactly the log `checkpoint` was taken over: same
+/// length and the same Merkle root, with leaves bound to `instance`.
+pub fn checkpoint_covers(
+    envs: &[Envelope],
+    checkpoint: &Checkpoint,
+    instance: &LedgerInstance,
+) -> bool {
+    if envs.len() != checkpoint.length {
+        return false;
+    }
+    let mut tree = MerkleAccumulator::for_instance(instance.clone());
+    for env in envs {
+        tree.push(envelope_hash(env));
+    }
+    tree.root() == Some(checkpoint.root)
+}
+
+/// Checkpoint writer produces periodic checkpoints and retains them as a
+/// hash-linked chain.
 #[derive(Debug, Default)]
 pub struct CheckpointWriter {
-    last_len: usize,
+    chain: Vec<Checkpoint>,
 }
 
 impl CheckpointWriter {
     /// Create new writer.
     pub fn new() -> Self {
-        Self { last_len: 0 }
+        Self { chain: Vec::new() }
+    }
+
+    /// Resume from a previously persisted chain.
+    pub fn from_chain(chain: Vec<Checkpoint>) -> Self {
+        Self { chain }
     }
 
     /// Emit a checkpoint if log advanced by at least `interval`.
     pub fn maybe_checkpoint(&mut self, log: &AppendLog, interval: usize) -> Option<Checkpoint> {
         let len = log.len();
-        if len >= self.last_len + interval {
+        let last_len = self.chain.last().map(|cp| cp.length).unwrap_or(0);
+        if len >= last_len + interval {
             let root = log.merkle_root()?;
-            self.last_len = len;
-            return Some(Checkpoint { length: len, root });
+            let cp = Checkpoint {
+                length: len,
+                root,
+                prev: self.chain.last().map(Checkpoint::hash),
+            };
+            self.chain.push(cp.clone());
+            return Some(cp);
         }
         None
     }
+
+    /// Checkpoints emitted so far, oldest first.
+    pub fn checkpoints(&self) -> &[Checkpoint] {
+        &self.chain
+    }
+
+    /// Confirm the retained chain is linked and monotonic.
+    pub fn verify_chain(&self) -> bool {
+        verify_checkpoint_chain(&self.chain)
+    }
 }
 
 /// Replay validator detects tampering or reordering.
@@ -587,13 +1076,79 @@ impl ReplayValidator {
     }
 
     /// Validate a sequence of envelopes starting from empty state.
-    pub fn validate_sequence(&self, seq: &[Envelope]) -> Result<(), ValidationError> {
+    pub fn validate_sequence(&self, seq: &[Envelope]) -> Result<(), ReplaySequenceError> {
         let mut state = ChannelState::default();
-        for env in seq {
-            state = ledger_spec::validate_envelope(env, &self.registry, &state)?;
+        for (index, env) in seq.iter().enumerate() {
+            state = self.validate_at(index, env, &state)?;
         }
         Ok(())
     }
+
+    /// Validate envelopes pulled one at a time from `iter`, starting from
+    /// empty state, and return how many were validated.
+    ///
+    /// Only the running [`ChannelState`] is retained between envelopes, so
+    /// memory stays flat however long the stream is.
+    pub fn validate_stream<E>(
+        &self,
+        iter: impl IntoIterator<Item = Result<Envelope, E>>,
+    ) -> Result<usize, ReplayStreamError<E>> {
+        let mut state = ChannelState::default();
+        let mut count = 0;
+        for item in iter {
+            let env = item.map_err(|source| ReplayStreamError::Read {
+                index: count,
+                source,
+            })?;
+            state = self.validate_at(count, &env, &state)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn validate_at(
+        &self,
+        index: usize,
+        env: &Envelope,
+        state: &ChannelState,
+    ) -> Result<ChannelState, ReplaySequenceError> {
+        ledger_spec::validate_envelope(env, &self.registry, state).map_err(|source| {
+            ReplaySequenceError {
+                index,
+                envelope_hash: envelope_hash(env),
+                source,
+            }
+        })
+    }
+}
+
+/// Replay failure identifying the offending envelope within the sequence.
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+#[error("replay validation failed at index {index}: {source}")]
+pub struct ReplaySequenceError {
+    /// Zero-based position of the envelope that failed.
+    pub index: usize,
+    /// Hash of the envelope that failed.
+    pub envelope_hash: [u8; 32],
+    /// Underlying validation failure.
+    #[source]
+    pub source: ValidationError,
+}
+
+/// Failure while replaying a stream of envelopes.
+#[derive(Debug, thiserror::Error)]
+pub enum ReplayStreamError<E> {
+    /// The underlying source failed to produce the envelope at `index`.
+    #[error("failed to read envelope at index {index}: {source}")]
+    Read {
+        /// Zero-based position of the envelope that could not be read.
+        index: usize,
+        /// Error reported by the source.
+        source: E,
+    },
+    /// An envelope was read but failed validation.
+    #[error(transparent)]
+    Invalid(#[from] ReplaySequenceError),
 }
 
 /// Envelope signer and verifier helpers.
@@ -652,12 +1207,15 @@ fn compute_merkle(items: &[[u8; 32]]) -> [u8; 32] {
     compute_merkle_root(items).unwrap_or([0u8; 32])
 }
 
-fn merkle_root_for(entries: &[Envelope]) -> Option<[u8; 32]> {
-    if entries.is_empty() {
-        return None;
-    }
-    let leaves: Vec<[u8; 32]> = entries.iter().map(envelope_hash).collect();
-    compute_merkle_root(&leaves)
+/// Leaf node for the envelope hash at `index` in `instance`'s tree.
+///
+/// Binding the position into the leaf means a tree commits to where each
+/// envelope sits, not just which envelopes it holds.
+fn merkle_leaf(instance: &LedgerInstance, index: usize, env_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = instance.hasher(b"leaf");
+    hasher.update(&(index as u64).to_le_bytes());
+    hasher.update(env_hash);
+    *hasher.finalize().as_bytes()
 }
 
 fn merkle_parent(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
@@ -669,7 +1227,11 @@ fn merkle_parent(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
 }
 
 fn compute_merkle_root(items: &[[u8; 32]]) -> Option<[u8; 32]> {
-    let mut leaves = items.to_vec();
+    let mut leaves: Vec<[u8; 32]> = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| merkle_leaf(&LedgerInstance::default(), index, item))
+        .collect();
     if leaves.is_empty() {
         return None;
     }
@@ -686,6 +1248,182 @@ fn compute_merkle_root(items: &[[u8; 32]]) -> Option<[u8; 32]> {
     leaves.into_iter().next()
 }
 
+/// Incremental Merkle accumulator over envelope hashes.
+///
+/// Each envelope hash is bound to its index before becoming a leaf. Every
+/// level keeps the parents of complete pairs, so appends are amortized
+/// O(1) and roots/receipts only walk the O(log n) right frontier. Roots match
+/// the batch construction, where an unpaired node is hashed with itself.
+#[derive(Debug, Default, Clone)]
+pub struct MerkleAccumulator {
+    instance: LedgerInstance,
+    envelopes: Vec<[u8; 32]>,
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleAccumulator {
+    /// Create an empty accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an empty accumulator whose leaves are bound to `instance`.
+    pub fn for_instance(instance: LedgerInstance) -> Self {
+        Self {
+            instance,
+            ..Self::default()
+        }
+    }
+
+    /// Build an accumulator by pushing each leaf in order.
+    pub fn from_leaves<I: IntoIterator<Item = [u8; 32]>>(leaves: I) -> Self {
+        let mut acc = Self::new();
+        for leaf in leaves {
+            acc.push(leaf);
+        }
+        acc
+    }
+
+    /// Number of leaves pushed so far.
+    pub fn len(&self) -> usize {
+        self.level(0).len()
+    }
+
+    /// Whether no leaves have been pushed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append an envelope hash, folding completed pairs into the levels above.
+    pub fn push(&mut self, env_hash: [u8; 32]) {
+        let mut node = merkle_leaf(&self.instance, self.envelopes.len(), &env_hash);
+        self.envelopes.push(env_hash);
+        let mut height = 0;
+        loop {
+            if self.levels.len() == height {
+                self.levels.push(Vec::new());
+            }
+            let nodes = &mut self.levels[height];
+            nodes.push(node);
+            if nodes.len() % 2 == 1 {
+                return;
+            }
+            node = merkle_parent(&nodes[nodes.len() - 2], &nodes[nodes.len() - 1]);
+            height += 1;
+        }
+    }
+
+    /// Current Merkle root, or `None` when empty.
+    pub fn root(&self) -> Option<[u8; 32]> {
+        let mut extra = None;
+        let mut height = 0;
+        loop {
+            let nodes = self.level(height);
+            if nodes.len() + usize::from(extra.is_some()) <= 1 {
+                return Self::node_at(nodes, extra, 0);
+            }
+            extra = Self::carry_up(nodes, extra);
+            height += 1;
+        }
+    }
+
+    /// Produce an inclusion receipt for the leaf at `index`.
+    pub fn receipt(&self, index: usize) -> Option<MerkleReceipt> {
+        let leaf = *self.envelopes.get(index)?;
+        let mut path = Vec::new();
+        let mut current = index;
+        let mut extra = None;
+        let mut height = 0;
+        loop {
+            let nodes = self.level(height);
+            if nodes.len() + usize::from(extra.is_some()) <= 1 {
+                return Some(MerkleReceipt {
+                    instance: self.instance.clone(),
+                    index,
+                    leaf_count: self.len(),
+                    leaf,
+                    root: Self::node_at(nodes, extra, 0)?,
+                    path,
+                });
+            }
+            let (sibling_index, position) = if current % 2 == 0 {
+                (current + 1, ProofPosition::Right)
+            } else {
+                (current - 1, ProofPosition::Left)
+            };
+            let sibling = Self::node_at(nodes, extra, sibling_index)
+                .or_else(|| Self::node_at(nodes, extra, current))?;
+            path.push(ProofNode { sibling, position });
+            extra = Self::carry_up(nodes, extra);
+            current /= 2;
+            height += 1;
+        }
+    }
+
+    /// Prove that the first `old_len` leaves are a prefix of the first
+    /// `new_len`, for checking with [`verify_consistency`].
+    ///
+    /// The proof lists the perfect subtrees of the old tree (one per set bit
+    /// of `old_len`, lowest first), the new right siblings on the path from
+    /// the last old leaf up to the first subtree the two sizes disagree on,
+    /// and the new tree's perfect subtrees below that height. Empty when
+    /// `old_len` is zero or the lengths are out of range.
+    pub fn consistency_proof(&self, old_len: usize, new_len: usize) -> Vec<[u8; 32]> {
+        if old_len == 0 || old_len > new_len || new_len > self.len() {
+            return Vec::new();
+        }
+        let node = |height: usize, index: usize| self.level(height)[index];
+        let mut proof: Vec<_> = set_bits(old_len)
+            .map(|height| node(height, (old_len >> height) - 1))
+            .collect();
+        if old_len == new_len {
+            return proof;
+        }
+
+        let split = consistency_split(old_len, new_len);
+        if old_len % (1 << split) == 0 {
+            proof.push(node(split, (new_len >> split) - 1));
+        } else {
+            for height in old_len.trailing_zeros() as usize..split {
+                let index = (old_len - 1) >> height;
+                if index % 2 == 0 {
+                    proof.push(node(height, index + 1));
+                }
+            }
+        }
+        proof.extend(
+            set_bits(new_len)
+                .filter(|&height| height < split)
+                .map(|height| node(height, (new_len >> height) - 1)),
+        );
+        proof
+    }
+
+    fn level(&self, height: usize) -> &[[u8; 32]] {
+        self.levels.get(height).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Node `i` of a level made of its complete nodes plus the frontier node.
+    fn node_at(nodes: &[[u8; 32]], extra: Option<[u8; 32]>, i: usize) -> Option<[u8; 32]> {
+        match nodes.get(i) {
+            Some(node) => Some(*node),
+            None if i == nodes.len() => extra,
+            None => None,
+        }
+    }
+
+    /// Frontier node of the next level up, derived from the unpaired tail.
+    fn carry_up(nodes: &[[u8; 32]], extra: Option<[u8; 32]>) -> Option<[u8; 32]> {
+        let last = nodes.last().filter(|_| nodes.len() % 2 == 1);
+        match (last, extra) {
+            (Some(last), Some(extra)) => Some(merkle_parent(last, &extra)),
+            (Some(last), None) => Some(merkle_parent(last, last)),
+            (None, Some(extra)) => Some(merkle_parent(&extra, &extra)),
+            (None, None) => None,
+        }
+    }
+}
+
 /// Merkle path position for a sibling hash.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum ProofPosition {
@@ -707,11 +1445,14 @@ pub struct ProofNode {
 /// Receipt proving inclusion of a log entry.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct MerkleReceipt {
+    /// Ledger instance whose tree the receipt was issued from.
+    #[serde(default)]
+    pub instance: LedgerInstance,
     /// Index of the leaf in the log.
     pub index: usize,
     /// Total leaf count at time of receipt generation.
     pub leaf_count: usize,
-    /// Hash of the leaf being proven (envelope hash).
+    /// Envelope hash being proven; bound to `index` when hashed into the tree.
     pub leaf: [u8; 32],
     /// Merkle root over the log at generation time.
     pub root: [u8; 32],
@@ -728,7 +1469,11 @@ impl MerkleReceipt {
 
         let mut path = Vec::new();
         let mut current_index = index;
-        let mut level = leaves.to_vec();
+        let mut level: Vec<[u8; 32]> = leaves
+            .iter()
+            .enumerate()
+            .map(|(i, leaf)| merkle_leaf(&LedgerInstance::default(), i, leaf))
+            .collect();
 
         while level.len() > 1 {
             let sibling_index = if current_index % 2 == 0 {
@@ -761,6 +1506,7 @@ impl MerkleReceipt {
         }
 
         Some(MerkleReceipt {
+            instance: LedgerInstance::default(),
             index,
             leaf_count: leaves.len(),
             leaf: leaves[index],
@@ -774,7 +1520,7 @@ impl MerkleReceipt {
         if self.path.is_empty() && self.leaf_count != 1 {
             return false;
         }
-        let mut hash = self.leaf;
+        let mut hash = merkle_leaf(&self.instance, self.index, &self.leaf);
         for node in &self.path {
             hash = match node.position {
                 ProofPosition::Left => merkle_parent(&node.sibling, &hash),
@@ -783,6 +1529,163 @@ impl MerkleReceipt {
         }
         hash == self.root
     }
+
+    /// Sibling hashes along the path, in leaf-to-root order.
+    pub fn sibling_hashes(&self) -> Vec<[u8; 32]> {
+        self.path.iter().map(|node| node.sibling).collect()
+    }
+}
+
+/// Verify an inclusion proof for `leaf_hash` at `index` against `root`.
+///
+/// The leaf is bound to `index` and sibling positions are derived from the
+/// index bits, matching the log's `ea-ledger:merkle` parent hashing where an
+/// unpaired node is paired with itself. Proofs with leftover index bits are
+/// rejected.
+pub fn verify_merkle_proof(
+    leaf_hash: [u8; 32],
+    index: usize,
+    proof: &[[u8; 32]],
+    root: [u8; 32],
+) -> bool {
+    verify_merkle_proof_in(&LedgerInstance::default(), leaf_hash, index, proof, root)
+}
+
+/// Verify an inclusion proof like [`verify_merkle_proof`] against a root
+/// from `instance`'s tree.
+pub fn verify_merkle_proof_in(
+    instance: &LedgerInstance,
+    leaf_hash: [u8; 32],
+    index: usize,
+    proof: &[[u8; 32]],
+    root: [u8; 32],
+) -> bool {
+    let mut hash = merkle_leaf(instance, index, &leaf_hash);
+    let mut position = index;
+    for sibling in proof {
+        hash = if position % 2 == 0 {
+            merkle_parent(&hash, sibling)
+        } else {
+            merkle_parent(sibling, &hash)
+        };
+        position /= 2;
+    }
+    position == 0 && hash == root
+}
+
+/// Verify a proof from [`MerkleAccumulator::consistency_proof`] that the log
+/// committed to by `new_root` at `new_len` extends, without rewriting, the
+/// log committed to by `old_root` at `old_len`.
+///
+/// This is the append-only analog of Certificate Transparency consistency
+/// proofs, over the log's `ea-ledger:merkle` hashing with self-paired
+/// unpaired nodes.
+pub fn verify_consistency(
+    old_root: [u8; 32],
+    old_len: usize,
+    new_root: [u8; 32],
+    new_len: usize,
+    proof: &[[u8; 32]],
+) -> bool {
+    if old_len == 0 || old_len > new_len {
+        return false;
+    }
+    let old_count = old_len.count_ones() as usize;
+    if proof.len() < old_count {
+        return false;
+    }
+    let (old_blocks, rest) = proof.split_at(old_count);
+    if root_from_blocks(old_len, old_blocks) != Some(old_root) {
+        return false;
+    }
+    if old_len == new_len {
+        return rest.is_empty() && old_root == new_root;
+    }
+    consistent_new_root(old_len, new_len, old_blocks, rest) == Some(new_root)
+}
+
+/// Recompute the new root from the old perfect subtrees and the rest of a
+/// consistency proof, requiring the proof to be consumed exactly.
+fn consistent_new_root(
+    old_len: usize,
+    new_len: usize,
+    old_blocks: &[[u8; 32]],
+    rest: &[[u8; 32]],
+) -> Option<[u8; 32]> {
+    // The old subtree at `height` is preceded by one per lower set bit.
+    let old_block =
+        |height: usize| old_blocks[(old_len & ((1 << height) - 1)).count_ones() as usize];
+    let mut rest = rest.iter();
+    let split = consistency_split(old_len, new_len);
+
+    let split_block = if old_len % (1 << split) == 0 {
+        *rest.next()?
+    } else {
+        let first = old_len.trailing_zeros() as usize;
+        let mut hash = old_block(first);
+        for height in first..split {
+            hash = if ((old_len - 1) >> height) % 2 == 0 {
+                merkle_parent(&hash, rest.next()?)
+            } else {
+                merkle_parent(&old_block(height), &hash)
+            };
+        }
+        hash
+    };
+
+    let mut new_blocks = Vec::with_capacity(new_len.count_ones() as usize);
+    for height in set_bits(new_len) {
+        new_blocks.push(match height.cmp(&split) {
+            std::cmp::Ordering::Less => *rest.next()?,
+            std::cmp::Ordering::Equal => split_block,
+            std::cmp::Ordering::Greater => old_block(height),
+        });
+    }
+    if rest.next().is_some() {
+        return None;
+    }
+    root_from_blocks(new_len, &new_blocks)
+}
+
+/// Heights of the perfect subtrees making up a tree of `len` leaves,
+/// lowest first.
+fn set_bits(len: usize) -> impl Iterator<Item = usize> {
+    (0..usize::BITS as usize).filter(move |&height| (len >> height) & 1 == 1)
+}
+
+/// Height of the first perfect subtree where trees of `old_len` and
+/// `new_len > old_len` leaves differ.
+fn consistency_split(old_len: usize, new_len: usize) -> usize {
+    (usize::BITS - 1 - (old_len ^ new_len).leading_zeros()) as usize
+}
+
+/// Root over `len` leaves from its perfect subtrees (see [`set_bits`]),
+/// folded as [`MerkleAccumulator::root`] folds its right frontier.
+fn root_from_blocks(len: usize, blocks: &[[u8; 32]]) -> Option<[u8; 32]> {
+    if blocks.len() != len.count_ones() as usize {
+        return None;
+    }
+    let mut blocks = blocks.iter();
+    let mut extra = None;
+    let mut height = 0;
+    loop {
+        let count = len >> height;
+        if count + usize::from(extra.is_some()) <= 1 {
+            return if count == 1 {
+                blocks.next().copied()
+            } else {
+                extra
+            };
+        }
+        let last = if count % 2 == 1 { blocks.next() } else { None };
+        extra = match (last, extra) {
+            (Some(last), Some(extra)) => Some(merkle_parent(last, &extra)),
+            (Some(last), None) => Some(merkle_parent(last, last)),
+            (None, Some(extra)) => Some(merkle_parent(&extra, &extra)),
+            (None, None) => None,
+        };
+        height += 1;
+    }
 }
 
 #[cfg(test)]
@@ -823,9 +1726,11 @@ mod tests {
             policy: ledger_spec::ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: vec![sk.verifying_key().to_bytes()],
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
         registry
     }
@@ -848,6 +1753,37 @@ mod tests {
         assert!(cp.root.iter().any(|b| *b != 0));
     }
 
+    #[test]
+    fn checkpoint_chain_links_and_detects_tamper() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        let log = AppendLog::new();
+        let mut writer = CheckpointWriter::new();
+        let mut prev = None;
+        for ts in 1..=6 {
+            let env = sample_env(prev, ts, &sk);
+            prev = Some(envelope_hash(&env));
+            log.append(env, &reg).unwrap();
+            writer.maybe_checkpoint(&log, 2);
+        }
+        let chain = writer.checkpoints().to_vec();
+        assert_eq!(
+            chain.iter().map(|cp| cp.length).collect::<Vec<_>>(),
+            vec![2, 4, 6]
+        );
+        assert!(chain[0].prev.is_none());
+        assert_eq!(chain[2].prev, Some(chain[1].hash()));
+        assert!(writer.verify_chain());
+
+        let mut tampered = chain.clone();
+        tampered[1].root[0] ^= 0xff;
+        assert!(!verify_checkpoint_chain(&tampered));
+
+        let mut reordered = chain;
+        reordered.swap(1, 2);
+        assert!(!verify_checkpoint_chain(&reordered));
+    }
+
     #[test]
     fn merkle_segmenter_emits_root() {
         let sk = SigningKey::generate(&mut OsRng);
@@ -874,7 +1810,69 @@ mod tests {
         env2.body.payload = serde_json::json!({"n": 99});
         let seq = vec![env1, env2];
         let err = validator.validate_sequence(&seq).unwrap_err();
-        assert_eq!(err, ValidationError::BodyHashMismatch);
+        assert_eq!(err.source, ValidationError::BodyHashMismatch);
+    }
+
+    #[test]
+    fn replay_validator_reports_offending_index() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let validator = ReplayValidator::new(registry(&sk));
+        let mut seq = Vec::new();
+        let mut prev = None;
+        for ts in 0..5 {
+            let env = sample_env(prev, ts, &sk);
+            prev = Some(envelope_hash(&env));
+            seq.push(env);
+        }
+        seq[3].body.payload = serde_json::json!({"n": 1000});
+        let err = validator.validate_sequence(&seq).unwrap_err();
+        assert_eq!(err.index, 3);
+        assert_eq!(err.envelope_hash, envelope_hash(&seq[3]));
+        assert_eq!(err.source, ValidationError::BodyHashMismatch);
+    }
+
+    #[test]
+    fn replay_stream_stops_at_tampered_envelope() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let validator = ReplayValidator::new(registry(&sk));
+        let mut prev = None;
+        let stream = (0..10_000u64).map(|ts| {
+            let mut env = sample_env(prev, ts, &sk);
+            prev = Some(envelope_hash(&env));
+            if ts == 7_321 {
+                env.body.payload = serde_json::json!({"n": 0});
+            }
+            Ok::<_, std::io::Error>(env)
+        });
+        match validator.validate_stream(stream) {
+            Err(ReplayStreamError::Invalid(err)) => {
+                assert_eq!(err.index, 7_321);
+                assert_eq!(err.source, ValidationError::BodyHashMismatch);
+            }
+            other => panic!("expected validation failure, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn replay_stream_counts_and_surfaces_read_errors() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let validator = ReplayValidator::new(registry(&sk));
+        let env1 = sample_env(None, 1, &sk);
+        let env2 = sample_env(Some(envelope_hash(&env1)), 2, &sk);
+        let ok: Vec<Result<Envelope, std::io::Error>> = vec![Ok(env1.clone()), Ok(env2)];
+        assert_eq!(validator.validate_stream(ok).unwrap(), 2);
+
+        let broken = vec![
+            Ok(env1),
+            Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "truncated",
+            )),
+        ];
+        match validator.validate_stream(broken) {
+            Err(ReplayStreamError::Read { index, .. }) => assert_eq!(index, 1),
+            other => panic!("expected read failure, got {other:?}"),
+        }
     }
 
     #[test]
@@ -893,6 +1891,148 @@ mod tests {
         assert_eq!(receipt.index, 2);
     }
 
+    #[test]
+    fn incremental_root_matches_recomputation() {
+        let leaves: Vec<[u8; 32]> = (0u32..70)
+            .map(|i| *blake3::hash(&i.to_le_bytes()).as_bytes())
+            .collect();
+        let mut acc = MerkleAccumulator::new();
+        assert_eq!(acc.root(), None);
+        for n in 1..=leaves.len() {
+            acc.push(leaves[n - 1]);
+            assert_eq!(acc.len(), n);
+            assert_eq!(acc.root(), compute_merkle_root(&leaves[..n]), "root at {n}");
+            for index in 0..n {
+                let receipt = acc.receipt(index).expect("receipt");
+                assert_eq!(
+                    Some(&receipt),
+                    MerkleReceipt::from_leaves(&leaves[..n], index).as_ref()
+                );
+                assert!(receipt.verify());
+            }
+        }
+        assert!(acc.receipt(leaves.len()).is_none());
+    }
+
+    fn receipt_fixture() -> MerkleReceipt {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        let log = AppendLog::new();
+        let mut prev = None;
+        for ts in 1..=5 {
+            let env = sample_env(prev, ts, &sk);
+            prev = Some(envelope_hash(&env));
+            log.append(env, &reg).unwrap();
+        }
+        log.receipt_for(3).expect("receipt exists")
+    }
+
+    #[test]
+    fn verify_merkle_proof_accepts_valid_receipt() {
+        let receipt = receipt_fixture();
+        assert!(verify_merkle_proof(
+            receipt.leaf,
+            receipt.index,
+            &receipt.sibling_hashes(),
+            receipt.root
+        ));
+    }
+
+    #[test]
+    fn verify_merkle_proof_rejects_tampered_leaf() {
+        let receipt = receipt_fixture();
+        let mut leaf = receipt.leaf;
+        leaf[0] ^= 0xff;
+        assert!(!verify_merkle_proof(
+            leaf,
+            receipt.index,
+            &receipt.sibling_hashes(),
+            receipt.root
+        ));
+    }
+
+    #[test]
+    fn verify_merkle_proof_rejects_wrong_index() {
+        let receipt = receipt_fixture();
+        let proof = receipt.sibling_hashes();
+        assert!(!verify_merkle_proof(receipt.leaf, 2, &proof, receipt.root));
+        assert!(!verify_merkle_proof(
+            receipt.leaf,
+            receipt.index + (1 << proof.len()),
+            &proof,
+            receipt.root
+        ));
+    }
+
+    #[test]
+    fn swapping_envelopes_changes_root() {
+        let leaves: Vec<[u8; 32]> = (0u8..4).map(|i| [i; 32]).collect();
+        let mut swapped = leaves.clone();
+        swapped.swap(1, 2);
+        assert_ne!(compute_merkle_root(&leaves), compute_merkle_root(&swapped));
+        // A lone leaf is still bound to its position.
+        assert_ne!(compute_merkle_root(&leaves[..1]), Some(leaves[0]));
+    }
+
+    #[test]
+    fn index_bound_receipts_verify() {
+        let leaves: Vec<[u8; 32]> = (0u8..5).map(|i| [i; 32]).collect();
+        let acc = MerkleAccumulator::from_leaves(leaves.iter().copied());
+        for (index, leaf) in leaves.iter().enumerate() {
+            let receipt = acc.receipt(index).expect("receipt");
+            assert_eq!(receipt.leaf, *leaf);
+            assert!(receipt.verify());
+            assert!(verify_merkle_proof(
+                *leaf,
+                index,
+                &receipt.sibling_hashes(),
+                receipt.root
+            ));
+        }
+
+        // A neighbour's envelope does not verify in this leaf's place.
+        let mut moved = acc.receipt(1).expect("receipt");
+        moved.leaf = leaves[0];
+        assert!(!moved.verify());
+    }
+
+    #[test]
+    fn instances_do_not_share_roots_or_proofs() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        let alpha = AppendLog::for_instance(LedgerInstance::new("alpha"));
+        let beta = AppendLog::for_instance(LedgerInstance::new("beta"));
+        let mut prev = None;
+        for ts in 1..=4 {
+            let env = sample_env(prev, ts, &sk);
+            prev = Some(envelope_hash(&env));
+            alpha.append(env.clone(), &reg).unwrap();
+            beta.append(env, &reg).unwrap();
+        }
+        assert_ne!(alpha.merkle_root(), beta.merkle_root());
+
+        let receipt = alpha.receipt_for(2).expect("receipt exists");
+        assert_eq!(receipt.instance, alpha.instance());
+        assert!(receipt.verify());
+        let proof = receipt.sibling_hashes();
+        assert!(verify_merkle_proof_in(
+            &alpha.instance(),
+            receipt.leaf,
+            receipt.index,
+            &proof,
+            receipt.root
+        ));
+        for root in [receipt.root, beta.merkle_root().unwrap()] {
+            assert!(!verify_merkle_proof_in(
+                &beta.instance(),
+                receipt.leaf,
+                receipt.index,
+                &proof,
+                root
+            ));
+        }
+    }
+
     fn temp_dir(prefix: &str) -> std::path::PathBuf {
         let mut path = std::env::temp_dir();
         let nanos = SystemTime::now()
@@ -904,6 +2044,61 @@ mod tests {
         path
     }
 
+    /// `sample_env` carrying a Policy attestation that expired at
+    /// `expires_at`, signed after the attestation is attached.
+    fn attested_env(ts: u64, expires_at: u64, sk: &SigningKey) -> Envelope {
+        let mut env = sample_env(None, ts, sk);
+        env.signatures.clear();
+        let statement = ledger_spec::AttestationKind::Policy {
+            bundle_hash: [7; 32],
+            expires_at,
+        };
+        let mut att = Attestation {
+            issuer: sk.verifying_key().to_bytes(),
+            statement_hash: ledger_spec::hash_attestation_statement(&statement),
+            statement,
+            signature: [0; 64],
+        };
+        signing::sign_attestation(&mut att, sk);
+        env.attestations.push(att);
+        signing::sign_envelope(&mut env, sk);
+        env
+    }
+
+    #[test]
+    fn backdated_envelope_with_expired_attestation_is_rejected() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        // Timestamp predates the expiry, but the wall clock is long past it.
+        let expired = || attested_env(500, 1_000, &sk);
+        let is_expired = |err: AppendError| {
+            matches!(
+                err,
+                AppendError::Validation(ValidationError::AttestationExpired {
+                    expires_at: 1_000,
+                    ..
+                })
+            )
+        };
+
+        let log = AppendLog::new();
+        assert!(is_expired(log.append(expired(), &reg).unwrap_err()));
+        let persistent = PersistentAppendLog::open(temp_dir("expired")).unwrap();
+        assert!(is_expired(persistent.append(expired(), &reg).unwrap_err()));
+        assert_eq!((log.len(), persistent.len()), (0, 0));
+
+        // Replay still judges the log by its own timestamps.
+        assert!(ReplayValidator::new(reg.clone())
+            .validate_sequence(&[expired()])
+            .is_ok());
+
+        let future = sample_env(None, now_millis() + 2 * ledger_spec::MAX_CLOCK_SKEW_MS, &sk);
+        assert!(matches!(
+            log.append(future, &reg).unwrap_err(),
+            AppendError::Validation(ValidationError::TimestampInFuture { .. })
+        ));
+    }
+
     #[test]
     fn persistent_log_recovers_merkle_root() {
         let sk = SigningKey::generate(&mut OsRng);
@@ -990,4 +2185,172 @@ mod tests {
         let err = PersistentAppendLog::open(&dir).unwrap_err();
         assert!(err.to_string().contains("metadata mismatch"));
     }
+
+    fn assert_tail_and_positions(
+        log: &dyn AppendLogStorage,
+        sk: &SigningKey,
+        reg: &ChannelRegistry,
+    ) {
+        assert_eq!(log.tail_hash(), None);
+        assert_eq!(log.position_of(&[7; 32]), None);
+
+        let mut hashes = Vec::new();
+        for ts in 1..=3 {
+            let env = sample_env(hashes.last().copied(), ts, sk);
+            hashes.push(envelope_hash(&env));
+            log.append(env, reg).unwrap();
+            assert_eq!(log.tail_hash(), hashes.last().copied());
+        }
+        for (index, hash) in hashes.iter().enumerate() {
+            assert_eq!(log.position_of(hash), Some(index));
+        }
+        assert_eq!(log.position_of(&[7; 32]), None);
+    }
+
+    #[test]
+    fn in_memory_log_tracks_tail_and_positions() {
+        let sk = SigningKey::generate(&mut OsRng);
+        assert_tail_and_positions(&AppendLog::new(), &sk, &registry(&sk));
+    }
+
+    fn assert_scan_matches_read(
+        log: &dyn AppendLogStorage,
+        sk: &SigningKey,
+        reg: &ChannelRegistry,
+    ) {
+        let mut prev = None;
+        for ts in 1..=5 {
+            let env = sample_env(prev, ts, sk);
+            prev = Some(envelope_hash(&env));
+            log.append(env, reg).unwrap();
+        }
+        for (offset, limit) in [(0, 5), (0, 100), (2, 2), (4, 10), (5, 1), (9, 3)] {
+            let mut scanned = Vec::new();
+            log.for_each(offset, limit, &mut |index, env| {
+                scanned.push((index, env.clone()));
+            });
+            let expected: Vec<_> = (offset..).zip(log.read(offset, limit)).collect();
+            assert_eq!(scanned, expected, "offset {offset} limit {limit}");
+        }
+    }
+
+    #[test]
+    fn in_memory_scan_matches_read() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        let log = AppendLog::new();
+        assert_scan_matches_read(&log, &sk, &reg);
+
+        let timestamps = log.fold(1, 3, Vec::new(), |mut acc, index, env| {
+            acc.push((index, env.header.timestamp));
+            acc
+        });
+        assert_eq!(timestamps, vec![(1, 2), (2, 3), (3, 4)]);
+        let via_trait = AppendLogStorage::fold(&log, 0, log.len(), 0, |n, _, _| n + 1);
+        assert_eq!(via_trait, 5);
+    }
+
+    #[test]
+    fn persistent_scan_matches_read() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        let log = PersistentAppendLog::open(temp_dir("scan")).unwrap();
+        assert_scan_matches_read(&log, &sk, &reg);
+
+        let total = log.fold(0, log.len(), 0, |sum, _, env| sum + env.header.timestamp);
+        assert_eq!(total, 15);
+    }
+
+    #[test]
+    fn persistent_log_tracks_tail_and_positions_across_restart() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        let dir = temp_dir("positions");
+        let log = PersistentAppendLog::open(&dir).unwrap();
+        assert_tail_and_positions(&log, &sk, &reg);
+        let tail = log.tail_hash().unwrap();
+        drop(log);
+
+        let reopened = PersistentAppendLog::open(&dir).unwrap();
+        assert_eq!(reopened.tail_hash(), Some(tail));
+        assert_eq!(reopened.position_of(&tail), Some(2));
+    }
+
+    fn leaf(n: u8) -> [u8; 32] {
+        *blake3::hash(&[n]).as_bytes()
+    }
+
+    #[test]
+    fn consistency_proofs_verify_for_every_prefix() {
+        let leaves: Vec<_> = (0..19).map(leaf).collect();
+        let acc = MerkleAccumulator::from_leaves(leaves.iter().copied());
+        for new_len in 1..=leaves.len() {
+            let new_root = compute_merkle_root(&leaves[..new_len]).unwrap();
+            for old_len in 1..=new_len {
+                let old_root = compute_merkle_root(&leaves[..old_len]).unwrap();
+                let proof = acc.consistency_proof(old_len, new_len);
+                assert!(
+                    verify_consistency(old_root, old_len, new_root, new_len, &proof),
+                    "{old_len} -> {new_len}"
+                );
+                let mut padded = proof.clone();
+                padded.push(leaf(0));
+                assert!(!verify_consistency(
+                    old_root, old_len, new_root, new_len, &padded
+                ));
+            }
+        }
+        assert!(acc.consistency_proof(0, 3).is_empty());
+        assert!(acc.consistency_proof(4, 3).is_empty());
+        assert!(acc.consistency_proof(3, 20).is_empty());
+    }
+
+    #[test]
+    fn consistency_proof_rejects_rewritten_history() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let reg = registry(&sk);
+        let log = AppendLog::new();
+        let mut writer = CheckpointWriter::new();
+        let mut prev = None;
+        let mut hashes = Vec::new();
+        for ts in 1..=11 {
+            let env = sample_env(prev, ts, &sk);
+            prev = Some(envelope_hash(&env));
+            hashes.push(envelope_hash(&env));
+            log.append(env, &reg).unwrap();
+            writer.maybe_checkpoint(&log, 6);
+        }
+        let old = writer.checkpoints()[0].clone();
+        assert_eq!(old.length, 6);
+        let new_root = log.merkle_root().unwrap();
+        let proof = log.consistency_proof(old.length, 11);
+        assert!(verify_consistency(
+            old.root, old.length, new_root, 11, &proof
+        ));
+        assert!(!verify_consistency(old.root, 5, new_root, 11, &proof));
+        assert!(!verify_consistency(
+            new_root, old.length, new_root, 11, &proof
+        ));
+
+        // Same length and later entries, but entry 3 was rewritten.
+        let mut forged = hashes;
+        forged[3] = leaf(0xFF);
+        let forged = MerkleAccumulator::from_leaves(forged);
+        let forged_root = forged.root().unwrap();
+        let forged_proof = forged.consistency_proof(old.length, 11);
+        assert!(!verify_consistency(
+            old.root,
+            old.length,
+            forged_root,
+            11,
+            &forged_proof
+        ));
+        assert!(!verify_consistency(
+            old.root,
+            old.length,
+            forged_root,
+            11,
+            &proof
+        ));
+    }
 }
@@ -5,12 +5,14 @@ use clap::{Args, Parser, Subcommand, ValueEnum};
 use ledger_core::{AppendLog, CheckpointWriter};
 use ledger_spec::{ChannelRegistry, ChannelSpec};
 use ledger_transport::{
-    bind_transport, AdapterCapability, AdapterKind, CapabilityAdvertisement, Transport,
-    TransportConfig, TransportDomain,
+    bind_transport, AdapterCapability, AdapterKind, CapabilityAdvertisement, LogBackend,
+    SharedRegistry, Transport, TransportConfig, TransportDomain, TrustLevel,
 };
 use prometheus::Encoder;
 use serde::Serialize;
-use tracing::{info, Level};
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::broadcast;
+use tracing::{info, warn, Level};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
 #[derive(Clone)]
@@ -185,6 +187,12 @@ enum Commands {
         #[arg(short, long, default_value = "10")]
         limit: usize,
     },
+    /// Tail new envelopes as they arrive (until Ctrl-C).
+    Subscribe {
+        /// Only print envelopes on this channel.
+        #[arg(short, long)]
+        channel: Option<String>,
+    },
 }
 
 /// Transport selection flags.
@@ -215,6 +223,22 @@ struct TransportCli {
         help = "Authority/endpoint for QUIC transport (e.g. https://ledgerd.example.com)"
     )]
     quic_endpoint: Option<String>,
+    /// Log storage for locally served transports.
+    #[arg(
+        long,
+        value_enum,
+        default_value_t = LogBackendKind::Temporary,
+        env = "LEDGER_LOG_BACKEND"
+    )]
+    log_backend: LogBackendKind,
+    /// Directory for the persistent log backend.
+    #[arg(
+        long,
+        env = "LEDGER_LOG_DIR",
+        value_name = "DIR",
+        help = "Directory holding the log when --log-backend=persistent"
+    )]
+    log_dir: Option<std::path::PathBuf>,
 }
 
 /// Supported transports exposed via CLI.
@@ -225,6 +249,14 @@ enum TransportKind {
     Quic,
 }
 
+/// Supported log backends exposed via CLI.
+#[derive(ValueEnum, Clone, Debug)]
+enum LogBackendKind {
+    Temporary,
+    Memory,
+    Persistent,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
@@ -253,14 +285,17 @@ async fn main() -> anyhow::Result<()> {
 
     let registry = load_registry(&cli.registry).await?;
     let transport_config = build_transport_config(&cli.transport)?;
-    let transport = bind_transport(registry.clone(), transport_config.clone()).await?;
+    // The daemon's reloads swap the policy the bound transport validates with.
+    let shared_registry = SharedRegistry::new(registry.clone());
+    let transport = bind_transport(shared_registry.clone(), transport_config.clone()).await?;
 
     match cli.command {
         Commands::Daemon { checkpoint } => {
             daemon(
                 checkpoint,
                 transport,
-                registry.clone(),
+                shared_registry,
+                &cli.registry,
                 cli.status_addr,
                 &transport_config,
             )
@@ -270,6 +305,10 @@ async fn main() -> anyhow::Result<()> {
         Commands::Read { offset, limit } => {
             read_entries(offset, limit, transport, &registry).await?
         }
+        Commands::Subscribe { channel } => {
+            let rx = transport.subscribe().await?;
+            tail_entries(rx, channel.as_deref(), &mut std::io::stdout()).await?
+        }
     }
     Ok(())
 }
@@ -277,7 +316,8 @@ async fn main() -> anyhow::Result<()> {
 async fn daemon(
     checkpoint_interval: usize,
     transport: std::sync::Arc<dyn Transport>,
-    registry: ChannelRegistry,
+    registry: SharedRegistry,
+    registry_path: &str,
     status_addr: String,
     transport_config: &TransportConfig,
 ) -> anyhow::Result<()> {
@@ -285,6 +325,7 @@ async fn daemon(
     let metrics = DaemonMetrics::new(attestation_configured);
     let mut writer = CheckpointWriter::new();
     let mut rx = transport.subscribe().await?;
+    let mut hangup = signal(SignalKind::hangup())?;
     let log = AppendLog::new();
     let status_state = std::sync::Arc::new(StatusState {
         metrics: metrics.clone(),
@@ -299,7 +340,18 @@ async fn daemon(
     loop {
         let backlog = rx.len() as i64;
         metrics.backlog_gauge.set(backlog);
-        let env = rx.recv().await?;
+        let env = tokio::select! {
+            env = rx.recv() => env?,
+            _ = hangup.recv() => {
+                match reload_registry(registry_path, &registry).await {
+                    Ok(()) => info!("registry reloaded from {}", registry_path),
+                    Err(err) => {
+                        warn!(error = %err, "registry reload rejected, keeping previous registry")
+                    }
+                }
+                continue;
+            }
+        };
         let span = tracing::info_span!(
             "daemon_append",
             channel = %env.header.channel,
@@ -308,7 +360,7 @@ async fn daemon(
         );
         let _guard = span.enter();
         let start = std::time::Instant::now();
-        let append_res = log.append_with_index(env.clone(), &registry);
+        let append_res = log.append_with_index(env.clone(), &registry.read());
         let latency = start.elapsed().as_millis() as f64;
         match append_res {
             Ok(idx) => {
@@ -391,14 +443,43 @@ async fn read_entries(
                 env.header.channel.as_str()
             );
         }
-        println!(
-            "channel={} ts={} payload={}",
-            env.header.channel, env.header.timestamp, env.body.payload
-        );
+        println!("{}", format_envelope(&env));
     }
     Ok(())
 }
 
+/// Write each envelope received on `rx` to `out`, optionally filtered by channel.
+///
+/// Returns once the transport closes the subscription.
+async fn tail_entries<W: std::io::Write>(
+    mut rx: broadcast::Receiver<ledger_spec::Envelope>,
+    channel: Option<&str>,
+    out: &mut W,
+) -> anyhow::Result<()> {
+    loop {
+        let env = match rx.recv().await {
+            Ok(env) => env,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!("subscriber lagged, skipped {} envelopes", skipped);
+                continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return Ok(()),
+        };
+        if channel.is_some_and(|c| c != env.header.channel.as_str()) {
+            continue;
+        }
+        writeln!(out, "{}", format_envelope(&env))?;
+        out.flush()?;
+    }
+}
+
+fn format_envelope(env: &ledger_spec::Envelope) -> String {
+    format!(
+        "channel={} ts={} payload={}",
+        env.header.channel, env.header.timestamp, env.body.payload
+    )
+}
+
 async fn start_status_server(
     listener: tokio::net::TcpListener,
     state: std::sync::Arc<StatusState>,
@@ -453,15 +534,40 @@ async fn load_registry(path: &str) -> anyhow::Result<ChannelRegistry> {
     Ok(registry)
 }
 
+/// Re-read the registry file and swap it in for the daemon and its bound
+/// transport; on error the current registry is kept.
+async fn reload_registry(path: &str, registry: &SharedRegistry) -> anyhow::Result<()> {
+    let reloaded = load_registry(path).await?;
+    registry.replace(reloaded);
+    Ok(())
+}
+
+fn build_log_backend(cli: &TransportCli) -> anyhow::Result<LogBackend> {
+    match cli.log_backend {
+        LogBackendKind::Temporary => Ok(LogBackend::Temporary),
+        LogBackendKind::Memory => Ok(LogBackend::Memory),
+        LogBackendKind::Persistent => {
+            let path = cli.log_dir.clone().ok_or_else(|| {
+                anyhow::anyhow!("--log-dir is required for persistent log backend")
+            })?;
+            Ok(LogBackend::Persistent { path })
+        }
+    }
+}
+
 fn build_transport_config(cli: &TransportCli) -> anyhow::Result<TransportConfig> {
+    let log_backend = build_log_backend(cli)?;
     match cli.transport {
-        TransportKind::Loopback => Ok(TransportConfig::loopback(TransportDomain::Ledger)),
+        TransportKind::Loopback => {
+            Ok(TransportConfig::loopback(TransportDomain::Ledger).with_log_backend(log_backend))
+        }
         TransportKind::Unix => {
             let path = cli.unix_path.clone();
             let selected = AdapterCapability {
                 adapter: AdapterKind::UnixIpc { path: path.clone() },
                 features: vec![],
                 attestation: None,
+                queue_depth: None,
             };
             let advertisement = CapabilityAdvertisement {
                 domain: TransportDomain::Ledger,
@@ -472,6 +578,9 @@ fn build_transport_config(cli: &TransportCli) -> anyhow::Result<TransportConfig>
             Ok(TransportConfig {
                 advertisement,
                 selected,
+                log_backend,
+                trust_level: TrustLevel::default(),
+                remote: None,
             })
         }
         TransportKind::Quic => {
@@ -486,6 +595,7 @@ fn build_transport_config(cli: &TransportCli) -> anyhow::Result<TransportConfig>
                 },
                 features: vec![],
                 attestation: None,
+                queue_depth: None,
             };
             let advertisement = CapabilityAdvertisement {
                 domain: TransportDomain::Ledger,
@@ -496,7 +606,182 @@ fn build_transport_config(cli: &TransportCli) -> anyhow::Result<TransportConfig>
             Ok(TransportConfig {
                 advertisement,
                 selected,
+                log_backend,
+                trust_level: TrustLevel::default(),
+                remote: None,
             })
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use rand_core::OsRng;
+
+    fn sample_env(
+        sk: &SigningKey,
+        ts: u64,
+        prev: Option<ledger_spec::Hash>,
+    ) -> ledger_spec::Envelope {
+        let body = ledger_spec::EnvelopeBody {
+            payload: serde_json::json!({"ts": ts}),
+            payload_type: Some("test".into()),
+        };
+        let mut env = ledger_spec::Envelope {
+            header: ledger_spec::EnvelopeHeader {
+                channel: "muscle_io".into(),
+                version: 1,
+                prev,
+                body_hash: ledger_spec::hash_body_for(&body, 1),
+                timestamp: ts,
+            },
+            body,
+            signatures: Vec::new(),
+            attestations: Vec::new(),
+        };
+        ledger_core::signing::sign_envelope(&mut env, sk);
+        env
+    }
+
+    fn loopback_config() -> TransportConfig {
+        let cli = TransportCli {
+            transport: TransportKind::Loopback,
+            unix_path: String::new(),
+            quic_endpoint: None,
+            log_backend: LogBackendKind::Memory,
+            log_dir: None,
+        };
+        build_transport_config(&cli).unwrap()
+    }
+
+    #[test]
+    fn log_backend_flags_select_persistent_log() {
+        let cli = Cli::try_parse_from([
+            "ledgerd",
+            "--registry",
+            "registry.json",
+            "--log-backend",
+            "persistent",
+            "--log-dir",
+            "/var/lib/ledgerd",
+            "read",
+        ])
+        .unwrap();
+        let config = build_transport_config(&cli.transport).unwrap();
+        assert_eq!(
+            config.log_backend,
+            LogBackend::Persistent {
+                path: "/var/lib/ledgerd".into()
+            }
+        );
+
+        let mut transport = cli.transport;
+        transport.log_dir = None;
+        assert!(build_transport_config(&transport).is_err());
+    }
+
+    async fn tail_loopback(channel: Option<&str>) -> String {
+        let transport = bind_transport(ChannelRegistry::new(), loopback_config())
+            .await
+            .unwrap();
+        let rx = transport.subscribe().await.unwrap();
+
+        let sk = SigningKey::generate(&mut OsRng);
+        let first = sample_env(&sk, 1, None);
+        let second = sample_env(&sk, 2, Some(ledger_spec::envelope_hash(&first)));
+        transport.append(first).await.unwrap();
+        transport.append(second).await.unwrap();
+        // Dropping the transport closes the subscription once drained.
+        drop(transport);
+
+        let mut sink = Vec::new();
+        tail_entries(rx, channel, &mut sink).await.unwrap();
+        String::from_utf8(sink).unwrap()
+    }
+
+    #[tokio::test]
+    async fn subscribe_prints_each_appended_envelope() {
+        let output = tail_loopback(None).await;
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("channel=muscle_io ts=1 "));
+        assert!(lines[1].starts_with("channel=muscle_io ts=2 "));
+    }
+
+    #[tokio::test]
+    async fn subscribe_filters_by_channel() {
+        assert!(tail_loopback(Some("other")).await.is_empty());
+    }
+
+    fn write_registry(path: &std::path::Path, allowed: ledger_spec::PublicKey) {
+        let specs = vec![ChannelSpec {
+            name: "muscle_io".into(),
+            policy: ledger_spec::ChannelPolicy {
+                allowed_signers: vec![allowed],
+                ..Default::default()
+            },
+            schemas: Default::default(),
+        }];
+        std::fs::write(path, serde_json::to_vec(&specs).unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn registry_reload_admits_new_signer() {
+        let path =
+            std::env::temp_dir().join(format!("ledgerd-registry-{}.json", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let sk = SigningKey::generate(&mut OsRng);
+        let other = SigningKey::generate(&mut OsRng);
+
+        write_registry(&path, other.verifying_key().to_bytes());
+        let registry = SharedRegistry::new(load_registry(path_str).await.unwrap());
+        let log = AppendLog::new();
+        let env = sample_env(&sk, 1, None);
+        assert!(log.append(env.clone(), &registry.read()).is_err());
+
+        write_registry(&path, sk.verifying_key().to_bytes());
+        reload_registry(path_str, &registry).await.unwrap();
+        log.append(env, &registry.read()).unwrap();
+
+        // A malformed reload is rejected and the previous registry stays active
+        std::fs::write(&path, b"not json").unwrap();
+        assert!(reload_registry(path_str, &registry).await.is_err());
+        let allowed = registry
+            .read()
+            .policy_for("muscle_io")
+            .unwrap()
+            .allowed_signers
+            .clone();
+        assert_eq!(allowed, vec![sk.verifying_key().to_bytes()]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+    #[tokio::test]
+    async fn registry_reload_reaches_the_bound_transport() {
+        let path = std::env::temp_dir().join(format!(
+            "ledgerd-transport-registry-{}.json",
+            std::process::id()
+        ));
+        let path_str = path.to_str().unwrap();
+        let sk = SigningKey::generate(&mut OsRng);
+        let other = SigningKey::generate(&mut OsRng);
+
+        write_registry(&path, other.verifying_key().to_bytes());
+        let registry = SharedRegistry::new(load_registry(path_str).await.unwrap());
+        let transport = bind_transport(registry.clone(), loopback_config())
+            .await
+            .unwrap();
+        let env = sample_env(&sk, 1, None);
+        assert!(transport.append(env.clone()).await.is_err());
+
+        // The transport validates with the reloaded policy, not its bind-time copy
+        write_registry(&path, sk.verifying_key().to_bytes());
+        reload_registry(path_str, &registry).await.unwrap();
+        transport.append(env.clone()).await.unwrap();
+        assert_eq!(transport.read(0, 10).await.unwrap(), vec![env]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
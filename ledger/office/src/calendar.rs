@@ -1,16 +1,24 @@
 //! Calendar application orchestrator.
 //!
 //! Provides ledger-backed event scheduling with full audit trail.
+//! Recurring events are stored once and expanded into occurrences on read.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
 
 use blake3::Hasher;
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
 use ed25519_dalek::SigningKey;
 use ledger_core::brainstem::{AppendReceipt, Ledger};
 use ledger_spec::{Hash, Timestamp};
 
-use crate::events::{EventChanges, OfficeEvent};
+use crate::events::{self, now_millis, EventChanges, Frequency, OfficeEvent, RecurrenceRule};
+use crate::permissions::{Permissions, WriteDenied};
+
+/// Upper bound on recurrence periods (days, weeks, or months) scanned
+/// when expanding a rule.
+const MAX_RECURRENCE_PERIODS: u64 = 100_000;
 
 /// Errors from calendar operations.
 #[derive(Debug, thiserror::Error)]
@@ -21,6 +29,15 @@ pub enum CalendarError {
     /// Invalid time range.
     #[error("invalid time range: start must be before end")]
     InvalidTimeRange,
+    /// The event has no active occurrence at the given start.
+    #[error("no occurrence starting at {0}")]
+    OccurrenceNotFound(Timestamp),
+    /// Not a known IANA timezone name.
+    #[error("unknown timezone: {0}")]
+    InvalidTimezone(String),
+    /// This app instance is read-only.
+    #[error("permission denied: app is read-only")]
+    PermissionDenied,
     /// Ledger operation failed.
     #[error("ledger error: {0}")]
     Ledger(#[from] ledger_core::apps::AppError),
@@ -29,6 +46,12 @@ pub enum CalendarError {
     Serialization(#[from] serde_json::Error),
 }
 
+impl From<WriteDenied> for CalendarError {
+    fn from(_: WriteDenied) -> Self {
+        Self::PermissionDenied
+    }
+}
+
 /// A calendar event.
 #[derive(Debug, Clone)]
 pub struct CalendarEvent {
@@ -44,8 +67,12 @@ pub struct CalendarEvent {
     pub description: Option<String>,
     /// Optional location.
     pub location: Option<String>,
-    /// Recurrence rule (iCal RRULE format).
-    pub recurrence: Option<String>,
+    /// Recurrence rule (if any).
+    pub recurrence: Option<RecurrenceRule>,
+    /// IANA timezone the event's wall-clock time is anchored to (UTC if unset).
+    pub timezone: Option<String>,
+    /// Start timestamps of individually cancelled occurrences.
+    pub exceptions: BTreeSet<Timestamp>,
     /// Whether the event is cancelled.
     pub cancelled: bool,
     /// Last modified timestamp.
@@ -79,6 +106,8 @@ impl CalendarEvent {
             description: None,
             location: None,
             recurrence: None,
+            timezone: None,
+            exceptions: BTreeSet::new(),
             cancelled: false,
             modified_at: now_millis(),
         })
@@ -93,6 +122,141 @@ impl CalendarEvent {
     pub fn overlaps(&self, range_start: Timestamp, range_end: Timestamp) -> bool {
         self.start < range_end && self.end > range_start
     }
+
+    /// Timezone recurrences are expanded in; UTC if unset or unknown.
+    pub fn zone(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|name| parse_timezone(name).ok())
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// Start timestamps of occurrences beginning in `[from, before)`, in
+    /// order.
+    ///
+    /// A non-recurring event has a single occurrence at `start`. Recurring
+    /// events repeat at the same wall-clock time in the event's timezone on
+    /// every date matched by the rule on or after the start date, so the
+    /// UTC instant shifts across DST transitions. Cancelled occurrences are
+    /// included (they still count towards `count`).
+    ///
+    /// Expansion jumps straight to the period containing `from`; occurrences
+    /// skipped over are only counted, not generated.
+    pub fn occurrence_starts(&self, from: Timestamp, before: Timestamp) -> Vec<Timestamp> {
+        use chrono::{Datelike, Days, NaiveDate};
+
+        let Some(rule) = &self.recurrence else {
+            return if (from..before).contains(&self.start) {
+                vec![self.start]
+            } else {
+                Vec::new()
+            };
+        };
+        if matches!(rule.frequency, Frequency::Monthly { day } if !(1..=31).contains(&day)) {
+            return Vec::new();
+        }
+        let zone = self.zone();
+        let Some(first) = zone.timestamp_millis_opt(self.start as i64).single() else {
+            return Vec::new();
+        };
+        let (first_date, time) = (first.date_naive(), first.time());
+        let first_weekday = first_date.weekday().num_days_from_monday();
+
+        let weekdays: Vec<u32> = match &rule.frequency {
+            Frequency::Weekly { weekdays } if !weekdays.is_empty() => {
+                let mut days: Vec<u32> =
+                    weekdays.iter().map(|d| d.num_days_from_monday()).collect();
+                days.sort_unstable();
+                days.dedup();
+                days
+            }
+            _ => vec![first_weekday],
+        };
+        let week_of_first = first_date.checked_sub_days(Days::new(first_weekday.into()));
+        let month_of = |date: NaiveDate| date.year() as i64 * 12 + date.month0() as i64;
+
+        // Every date in a period before the one holding the day before `from`
+        // (in the event's zone) starts before `from`.
+        let skip = zone
+            .timestamp_millis_opt(from as i64)
+            .single()
+            .and_then(|from| from.date_naive().checked_sub_days(Days::new(1)))
+            .filter(|from_date| *from_date > first_date)
+            .map_or(0, |from_date| match &rule.frequency {
+                Frequency::Daily => (from_date - first_date).num_days() as u64,
+                Frequency::Weekly { .. } => {
+                    week_of_first.map_or(0, |monday| (from_date - monday).num_days() as u64 / 7)
+                }
+                Frequency::Monthly { .. } => (month_of(from_date) - month_of(first_date)) as u64,
+            });
+        let mut counted = match &rule.frequency {
+            _ if rule.count.is_none() || skip == 0 => 0,
+            Frequency::Daily => skip,
+            Frequency::Weekly { .. } => {
+                let first_week = weekdays.iter().filter(|wd| **wd >= first_weekday).count();
+                first_week as u64 + (skip - 1) * weekdays.len() as u64
+            }
+            Frequency::Monthly { day } => (0..skip)
+                .filter_map(|period| {
+                    let months = month_of(first_date) + period as i64;
+                    NaiveDate::from_ymd_opt((months / 12) as i32, (months % 12) as u32 + 1, *day)
+                })
+                .filter(|date| *date >= first_date)
+                .count() as u64,
+        };
+
+        let mut starts = Vec::new();
+        for period in skip..skip.saturating_add(MAX_RECURRENCE_PERIODS) {
+            let dates: Vec<NaiveDate> = match &rule.frequency {
+                Frequency::Daily => first_date
+                    .checked_add_days(Days::new(period))
+                    .into_iter()
+                    .collect(),
+                Frequency::Weekly { .. } => {
+                    let week = week_of_first
+                        .and_then(|monday| monday.checked_add_days(Days::new(period * 7)));
+                    weekdays
+                        .iter()
+                        .filter_map(|wd| week?.checked_add_days(Days::new((*wd).into())))
+                        .collect()
+                }
+                Frequency::Monthly { day } => {
+                    let months = month_of(first_date) + period as i64;
+                    NaiveDate::from_ymd_opt((months / 12) as i32, (months % 12) as u32 + 1, *day)
+                        .into_iter()
+                        .collect()
+                }
+            };
+
+            for date in dates.into_iter().filter(|d| *d >= first_date) {
+                let Some(local) = resolve_local(zone, date.and_time(time)) else {
+                    continue;
+                };
+                let start = local.timestamp_millis() as u64;
+                let past_until = rule.until.is_some_and(|until| start > until);
+                let counted_out = rule.count.is_some_and(|count| counted >= count as u64);
+                if start >= before || past_until || counted_out {
+                    return starts;
+                }
+                counted += 1;
+                if start >= from {
+                    starts.push(start);
+                }
+            }
+        }
+        starts
+    }
+}
+
+/// A single concrete occurrence of a (possibly recurring) event.
+#[derive(Debug, Clone, Copy)]
+pub struct Occurrence<'a> {
+    /// The event this occurrence belongs to.
+    pub event: &'a CalendarEvent,
+    /// Occurrence start timestamp.
+    pub start: Timestamp,
+    /// Occurrence end timestamp.
+    pub end: Timestamp,
 }
 
 /// Calendar application orchestrator.
@@ -100,7 +264,10 @@ pub struct CalendarApp {
     ledger: Ledger,
     signer: Arc<SigningKey>,
     channel: String,
+    permissions: Permissions,
     schema_version: u16,
+    /// Timezone event times are presented in.
+    display_tz: Tz,
     /// In-memory event index.
     events: HashMap<Hash, CalendarEvent>,
 }
@@ -117,35 +284,57 @@ impl CalendarApp {
             ledger,
             signer: Arc::new(signer),
             channel: channel.into(),
+            permissions: Permissions::default(),
             schema_version,
+            display_tz: Tz::UTC,
             events: HashMap::new(),
         }
     }
 
+    /// Restrict this calendar to `permissions`. A read-only calendar still
+    /// answers agenda and range queries but cannot schedule, change or
+    /// cancel events.
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Whether this calendar may schedule, modify and cancel events.
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
+    /// Present event times in `tz` instead of UTC.
+    pub fn with_display_timezone(mut self, tz: Tz) -> Self {
+        self.display_tz = tz;
+        self
+    }
+
+    /// Timezone event times are presented in.
+    pub fn display_timezone(&self) -> Tz {
+        self.display_tz
+    }
+
+    /// Change the timezone event times are presented in.
+    pub fn set_display_timezone(&mut self, tz: Tz) {
+        self.display_tz = tz;
+    }
+
+    /// Convert a timestamp to wall-clock time in the display timezone.
+    pub fn display_time(&self, ts: Timestamp) -> Option<DateTime<Tz>> {
+        self.display_tz.timestamp_millis_opt(ts as i64).single()
+    }
+
     /// Append an office event to the ledger.
     fn append_office_event(&self, event: OfficeEvent) -> Result<AppendReceipt, CalendarError> {
-        let payload = serde_json::to_value(&event)?;
-        let body = ledger_spec::EnvelopeBody {
-            payload,
-            payload_type: Some("ea.office.v1".into()),
-        };
-        let body_hash = ledger_spec::hash_body(&body);
-        let mut env = ledger_spec::Envelope {
-            header: ledger_spec::EnvelopeHeader {
-                channel: self.channel.clone(),
-                version: self.schema_version,
-                prev: self.ledger.tail_hash(),
-                body_hash,
-                timestamp: now_millis(),
-            },
-            body,
-            signatures: Vec::new(),
-            attestations: Vec::new(),
-        };
-        ledger_core::signing::sign_envelope(&mut env, &self.signer);
-        self.ledger.append(env).map_err(|e| CalendarError::Ledger(
-            ledger_core::apps::AppError::Ledger(e)
-        ))
+        events::append_office_event(
+            &self.ledger,
+            &self.signer,
+            &self.channel,
+            self.schema_version,
+            self.permissions,
+            event,
+        )
     }
 
     /// Schedule a new event.
@@ -156,20 +345,7 @@ impl CalendarApp {
         end: Timestamp,
     ) -> Result<(CalendarEvent, AppendReceipt), CalendarError> {
         let event = CalendarEvent::new(title, start, end)?;
-
-        let office_event = OfficeEvent::EventScheduled {
-            id: event.id,
-            title: event.title.clone(),
-            start: event.start,
-            end: event.end,
-            description: event.description.clone(),
-            location: event.location.clone(),
-            recurrence: event.recurrence.clone(),
-        };
-
-        let receipt = self.append_office_event(office_event)?;
-        self.events.insert(event.id, event.clone());
-        Ok((event, receipt))
+        self.record_scheduled(event)
     }
 
     /// Schedule an event with additional details.
@@ -180,13 +356,37 @@ impl CalendarApp {
         end: Timestamp,
         description: Option<String>,
         location: Option<String>,
-        recurrence: Option<String>,
+        recurrence: Option<RecurrenceRule>,
     ) -> Result<(CalendarEvent, AppendReceipt), CalendarError> {
         let mut event = CalendarEvent::new(title, start, end)?;
         event.description = description;
         event.location = location;
         event.recurrence = recurrence;
+        self.record_scheduled(event)
+    }
 
+    /// Schedule an event whose wall-clock time is anchored to an IANA
+    /// timezone, so recurrences keep their local time across DST changes.
+    pub fn schedule_zoned_event(
+        &mut self,
+        title: impl Into<String>,
+        start: Timestamp,
+        end: Timestamp,
+        timezone: &str,
+        recurrence: Option<RecurrenceRule>,
+    ) -> Result<(CalendarEvent, AppendReceipt), CalendarError> {
+        let zone = parse_timezone(timezone)?;
+        let mut event = CalendarEvent::new(title, start, end)?;
+        event.timezone = Some(zone.name().to_string());
+        event.recurrence = recurrence;
+        self.record_scheduled(event)
+    }
+
+    /// Record a newly scheduled event to the ledger and index it.
+    fn record_scheduled(
+        &mut self,
+        event: CalendarEvent,
+    ) -> Result<(CalendarEvent, AppendReceipt), CalendarError> {
         let office_event = OfficeEvent::EventScheduled {
             id: event.id,
             title: event.title.clone(),
@@ -195,6 +395,7 @@ impl CalendarApp {
             description: event.description.clone(),
             location: event.location.clone(),
             recurrence: event.recurrence.clone(),
+            timezone: event.timezone.clone(),
         };
 
         let receipt = self.append_office_event(office_event)?;
@@ -208,11 +409,11 @@ impl CalendarApp {
         id: Hash,
         changes: EventChanges,
     ) -> Result<(CalendarEvent, AppendReceipt), CalendarError> {
-        if !self.events.contains_key(&id) {
-            return Err(CalendarError::NotFound(hex::encode(id)));
-        }
-
-        let event = self.events.get_mut(&id).unwrap();
+        let mut event = self
+            .events
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| CalendarError::NotFound(hex::encode(id)))?;
 
         // Apply changes
         if let Some(ref title) = changes.title {
@@ -237,14 +438,13 @@ impl CalendarApp {
             return Err(CalendarError::InvalidTimeRange);
         }
 
-        let event = event.clone();
-
         let office_event = OfficeEvent::EventModified {
             id,
             changes,
         };
 
         let receipt = self.append_office_event(office_event)?;
+        self.events.insert(id, event.clone());
         Ok((event, receipt))
     }
 
@@ -254,18 +454,55 @@ impl CalendarApp {
         id: Hash,
         reason: impl Into<String>,
     ) -> Result<AppendReceipt, CalendarError> {
-        let event = self.events.get_mut(&id)
-            .ok_or_else(|| CalendarError::NotFound(hex::encode(id)))?;
+        if !self.events.contains_key(&id) {
+            return Err(CalendarError::NotFound(hex::encode(id)));
+        }
 
+        let office_event = OfficeEvent::EventCancelled {
+            id,
+            reason: reason.into(),
+        };
+
+        let receipt = self.append_office_event(office_event)?;
+        let event = self.events.get_mut(&id).unwrap();
         event.cancelled = true;
         event.modified_at = now_millis();
+        Ok(receipt)
+    }
 
-        let office_event = OfficeEvent::EventCancelled {
+    /// Cancel a single occurrence of a recurring event.
+    ///
+    /// Records an `OccurrenceCancelled` tombstone; the rest of the series
+    /// is unaffected.
+    pub fn cancel_occurrence(
+        &mut self,
+        id: Hash,
+        occurrence_start: Timestamp,
+        reason: impl Into<String>,
+    ) -> Result<AppendReceipt, CalendarError> {
+        let event = self
+            .events
+            .get(&id)
+            .ok_or_else(|| CalendarError::NotFound(hex::encode(id)))?;
+
+        let exists = event
+            .occurrence_starts(occurrence_start, occurrence_start.saturating_add(1))
+            .contains(&occurrence_start);
+        if !exists || event.exceptions.contains(&occurrence_start) {
+            return Err(CalendarError::OccurrenceNotFound(occurrence_start));
+        }
+
+        let office_event = OfficeEvent::OccurrenceCancelled {
             id,
+            occurrence_start,
             reason: reason.into(),
         };
+        let receipt = self.append_office_event(office_event)?;
 
-        self.append_office_event(office_event)
+        let event = self.events.get_mut(&id).unwrap();
+        event.exceptions.insert(occurrence_start);
+        event.modified_at = now_millis();
+        Ok(receipt)
     }
 
     /// Get an event by ID.
@@ -285,6 +522,35 @@ impl CalendarApp {
             .collect()
     }
 
+    /// Expand active events into occurrences overlapping a time range,
+    /// ordered by start time. Cancelled occurrences are omitted.
+    pub fn list_active_events_in_range(
+        &self,
+        range_start: Timestamp,
+        range_end: Timestamp,
+    ) -> Vec<Occurrence<'_>> {
+        let mut occurrences: Vec<Occurrence<'_>> = self
+            .events
+            .values()
+            .filter(|e| !e.cancelled)
+            .flat_map(|event| {
+                let duration = event.duration_ms();
+                event
+                    .occurrence_starts(range_start.saturating_sub(duration), range_end)
+                    .into_iter()
+                    .filter(move |start| !event.exceptions.contains(start))
+                    .map(move |start| Occurrence {
+                        event,
+                        start,
+                        end: start + duration,
+                    })
+                    .filter(move |o| o.end > range_start)
+            })
+            .collect();
+        occurrences.sort_by_key(|o| (o.start, o.event.id));
+        occurrences
+    }
+
     /// Query events in a time range.
     pub fn query_events(
         &self,
@@ -296,17 +562,19 @@ impl CalendarApp {
             .collect()
     }
 
-    /// Get events for a specific day.
+    /// Get events for a specific day in the display timezone.
     pub fn events_for_day(&self, year: i32, month: u32, day: u32) -> Vec<&CalendarEvent> {
-        use chrono::{NaiveDate, TimeZone, Utc};
+        use chrono::NaiveDate;
 
         let date = match NaiveDate::from_ymd_opt(year, month, day) {
             Some(d) => d,
             None => return Vec::new(),
         };
 
-        let start_of_day = Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap());
-        let end_of_day = Utc.from_utc_datetime(&date.and_hms_opt(23, 59, 59).unwrap());
+        let at = |h, m, s| resolve_local(self.display_tz, date.and_hms_opt(h, m, s).unwrap());
+        let Some((start_of_day, end_of_day)) = at(0, 0, 0).zip(at(23, 59, 59)) else {
+            return Vec::new();
+        };
 
         let start_ms = start_of_day.timestamp_millis() as u64;
         let end_ms = end_of_day.timestamp_millis() as u64;
@@ -315,12 +583,21 @@ impl CalendarApp {
     }
 }
 
-fn now_millis() -> Timestamp {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+/// Parse an IANA timezone name such as `"Europe/Berlin"`.
+pub fn parse_timezone(name: &str) -> Result<Tz, CalendarError> {
+    name.parse()
+        .map_err(|_| CalendarError::InvalidTimezone(name.to_string()))
+}
+
+/// The instant a local wall-clock time denotes in `zone`.
+///
+/// Ambiguous times (clocks falling back) resolve to the earlier instant;
+/// times skipped by clocks springing forward move forward by the gap.
+fn resolve_local(zone: Tz, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+    zone.from_local_datetime(&local).earliest().or_else(|| {
+        zone.from_local_datetime(&(local + Duration::hours(1)))
+            .earliest()
+    })
 }
 
 #[cfg(test)]
@@ -337,9 +614,11 @@ mod tests {
             policy: ledger_spec::ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: vec![signer.verifying_key().to_bytes()],
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
         let ledger = Ledger::new(registry);
         CalendarApp::new(ledger, signer, "office.calendar", 1)
@@ -410,6 +689,261 @@ mod tests {
         assert!(matches!(result, Err(CalendarError::InvalidTimeRange)));
     }
 
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> Timestamp {
+        use chrono::{TimeZone, Utc};
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0)
+            .unwrap()
+            .timestamp_millis() as u64
+    }
+
+    fn schedule_recurring(
+        app: &mut CalendarApp,
+        start: Timestamp,
+        rule: RecurrenceRule,
+    ) -> CalendarEvent {
+        let (event, receipt) = app
+            .schedule_event_full("Standup", start, start + 900_000, None, None, Some(rule))
+            .unwrap();
+        assert!(receipt.merkle.verify());
+        event
+    }
+
+    #[test]
+    fn weekly_rule_expands_across_month() {
+        use crate::events::Weekday;
+
+        let mut app = test_app();
+        // 2024-01-01 is a Monday.
+        let rule = RecurrenceRule {
+            frequency: Frequency::Weekly {
+                weekdays: vec![Weekday::Fri, Weekday::Mon, Weekday::Wed],
+            },
+            until: None,
+            count: None,
+        };
+        schedule_recurring(&mut app, utc(2024, 1, 1, 9), rule);
+
+        let occurrences = app.list_active_events_in_range(utc(2024, 1, 1, 0), utc(2024, 2, 1, 0));
+        // Mondays 1..29 (5), Wednesdays 3..31 (5), Fridays 5..26 (4).
+        assert_eq!(occurrences.len(), 14);
+        assert_eq!(occurrences[0].start, utc(2024, 1, 1, 9));
+        assert_eq!(occurrences[1].start, utc(2024, 1, 3, 9));
+        assert_eq!(occurrences[13].start, utc(2024, 1, 31, 9));
+        assert!(occurrences.iter().all(|o| o.end - o.start == 900_000));
+    }
+
+    #[test]
+    fn recurrence_honors_count_and_until() {
+        let mut app = test_app();
+        let daily = RecurrenceRule {
+            frequency: Frequency::Daily,
+            until: None,
+            count: Some(5),
+        };
+        schedule_recurring(&mut app, utc(2024, 3, 10, 8), daily);
+        let monthly = RecurrenceRule {
+            frequency: Frequency::Monthly { day: 31 },
+            until: Some(utc(2024, 8, 31, 8)),
+            count: None,
+        };
+        schedule_recurring(&mut app, utc(2024, 1, 31, 8), monthly);
+
+        let occurrences = app.list_active_events_in_range(utc(2024, 1, 1, 0), utc(2025, 1, 1, 0));
+        let daily: Vec<_> = occurrences
+            .iter()
+            .filter(|o| o.event.recurrence.as_ref().unwrap().count.is_some())
+            .collect();
+        assert_eq!(daily.len(), 5);
+        assert_eq!(daily[4].start, utc(2024, 3, 14, 8));
+
+        // Months without a 31st are skipped: Jan, Mar, May, Jul, Aug.
+        let monthly: Vec<Timestamp> = occurrences
+            .iter()
+            .filter(|o| o.event.recurrence.as_ref().unwrap().until.is_some())
+            .map(|o| o.start)
+            .collect();
+        assert_eq!(
+            monthly,
+            vec![
+                utc(2024, 1, 31, 8),
+                utc(2024, 3, 31, 8),
+                utc(2024, 5, 31, 8),
+                utc(2024, 7, 31, 8),
+                utc(2024, 8, 31, 8),
+            ]
+        );
+    }
+
+    #[test]
+    fn expansion_jumps_to_the_window_start() {
+        use crate::events::Weekday;
+
+        let mut app = test_app();
+        let rules = [
+            (Frequency::Daily, Some(9_020)),
+            (
+                Frequency::Weekly {
+                    weekdays: vec![Weekday::Tue, Weekday::Sat],
+                },
+                Some(2_581),
+            ),
+            (Frequency::Monthly { day: 31 }, Some(173)),
+            (Frequency::Daily, None),
+        ];
+        // 2000-01-05 is a Wednesday, so the first week holds only Saturday.
+        let start = utc(2000, 1, 5, 9);
+        for (frequency, count) in rules {
+            let rule = RecurrenceRule {
+                frequency,
+                until: None,
+                count,
+            };
+            let event = schedule_recurring(&mut app, start, rule);
+
+            let (from, before) = (utc(2024, 8, 30, 0), utc(2024, 11, 1, 0));
+            let full: Vec<Timestamp> = event
+                .occurrence_starts(0, before)
+                .into_iter()
+                .filter(|s| *s >= from)
+                .collect();
+            assert_eq!(event.occurrence_starts(from, before), full);
+        }
+
+        // Counts still cut the series off inside the window.
+        let occurrences = app.list_active_events_in_range(utc(2024, 8, 30, 0), utc(2024, 11, 1, 0));
+        let starts = |count| -> Vec<Timestamp> {
+            occurrences
+                .iter()
+                .filter(|o| o.event.recurrence.as_ref().unwrap().count == count)
+                .map(|o| o.start)
+                .collect()
+        };
+        let days = starts(Some(9_020));
+        assert_eq!(days.len(), 16);
+        assert_eq!(days.last(), Some(&utc(2024, 9, 14, 9)));
+        let weeks = starts(Some(2_581));
+        assert_eq!(weeks.len(), 9);
+        assert_eq!(weeks.last(), Some(&utc(2024, 9, 28, 9)));
+        assert_eq!(starts(Some(173)), vec![utc(2024, 8, 31, 9)]);
+        assert_eq!(starts(None).len(), 63);
+    }
+
+    #[test]
+    fn cancelled_occurrence_is_excluded() {
+        use crate::events::Weekday;
+
+        let mut app = test_app();
+        let rule = RecurrenceRule {
+            frequency: Frequency::Weekly {
+                weekdays: vec![Weekday::Mon],
+            },
+            until: None,
+            count: None,
+        };
+        let event = schedule_recurring(&mut app, utc(2024, 1, 1, 9), rule);
+
+        let receipt = app
+            .cancel_occurrence(event.id, utc(2024, 1, 15, 9), "Holiday")
+            .unwrap();
+        assert!(receipt.merkle.verify());
+        assert!(matches!(
+            app.cancel_occurrence(event.id, utc(2024, 1, 15, 9), "again"),
+            Err(CalendarError::OccurrenceNotFound(_))
+        ));
+        assert!(matches!(
+            app.cancel_occurrence(event.id, utc(2024, 1, 16, 9), "not a Monday"),
+            Err(CalendarError::OccurrenceNotFound(_))
+        ));
+
+        let starts: Vec<Timestamp> = app
+            .list_active_events_in_range(utc(2024, 1, 1, 0), utc(2024, 2, 1, 0))
+            .iter()
+            .map(|o| o.start)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                utc(2024, 1, 1, 9),
+                utc(2024, 1, 8, 9),
+                utc(2024, 1, 22, 9),
+                utc(2024, 1, 29, 9)
+            ]
+        );
+    }
+
+    #[test]
+    fn event_renders_in_display_timezone() {
+        use chrono::{Datelike, Timelike};
+
+        let mut app = test_app();
+        let (event, _) = app
+            .schedule_event("Launch", utc(2024, 7, 1, 12), utc(2024, 7, 1, 13))
+            .unwrap();
+
+        app.set_display_timezone(parse_timezone("America/New_York").unwrap());
+        let local = app.display_time(event.start).unwrap();
+        assert_eq!((local.day(), local.hour()), (1, 8));
+
+        app.set_display_timezone(parse_timezone("Asia/Tokyo").unwrap());
+        let local = app.display_time(event.start).unwrap();
+        assert_eq!((local.day(), local.hour()), (1, 21));
+        assert!(matches!(
+            parse_timezone("Mars/Olympus_Mons"),
+            Err(CalendarError::InvalidTimezone(_))
+        ));
+    }
+
+    #[test]
+    fn zoned_recurrence_keeps_wall_clock_across_dst() {
+        use chrono::Timelike;
+
+        let mut app = test_app();
+        let rule = RecurrenceRule {
+            frequency: Frequency::Daily,
+            until: None,
+            count: None,
+        };
+        // 09:00 EST on 2024-03-08; US clocks spring forward on 2024-03-10.
+        let (event, _) = app
+            .schedule_zoned_event(
+                "Standup",
+                utc(2024, 3, 8, 14),
+                utc(2024, 3, 8, 15),
+                "America/New_York",
+                Some(rule),
+            )
+            .unwrap();
+        assert_eq!(event.timezone.as_deref(), Some("America/New_York"));
+
+        let starts: Vec<Timestamp> = app
+            .list_active_events_in_range(utc(2024, 3, 8, 0), utc(2024, 3, 12, 0))
+            .iter()
+            .map(|o| o.start)
+            .collect();
+        assert_eq!(
+            starts,
+            vec![
+                utc(2024, 3, 8, 14),
+                utc(2024, 3, 9, 14),
+                utc(2024, 3, 10, 13),
+                utc(2024, 3, 11, 13),
+            ]
+        );
+
+        app.set_display_timezone(parse_timezone("America/New_York").unwrap());
+        assert!(starts
+            .iter()
+            .all(|&start| app.display_time(start).unwrap().hour() == 9));
+
+        // London has not changed its clocks yet, so the meeting moves earlier.
+        app.set_display_timezone(parse_timezone("Europe/London").unwrap());
+        let hours: Vec<u32> = starts
+            .iter()
+            .map(|&start| app.display_time(start).unwrap().hour())
+            .collect();
+        assert_eq!(hours, vec![14, 14, 13, 13]);
+    }
+
     #[test]
     fn event_overlap_detection() {
         let event = CalendarEvent::new("Test", 1000, 2000).unwrap();
@@ -1,18 +1,24 @@
 //! Document application orchestrator.
 //!
 //! Provides ledger-backed document management with full version history
-//! and Merkle proofs for every save operation.
+//! and Merkle proofs for every save operation. Updates store a line-based
+//! diff against the previous version, with a full snapshot every
+//! `snapshot_interval` versions to bound replay cost.
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use blake3::Hasher;
 use ed25519_dalek::SigningKey;
-use ledger_core::brainstem::{AppendReceipt, Ledger};
+use ledger_core::brainstem::{AppendReceipt, Ledger, SliceQuery};
+use ledger_core::MerkleReceipt;
 use ledger_spec::events::ContentRef;
-use ledger_spec::{ChannelRegistry, Hash, SchemaVersion, Timestamp};
+use ledger_spec::{ChannelRegistry, Envelope, Hash, SchemaVersion, Timestamp};
+use serde::{Deserialize, Serialize};
 
-use crate::events::OfficeEvent;
+use crate::events::{self, now_millis, OfficeEvent, OFFICE_PAYLOAD_TYPE};
+use crate::permissions::{Permissions, WriteDenied};
+use crate::search::{SearchHit, SearchIndex};
 
 /// Errors from document operations.
 #[derive(Debug, thiserror::Error)]
@@ -20,12 +26,185 @@ pub enum DocumentError {
     /// Document not found.
     #[error("document not found: {0}")]
     NotFound(String),
+    /// This app instance is read-only.
+    #[error("permission denied: app is read-only")]
+    PermissionDenied,
     /// Ledger operation failed.
     #[error("ledger error: {0}")]
     Ledger(#[from] ledger_core::apps::AppError),
     /// Serialization failed.
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+    /// Requested version was never recorded for the document.
+    #[error("version {version} not found for document {id}")]
+    VersionNotFound {
+        /// Hex-encoded document identifier.
+        id: String,
+        /// Requested version.
+        version: u64,
+    },
+    /// Stored content or diff could not be loaded or did not match its hash.
+    #[error("content unavailable: {0}")]
+    ContentUnavailable(String),
+    /// The document advanced past the version the edit was based on.
+    #[error("version conflict: edit based on version {expected}, latest is {actual}")]
+    VersionConflict {
+        /// Base version the caller edited.
+        expected: u64,
+        /// Latest version recorded on the ledger.
+        actual: u64,
+    },
+}
+
+impl From<WriteDenied> for DocumentError {
+    fn from(_: WriteDenied) -> Self {
+        Self::PermissionDenied
+    }
+}
+
+/// Default number of versions between full content snapshots.
+pub const DEFAULT_SNAPSHOT_INTERVAL: u64 = 16;
+
+/// Product of changed-region line counts above which diffing falls back
+/// to replacing the whole region.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+/// One edit operation in a [`LineDiff`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LineOp {
+    /// Copy the next `n` lines from the previous version.
+    Keep(usize),
+    /// Skip the next `n` lines of the previous version.
+    Delete(usize),
+    /// Insert these lines (including their line terminators).
+    Insert(Vec<String>),
+}
+
+/// Line-based diff between two versions of a document.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LineDiff {
+    /// Edit operations applied in order against the previous version.
+    pub ops: Vec<LineOp>,
+}
+
+impl LineDiff {
+    /// Compute the diff turning `old` into `new`.
+    pub fn compute(old: &str, new: &str) -> Self {
+        let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+
+        let prefix = old_lines
+            .iter()
+            .zip(&new_lines)
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = old_lines[prefix..]
+            .iter()
+            .rev()
+            .zip(new_lines[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let mut diff = Self::default();
+        diff.push(LineOp::Keep(prefix));
+        diff.diff_middle(
+            &old_lines[prefix..old_lines.len() - suffix],
+            &new_lines[prefix..new_lines.len() - suffix],
+        );
+        diff.push(LineOp::Keep(suffix));
+        diff
+    }
+
+    /// Apply the diff to `old`, returning the new content.
+    pub fn apply(&self, old: &str) -> Result<String, DocumentError> {
+        let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+        let mut pos = 0;
+        let mut out = String::with_capacity(old.len());
+        for op in &self.ops {
+            match op {
+                LineOp::Keep(n) | LineOp::Delete(n) => {
+                    let end = pos + n;
+                    let lines = old_lines.get(pos..end).ok_or_else(|| {
+                        DocumentError::ContentUnavailable("diff exceeds base content".into())
+                    })?;
+                    if matches!(op, LineOp::Keep(_)) {
+                        out.extend(lines.iter().copied());
+                    }
+                    pos = end;
+                }
+                LineOp::Insert(lines) => out.extend(lines.iter().map(String::as_str)),
+            }
+        }
+        if pos != old_lines.len() {
+            return Err(DocumentError::ContentUnavailable(
+                "diff does not cover base content".into(),
+            ));
+        }
+        Ok(out)
+    }
+
+    /// Diff the changed region with an LCS table, or replace it wholesale
+    /// when the table would be too large.
+    fn diff_middle(&mut self, old: &[&str], new: &[&str]) {
+        if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+            self.push(LineOp::Delete(old.len()));
+            self.push(LineOp::Insert(new.iter().map(|l| l.to_string()).collect()));
+            return;
+        }
+
+        // lcs[i][j] = LCS length of old[i..] and new[j..].
+        let mut lcs = vec![vec![0u32; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                self.push(LineOp::Keep(1));
+                i += 1;
+                j += 1;
+            } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                self.push(LineOp::Insert(vec![new[j].to_string()]));
+                j += 1;
+            } else {
+                self.push(LineOp::Delete(1));
+                i += 1;
+            }
+        }
+    }
+
+    /// Append an op, merging it into the previous op of the same kind.
+    fn push(&mut self, op: LineOp) {
+        match (self.ops.last_mut(), op) {
+            (_, LineOp::Keep(0) | LineOp::Delete(0)) => {}
+            (_, LineOp::Insert(lines)) if lines.is_empty() => {}
+            (Some(LineOp::Keep(n)), LineOp::Keep(m)) => *n += m,
+            (Some(LineOp::Delete(n)), LineOp::Delete(m)) => *n += m,
+            (Some(LineOp::Insert(lines)), LineOp::Insert(more)) => lines.extend(more),
+            (_, op) => self.ops.push(op),
+        }
+    }
+}
+
+/// How a single document version is stored.
+#[derive(Debug, Clone)]
+enum VersionEntry {
+    /// Full content stored in CAS.
+    Snapshot(ContentRef),
+    /// Serialized [`LineDiff`] against the previous version, stored in CAS.
+    Diff {
+        /// Reference to the diff bytes.
+        diff: ContentRef,
+        /// Hash of the content this version reconstructs to.
+        content_hash: Hash,
+    },
 }
 
 /// In-memory document state.
@@ -71,9 +250,20 @@ pub struct DocumentApp {
     ledger: Ledger,
     signer: Arc<SigningKey>,
     channel: String,
+    permissions: Permissions,
     schema_version: SchemaVersion,
     /// In-memory document index.
     documents: HashMap<Hash, Document>,
+    /// Per-document version storage, indexed by `version - 1`.
+    history: HashMap<Hash, Vec<VersionEntry>>,
+    /// Versions between full content snapshots.
+    snapshot_interval: u64,
+    /// Full-text index over the latest version of each document.
+    search_index: SearchIndex,
+    /// Latest ledger version of each live document on this channel.
+    ledger_versions: HashMap<Hash, u64>,
+    /// Log offset up to which channel events are folded into `ledger_versions`.
+    indexed_to: usize,
 }
 
 impl DocumentApp {
@@ -88,11 +278,36 @@ impl DocumentApp {
             ledger,
             signer: Arc::new(signer),
             channel: channel.into(),
+            permissions: Permissions::default(),
             schema_version,
             documents: HashMap::new(),
+            history: HashMap::new(),
+            snapshot_interval: DEFAULT_SNAPSHOT_INTERVAL,
+            search_index: SearchIndex::new(),
+            ledger_versions: HashMap::new(),
+            indexed_to: 0,
         }
     }
 
+    /// Restrict this instance to `permissions`. A read-only instance can
+    /// still open, reconstruct, export and search documents from the
+    /// ledger, e.g. for a reviewer sharing an author's ledger.
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Whether this instance may create, update and delete documents.
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
+    /// Store a full snapshot every `interval` versions (minimum 1).
+    pub fn with_snapshot_interval(mut self, interval: u64) -> Self {
+        self.snapshot_interval = interval.max(1);
+        self
+    }
+
     /// Store content in CAS and return a reference.
     fn store_content(&self, content: &str) -> ContentRef {
         let bytes = content.as_bytes().to_vec();
@@ -105,35 +320,77 @@ impl DocumentApp {
         }
     }
 
+    /// Store a serialized diff in CAS and return a reference.
+    fn store_diff(&self, diff: &LineDiff) -> Result<ContentRef, DocumentError> {
+        let bytes = serde_json::to_vec(diff)?;
+        let len = bytes.len() as u64;
+        let digest = self.ledger.content_store().put(bytes);
+        Ok(ContentRef {
+            locator: format!("cas:{}", hex::encode(digest)),
+            hash: digest,
+            media_type: Some("application/vnd.ea.line-diff+json".into()),
+            bytes: Some(len),
+        })
+    }
+
+    /// Reference to content reconstructed from diffs rather than stored in CAS.
+    fn derived_content_ref(content: &str) -> ContentRef {
+        let digest = *blake3::hash(content.as_bytes()).as_bytes();
+        ContentRef {
+            locator: format!("diff:{}", hex::encode(digest)),
+            hash: digest,
+            media_type: Some("text/markdown".into()),
+            bytes: Some(content.len() as u64),
+        }
+    }
+
+    /// Load raw bytes from CAS.
+    fn load_bytes(&self, reference: &ContentRef) -> Result<Vec<u8>, DocumentError> {
+        self.ledger
+            .content_store()
+            .get(&reference.hash)
+            .ok_or_else(|| DocumentError::ContentUnavailable(reference.locator.clone()))
+    }
+
+    /// Load content bytes from CAS as UTF-8.
+    fn load(&self, reference: &ContentRef) -> Result<String, DocumentError> {
+        let bytes = self.load_bytes(reference)?;
+        String::from_utf8(bytes)
+            .map_err(|_| DocumentError::ContentUnavailable(reference.locator.clone()))
+    }
+
     /// Append an office event to the ledger.
     fn append_office_event(&self, event: OfficeEvent) -> Result<AppendReceipt, DocumentError> {
-        // Wrap office event in JSON and use a custom payload type
-        let payload = serde_json::to_value(&event)?;
-        let body = ledger_spec::EnvelopeBody {
-            payload,
-            payload_type: Some("ea.office.v1".into()),
-        };
-        let body_hash = ledger_spec::hash_body(&body);
-        let mut env = ledger_spec::Envelope {
-            header: ledger_spec::EnvelopeHeader {
-                channel: self.channel.clone(),
-                version: self.schema_version,
-                prev: self.ledger.tail_hash(),
-                body_hash,
-                timestamp: now_millis(),
-            },
-            body,
-            signatures: Vec::new(),
-            attestations: Vec::new(),
-        };
-        ledger_core::signing::sign_envelope(&mut env, &self.signer);
-        self.ledger.append(env).map_err(|e| DocumentError::Ledger(
-            ledger_core::apps::AppError::Ledger(e)
-        ))
+        events::append_office_event(
+            &self.ledger,
+            &self.signer,
+            &self.channel,
+            self.schema_version,
+            self.permissions,
+            event,
+        )
+    }
+
+    /// Append an office event chained onto `prev` rather than the current tail.
+    fn append_office_event_after(
+        &self,
+        prev: Option<Hash>,
+        event: OfficeEvent,
+    ) -> Result<AppendReceipt, DocumentError> {
+        events::append_office_event_after(
+            &self.ledger,
+            &self.signer,
+            &self.channel,
+            self.schema_version,
+            self.permissions,
+            event,
+            prev,
+        )
     }
 
     /// Create a new document.
     pub fn create_document(&mut self, title: impl Into<String>) -> Result<(Document, AppendReceipt), DocumentError> {
+        self.permissions.check_write()?;
         let mut doc = Document::new(title);
         let content_ref = self.store_content(&doc.content);
         doc.content_ref = Some(content_ref.clone());
@@ -142,43 +399,344 @@ impl DocumentApp {
         let event = OfficeEvent::DocumentCreated {
             id: doc.id,
             title: doc.title.clone(),
-            content: content_ref,
+            content: content_ref.clone(),
         };
 
         let receipt = self.append_office_event(event)?;
+        self.history
+            .insert(doc.id, vec![VersionEntry::Snapshot(content_ref)]);
+        self.search_index
+            .index_document(doc.id, doc.version, &doc.content);
         self.documents.insert(doc.id, doc.clone());
         Ok((doc, receipt))
     }
 
     /// Update a document's content.
+    ///
+    /// `expected_base_version` is the version the edit was made against.
+    /// If the ledger already records a later version (for example from
+    /// another signer sharing the ledger), or this instance has not loaded
+    /// that version, the update is rejected with
+    /// [`DocumentError::VersionConflict`] instead of forking the history.
     pub fn update_document(
         &mut self,
         id: Hash,
+        expected_base_version: u64,
         new_content: impl Into<String>,
     ) -> Result<(Document, AppendReceipt), DocumentError> {
-        if !self.documents.contains_key(&id) {
-            return Err(DocumentError::NotFound(hex::encode(id)));
-        }
+        self.permissions.check_write()?;
+        let (old_content, local_version) = match self.documents.get(&id) {
+            Some(doc) => (doc.content.clone(), doc.version),
+            None => return Err(DocumentError::NotFound(hex::encode(id))),
+        };
+        let mut tail = self.ledger.tail_hash();
+        let version = self.check_base_version(id, expected_base_version, local_version)? + 1;
 
         let new_content = new_content.into();
-        let content_ref = self.store_content(&new_content);
+        let (content_ref, entry) = if (version - 1) % self.snapshot_interval == 0 {
+            let content_ref = self.store_content(&new_content);
+            (content_ref.clone(), VersionEntry::Snapshot(content_ref))
+        } else {
+            let diff = LineDiff::compute(&old_content, &new_content);
+            let diff_ref = self.store_diff(&diff)?;
+            let content_ref = Self::derived_content_ref(&new_content);
+            let entry = VersionEntry::Diff {
+                diff: diff_ref,
+                content_hash: content_ref.hash,
+            };
+            (content_ref, entry)
+        };
 
+        let event = OfficeEvent::DocumentUpdated {
+            id,
+            version,
+            content: content_ref.clone(),
+            diff: match &entry {
+                VersionEntry::Diff { diff, .. } => Some(diff.clone()),
+                VersionEntry::Snapshot(_) => None,
+            },
+        };
+        // Chain onto the tail the check saw, so an entry appended since then
+        // is caught by the log under its write lock; re-check and retry then.
+        let receipt = loop {
+            match self.append_office_event_after(tail, event.clone()) {
+                Ok(receipt) => break receipt,
+                Err(err) => {
+                    let current = self.ledger.tail_hash();
+                    if current == tail {
+                        return Err(err);
+                    }
+                    tail = current;
+                    self.check_base_version(id, expected_base_version, local_version)?;
+                }
+            }
+        };
+
+        self.history.entry(id).or_default().push(entry);
         let doc = self.documents.get_mut(&id).unwrap();
         doc.content = new_content;
-        doc.version += 1;
+        doc.version = version;
         doc.modified_at = now_millis();
-        doc.content_ref = Some(content_ref.clone());
+        doc.content_ref = Some(content_ref);
+        self.search_index.index_document(id, version, &doc.content);
+        Ok((doc.clone(), receipt))
+    }
 
-        let event = OfficeEvent::DocumentUpdated {
-            id: doc.id,
-            version: doc.version,
-            content: content_ref,
-            diff: None, // Full content stored in CAS; diff computation optional for future optimization
+    /// Reconstruct a document's content at `version` by replaying diffs
+    /// from the nearest preceding snapshot.
+    pub fn reconstruct(&self, id: Hash, version: u64) -> Result<String, DocumentError> {
+        let history = self
+            .history
+            .get(&id)
+            .ok_or_else(|| DocumentError::NotFound(hex::encode(id)))?;
+        let target = version
+            .checked_sub(1)
+            .map(|v| v as usize)
+            .filter(|v| *v < history.len())
+            .ok_or_else(|| DocumentError::VersionNotFound {
+                id: hex::encode(id),
+                version,
+            })?;
+
+        let base = history[..=target]
+            .iter()
+            .rposition(|entry| matches!(entry, VersionEntry::Snapshot(_)))
+            .ok_or_else(|| DocumentError::ContentUnavailable("no base snapshot".into()))?;
+
+        let mut content = String::new();
+        for entry in &history[base..=target] {
+            content = self.apply_entry(&content, entry)?;
+        }
+        Ok(content)
+    }
+
+    /// Produce the content of a version from the previous version's content.
+    fn apply_entry(&self, previous: &str, entry: &VersionEntry) -> Result<String, DocumentError> {
+        match entry {
+            VersionEntry::Snapshot(content_ref) => self.load(content_ref),
+            VersionEntry::Diff { diff, content_hash } => {
+                let diff: LineDiff = serde_json::from_str(&self.load(diff)?)?;
+                let content = diff.apply(previous)?;
+                if blake3::hash(content.as_bytes()).as_bytes() != content_hash {
+                    return Err(DocumentError::ContentUnavailable(format!(
+                        "reconstructed content hash mismatch at {}",
+                        hex::encode(content_hash)
+                    )));
+                }
+                Ok(content)
+            }
+        }
+    }
+
+    /// This app's office envelopes on the ledger, in log order, with
+    /// their inclusion receipts.
+    fn channel_entries(&self) -> Result<Vec<(Envelope, MerkleReceipt)>, DocumentError> {
+        let offsets = self.ledger.offsets_for_channel(&self.channel);
+        let (Some(first), Some(last)) = (offsets.first(), offsets.last()) else {
+            return Ok(Vec::new());
         };
+        let resp = self
+            .ledger
+            .query(SliceQuery {
+                from: *first,
+                limit: last - first + 1,
+                include_payloads: false,
+            })
+            .map_err(|e| DocumentError::Ledger(ledger_core::apps::AppError::Ledger(e)))?;
+        Ok(resp
+            .envelopes
+            .into_iter()
+            .zip(resp.receipts)
+            .filter(|(env, _)| env.header.channel == self.channel)
+            .filter(|(env, _)| env.body.payload_type.as_deref() == Some(OFFICE_PAYLOAD_TYPE))
+            .collect())
+    }
 
-        let doc = doc.clone();
-        let receipt = self.append_office_event(event)?;
-        Ok((doc, receipt))
+    /// Decode this app's office events from the ledger, in log order,
+    /// with their envelope timestamps.
+    fn ledger_events(&self) -> Result<Vec<(Timestamp, OfficeEvent)>, DocumentError> {
+        Ok(self
+            .channel_entries()?
+            .into_iter()
+            .filter_map(|(env, _)| {
+                let event = OfficeEvent::from_payload(&env.body.payload).ok()?;
+                Some((env.header.timestamp, event))
+            })
+            .collect())
+    }
+
+    /// Current version of `id`, provided the ledger and this instance are
+    /// both at `expected`.
+    fn check_base_version(
+        &mut self,
+        id: Hash,
+        expected: u64,
+        local_version: u64,
+    ) -> Result<u64, DocumentError> {
+        let actual = self.ledger_version(id)?.unwrap_or(local_version);
+        if expected != actual || local_version != actual {
+            return Err(DocumentError::VersionConflict { expected, actual });
+        }
+        Ok(actual)
+    }
+
+    /// Latest version of a document recorded on the ledger, or `None` if
+    /// it was never created or has been deleted.
+    ///
+    /// Only channel entries appended since the previous call, by this
+    /// instance or any other signer, are decoded and folded into the index.
+    fn ledger_version(&mut self, id: Hash) -> Result<Option<u64>, DocumentError> {
+        let offsets = self.ledger.offsets_for_channel(&self.channel);
+        let pending = &offsets[offsets.partition_point(|&offset| offset < self.indexed_to)..];
+        if let (Some(&first), Some(&last)) = (pending.first(), pending.last()) {
+            let resp = self
+                .ledger
+                .query(SliceQuery {
+                    from: first,
+                    limit: last - first + 1,
+                    include_payloads: false,
+                })
+                .map_err(|e| DocumentError::Ledger(ledger_core::apps::AppError::Ledger(e)))?;
+            for env in resp.envelopes {
+                if env.header.channel != self.channel
+                    || env.body.payload_type.as_deref() != Some(OFFICE_PAYLOAD_TYPE)
+                {
+                    continue;
+                }
+                match OfficeEvent::from_payload(&env.body.payload) {
+                    Ok(OfficeEvent::DocumentCreated { id, .. }) => {
+                        self.ledger_versions.insert(id, 1);
+                    }
+                    Ok(OfficeEvent::DocumentUpdated { id, version, .. }) => {
+                        self.ledger_versions.insert(id, version);
+                    }
+                    Ok(OfficeEvent::DocumentDeleted { id, .. }) => {
+                        self.ledger_versions.remove(&id);
+                    }
+                    _ => {}
+                }
+            }
+            self.indexed_to = last + 1;
+        }
+        Ok(self.ledger_versions.get(&id).copied())
+    }
+
+    /// Export a document's full version history with ledger receipts.
+    ///
+    /// Each version carries its envelope, inclusion receipt and the stored
+    /// blob (full content for snapshots, serialized diff otherwise), so the
+    /// bundle can be checked with [`verify_bundle`] without ledger access.
+    pub fn export_bundle(&self, id: Hash) -> Result<VersionBundle, DocumentError> {
+        let mut title = None;
+        let mut versions = Vec::new();
+        for (envelope, receipt) in self.channel_entries()? {
+            let Ok(event) = OfficeEvent::from_payload(&envelope.body.payload) else {
+                continue;
+            };
+            let (version, blob) = match event {
+                OfficeEvent::DocumentCreated {
+                    id: doc_id,
+                    title: t,
+                    content,
+                } if doc_id == id => {
+                    title = Some(t);
+                    versions.clear();
+                    (1, self.load_bytes(&content)?)
+                }
+                OfficeEvent::DocumentUpdated {
+                    id: doc_id,
+                    version,
+                    content,
+                    diff,
+                } if doc_id == id => (version, self.load_bytes(diff.as_ref().unwrap_or(&content))?),
+                OfficeEvent::DocumentDeleted { id: doc_id, .. } if doc_id == id => {
+                    title = None;
+                    versions.clear();
+                    continue;
+                }
+                _ => continue,
+            };
+            versions.push(BundledVersion {
+                version,
+                envelope,
+                receipt,
+                blob,
+            });
+        }
+
+        let (Some(title), Some(last)) = (title, versions.last()) else {
+            return Err(DocumentError::NotFound(hex::encode(id)));
+        };
+        Ok(VersionBundle {
+            doc_id: id,
+            title,
+            root: last.receipt.root,
+            versions,
+        })
+    }
+
+    /// Load the latest state of a document from the ledger, replacing any
+    /// in-memory copy. Used to pick up edits made by other signers.
+    pub fn open_document(&mut self, id: Hash) -> Result<Document, DocumentError> {
+        let mut doc: Option<Document> = None;
+        let mut history = Vec::new();
+        for (timestamp, event) in self.ledger_events()? {
+            match event {
+                OfficeEvent::DocumentCreated {
+                    id: doc_id,
+                    title,
+                    content,
+                } if doc_id == id => {
+                    let entry = VersionEntry::Snapshot(content.clone());
+                    doc = Some(Document {
+                        id,
+                        title,
+                        content: self.apply_entry("", &entry)?,
+                        version: 1,
+                        modified_at: timestamp,
+                        content_ref: Some(content),
+                    });
+                    history = vec![entry];
+                }
+                OfficeEvent::DocumentUpdated {
+                    id: doc_id,
+                    version,
+                    content,
+                    diff,
+                } if doc_id == id => {
+                    let Some(doc) = doc.as_mut() else {
+                        continue;
+                    };
+                    let entry = match diff {
+                        Some(diff) => VersionEntry::Diff {
+                            diff,
+                            content_hash: content.hash,
+                        },
+                        None => VersionEntry::Snapshot(content.clone()),
+                    };
+                    doc.content = self.apply_entry(&doc.content, &entry)?;
+                    doc.version = version;
+                    doc.modified_at = timestamp;
+                    doc.content_ref = Some(content);
+                    history.push(entry);
+                }
+                OfficeEvent::DocumentDeleted { id: doc_id, .. } if doc_id == id => {
+                    doc = None;
+                    history.clear();
+                }
+                _ => {}
+            }
+        }
+
+        let Some(doc) = doc else {
+            self.search_index.remove_document(id);
+            return Err(DocumentError::NotFound(hex::encode(id)));
+        };
+        self.search_index
+            .index_document(id, doc.version, &doc.content);
+        self.history.insert(id, history);
+        self.documents.insert(id, doc.clone());
+        Ok(doc)
     }
 
     /// Delete a document.
@@ -198,9 +756,58 @@ impl DocumentApp {
 
         let receipt = self.append_office_event(event)?;
         self.documents.remove(&id);
+        self.search_index.remove_document(id);
         Ok(receipt)
     }
 
+    /// Search the latest version of every indexed document for all terms
+    /// of `query` (whitespace-separated, case-insensitive).
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        self.search_index.search(query)
+    }
+
+    /// Rebuild the search index from scratch by replaying this channel's
+    /// document events, covering documents written by other signers.
+    pub fn rebuild_search_index(&mut self) -> Result<(), DocumentError> {
+        let mut index = SearchIndex::new();
+        let mut contents: HashMap<Hash, String> = HashMap::new();
+        for (_, event) in self.ledger_events()? {
+            match event {
+                OfficeEvent::DocumentCreated { id, content, .. } => {
+                    let text = self.apply_entry("", &VersionEntry::Snapshot(content))?;
+                    index.index_document(id, 1, &text);
+                    contents.insert(id, text);
+                }
+                OfficeEvent::DocumentUpdated {
+                    id,
+                    version,
+                    content,
+                    diff,
+                } => {
+                    let Some(previous) = contents.get_mut(&id) else {
+                        continue;
+                    };
+                    let entry = match diff {
+                        Some(diff) => VersionEntry::Diff {
+                            diff,
+                            content_hash: content.hash,
+                        },
+                        None => VersionEntry::Snapshot(content),
+                    };
+                    *previous = self.apply_entry(previous, &entry)?;
+                    index.index_document(id, version, previous);
+                }
+                OfficeEvent::DocumentDeleted { id, .. } => {
+                    index.remove_document(id);
+                    contents.remove(&id);
+                }
+                _ => {}
+            }
+        }
+        self.search_index = index;
+        Ok(())
+    }
+
     /// Get a document by ID.
     pub fn get_document(&self, id: &Hash) -> Option<&Document> {
         self.documents.get(id)
@@ -217,12 +824,241 @@ impl DocumentApp {
     }
 }
 
-fn now_millis() -> Timestamp {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+/// One version of a document inside a [`VersionBundle`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BundledVersion {
+    /// Document version number.
+    pub version: u64,
+    /// Ledger envelope that recorded this version.
+    pub envelope: Envelope,
+    /// Inclusion proof for the envelope.
+    pub receipt: MerkleReceipt,
+    /// Full content for snapshot versions, or the serialized [`LineDiff`].
+    pub blob: Vec<u8>,
+}
+
+/// Self-contained version history of a document with Merkle proofs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VersionBundle {
+    /// Document identifier.
+    pub doc_id: Hash,
+    /// Document title at creation.
+    pub title: String,
+    /// Merkle root every receipt was generated against.
+    pub root: Hash,
+    /// Versions in order, starting from creation.
+    pub versions: Vec<BundledVersion>,
+}
+
+impl VersionBundle {
+    /// Serialize the bundle to JSON.
+    pub fn to_json(&self) -> Result<Vec<u8>, DocumentError> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Deserialize a bundle from JSON.
+    pub fn from_json(bytes: &[u8]) -> Result<Self, DocumentError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Reasons a [`VersionBundle`] fails verification.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BundleError {
+    /// The bundle was exported against a different ledger root.
+    #[error("bundle root does not match expected root")]
+    RootMismatch,
+    /// The bundle carries no versions.
+    #[error("bundle contains no versions")]
+    Empty,
+    /// A version failed an integrity check.
+    #[error("version {version}: {reason}")]
+    InvalidVersion {
+        /// Version number as recorded in the bundle.
+        version: u64,
+        /// What failed.
+        reason: String,
+    },
+}
+
+/// Verify a bundle against a trusted Merkle root without ledger access.
+///
+/// Checks that every envelope body matches its header, every receipt
+/// proves that envelope under `expected_root`, versions are consecutive,
+/// and each blob hashes to the reference recorded in its event (replaying
+/// diffs to check reconstructed content). Returns the latest content.
+pub fn verify_bundle(bundle: &VersionBundle, expected_root: &Hash) -> Result<String, BundleError> {
+    if &bundle.root != expected_root {
+        return Err(BundleError::RootMismatch);
+    }
+    if bundle.versions.is_empty() {
+        return Err(BundleError::Empty);
+    }
+
+    let mut content = String::new();
+    for (i, entry) in bundle.versions.iter().enumerate() {
+        let fail = |reason: &str| BundleError::InvalidVersion {
+            version: entry.version,
+            reason: reason.to_string(),
+        };
+        let env = &entry.envelope;
+        if entry.version != i as u64 + 1 {
+            return Err(fail("version out of sequence"));
+        }
+        if ledger_spec::hash_body_for(&env.body, env.header.version) != env.header.body_hash {
+            return Err(fail("body does not match header"));
+        }
+        if ledger_spec::envelope_hash(env) != entry.receipt.leaf {
+            return Err(fail("receipt does not cover envelope"));
+        }
+        if entry.receipt.root != bundle.root || !entry.receipt.verify() {
+            return Err(fail("invalid inclusion proof"));
+        }
+
+        let blob_hash = *blake3::hash(&entry.blob).as_bytes();
+        let event = OfficeEvent::from_payload(&env.body.payload)
+            .map_err(|_| fail("payload is not an office event"))?;
+        let (reference, expected_content) = match event {
+            OfficeEvent::DocumentCreated { id, title, content }
+                if i == 0 && id == bundle.doc_id && title == bundle.title =>
+            {
+                (content, None)
+            }
+            OfficeEvent::DocumentUpdated {
+                id,
+                version,
+                content,
+                diff,
+            } if i > 0 && id == bundle.doc_id && version == entry.version => match diff {
+                Some(diff) => (diff, Some(content.hash)),
+                None => (content, None),
+            },
+            _ => return Err(fail("unexpected event for document")),
+        };
+        if reference.hash != blob_hash {
+            return Err(fail("blob does not match recorded hash"));
+        }
+
+        content = match expected_content {
+            None => {
+                String::from_utf8(entry.blob.clone()).map_err(|_| fail("content is not UTF-8"))?
+            }
+            Some(content_hash) => {
+                let diff: LineDiff =
+                    serde_json::from_slice(&entry.blob).map_err(|_| fail("malformed diff"))?;
+                let next = diff
+                    .apply(&content)
+                    .map_err(|_| fail("diff does not apply"))?;
+                if blake3::hash(next.as_bytes()).as_bytes() != &content_hash {
+                    return Err(fail("reconstructed content hash mismatch"));
+                }
+                next
+            }
+        };
+    }
+    Ok(content)
+}
+
+/// Presentation of a rendered markdown span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanKind {
+    /// Unstyled text.
+    Text,
+    /// ATX heading text, with its level (1-6).
+    Heading(u8),
+    /// `**bold**` text.
+    Bold,
+    /// `*italic*` text.
+    Italic,
+    /// `` `code` `` text.
+    Code,
+    /// Bullet replacing a `- ` list marker.
+    Bullet,
+}
+
+/// A run of rendered markdown text sharing one style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StyledSpan {
+    /// Source line the span was rendered from (0-based).
+    pub line: usize,
+    /// How the span is presented.
+    pub kind: SpanKind,
+    /// Text with markup removed.
+    pub text: String,
+}
+
+/// Render the markdown subset the preview pane understands.
+///
+/// Supports ATX headings, `- ` list items, and inline `**bold**`,
+/// `*italic*` and `` `code` ``. Unclosed markers are kept as literal text;
+/// blank lines produce no spans.
+pub fn render_markdown(src: &str) -> Vec<StyledSpan> {
+    let mut spans = Vec::new();
+    for (line, text) in src.lines().enumerate() {
+        let hashes = text.chars().take_while(|&c| c == '#').count();
+        if (1..=6).contains(&hashes) && text[hashes..].starts_with(' ') {
+            spans.push(StyledSpan {
+                line,
+                kind: SpanKind::Heading(hashes as u8),
+                text: text[hashes..].trim().to_string(),
+            });
+        } else if let Some(item) = text.strip_prefix("- ") {
+            spans.push(StyledSpan {
+                line,
+                kind: SpanKind::Bullet,
+                text: "• ".into(),
+            });
+            render_inline(item, line, &mut spans);
+        } else {
+            render_inline(text, line, &mut spans);
+        }
+    }
+    spans
+}
+
+/// Split one line into plain, bold, italic and code spans.
+fn render_inline(text: &str, line: usize, spans: &mut Vec<StyledSpan>) {
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let marker = if rest.starts_with('`') {
+            Some(("`", SpanKind::Code))
+        } else if rest.starts_with("**") {
+            Some(("**", SpanKind::Bold))
+        } else if rest.starts_with('*') {
+            Some(("*", SpanKind::Italic))
+        } else {
+            None
+        };
+        if let Some((delim, kind)) = marker {
+            let body = &rest[delim.len()..];
+            if let Some(end) = body.find(delim).filter(|&end| end > 0) {
+                if !plain.is_empty() {
+                    spans.push(StyledSpan {
+                        line,
+                        kind: SpanKind::Text,
+                        text: std::mem::take(&mut plain),
+                    });
+                }
+                spans.push(StyledSpan {
+                    line,
+                    kind,
+                    text: body[..end].to_string(),
+                });
+                rest = &body[end + delim.len()..];
+                continue;
+            }
+        }
+        plain.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    if !plain.is_empty() {
+        spans.push(StyledSpan {
+            line,
+            kind: SpanKind::Text,
+            text: plain,
+        });
+    }
 }
 
 #[cfg(test)]
@@ -232,6 +1068,10 @@ mod tests {
     use rand_core::OsRng;
 
     fn test_app() -> DocumentApp {
+        test_app_at(1)
+    }
+
+    fn test_app_at(schema_version: SchemaVersion) -> DocumentApp {
         let signer = SigningKey::generate(&mut OsRng);
         let mut registry = ChannelRegistry::new();
         registry.upsert(ChannelSpec {
@@ -239,12 +1079,22 @@ mod tests {
             policy: ledger_spec::ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: vec![signer.verifying_key().to_bytes()],
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
         let ledger = Ledger::new(registry);
-        DocumentApp::new(ledger, signer, "office.documents", 1)
+        DocumentApp::new(ledger, signer, "office.documents", schema_version)
+    }
+
+    #[test]
+    fn canonical_schema_version_appends_validate() {
+        let mut app = test_app_at(ledger_spec::CANONICAL_BODY_VERSION);
+
+        let (doc, _) = app.create_document("Canonical").unwrap();
+        app.update_document(doc.id, 1, "# Canonical").unwrap();
     }
 
     #[test]
@@ -256,7 +1106,7 @@ mod tests {
         assert_eq!(doc.version, 1);
         assert!(receipt.merkle.verify());
 
-        let (updated, receipt2) = app.update_document(doc.id, "Hello, world!").unwrap();
+        let (updated, receipt2) = app.update_document(doc.id, 1, "Hello, world!").unwrap();
         assert_eq!(updated.content, "Hello, world!");
         assert_eq!(updated.version, 2);
         assert!(receipt2.merkle.verify());
@@ -285,4 +1135,349 @@ mod tests {
         let docs = app.list_documents();
         assert_eq!(docs.len(), 3);
     }
+
+    #[test]
+    fn concurrent_edit_is_rejected_with_conflict() {
+        let signer_a = SigningKey::generate(&mut OsRng);
+        let signer_b = SigningKey::generate(&mut OsRng);
+        let mut registry = ChannelRegistry::new();
+        registry.upsert(ChannelSpec {
+            name: "office.documents".into(),
+            policy: ledger_spec::ChannelPolicy {
+                min_signers: 1,
+                allowed_signers: vec![
+                    signer_a.verifying_key().to_bytes(),
+                    signer_b.verifying_key().to_bytes(),
+                ],
+                signer_weights: Vec::new(),
+                require_attestations: false,
+                enforce_timestamp_ordering: false,
+            },
+            schemas: Default::default(),
+        });
+        let ledger = Ledger::new(registry);
+        let mut alice = DocumentApp::new(ledger.clone(), signer_a, "office.documents", 1);
+        let mut bob = DocumentApp::new(ledger, signer_b, "office.documents", 1);
+
+        let (doc, _) = alice.create_document("Shared").unwrap();
+        let (alice_base, _) = alice.update_document(doc.id, 1, "first\n").unwrap();
+        let bob_base = bob.open_document(doc.id).unwrap();
+        assert_eq!(bob_base.version, alice_base.version);
+        assert_eq!(bob_base.content, "first\n");
+
+        // Both edit version 2; Alice saves first.
+        alice.update_document(doc.id, 2, "first\nalice\n").unwrap();
+        let err = bob.update_document(doc.id, 2, "first\nbob\n").unwrap_err();
+        assert!(matches!(
+            err,
+            DocumentError::VersionConflict {
+                expected: 2,
+                actual: 3
+            }
+        ));
+        assert_eq!(
+            alice.ledger().offsets_for_channel("office.documents").len(),
+            3
+        );
+
+        // After reloading, Bob can apply his edit on top of version 3.
+        let reloaded = bob.open_document(doc.id).unwrap();
+        assert_eq!(reloaded.content, "first\nalice\n");
+        let (merged, _) = bob
+            .update_document(doc.id, 3, "first\nalice\nbob\n")
+            .unwrap();
+        assert_eq!(merged.version, 4);
+        assert_eq!(
+            alice.open_document(doc.id).unwrap().content,
+            "first\nalice\nbob\n"
+        );
+        assert_eq!(alice.reconstruct(doc.id, 3).unwrap(), "first\nalice\n");
+    }
+
+    #[test]
+    fn read_only_app_serves_queries_but_rejects_writes() {
+        let editor_key = SigningKey::generate(&mut OsRng);
+        let viewer_key = SigningKey::generate(&mut OsRng);
+        let mut registry = ChannelRegistry::new();
+        registry.upsert(ChannelSpec {
+            name: "office.documents".into(),
+            policy: ledger_spec::ChannelPolicy {
+                min_signers: 1,
+                allowed_signers: vec![
+                    editor_key.verifying_key().to_bytes(),
+                    viewer_key.verifying_key().to_bytes(),
+                ],
+                signer_weights: Vec::new(),
+                require_attestations: false,
+                enforce_timestamp_ordering: false,
+            },
+            schemas: Default::default(),
+        });
+        let ledger = Ledger::new(registry);
+        let mut editor = DocumentApp::new(ledger.clone(), editor_key, "office.documents", 1);
+        let mut viewer = DocumentApp::new(ledger, viewer_key, "office.documents", 1)
+            .with_permissions(Permissions::ReadOnly);
+        assert_eq!(editor.permissions(), Permissions::ReadWrite);
+
+        let (doc, _) = editor.create_document("Shared").unwrap();
+        editor.update_document(doc.id, 1, "draft\n").unwrap();
+
+        let opened = viewer.open_document(doc.id).unwrap();
+        assert_eq!(opened.content, "draft\n");
+        let listed = viewer.list_documents();
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].id, doc.id);
+
+        let err = viewer
+            .update_document(doc.id, 2, "draft\nviewer edit\n")
+            .unwrap_err();
+        assert!(matches!(err, DocumentError::PermissionDenied));
+        assert!(matches!(
+            viewer.create_document("Mine").unwrap_err(),
+            DocumentError::PermissionDenied
+        ));
+        assert_eq!(
+            editor
+                .ledger()
+                .offsets_for_channel("office.documents")
+                .len(),
+            2
+        );
+        assert_eq!(viewer.get_document(&doc.id).unwrap().version, 2);
+    }
+
+    /// Six versions of a document, and the ledger root after the last edit.
+    fn bundle_fixture() -> (DocumentApp, VersionBundle, Hash) {
+        let mut app = test_app().with_snapshot_interval(3);
+        let (doc, _) = app.create_document("Audited").unwrap();
+        let mut content = String::new();
+        let mut root = [0u8; 32];
+        for i in 0..5u64 {
+            content.push_str(&format!("clause {i}\n"));
+            let (_, receipt) = app.update_document(doc.id, i + 1, content.clone()).unwrap();
+            root = receipt.merkle.root;
+        }
+        let bundle = app.export_bundle(doc.id).unwrap();
+        (app, bundle, root)
+    }
+
+    #[test]
+    fn exported_bundle_verifies() {
+        let (app, bundle, root) = bundle_fixture();
+        assert_eq!(bundle.versions.len(), 6);
+
+        let restored = VersionBundle::from_json(&bundle.to_json().unwrap()).unwrap();
+        assert_eq!(restored, bundle);
+        let latest = verify_bundle(&restored, &root).unwrap();
+        assert_eq!(latest, app.reconstruct(bundle.doc_id, 6).unwrap());
+
+        assert_eq!(
+            verify_bundle(&bundle, &[0u8; 32]),
+            Err(BundleError::RootMismatch)
+        );
+    }
+
+    #[test]
+    fn tampered_bundle_fails_verification() {
+        let (_, bundle, root) = bundle_fixture();
+
+        for i in 0..bundle.versions.len() {
+            let mut tampered = bundle.clone();
+            let blob = &mut tampered.versions[i].blob;
+            match blob.last_mut() {
+                Some(byte) => *byte ^= 0x01,
+                None => blob.push(b'x'),
+            }
+            assert_eq!(
+                verify_bundle(&tampered, &root).map_err(|e| match e {
+                    BundleError::InvalidVersion { version, .. } => version,
+                    _ => 0,
+                }),
+                Err(i as u64 + 1)
+            );
+        }
+
+        let mut tampered = bundle.clone();
+        tampered.versions[2].envelope.body.payload["data"]["version"] = serde_json::json!(7);
+        assert!(verify_bundle(&tampered, &root).is_err());
+
+        let mut tampered = bundle;
+        tampered.versions.remove(1);
+        assert!(verify_bundle(&tampered, &root).is_err());
+    }
+
+    #[test]
+    fn line_diff_round_trips() {
+        let cases = [
+            ("", "hello\n"),
+            ("a\nb\nc\n", "a\nc\n"),
+            ("a\nb\nc", "x\na\nc\ny"),
+            ("one\ntwo\n", ""),
+            ("same\n", "same\n"),
+        ];
+        for (old, new) in cases {
+            let diff = LineDiff::compute(old, new);
+            assert_eq!(diff.apply(old).unwrap(), new);
+        }
+    }
+
+    #[test]
+    fn single_line_edit_stores_small_diff() {
+        let mut app = test_app();
+        let (doc, _) = app.create_document("Large").unwrap();
+
+        let mut lines: Vec<String> = (0..1000).map(|i| format!("line number {i}\n")).collect();
+        let original = lines.concat();
+        app.update_document(doc.id, 1, original.clone()).unwrap();
+
+        lines[500] = "edited line\n".into();
+        let edited = lines.concat();
+        app.update_document(doc.id, 2, edited.clone()).unwrap();
+
+        let diff_ref = match app.history[&doc.id].last().unwrap() {
+            VersionEntry::Diff { diff, .. } => diff.clone(),
+            VersionEntry::Snapshot(_) => panic!("expected a diff entry"),
+        };
+        let diff_len = diff_ref.bytes.unwrap();
+        assert!(diff_len < 200, "diff was {diff_len} bytes");
+        assert!(diff_len * 50 < edited.len() as u64);
+        assert_eq!(app.reconstruct(doc.id, 3).unwrap(), edited);
+    }
+
+    #[test]
+    fn update_chained_onto_stale_tail_is_rejected() {
+        let mut app = test_app();
+        let (doc, _) = app.create_document("Race").unwrap();
+        let stale = app.ledger().tail_hash();
+        app.update_document(doc.id, 1, "first\n").unwrap();
+
+        // An update signed against the tail its version check saw cannot
+        // land once another entry has been logged after that tail
+        let event = OfficeEvent::DocumentUpdated {
+            id: doc.id,
+            version: 2,
+            content: DocumentApp::derived_content_ref("second\n"),
+            diff: None,
+        };
+        assert!(app.append_office_event_after(stale, event).is_err());
+        assert_eq!(
+            app.ledger().offsets_for_channel("office.documents").len(),
+            2
+        );
+    }
+
+    #[test]
+    fn ledger_version_index_folds_in_only_new_entries() {
+        let mut app = test_app();
+        let (doc, _) = app.create_document("Notes").unwrap();
+        assert_eq!(app.ledger_version(doc.id).unwrap(), Some(1));
+        assert_eq!(app.indexed_to, 1);
+
+        // The update checks the index before appending, then its own
+        // entry is folded in on the next lookup
+        app.update_document(doc.id, 1, "draft\n").unwrap();
+        assert_eq!(app.indexed_to, 1);
+        assert_eq!(app.ledger_version(doc.id).unwrap(), Some(2));
+        assert_eq!(app.indexed_to, 2);
+
+        app.delete_document(doc.id, "done").unwrap();
+        assert_eq!(app.ledger_version(doc.id).unwrap(), None);
+        assert_eq!(app.indexed_to, 3);
+    }
+
+    #[test]
+    fn reconstruct_every_historical_version() {
+        let mut app = test_app().with_snapshot_interval(4);
+        let (doc, _) = app.create_document("History").unwrap();
+
+        let mut expected = vec![String::new()];
+        let mut lines: Vec<String> = Vec::new();
+        for i in 0..10 {
+            lines.push(format!("entry {i}\n"));
+            if i % 3 == 0 {
+                lines.remove(0);
+            }
+            let content = lines.concat();
+            app.update_document(doc.id, i + 1, content.clone()).unwrap();
+            expected.push(content);
+        }
+
+        for (i, content) in expected.iter().enumerate() {
+            assert_eq!(&app.reconstruct(doc.id, i as u64 + 1).unwrap(), content);
+        }
+        assert!(matches!(
+            app.reconstruct(doc.id, 12),
+            Err(DocumentError::VersionNotFound { .. })
+        ));
+        assert!(matches!(
+            app.reconstruct(doc.id, 0),
+            Err(DocumentError::VersionNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn search_finds_matching_documents() {
+        let mut app = test_app();
+        let (a, _) = app.create_document("Plans").unwrap();
+        let (b, _) = app.create_document("Recipes").unwrap();
+        let (c, _) = app.create_document("Notes").unwrap();
+        app.update_document(a.id, 1, "Quarterly plans\nShip the Ledger sync\n")
+            .unwrap();
+        app.update_document(b.id, 1, "Soup\nBread and butter\n")
+            .unwrap();
+        app.update_document(c.id, 1, "Meeting notes\nledger replay is slow\n")
+            .unwrap();
+
+        let mut hits = app.search("LEDGER");
+        hits.sort_by_key(|hit| hit.snippet.clone());
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].doc_id, a.id);
+        assert_eq!(hits[0].version, 2);
+        assert_eq!(hits[0].snippet, "Ship the Ledger sync");
+        assert_eq!(hits[1].doc_id, c.id);
+        assert_eq!(hits[1].snippet, "ledger replay is slow");
+
+        let live = app.search("ledger");
+        app.rebuild_search_index().unwrap();
+        assert_eq!(app.search("ledger"), live);
+
+        app.delete_document(c.id, "done").unwrap();
+        assert_eq!(app.search("ledger").len(), 1);
+        app.rebuild_search_index().unwrap();
+        assert_eq!(app.search("ledger").len(), 1);
+    }
+
+    #[test]
+    fn markdown_heading_renders_as_one_span() {
+        let spans = render_markdown("## Release notes");
+        assert_eq!(
+            spans,
+            vec![StyledSpan {
+                line: 0,
+                kind: SpanKind::Heading(2),
+                text: "Release notes".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn markdown_inline_bold_splits_spans() {
+        let spans = render_markdown("plain\n- ship **now** or `later`\n*open");
+        let kinds: Vec<_> = spans
+            .iter()
+            .map(|s| (s.line, s.kind, s.text.as_str()))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (0, SpanKind::Text, "plain"),
+                (1, SpanKind::Bullet, "• "),
+                (1, SpanKind::Text, "ship "),
+                (1, SpanKind::Bold, "now"),
+                (1, SpanKind::Text, " or "),
+                (1, SpanKind::Code, "later"),
+                (2, SpanKind::Text, "*open"),
+            ]
+        );
+    }
 }
@@ -3,10 +3,15 @@
 //! These events extend the core ledger event system with document,
 //! spreadsheet, file, and calendar operations.
 
+use ed25519_dalek::SigningKey;
+use ledger_core::apps::AppError;
+use ledger_core::brainstem::{AppendReceipt, Ledger};
 use ledger_spec::events::ContentRef;
-use ledger_spec::{Hash, Timestamp};
+use ledger_spec::{Hash, SchemaVersion, Timestamp};
 use serde::{Deserialize, Serialize};
 
+use crate::permissions::{Permissions, WriteDenied};
+
 /// Reference to a spreadsheet cell.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct CellRef {
@@ -28,6 +33,25 @@ impl CellRef {
         format!("{}{}", col_name, self.row + 1)
     }
 
+    /// Parse A1 notation (e.g., "B3"); column letters are case-insensitive.
+    pub fn from_a1(s: &str) -> Option<Self> {
+        let digits = s.find(|c: char| c.is_ascii_digit())?;
+        let (letters, number) = s.split_at(digits);
+        if letters.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+            return None;
+        }
+        let mut col: u32 = 0;
+        for c in letters.chars() {
+            let n = (c.to_ascii_uppercase() as u8 - b'A') as u32 + 1;
+            col = col.checked_mul(26)?.checked_add(n)?;
+        }
+        let row: u32 = number.parse().ok()?;
+        if row == 0 {
+            return None;
+        }
+        Some(Self::new(col - 1, row - 1))
+    }
+
     fn col_to_letter(col: u32) -> String {
         let mut result = String::new();
         let mut n = col;
@@ -78,6 +102,62 @@ pub struct FileMetadata {
     pub is_directory: bool,
 }
 
+/// Day of the week for weekly recurrence rules.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Weekday {
+    /// Monday.
+    Mon,
+    /// Tuesday.
+    Tue,
+    /// Wednesday.
+    Wed,
+    /// Thursday.
+    Thu,
+    /// Friday.
+    Fri,
+    /// Saturday.
+    Sat,
+    /// Sunday.
+    Sun,
+}
+
+impl Weekday {
+    /// Days since Monday (Monday = 0).
+    pub fn num_days_from_monday(self) -> u32 {
+        self as u32
+    }
+}
+
+/// How often a recurring event repeats.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum Frequency {
+    /// Every day.
+    Daily,
+    /// Every week on the given days (the start's weekday if empty).
+    Weekly {
+        /// Days of the week the event occurs on.
+        weekdays: Vec<Weekday>,
+    },
+    /// Every month on the given day; months without that day are skipped.
+    Monthly {
+        /// Day of the month (1-31).
+        day: u32,
+    },
+}
+
+/// RRULE-style recurrence rule for a calendar event.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    /// Repetition frequency.
+    pub frequency: Frequency,
+    /// Last allowed occurrence start (inclusive).
+    #[serde(default)]
+    pub until: Option<Timestamp>,
+    /// Maximum number of occurrences, including cancelled ones.
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
 /// Changes to a calendar event.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct EventChanges {
@@ -93,7 +173,22 @@ pub struct EventChanges {
     pub location: Option<String>,
 }
 
+/// Envelope payload type shared by every office event schema version.
+pub const OFFICE_PAYLOAD_TYPE: &str = "ea.office.v1";
+
+/// Schema version written into newly encoded office event payloads.
+///
+/// - 1: original layout; payloads carry no `version` field.
+/// - 2: `EventScheduled` gains `recurrence` and `OccurrenceCancelled` is added.
+/// - 3: `EventScheduled` gains `timezone`.
+/// - 4: `NameDefined` and `CellCommented` are added.
+pub const OFFICE_SCHEMA_VERSION: u32 = 4;
+
 /// Office application events recorded to the ledger.
+///
+/// Encoded adjacently tagged (`{"type": ..., "data": ...}`) alongside a
+/// top-level `version`; use [`OfficeEvent::to_payload`] and
+/// [`OfficeEvent::from_payload`] so historical payloads are migrated.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "data")]
 pub enum OfficeEvent {
@@ -165,6 +260,26 @@ pub enum OfficeEvent {
         updates: Vec<(CellRef, CellValue, Option<String>)>,
     },
 
+    /// A named range was defined or moved.
+    NameDefined {
+        /// Sheet identifier.
+        sheet_id: Hash,
+        /// Name as entered; names are case-insensitive.
+        name: String,
+        /// Range in A1 notation (e.g., "B2:B13").
+        range: String,
+    },
+
+    /// A comment was attached to a cell.
+    CellCommented {
+        /// Sheet identifier.
+        sheet_id: Hash,
+        /// Cell reference.
+        cell: CellRef,
+        /// Comment text; empty when the comment was removed.
+        comment: String,
+    },
+
     /// A spreadsheet was deleted.
     SheetDeleted {
         /// Sheet identifier.
@@ -200,6 +315,14 @@ pub enum OfficeEvent {
         path: String,
     },
 
+    /// A directory was deleted.
+    DirectoryDeleted {
+        /// Virtual path.
+        path: String,
+        /// Reason for deletion.
+        reason: String,
+    },
+
     /// A file or directory was moved.
     FileMoved {
         /// Original path.
@@ -226,7 +349,9 @@ pub enum OfficeEvent {
         /// Optional location.
         location: Option<String>,
         /// Recurrence rule (if any).
-        recurrence: Option<String>,
+        recurrence: Option<RecurrenceRule>,
+        /// IANA timezone the event's wall-clock time is anchored to (UTC if absent).
+        timezone: Option<String>,
     },
 
     /// A calendar event was modified.
@@ -244,6 +369,255 @@ pub enum OfficeEvent {
         /// Reason for cancellation.
         reason: String,
     },
+
+    /// A single occurrence of a recurring event was cancelled.
+    OccurrenceCancelled {
+        /// Event identifier.
+        id: Hash,
+        /// Start timestamp of the cancelled occurrence.
+        occurrence_start: Timestamp,
+        /// Reason for cancellation.
+        reason: String,
+    },
+}
+
+impl OfficeEvent {
+    /// Encode as an envelope payload tagged with the current schema version.
+    pub fn to_payload(&self) -> serde_json::Result<serde_json::Value> {
+        let mut payload = serde_json::to_value(self)?;
+        if let Some(obj) = payload.as_object_mut() {
+            obj.insert("version".into(), OFFICE_SCHEMA_VERSION.into());
+        }
+        Ok(payload)
+    }
+
+    /// Decode an envelope payload of any known schema version, migrating
+    /// older layouts to the current one first.
+    pub fn from_payload(payload: &serde_json::Value) -> serde_json::Result<Self> {
+        use serde::de::Error as _;
+
+        let version = match payload.get("version") {
+            None => 1,
+            Some(v) => v.as_u64().ok_or_else(|| {
+                serde_json::Error::custom("office event version is not an integer")
+            })?,
+        };
+        let mut payload = match version {
+            1 => migrate_v2_to_v3(migrate_v1_to_v2(payload.clone())?),
+            2 => migrate_v2_to_v3(payload.clone()),
+            // Version 4 only adds variants, so version 3 payloads decode as-is.
+            3 | 4 => payload.clone(),
+            other => {
+                return Err(serde_json::Error::custom(format!(
+                    "unsupported office event schema version {other}"
+                )))
+            }
+        };
+        if let Some(obj) = payload.as_object_mut() {
+            obj.remove("version");
+        }
+        serde_json::from_value(payload)
+    }
+}
+
+/// Upgrade a version 1 office payload to the version 2 layout.
+///
+/// Version 1 `EventScheduled` recurrences are free-form strings: a missing
+/// one becomes `null` and the rest are parsed into a [`RecurrenceRule`] by
+/// [`parse_v1_recurrence`]. A string that cannot be parsed is an error
+/// rather than being dropped. Every other variant is unchanged.
+pub fn migrate_v1_to_v2(mut payload: serde_json::Value) -> serde_json::Result<serde_json::Value> {
+    use serde::de::Error as _;
+
+    if payload.get("type").and_then(|t| t.as_str()) == Some("EventScheduled") {
+        if let Some(data) = payload.get_mut("data").and_then(|d| d.as_object_mut()) {
+            let start = data.get("start").and_then(|s| s.as_u64()).unwrap_or(0);
+            let recurrence = match data.get("recurrence") {
+                Some(serde_json::Value::String(text)) => {
+                    let rule = parse_v1_recurrence(text, start).ok_or_else(|| {
+                        serde_json::Error::custom(format!("unrecognised v1 recurrence {text:?}"))
+                    })?;
+                    serde_json::to_value(rule)?
+                }
+                Some(other) => other.clone(),
+                None => serde_json::Value::Null,
+            };
+            data.insert("recurrence".into(), recurrence);
+        }
+    }
+    if let Some(obj) = payload.as_object_mut() {
+        obj.insert("version".into(), 2.into());
+    }
+    Ok(payload)
+}
+
+/// Parse a version 1 recurrence string for an event starting at `start`.
+///
+/// Accepts the keywords `daily`, `weekly` and `monthly` (any case) and
+/// RRULE-style `FREQ=...` lists with optional `COUNT`, `BYDAY` (weekly),
+/// `BYMONTHDAY` (monthly) and `INTERVAL=1`. Weekly rules without days repeat
+/// on the start's weekday; monthly ones default to the start's UTC day.
+pub fn parse_v1_recurrence(text: &str, start: Timestamp) -> Option<RecurrenceRule> {
+    use chrono::{Datelike, TimeZone};
+
+    let start_day = || {
+        chrono::Utc
+            .timestamp_millis_opt(start as i64)
+            .single()
+            .map(|t| t.day())
+    };
+    let text = text.trim();
+    let rrule = text
+        .get(..6)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("RRULE:"))
+        .map_or(text, |_| &text[6..]);
+    if !rrule.contains('=') {
+        let frequency = match rrule.to_ascii_lowercase().as_str() {
+            "daily" => Frequency::Daily,
+            "weekly" => Frequency::Weekly {
+                weekdays: Vec::new(),
+            },
+            "monthly" => Frequency::Monthly { day: start_day()? },
+            _ => return None,
+        };
+        return Some(RecurrenceRule {
+            frequency,
+            until: None,
+            count: None,
+        });
+    }
+
+    let (mut freq, mut count, mut weekdays, mut month_day) = (None, None, Vec::new(), None);
+    for part in rrule.split(';').filter(|p| !p.is_empty()) {
+        let (key, value) = part.split_once('=')?;
+        match key.trim().to_ascii_uppercase().as_str() {
+            "FREQ" => freq = Some(value.trim().to_ascii_uppercase()),
+            "COUNT" => count = Some(value.trim().parse().ok()?),
+            "INTERVAL" if value.trim() == "1" => {}
+            "BYDAY" => {
+                for day in value.split(',') {
+                    weekdays.push(match day.trim().to_ascii_uppercase().as_str() {
+                        "MO" => Weekday::Mon,
+                        "TU" => Weekday::Tue,
+                        "WE" => Weekday::Wed,
+                        "TH" => Weekday::Thu,
+                        "FR" => Weekday::Fri,
+                        "SA" => Weekday::Sat,
+                        "SU" => Weekday::Sun,
+                        _ => return None,
+                    });
+                }
+            }
+            "BYMONTHDAY" => month_day = Some(value.trim().parse().ok()?),
+            _ => return None,
+        }
+    }
+    let frequency = match freq?.as_str() {
+        "DAILY" if weekdays.is_empty() && month_day.is_none() => Frequency::Daily,
+        "WEEKLY" if month_day.is_none() => Frequency::Weekly { weekdays },
+        "MONTHLY" if weekdays.is_empty() => Frequency::Monthly {
+            day: match month_day {
+                Some(day @ 1..=31) => day,
+                Some(_) => return None,
+                None => start_day()?,
+            },
+        },
+        _ => return None,
+    };
+    Some(RecurrenceRule {
+        frequency,
+        until: None,
+        count,
+    })
+}
+
+/// Upgrade a version 2 office payload to the version 3 layout.
+///
+/// Version 2 calendar events are all anchored to UTC, so `EventScheduled`
+/// gets an explicit `timezone: null`; every other variant is unchanged.
+pub fn migrate_v2_to_v3(mut payload: serde_json::Value) -> serde_json::Value {
+    if payload.get("type").and_then(|t| t.as_str()) == Some("EventScheduled") {
+        if let Some(data) = payload.get_mut("data").and_then(|d| d.as_object_mut()) {
+            data.entry("timezone").or_insert(serde_json::Value::Null);
+        }
+    }
+    if let Some(obj) = payload.as_object_mut() {
+        obj.insert("version".into(), 3.into());
+    }
+    payload
+}
+
+/// Sign `event` onto `channel` and append it to `ledger`.
+///
+/// Every office app appends through here, so a [`Permissions::ReadOnly`]
+/// instance is refused before anything is signed. Apps that change local
+/// state ahead of the append must check [`Permissions::check_write`]
+/// themselves first.
+pub(crate) fn append_office_event<E>(
+    ledger: &Ledger,
+    signer: &SigningKey,
+    channel: &str,
+    schema_version: SchemaVersion,
+    permissions: Permissions,
+    event: OfficeEvent,
+) -> Result<AppendReceipt, E>
+where
+    E: From<WriteDenied> + From<serde_json::Error> + From<AppError>,
+{
+    let prev = ledger.tail_hash();
+    append_office_event_after(
+        ledger,
+        signer,
+        channel,
+        schema_version,
+        permissions,
+        event,
+        prev,
+    )
+}
+
+/// Like [`append_office_event`], but chained onto `prev` rather than the
+/// current tail, so the append fails if anything was logged after `prev`.
+pub(crate) fn append_office_event_after<E>(
+    ledger: &Ledger,
+    signer: &SigningKey,
+    channel: &str,
+    schema_version: SchemaVersion,
+    permissions: Permissions,
+    event: OfficeEvent,
+    prev: Option<Hash>,
+) -> Result<AppendReceipt, E>
+where
+    E: From<WriteDenied> + From<serde_json::Error> + From<AppError>,
+{
+    permissions.check_write()?;
+    let body = ledger_spec::EnvelopeBody {
+        payload: event.to_payload()?,
+        payload_type: Some(OFFICE_PAYLOAD_TYPE.into()),
+    };
+    let mut env = ledger_spec::Envelope {
+        header: ledger_spec::EnvelopeHeader {
+            channel: channel.to_string(),
+            version: schema_version,
+            prev,
+            body_hash: ledger_spec::hash_body_for(&body, schema_version),
+            timestamp: now_millis(),
+        },
+        body,
+        signatures: Vec::new(),
+        attestations: Vec::new(),
+    };
+    ledger_core::signing::sign_envelope(&mut env, signer);
+    ledger.append(env).map_err(|e| AppError::Ledger(e).into())
+}
+
+/// Current wall-clock time in milliseconds since the Unix epoch.
+pub(crate) fn now_millis() -> Timestamp {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
 }
 
 #[cfg(test)]
@@ -260,6 +634,18 @@ mod tests {
         assert_eq!(CellRef::new(701, 99).to_a1(), "ZZ100");
     }
 
+    #[test]
+    fn cell_ref_from_a1() {
+        for (col, row) in [(0, 0), (1, 2), (25, 0), (26, 0), (701, 99)] {
+            let cell = CellRef::new(col, row);
+            assert_eq!(CellRef::from_a1(&cell.to_a1()), Some(cell));
+        }
+        assert_eq!(CellRef::from_a1("b3"), Some(CellRef::new(1, 2)));
+        assert_eq!(CellRef::from_a1("A0"), None);
+        assert_eq!(CellRef::from_a1("12"), None);
+        assert_eq!(CellRef::from_a1("A1B"), None);
+    }
+
     #[test]
     fn office_event_serialization() {
         let event = OfficeEvent::DocumentCreated {
@@ -276,4 +662,225 @@ mod tests {
         let restored: OfficeEvent = serde_json::from_str(&json).unwrap();
         assert_eq!(event, restored);
     }
+
+    fn content(tag: u8) -> ContentRef {
+        ContentRef {
+            locator: format!("cas:{tag:02x}"),
+            hash: [tag; 32],
+            media_type: None,
+            bytes: Some(u64::from(tag)),
+        }
+    }
+
+    fn every_variant() -> Vec<OfficeEvent> {
+        let metadata = FileMetadata {
+            size: 3,
+            mime_type: Some("text/plain".into()),
+            created_at: 1,
+            modified_at: 2,
+            is_directory: false,
+        };
+        vec![
+            OfficeEvent::DocumentCreated {
+                id: [1; 32],
+                title: "Doc".into(),
+                content: content(1),
+            },
+            OfficeEvent::DocumentUpdated {
+                id: [1; 32],
+                version: 2,
+                content: content(2),
+                diff: Some(content(3)),
+            },
+            OfficeEvent::DocumentDeleted {
+                id: [1; 32],
+                reason: "gone".into(),
+            },
+            OfficeEvent::SheetCreated {
+                id: [2; 32],
+                name: "Sheet".into(),
+                columns: 4,
+                rows: 8,
+            },
+            OfficeEvent::CellUpdated {
+                sheet_id: [2; 32],
+                cell: CellRef::new(1, 2),
+                value: CellValue::Number(1.5),
+                formula: Some("=A1".into()),
+            },
+            OfficeEvent::CellBatchUpdated {
+                sheet_id: [2; 32],
+                updates: vec![(CellRef::new(0, 0), CellValue::Text("x".into()), None)],
+            },
+            OfficeEvent::NameDefined {
+                sheet_id: [2; 32],
+                name: "Revenue".into(),
+                range: "B2:B13".into(),
+            },
+            OfficeEvent::CellCommented {
+                sheet_id: [2; 32],
+                cell: CellRef::new(1, 2),
+                comment: "check this".into(),
+            },
+            OfficeEvent::SheetDeleted {
+                id: [2; 32],
+                reason: "gone".into(),
+            },
+            OfficeEvent::FileStored {
+                path: "/a.txt".into(),
+                content: content(4),
+                metadata,
+            },
+            OfficeEvent::FileDeleted {
+                path: "/a.txt".into(),
+                reason: "gone".into(),
+            },
+            OfficeEvent::DirectoryCreated { path: "/d".into() },
+            OfficeEvent::DirectoryDeleted {
+                path: "/d".into(),
+                reason: "gone".into(),
+            },
+            OfficeEvent::FileMoved {
+                from: "/a".into(),
+                to: "/b".into(),
+            },
+            OfficeEvent::EventScheduled {
+                id: [3; 32],
+                title: "Standup".into(),
+                start: 10,
+                end: 20,
+                description: None,
+                location: Some("Room 1".into()),
+                recurrence: Some(RecurrenceRule {
+                    frequency: Frequency::Weekly {
+                        weekdays: vec![Weekday::Mon, Weekday::Thu],
+                    },
+                    until: None,
+                    count: Some(4),
+                }),
+                timezone: Some("Europe/Berlin".into()),
+            },
+            OfficeEvent::EventModified {
+                id: [3; 32],
+                changes: EventChanges {
+                    title: Some("Sync".into()),
+                    start: None,
+                    end: None,
+                    description: None,
+                    location: None,
+                },
+            },
+            OfficeEvent::EventCancelled {
+                id: [3; 32],
+                reason: "gone".into(),
+            },
+            OfficeEvent::OccurrenceCancelled {
+                id: [3; 32],
+                occurrence_start: 10,
+                reason: "holiday".into(),
+            },
+        ]
+    }
+
+    #[test]
+    fn office_event_payload_roundtrip() {
+        for event in every_variant() {
+            let payload = event.to_payload().unwrap();
+            assert_eq!(payload["version"], OFFICE_SCHEMA_VERSION);
+            assert_eq!(OfficeEvent::from_payload(&payload).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn v1_event_migrates_to_current() {
+        let id: Hash = [7; 32];
+        let v1 = serde_json::json!({
+            "type": "EventScheduled",
+            "data": {
+                "id": id,
+                "title": "Review",
+                "start": 100,
+                "end": 200,
+                "description": "quarterly",
+                "location": null
+            }
+        });
+        let event = OfficeEvent::from_payload(&v1).unwrap();
+        assert_eq!(
+            event,
+            OfficeEvent::EventScheduled {
+                id,
+                title: "Review".into(),
+                start: 100,
+                end: 200,
+                description: Some("quarterly".into()),
+                location: None,
+                recurrence: None,
+                timezone: None,
+            }
+        );
+
+        let future =
+            serde_json::json!({"version": 99, "type": "DirectoryCreated", "data": {"path": "/"}});
+        assert!(OfficeEvent::from_payload(&future).is_err());
+    }
+
+    /// A version 1 `EventScheduled` payload as the original calendar wrote
+    /// it, with a free-form recurrence string.
+    fn v1_recurring(recurrence: &str) -> serde_json::Value {
+        let id: Hash = [9; 32];
+        serde_json::json!({
+            "type": "EventScheduled",
+            "data": {
+                "id": id,
+                "title": "Standup",
+                // 2024-05-15T09:00:00Z
+                "start": 1_715_763_600_000u64,
+                "end": 1_715_765_400_000u64,
+                "description": null,
+                "location": "Room 1",
+                "recurrence": recurrence
+            }
+        })
+    }
+
+    fn migrated_rule(recurrence: &str) -> Option<RecurrenceRule> {
+        match OfficeEvent::from_payload(&v1_recurring(recurrence)).unwrap() {
+            OfficeEvent::EventScheduled { recurrence, .. } => recurrence,
+            other => panic!("unexpected event {other:?}"),
+        }
+    }
+
+    #[test]
+    fn v1_recurrence_strings_migrate_to_rules() {
+        let rule = |frequency, count| RecurrenceRule {
+            frequency,
+            until: None,
+            count,
+        };
+        assert_eq!(migrated_rule("daily"), Some(rule(Frequency::Daily, None)));
+        assert_eq!(
+            migrated_rule("Weekly"),
+            Some(rule(Frequency::Weekly { weekdays: vec![] }, None))
+        );
+        assert_eq!(
+            migrated_rule("monthly"),
+            Some(rule(Frequency::Monthly { day: 15 }, None))
+        );
+        assert_eq!(
+            migrated_rule("RRULE:FREQ=WEEKLY;BYDAY=MO,TH;COUNT=6"),
+            Some(rule(
+                Frequency::Weekly {
+                    weekdays: vec![Weekday::Mon, Weekday::Thu]
+                },
+                Some(6)
+            ))
+        );
+    }
+
+    #[test]
+    fn unparseable_v1_recurrence_is_an_error() {
+        let err = OfficeEvent::from_payload(&v1_recurring("every other blue moon")).unwrap_err();
+        assert!(err.to_string().contains("every other blue moon"), "{err}");
+    }
 }
@@ -2,15 +2,15 @@
 //!
 //! Provides CAS-backed virtual file system with ledger audit trail.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 use ed25519_dalek::SigningKey;
 use ledger_core::brainstem::{AppendReceipt, Ledger};
 use ledger_spec::events::ContentRef;
-use ledger_spec::Timestamp;
 
-use crate::events::{FileMetadata, OfficeEvent};
+use crate::events::{self, now_millis, FileMetadata, OfficeEvent};
+use crate::permissions::{Permissions, WriteDenied};
 
 /// Errors from file operations.
 #[derive(Debug, thiserror::Error)]
@@ -27,6 +27,12 @@ pub enum FileError {
     /// Parent directory not found.
     #[error("parent directory not found: {0}")]
     ParentNotFound(String),
+    /// Directory has entries and recursive deletion was not requested.
+    #[error("directory not empty: {0}")]
+    DirectoryNotEmpty(String),
+    /// This app instance is read-only.
+    #[error("permission denied: app is read-only")]
+    PermissionDenied,
     /// Ledger operation failed.
     #[error("ledger error: {0}")]
     Ledger(#[from] ledger_core::apps::AppError),
@@ -35,6 +41,12 @@ pub enum FileError {
     Serialization(#[from] serde_json::Error),
 }
 
+impl From<WriteDenied> for FileError {
+    fn from(_: WriteDenied) -> Self {
+        Self::PermissionDenied
+    }
+}
+
 /// Virtual file entry.
 #[derive(Debug, Clone)]
 pub struct FileEntry {
@@ -58,11 +70,32 @@ impl FileEntry {
     }
 }
 
+/// Storage used by stored files, before and after CAS deduplication.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StorageStats {
+    /// Number of files (directories excluded).
+    pub files: usize,
+    /// Number of distinct contents backing those files.
+    pub unique_contents: usize,
+    /// Total size of all files, as if each were stored separately.
+    pub logical_bytes: u64,
+    /// Size of the distinct contents actually held in CAS.
+    pub physical_bytes: u64,
+}
+
+impl StorageStats {
+    /// Bytes saved by sharing identical content.
+    pub fn saved_bytes(&self) -> u64 {
+        self.logical_bytes - self.physical_bytes
+    }
+}
+
 /// File manager application orchestrator.
 pub struct FileManagerApp {
     ledger: Ledger,
     signer: Arc<SigningKey>,
     channel: String,
+    permissions: Permissions,
     schema_version: u16,
     /// Virtual file system.
     files: HashMap<String, FileEntry>,
@@ -80,6 +113,7 @@ impl FileManagerApp {
             ledger,
             signer: Arc::new(signer),
             channel: channel.into(),
+            permissions: Permissions::default(),
             schema_version,
             files: HashMap::new(),
         };
@@ -98,6 +132,18 @@ impl FileManagerApp {
         app
     }
 
+    /// Restrict this file manager to `permissions`. A read-only file
+    /// manager can list and read files but not store, move or delete them.
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Whether this file manager may change the tree.
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
     /// Normalize a path (remove trailing slashes, handle ../).
     fn normalize_path(path: &str) -> String {
         let mut parts: Vec<&str> = Vec::new();
@@ -134,28 +180,14 @@ impl FileManagerApp {
 
     /// Append an office event to the ledger.
     fn append_office_event(&self, event: OfficeEvent) -> Result<AppendReceipt, FileError> {
-        let payload = serde_json::to_value(&event)?;
-        let body = ledger_spec::EnvelopeBody {
-            payload,
-            payload_type: Some("ea.office.v1".into()),
-        };
-        let body_hash = ledger_spec::hash_body(&body);
-        let mut env = ledger_spec::Envelope {
-            header: ledger_spec::EnvelopeHeader {
-                channel: self.channel.clone(),
-                version: self.schema_version,
-                prev: self.ledger.tail_hash(),
-                body_hash,
-                timestamp: now_millis(),
-            },
-            body,
-            signatures: Vec::new(),
-            attestations: Vec::new(),
-        };
-        ledger_core::signing::sign_envelope(&mut env, &self.signer);
-        self.ledger.append(env).map_err(|e| FileError::Ledger(
-            ledger_core::apps::AppError::Ledger(e)
-        ))
+        events::append_office_event(
+            &self.ledger,
+            &self.signer,
+            &self.channel,
+            self.schema_version,
+            self.permissions,
+            event,
+        )
     }
 
     /// Create a directory.
@@ -213,6 +245,7 @@ impl FileManagerApp {
             }
         }
 
+        self.permissions.check_write()?;
         let size = content.len() as u64;
         let digest = self.ledger.content_store().put(content);
         let content_ref = ContentRef {
@@ -247,8 +280,20 @@ impl FileManagerApp {
     }
 
     /// Delete a file or directory.
-    pub fn delete(&mut self, path: impl Into<String>, reason: impl Into<String>) -> Result<AppendReceipt, FileError> {
+    ///
+    /// Deletion appends tombstone events; nothing is removed from the
+    /// ledger. With `recursive`, a directory's subtree is tombstoned
+    /// depth-first (children in name order, each directory after its
+    /// contents) and one receipt is returned per event. A non-empty
+    /// directory without `recursive` is rejected.
+    pub fn delete(
+        &mut self,
+        path: impl Into<String>,
+        recursive: bool,
+        reason: impl Into<String>,
+    ) -> Result<Vec<AppendReceipt>, FileError> {
         let path = Self::normalize_path(&path.into());
+        let reason = reason.into();
 
         if path == "/" {
             return Err(FileError::InvalidPath("cannot delete root".into()));
@@ -258,24 +303,50 @@ impl FileManagerApp {
             return Err(FileError::NotFound(path));
         }
 
-        // Check if directory is empty
-        let entry = &self.files[&path];
-        if entry.is_directory() {
-            let has_children = self.files.keys()
-                .any(|p| p != &path && p.starts_with(&format!("{}/", path)));
-            if has_children {
-                return Err(FileError::InvalidPath("directory not empty".into()));
-            }
+        let targets = self.subtree_post_order(&path);
+        if targets.len() > 1 && !recursive {
+            return Err(FileError::DirectoryNotEmpty(path));
         }
 
-        let event = OfficeEvent::FileDeleted {
-            path: path.clone(),
-            reason: reason.into(),
-        };
-        let receipt = self.append_office_event(event)?;
+        let mut receipts = Vec::with_capacity(targets.len());
+        for target in targets {
+            let event = if self.files[&target].is_directory() {
+                OfficeEvent::DirectoryDeleted {
+                    path: target.clone(),
+                    reason: reason.clone(),
+                }
+            } else {
+                OfficeEvent::FileDeleted {
+                    path: target.clone(),
+                    reason: reason.clone(),
+                }
+            };
+            receipts.push(self.append_office_event(event)?);
+            self.files.remove(&target);
+        }
+        Ok(receipts)
+    }
 
-        self.files.remove(&path);
-        Ok(receipt)
+    /// Paths under `path` (inclusive) in depth-first post-order.
+    fn subtree_post_order(&self, path: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        if self.files.get(path).is_some_and(FileEntry::is_directory) {
+            let prefix = format!("{}/", path);
+            let mut children: Vec<&String> = self
+                .files
+                .keys()
+                .filter(|p| {
+                    p.strip_prefix(&prefix)
+                        .is_some_and(|rest| !rest.contains('/'))
+                })
+                .collect();
+            children.sort();
+            for child in children {
+                out.extend(self.subtree_post_order(child));
+            }
+        }
+        out.push(path.to_string());
+        out
     }
 
     /// Move a file or directory.
@@ -284,8 +355,21 @@ impl FileManagerApp {
         from: impl Into<String>,
         to: impl Into<String>,
     ) -> Result<AppendReceipt, FileError> {
-        let from = Self::normalize_path(&from.into());
-        let to = Self::normalize_path(&to.into());
+        self.rename(&from.into(), &to.into())
+    }
+
+    /// Rename or move a file or directory.
+    ///
+    /// Entries keep their CAS content references; moving a directory
+    /// rewrites the paths of everything beneath it. A directory cannot be
+    /// moved into its own subtree.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<AppendReceipt, FileError> {
+        let from = Self::normalize_path(from);
+        let to = Self::normalize_path(to);
+
+        if from == "/" {
+            return Err(FileError::InvalidPath("cannot move root".into()));
+        }
 
         if !self.files.contains_key(&from) {
             return Err(FileError::NotFound(from));
@@ -295,11 +379,25 @@ impl FileManagerApp {
             return Err(FileError::AlreadyExists(to));
         }
 
+        let from_prefix = format!("{}/", from);
+        if to.starts_with(&from_prefix) {
+            return Err(FileError::InvalidPath(format!(
+                "cannot move {} into its own subtree",
+                from
+            )));
+        }
+
         // Check destination parent exists
         if let Some(parent) = Self::parent_path(&to) {
             if !self.files.contains_key(&parent) {
                 return Err(FileError::ParentNotFound(parent));
             }
+            if !self.files[&parent].is_directory() {
+                return Err(FileError::InvalidPath(format!(
+                    "{} is not a directory",
+                    parent
+                )));
+            }
         }
 
         let event = OfficeEvent::FileMoved {
@@ -308,11 +406,21 @@ impl FileManagerApp {
         };
         let receipt = self.append_office_event(event)?;
 
-        // Move the entry
-        if let Some(mut entry) = self.files.remove(&from) {
-            entry.path = to.clone();
-            entry.metadata.modified_at = now_millis();
-            self.files.insert(to, entry);
+        let moved: Vec<String> = self
+            .files
+            .keys()
+            .filter(|p| **p == from || p.starts_with(&from_prefix))
+            .cloned()
+            .collect();
+        let now = now_millis();
+        for old_path in moved {
+            if let Some(mut entry) = self.files.remove(&old_path) {
+                entry.path = format!("{}{}", to, &old_path[from.len()..]);
+                if old_path == from {
+                    entry.metadata.modified_at = now;
+                }
+                self.files.insert(entry.path.clone(), entry);
+            }
         }
 
         Ok(receipt)
@@ -358,6 +466,46 @@ impl FileManagerApp {
         Ok(entries)
     }
 
+    /// Contents referenced by more than one file, each with its paths in
+    /// sorted order. Groups are ordered by content hash.
+    pub fn find_duplicates(&self) -> Vec<(ContentRef, Vec<String>)> {
+        self.files_by_content()
+            .into_values()
+            .filter(|entries| entries.len() > 1)
+            .filter_map(|entries| {
+                let content = entries[0].content_ref.clone()?;
+                let paths = entries.into_iter().map(|e| e.path.clone()).collect();
+                Some((content, paths))
+            })
+            .collect()
+    }
+
+    /// Logical vs physical storage of all files, counting shared content once.
+    pub fn storage_stats(&self) -> StorageStats {
+        let mut stats = StorageStats::default();
+        for entries in self.files_by_content().into_values() {
+            stats.files += entries.len();
+            stats.unique_contents += 1;
+            stats.logical_bytes += entries.iter().map(|e| e.metadata.size).sum::<u64>();
+            stats.physical_bytes += entries[0].metadata.size;
+        }
+        stats
+    }
+
+    /// Files grouped by content hash, each group sorted by path.
+    fn files_by_content(&self) -> BTreeMap<ledger_spec::Hash, Vec<&FileEntry>> {
+        let mut groups: BTreeMap<_, Vec<&FileEntry>> = BTreeMap::new();
+        for entry in self.files.values() {
+            if let Some(content) = &entry.content_ref {
+                groups.entry(content.hash).or_default().push(entry);
+            }
+        }
+        for entries in groups.values_mut() {
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+        groups
+    }
+
     /// Read file content from CAS.
     pub fn read_file(&self, path: &str) -> Result<Vec<u8>, FileError> {
         let path = Self::normalize_path(path);
@@ -376,14 +524,6 @@ impl FileManagerApp {
     }
 }
 
-fn now_millis() -> Timestamp {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,9 +538,11 @@ mod tests {
             policy: ledger_spec::ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: vec![signer.verifying_key().to_bytes()],
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
         let ledger = Ledger::new(registry);
         FileManagerApp::new(ledger, signer, "office.files", 1)
@@ -458,11 +600,179 @@ mod tests {
         app.store_file("/temp.txt", b"temp".to_vec(), None).unwrap();
         assert!(app.get("/temp.txt").is_some());
 
-        let receipt = app.delete("/temp.txt", "cleanup").unwrap();
-        assert!(receipt.merkle.verify());
+        let receipts = app.delete("/temp.txt", false, "cleanup").unwrap();
+        assert_eq!(receipts.len(), 1);
+        assert!(receipts[0].merkle.verify());
         assert!(app.get("/temp.txt").is_none());
     }
 
+    #[test]
+    fn recursive_delete_emits_depth_first_tombstones() {
+        let mut app = test_app();
+
+        app.create_directory("/proj").unwrap();
+        app.create_directory("/proj/src").unwrap();
+        app.create_directory("/proj/src/empty").unwrap();
+        app.store_file("/proj/src/main.rs", b"fn main() {}".to_vec(), None)
+            .unwrap();
+        app.store_file("/proj/README", b"readme".to_vec(), None)
+            .unwrap();
+        app.store_file("/keep.txt", b"keep".to_vec(), None).unwrap();
+
+        assert!(matches!(
+            app.delete("/proj", false, "cleanup"),
+            Err(FileError::DirectoryNotEmpty(p)) if p == "/proj"
+        ));
+        assert!(app.get("/proj/src/main.rs").is_some());
+
+        let before = app.ledger.offsets_for_channel("office.files").len();
+        let receipts = app.delete("/proj", true, "cleanup").unwrap();
+        assert_eq!(receipts.len(), 5);
+        assert!(receipts.iter().all(|r| r.merkle.verify()));
+
+        let resp = app
+            .ledger
+            .query(ledger_core::brainstem::SliceQuery {
+                from: before,
+                limit: 10,
+                include_payloads: false,
+            })
+            .unwrap();
+        let events: Vec<OfficeEvent> = resp
+            .envelopes
+            .into_iter()
+            .map(|env| OfficeEvent::from_payload(&env.body.payload).unwrap())
+            .collect();
+        let tombstone = |path: &str, dir: bool| {
+            let (path, reason) = (path.to_string(), "cleanup".to_string());
+            if dir {
+                OfficeEvent::DirectoryDeleted { path, reason }
+            } else {
+                OfficeEvent::FileDeleted { path, reason }
+            }
+        };
+        assert_eq!(
+            events,
+            vec![
+                tombstone("/proj/README", false),
+                tombstone("/proj/src/empty", true),
+                tombstone("/proj/src/main.rs", false),
+                tombstone("/proj/src", true),
+                tombstone("/proj", true),
+            ]
+        );
+
+        let root: Vec<&str> = app
+            .list_directory("/")
+            .unwrap()
+            .iter()
+            .map(|e| e.name())
+            .collect();
+        assert_eq!(root, vec!["keep.txt"]);
+        assert!(app.get("/proj/src").is_none());
+        assert!(matches!(
+            app.list_directory("/proj"),
+            Err(FileError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rename_file_keeps_content_ref() {
+        let mut app = test_app();
+
+        app.store_file("/draft.txt", b"draft".to_vec(), None)
+            .unwrap();
+        let original = app.get("/draft.txt").unwrap().content_ref.clone();
+
+        let receipt = app.rename("/draft.txt", "/final.txt").unwrap();
+        assert!(receipt.merkle.verify());
+        assert!(app.get("/draft.txt").is_none());
+        assert_eq!(app.get("/final.txt").unwrap().content_ref, original);
+        assert_eq!(app.read_file("/final.txt").unwrap(), b"draft");
+        let hash = original.unwrap().hash;
+        assert_eq!(app.ledger.content_store().get(&hash).unwrap(), b"draft");
+
+        assert!(matches!(
+            app.rename("/missing.txt", "/other.txt"),
+            Err(FileError::NotFound(_))
+        ));
+        assert!(matches!(
+            app.rename("/final.txt", "/nowhere/final.txt"),
+            Err(FileError::ParentNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn rename_directory_moves_children() {
+        let mut app = test_app();
+
+        app.create_directory("/a").unwrap();
+        app.create_directory("/a/b").unwrap();
+        app.store_file("/a/b/c.txt", b"c".to_vec(), None).unwrap();
+        app.create_directory("/archive").unwrap();
+
+        app.rename("/a", "/archive/a2").unwrap();
+        assert!(app.get("/a").is_none());
+        assert!(app.get("/a/b/c.txt").is_none());
+        assert_eq!(
+            app.get("/archive/a2/b/c.txt").unwrap().path,
+            "/archive/a2/b/c.txt"
+        );
+        assert_eq!(app.read_file("/archive/a2/b/c.txt").unwrap(), b"c");
+        let names: Vec<&str> = app
+            .list_directory("/archive/a2")
+            .unwrap()
+            .iter()
+            .map(|e| e.name())
+            .collect();
+        assert_eq!(names, vec!["b"]);
+    }
+
+    #[test]
+    fn rename_rejects_move_into_own_subtree() {
+        let mut app = test_app();
+
+        app.create_directory("/a").unwrap();
+        app.create_directory("/a/b").unwrap();
+        app.create_directory("/ab").unwrap();
+
+        assert!(matches!(
+            app.rename("/a", "/a/b/a"),
+            Err(FileError::InvalidPath(_))
+        ));
+        assert!(app.get("/a/b").is_some());
+
+        // A sibling sharing the name prefix is not part of the subtree.
+        app.rename("/ab", "/a/ab").unwrap();
+        assert!(app.get("/a/ab").is_some());
+    }
+
+    #[test]
+    fn duplicate_contents_are_grouped_and_counted_once() {
+        let mut app = test_app();
+
+        app.create_directory("/a").unwrap();
+        app.store_file("/a/copy.txt", b"shared".to_vec(), None)
+            .unwrap();
+        app.store_file("/orig.txt", b"shared".to_vec(), None)
+            .unwrap();
+        app.store_file("/unique.txt", b"one of a kind".to_vec(), None)
+            .unwrap();
+
+        let duplicates = app.find_duplicates();
+        assert_eq!(duplicates.len(), 1);
+        let (content, paths) = &duplicates[0];
+        assert_eq!(content.bytes, Some(6));
+        assert_eq!(paths, &["/a/copy.txt", "/orig.txt"]);
+
+        let stats = app.storage_stats();
+        assert_eq!(stats.files, 3);
+        assert_eq!(stats.unique_contents, 2);
+        assert_eq!(stats.logical_bytes, 6 + 6 + 13);
+        assert_eq!(stats.physical_bytes, 6 + 13);
+        assert_eq!(stats.saved_bytes(), 6);
+    }
+
     #[test]
     fn path_normalization() {
         assert_eq!(FileManagerApp::normalize_path("/a/b/c"), "/a/b/c");
@@ -5,6 +5,7 @@
 //! - **Spreadsheet**: Grid-based data with formulas
 //! - **File Manager**: CAS-backed file browser
 //! - **Calendar**: Event scheduling with audit trails
+//! - **Audit**: Raw ledger history across all office channels
 //!
 //! All operations are recorded to the Eä ledger with Merkle proofs.
 
@@ -15,10 +16,16 @@ pub mod document;
 pub mod spreadsheet;
 pub mod files;
 pub mod calendar;
+pub mod search;
+pub mod permissions;
+pub mod audit;
 pub mod ui;
 
 pub use events::OfficeEvent;
 pub use document::DocumentApp;
 pub use spreadsheet::SpreadsheetApp;
-pub use files::FileManagerApp;
+pub use files::{FileManagerApp, StorageStats};
 pub use calendar::CalendarApp;
+pub use search::{SearchHit, SearchIndex};
+pub use permissions::Permissions;
+pub use audit::{AuditEntry, load_audit_trail};
@@ -3,7 +3,7 @@
 //! A complete office suite with cryptographic versioning and Merkle proofs.
 
 use std::io::{stdout, Write};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
@@ -19,14 +19,32 @@ use ledger_core::brainstem::Ledger;
 use ledger_spec::{ChannelPolicy, ChannelRegistry, ChannelSpec};
 
 use ledger_office::{
-    CalendarApp, DocumentApp, FileManagerApp, SpreadsheetApp,
+    document::{DocumentError, render_markdown},
+    load_audit_trail, AuditEntry, CalendarApp, DocumentApp, FileManagerApp, SpreadsheetApp,
     ui::{self, Rect, colors, draw_box},
-    ui::editor::{EditorState, render_editor},
-    ui::grid::{GridState, render_grid},
+    ui::audit::{AuditState, render_audit},
+    ui::editor::{AutosaveTimer, EditorState, render_editor, render_preview},
+    ui::grid::{GridState, render_grid_with_comments},
     ui::tree::{TreeState, TreeNode, render_tree},
-    ui::calendar::{CalendarState, render_month, EventMarker},
+    ui::calendar::{
+        AgendaEntry, CalendarState, CalendarView, EventMarker, render_agenda, render_month,
+    },
 };
 
+/// Idle time after the last edit before a modified document is autosaved.
+const AUTOSAVE_DEBOUNCE: Duration = Duration::from_secs(3);
+
+/// How far ahead the agenda view lists event occurrences.
+const AGENDA_HORIZON_MS: u64 = 30 * 24 * 60 * 60 * 1000;
+
+/// Ledger channels written by the office apps, in menu order.
+const OFFICE_CHANNELS: [&str; 4] = [
+    "office.documents",
+    "office.spreadsheets",
+    "office.files",
+    "office.calendar",
+];
+
 /// Application mode.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum AppMode {
@@ -40,6 +58,8 @@ enum AppMode {
     Files,
     /// Calendar.
     Calendar,
+    /// Ledger audit log.
+    Audit,
 }
 
 /// Main application state.
@@ -53,12 +73,18 @@ struct OfficeTui {
     sheet_app: SpreadsheetApp,
     file_app: FileManagerApp,
     cal_app: CalendarApp,
+    ledger: Ledger,
 
     // UI states
     editor_state: EditorState,
+    autosave: AutosaveTimer,
+    /// Whether the markdown preview pane is shown beside the editor.
+    doc_preview: bool,
     grid_state: GridState,
     tree_state: TreeState,
     calendar_state: CalendarState,
+    audit_state: AuditState,
+    audit_entries: Vec<AuditEntry>,
 
     // Status
     status_message: String,
@@ -72,15 +98,17 @@ impl OfficeTui {
 
         // Create channel registry
         let mut registry = ChannelRegistry::new();
-        for channel in ["office.documents", "office.spreadsheets", "office.files", "office.calendar"] {
+        for channel in OFFICE_CHANNELS {
             registry.upsert(ChannelSpec {
                 name: channel.into(),
                 policy: ChannelPolicy {
                     min_signers: 1,
                     allowed_signers: vec![pub_key],
+                    signer_weights: Vec::new(),
                     require_attestations: false,
                     enforce_timestamp_ordering: true,
                 },
+                schemas: Default::default(),
             });
         }
 
@@ -94,12 +122,18 @@ impl OfficeTui {
             doc_app: DocumentApp::new(ledger.clone(), signer.clone(), "office.documents", 1),
             sheet_app: SpreadsheetApp::new(ledger.clone(), signer.clone(), "office.spreadsheets", 1),
             file_app: FileManagerApp::new(ledger.clone(), signer.clone(), "office.files", 1),
-            cal_app: CalendarApp::new(ledger, signer, "office.calendar", 1),
+            cal_app: CalendarApp::new(ledger.clone(), signer, "office.calendar", 1)
+                .with_display_timezone(display_timezone()),
+            ledger,
 
             editor_state: EditorState::default(),
+            autosave: AutosaveTimer::new(AUTOSAVE_DEBOUNCE),
+            doc_preview: false,
             grid_state: GridState::new(26, 100),
             tree_state: TreeState::default(),
             calendar_state: CalendarState::default(),
+            audit_state: AuditState::default(),
+            audit_entries: Vec::new(),
 
             status_message: "Welcome to Eä Office Suite".into(),
             last_receipt: None,
@@ -109,10 +143,16 @@ impl OfficeTui {
     fn handle_input(&mut self, key: KeyCode, modifiers: KeyModifiers) {
         match self.mode {
             AppMode::Menu => self.handle_menu_input(key),
-            AppMode::Documents => self.handle_editor_input(key, modifiers),
+            AppMode::Documents => {
+                self.handle_editor_input(key, modifiers);
+                if self.editor_state.modified {
+                    self.autosave.record_edit(Instant::now());
+                }
+            }
             AppMode::Spreadsheet => self.handle_grid_input(key),
             AppMode::Files => self.handle_files_input(key),
             AppMode::Calendar => self.handle_calendar_input(key),
+            AppMode::Audit => self.handle_audit_input(key),
         }
     }
 
@@ -124,7 +164,7 @@ impl OfficeTui {
                 }
             }
             KeyCode::Down => {
-                if self.menu_selection < 3 {
+                if self.menu_selection < 4 {
                     self.menu_selection += 1;
                 }
             }
@@ -134,9 +174,13 @@ impl OfficeTui {
                     1 => AppMode::Spreadsheet,
                     2 => AppMode::Files,
                     3 => AppMode::Calendar,
+                    4 => AppMode::Audit,
                     _ => AppMode::Menu,
                 };
                 self.status_message = format!("Opened {:?}", self.mode);
+                if self.mode == AppMode::Audit {
+                    self.refresh_audit();
+                }
             }
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.running = false;
@@ -174,6 +218,9 @@ impl OfficeTui {
                 KeyCode::Char('i') => {
                     self.editor_state.insert_mode = true;
                 }
+                KeyCode::Char('p') => {
+                    self.doc_preview = !self.doc_preview;
+                }
                 KeyCode::Char('q') | KeyCode::Esc => {
                     self.mode = AppMode::Menu;
                 }
@@ -191,15 +238,29 @@ impl OfficeTui {
         }
     }
 
-    fn save_document(&mut self) {
+    /// Autosave the document once editing has paused long enough.
+    fn tick(&mut self, now: Instant) {
+        if self.autosave.poll(now, self.editor_state.modified) {
+            if let Some(version) = self.save_document() {
+                self.status_message = format!("Autosaved v{}", version);
+            }
+        }
+    }
+
+    /// Save the editor buffer, returning the new document version on success.
+    fn save_document(&mut self) -> Option<u64> {
         let content = self.editor_state.content();
         let docs = self.doc_app.list_documents();
 
         let result = if let Some(doc) = docs.first() {
-            self.doc_app.update_document(doc.id, content)
+            self.doc_app.update_document(doc.id, doc.version, content)
         } else {
-            self.doc_app.create_document("Untitled")
-                .and_then(|(doc, _)| self.doc_app.update_document(doc.id, self.editor_state.content()))
+            self.doc_app
+                .create_document("Untitled")
+                .and_then(|(doc, _)| {
+                    self.doc_app
+                        .update_document(doc.id, doc.version, self.editor_state.content())
+                })
         };
 
         match result {
@@ -207,9 +268,18 @@ impl OfficeTui {
                 self.editor_state.modified = false;
                 self.status_message = format!("Saved v{} with Merkle proof", doc.version);
                 self.last_receipt = Some(hex::encode(&receipt.merkle.root[..8]));
+                Some(doc.version)
+            }
+            Err(DocumentError::VersionConflict { expected, actual }) => {
+                self.status_message = format!(
+                    "Save conflict: edited v{} but ledger is at v{}; reload before saving",
+                    expected, actual
+                );
+                None
             }
             Err(e) => {
                 self.status_message = format!("Save failed: {}", e);
+                None
             }
         }
     }
@@ -244,11 +314,36 @@ impl OfficeTui {
                 KeyCode::Enter | KeyCode::Char('e') => {
                     self.grid_state.start_edit("");
                 }
+                KeyCode::Char('u') => self.undo_cell(),
+                KeyCode::Char('r') => self.redo_cell(),
+                KeyCode::Char('c') => self.grid_state.toggle_comment(),
                 _ => {}
             }
         }
     }
 
+    fn undo_cell(&mut self) {
+        match self.sheet_app.undo() {
+            Ok(Some(receipt)) => {
+                self.status_message = "Undo recorded".into();
+                self.last_receipt = Some(hex::encode(&receipt.merkle.root[..8]));
+            }
+            Ok(None) => self.status_message = "Nothing to undo".into(),
+            Err(e) => self.status_message = format!("Undo failed: {}", e),
+        }
+    }
+
+    fn redo_cell(&mut self) {
+        match self.sheet_app.redo() {
+            Ok(Some(receipt)) => {
+                self.status_message = "Redo recorded".into();
+                self.last_receipt = Some(hex::encode(&receipt.merkle.root[..8]));
+            }
+            Ok(None) => self.status_message = "Nothing to redo".into(),
+            Err(e) => self.status_message = format!("Redo failed: {}", e),
+        }
+    }
+
     fn update_cell(&mut self, value: String) {
         use ledger_office::events::CellValue;
 
@@ -331,6 +426,13 @@ impl OfficeTui {
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.mode = AppMode::Menu;
             }
+            KeyCode::Up if self.calendar_state.view == CalendarView::Agenda => {
+                self.calendar_state.scroll_agenda_up();
+            }
+            KeyCode::Down if self.calendar_state.view == CalendarView::Agenda => {
+                let len = self.agenda_entries().len();
+                self.calendar_state.scroll_agenda_down(len);
+            }
             KeyCode::Left => self.calendar_state.prev_month(),
             KeyCode::Right => self.calendar_state.next_month(),
             KeyCode::Up => self.calendar_state.prev_week(),
@@ -348,6 +450,38 @@ impl OfficeTui {
         }
     }
 
+    fn handle_audit_input(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.mode = AppMode::Menu;
+            }
+            KeyCode::Up => self.audit_state.move_up(),
+            KeyCode::Down => {
+                let len = self.audit_state.visible(&self.audit_entries).len();
+                self.audit_state.move_down(len);
+            }
+            KeyCode::Char('f') => {
+                self.audit_state.cycle_filter(&OFFICE_CHANNELS);
+                let scope = self.audit_state.filter.as_deref().unwrap_or("all channels");
+                self.status_message = format!("Showing {}", scope);
+            }
+            KeyCode::Char('r') => self.refresh_audit(),
+            _ => {}
+        }
+    }
+
+    fn refresh_audit(&mut self) {
+        match load_audit_trail(&self.ledger, &OFFICE_CHANNELS) {
+            Ok(entries) => {
+                self.status_message = format!("Loaded {} ledger events", entries.len());
+                self.audit_entries = entries;
+            }
+            Err(e) => {
+                self.status_message = format!("Audit load failed: {:?}", e);
+            }
+        }
+    }
+
     fn render(&self, width: u16, height: u16) -> Vec<(u16, u16, String, Color)> {
         let mut output = Vec::new();
         let area = Rect::new(0, 0, width, height);
@@ -358,6 +492,7 @@ impl OfficeTui {
             AppMode::Spreadsheet => output.extend(self.render_spreadsheet(&area)),
             AppMode::Files => output.extend(self.render_files(&area)),
             AppMode::Calendar => output.extend(self.render_calendar(&area)),
+            AppMode::Audit => output.extend(self.render_audit(&area)),
         }
 
         // Status bar
@@ -388,6 +523,7 @@ impl OfficeTui {
             ("📊", "Spreadsheet", "Grid with formulas and cell tracking"),
             ("📁", "Files", "CAS-backed file manager"),
             ("📅", "Calendar", "Event scheduling with audit trail"),
+            ("🔍", "Audit", "Ledger event history with Merkle receipts"),
         ];
 
         for (i, (icon, name, desc)) in items.iter().enumerate() {
@@ -401,15 +537,27 @@ impl OfficeTui {
         }
 
         // Help
-        output.push((title_x, 18, "↑↓: Navigate | Enter: Select | Q: Quit".into(), colors::MUTED));
+        output.push((title_x, 20, "↑↓: Navigate | Enter: Select | Q: Quit".into(), colors::MUTED));
 
         output
     }
 
     fn render_documents(&self, area: &Rect) -> Vec<(u16, u16, String, Color)> {
-        let mut output = draw_box(area, Some("Documents"));
-        let inner = area.inner(1);
-        output.extend(render_editor(&self.editor_state, &inner));
+        if !self.doc_preview {
+            let mut output = draw_box(area, Some("Documents"));
+            output.extend(render_editor(&self.editor_state, &area.inner(1)));
+            return output;
+        }
+
+        let (editor_area, preview_area) = area.split_horizontal(50);
+        let editor_inner = editor_area.inner(1);
+        let mut output = draw_box(&editor_area, Some("Documents"));
+        output.extend(render_editor(&self.editor_state, &editor_inner));
+        output.extend(draw_box(&preview_area, Some("Preview")));
+        let spans = render_markdown(&self.editor_state.content());
+        let scroll_offset = self.editor_state.scroll_for_area(&editor_inner);
+        let preview_inner = preview_area.inner(1);
+        output.extend(render_preview(&spans, &preview_inner, scroll_offset));
         output
     }
 
@@ -426,7 +574,35 @@ impl OfficeTui {
             }
         };
 
-        output.extend(render_grid(&self.grid_state, &inner, &get_cell));
+        let get_comment = |col: u32, row: u32| {
+            let sheets = self.sheet_app.list_sheets();
+            sheets
+                .first()
+                .and_then(|sheet| sheet.comment(col, row).map(str::to_string))
+        };
+
+        output.extend(render_grid_with_comments(
+            &self.grid_state,
+            &inner,
+            &get_cell,
+            &get_comment,
+        ));
+
+        // Undo/redo hints, greyed out when unavailable
+        let hint_y = area.y + area.height - 1;
+        let hint_color = |enabled: bool| if enabled { colors::TEXT } else { colors::MUTED };
+        output.push((
+            area.x + 2,
+            hint_y,
+            " U: Undo ".into(),
+            hint_color(self.sheet_app.can_undo()),
+        ));
+        output.push((
+            area.x + 11,
+            hint_y,
+            " R: Redo ".into(),
+            hint_color(self.sheet_app.can_redo()),
+        ));
         output
     }
 
@@ -437,19 +613,79 @@ impl OfficeTui {
         output
     }
 
+    fn render_audit(&self, area: &Rect) -> Vec<(u16, u16, String, Color)> {
+        let mut output = draw_box(area, Some("Audit"));
+        let inner = area.inner(1);
+        output.extend(render_audit(&self.audit_state, &inner, &self.audit_entries));
+        output
+    }
+
+    /// Occurrences that have not yet ended and start within
+    /// [`AGENDA_HORIZON_MS`], with recurring events expanded, for the
+    /// agenda view.
+    fn agenda_entries(&self) -> Vec<AgendaEntry> {
+        let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+        self.cal_app
+            .list_active_events_in_range(now, now.saturating_add(AGENDA_HORIZON_MS))
+            .into_iter()
+            .filter_map(|o| {
+                let at = |ms: u64| self.cal_app.display_time(ms);
+                Some(AgendaEntry {
+                    start: at(o.start)?.naive_local(),
+                    end: at(o.end)?.naive_local(),
+                    title: o.event.title.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Current date in the calendar's display timezone.
+    fn display_today(&self) -> chrono::NaiveDate {
+        let now = chrono::Utc::now().timestamp_millis().max(0) as u64;
+        self.cal_app
+            .display_time(now)
+            .map_or_else(|| chrono::Utc::now().date_naive(), |t| t.date_naive())
+    }
+
     fn render_calendar(&self, area: &Rect) -> Vec<(u16, u16, String, Color)> {
         let mut output = draw_box(area, Some("Calendar"));
         let inner = area.inner(1);
 
-        // Get events for display
-        let events: Vec<EventMarker> = self.cal_app.list_active_events()
+        if self.calendar_state.view == CalendarView::Agenda {
+            output.extend(render_agenda(
+                &self.calendar_state,
+                &inner,
+                &self.agenda_entries(),
+                self.display_today(),
+            ));
+            return output;
+        }
+
+        // Get occurrences (including recurring expansions) for the shown month
+        let (month_start, month_end) = {
+            use chrono::{Months, NaiveDate, TimeZone};
+            let tz = self.cal_app.display_timezone();
+            let first =
+                NaiveDate::from_ymd_opt(self.calendar_state.year, self.calendar_state.month, 1);
+            let bounds = first.and_then(|d| Some((d, d.checked_add_months(Months::new(1))?)));
+            bounds.map_or((0, 0), |(start, end)| {
+                let millis = |d: NaiveDate| {
+                    tz.from_local_datetime(&d.and_hms_opt(0, 0, 0).unwrap())
+                        .earliest()
+                        .map_or(0, |t| t.timestamp_millis().max(0) as u64)
+                };
+                (millis(start), millis(end))
+            })
+        };
+        let events: Vec<EventMarker> = self
+            .cal_app
+            .list_active_events_in_range(month_start, month_end)
             .iter()
-            .filter_map(|e| {
-                use chrono::{TimeZone, Utc};
-                let date = Utc.timestamp_millis_opt(e.start as i64).single()?;
+            .filter_map(|o| {
+                let date = self.cal_app.display_time(o.start)?;
                 Some(EventMarker {
                     date: date.date_naive(),
-                    title: e.title.clone(),
+                    title: o.event.title.clone(),
                     color: colors::WARNING,
                 })
             })
@@ -460,6 +696,14 @@ impl OfficeTui {
     }
 }
 
+/// Display timezone for calendar views: the IANA name in `TZ`, else UTC.
+fn display_timezone() -> chrono_tz::Tz {
+    std::env::var("TZ")
+        .ok()
+        .and_then(|name| ledger_office::calendar::parse_timezone(&name).ok())
+        .unwrap_or(chrono_tz::Tz::UTC)
+}
+
 fn main() -> std::io::Result<()> {
     let mut stdout = stdout();
 
@@ -477,9 +721,15 @@ fn main() -> std::io::Result<()> {
                 app.handle_input(key.code, key.modifiers);
             }
         }
+        app.tick(Instant::now());
 
         // Get terminal size
         let (width, height) = terminal::size()?;
+        // Every view renders inside the outer box
+        let inner = Rect::new(0, 0, width, height).inner(1);
+        app.editor_state.set_viewport(&inner);
+        app.grid_state.set_viewport(&inner);
+        app.audit_state.set_viewport(&inner);
 
         // Clear and render
         execute!(stdout, MoveTo(0, 0), Clear(ClearType::All))?;
@@ -0,0 +1,39 @@
+//! Per-app access control.
+//!
+//! A channel's `allowed_signers` decides which keys the ledger accepts;
+//! [`Permissions`] decide whether an app instance holding such a key may
+//! write at all, so viewers and editors can share one ledger.
+
+/// What an office app instance may do with its channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Permissions {
+    /// Query the ledger; every append is refused.
+    ReadOnly,
+    /// Query the ledger and append office events.
+    #[default]
+    ReadWrite,
+}
+
+impl Permissions {
+    /// Whether this instance may append office events.
+    pub fn can_write(self) -> bool {
+        matches!(self, Self::ReadWrite)
+    }
+
+    /// [`Self::can_write`] as a `Result`, for bailing out of an operation
+    /// before it touches any state.
+    pub fn check_write(self) -> Result<(), WriteDenied> {
+        if self.can_write() {
+            Ok(())
+        } else {
+            Err(WriteDenied)
+        }
+    }
+}
+
+/// An append refused because the instance is [`Permissions::ReadOnly`].
+///
+/// Each app error converts this into its own `PermissionDenied` variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("permission denied: app is read-only")]
+pub struct WriteDenied;
@@ -0,0 +1,169 @@
+//! Full-text search over document contents.
+//!
+//! The index is an inverted map from lowercase whitespace tokens to the
+//! documents containing them. It holds only derived state: replaying the
+//! ledger's document events in order rebuilds it exactly, and each new
+//! event is applied incrementally.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use ledger_spec::Hash;
+
+/// A document matching a search query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    /// Matching document.
+    pub doc_id: Hash,
+    /// Document version the match was found in.
+    pub version: u64,
+    /// Trimmed line containing the first matched term.
+    pub snippet: String,
+}
+
+/// Indexed state of one document.
+#[derive(Debug, Clone)]
+struct IndexedDocument {
+    version: u64,
+    content: String,
+    tokens: BTreeSet<String>,
+}
+
+/// Inverted index over the latest version of each document.
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    postings: BTreeMap<String, BTreeSet<Hash>>,
+    documents: BTreeMap<Hash, IndexedDocument>,
+}
+
+impl SearchIndex {
+    /// Create an empty index.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index `content` as the given version of a document, replacing any
+    /// previously indexed version.
+    pub fn index_document(&mut self, doc_id: Hash, version: u64, content: &str) {
+        self.remove_document(doc_id);
+        let tokens: BTreeSet<String> = tokenize(content).collect();
+        for token in &tokens {
+            self.postings
+                .entry(token.clone())
+                .or_default()
+                .insert(doc_id);
+        }
+        self.documents.insert(
+            doc_id,
+            IndexedDocument {
+                version,
+                content: content.to_string(),
+                tokens,
+            },
+        );
+    }
+
+    /// Drop a document from the index.
+    pub fn remove_document(&mut self, doc_id: Hash) {
+        let Some(doc) = self.documents.remove(&doc_id) else {
+            return;
+        };
+        for token in doc.tokens {
+            if let Some(ids) = self.postings.get_mut(&token) {
+                ids.remove(&doc_id);
+                if ids.is_empty() {
+                    self.postings.remove(&token);
+                }
+            }
+        }
+    }
+
+    /// Documents containing every whitespace-separated term of `query`,
+    /// compared case-insensitively, ordered by document id.
+    pub fn search(&self, query: &str) -> Vec<SearchHit> {
+        let terms: BTreeSet<String> = tokenize(query).collect();
+        let mut postings = terms.iter().map(|term| self.postings.get(term));
+        let Some(Some(first)) = postings.next() else {
+            return Vec::new();
+        };
+        let mut matches = first.clone();
+        for ids in postings {
+            let Some(ids) = ids else {
+                return Vec::new();
+            };
+            matches.retain(|id| ids.contains(id));
+        }
+
+        matches
+            .into_iter()
+            .filter_map(|doc_id| {
+                let doc = self.documents.get(&doc_id)?;
+                Some(SearchHit {
+                    doc_id,
+                    version: doc.version,
+                    snippet: snippet(&doc.content, &terms),
+                })
+            })
+            .collect()
+    }
+
+    /// Number of indexed documents.
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Whether no documents are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+}
+
+/// Normalize one whitespace-separated word: strip surrounding punctuation
+/// and lowercase.
+fn normalize(word: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+    (!word.is_empty()).then(|| word.to_lowercase())
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split_whitespace().filter_map(normalize)
+}
+
+/// First line of `content` containing one of `terms`.
+fn snippet(content: &str, terms: &BTreeSet<String>) -> String {
+    content
+        .lines()
+        .find(|line| tokenize(line).any(|token| terms.contains(&token)))
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reindexing_replaces_old_tokens() {
+        let mut index = SearchIndex::new();
+        index.index_document([1; 32], 1, "alpha beta");
+        index.index_document([1; 32], 2, "gamma");
+        assert!(index.search("alpha").is_empty());
+        assert_eq!(index.search("GAMMA")[0].version, 2);
+
+        index.remove_document([1; 32]);
+        assert!(index.is_empty());
+        assert!(index.search("gamma").is_empty());
+    }
+
+    #[test]
+    fn all_terms_must_match() {
+        let mut index = SearchIndex::new();
+        index.index_document([1; 32], 1, "red fish\nblue fish");
+        index.index_document([2; 32], 1, "red car");
+        let hits = index.search("fish, red");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].doc_id, [1; 32]);
+        assert_eq!(hits[0].snippet, "red fish");
+        assert!(index.search("").is_empty());
+    }
+}
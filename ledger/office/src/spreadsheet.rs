@@ -1,9 +1,11 @@
 //! Spreadsheet application orchestrator.
 //!
 //! Provides ledger-backed spreadsheet with cell-level versioning
-//! and formula support.
+//! and formula support. Formulas may reference cells (`A1`), ranges
+//! (`A1:A10`) and named ranges (`Revenue`); dependent cells are recalculated
+//! whenever an input or a name's definition changes.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 
 use blake3::Hasher;
@@ -11,7 +13,8 @@ use ed25519_dalek::SigningKey;
 use ledger_core::brainstem::{AppendReceipt, Ledger};
 use ledger_spec::{Hash, Timestamp};
 
-use crate::events::{CellRef, CellValue, OfficeEvent};
+use crate::events::{self, now_millis, CellRef, CellValue, OfficeEvent};
+use crate::permissions::{Permissions, WriteDenied};
 
 /// Errors from spreadsheet operations.
 #[derive(Debug, thiserror::Error)]
@@ -25,12 +28,313 @@ pub enum SpreadsheetError {
     /// Formula evaluation error.
     #[error("formula error: {0}")]
     FormulaError(String),
+    /// Name is not usable as a named range.
+    #[error("invalid name: {0}")]
+    InvalidName(String),
+    /// This app instance is read-only.
+    #[error("permission denied: app is read-only")]
+    PermissionDenied,
     /// Ledger operation failed.
     #[error("ledger error: {0}")]
     Ledger(#[from] ledger_core::apps::AppError),
     /// Serialization failed.
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+    /// CSV input was malformed or does not fit the sheet.
+    #[error("csv error: {0}")]
+    Csv(String),
+}
+
+impl From<WriteDenied> for SpreadsheetError {
+    fn from(_: WriteDenied) -> Self {
+        Self::PermissionDenied
+    }
+}
+
+/// Error value recorded for cells that take part in a reference cycle.
+pub const CIRCULAR_REF_ERROR: &str = "#CIRC";
+
+/// Parsed formula expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Cell(u32, u32),
+    Range((u32, u32), (u32, u32)),
+    /// Named range, stored uppercase.
+    Name(String),
+    Neg(Box<Expr>),
+    Binary(char, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+impl Expr {
+    /// Parse a formula (with or without the leading `=`).
+    fn parse(formula: &str) -> Result<Self, String> {
+        let src = formula.trim();
+        let src = src.strip_prefix('=').unwrap_or(src);
+        let mut parser = FormulaParser {
+            chars: src.chars().filter(|c| !c.is_whitespace()).collect(),
+            pos: 0,
+        };
+        let expr = parser.expr()?;
+        if parser.pos != parser.chars.len() {
+            return Err(format!("unexpected input at {}", parser.pos));
+        }
+        Ok(expr)
+    }
+
+    /// Collect every cell this expression reads, resolving names
+    /// through `names`; undefined names read nothing. Ranges are clipped
+    /// to `bounds` (columns, rows) so huge ranges stay cheap to track.
+    fn references(
+        &self,
+        names: &HashMap<String, CellRange>,
+        bounds: (u32, u32),
+        out: &mut HashSet<(u32, u32)>,
+    ) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Cell(col, row) => {
+                out.insert((*col, *row));
+            }
+            Expr::Range(from, to) => out.extend(range_cells(*from, *to, bounds)),
+            Expr::Name(name) => {
+                if let Some((from, to)) = names.get(name) {
+                    out.extend(range_cells(*from, *to, bounds));
+                }
+            }
+            Expr::Neg(inner) => inner.references(names, bounds, out),
+            Expr::Binary(_, lhs, rhs) => {
+                lhs.references(names, bounds, out);
+                rhs.references(names, bounds, out);
+            }
+            Expr::Call(_, args) => args
+                .iter()
+                .for_each(|arg| arg.references(names, bounds, out)),
+        }
+    }
+
+    /// Whether this expression refers to the (uppercase) `name`.
+    fn mentions(&self, name: &str) -> bool {
+        match self {
+            Expr::Name(n) => n == name,
+            Expr::Neg(inner) => inner.mentions(name),
+            Expr::Binary(_, lhs, rhs) => lhs.mentions(name) || rhs.mentions(name),
+            Expr::Call(_, args) => args.iter().any(|arg| arg.mentions(name)),
+            Expr::Number(_) | Expr::Cell(..) | Expr::Range(..) => false,
+        }
+    }
+
+    /// Evaluate against current sheet values; errors are cell error codes.
+    fn eval(&self, sheet: &Sheet) -> Result<f64, String> {
+        match self {
+            Expr::Number(n) => Ok(*n),
+            Expr::Cell(col, row) => match &sheet.get_cell(*col, *row).value {
+                CellValue::Empty => Ok(0.0),
+                CellValue::Number(n) => Ok(*n),
+                CellValue::Boolean(b) => Ok(if *b { 1.0 } else { 0.0 }),
+                CellValue::Text(_) => Err("#VALUE!".into()),
+                CellValue::Error(e) => Err(e.clone()),
+            },
+            Expr::Range(..) => Err("#VALUE!".into()),
+            Expr::Name(name) => match sheet.names.get(name) {
+                Some(&(from, to)) if from == to => Expr::Cell(from.0, from.1).eval(sheet),
+                Some(_) => Err("#VALUE!".into()),
+                None => Err("#NAME?".into()),
+            },
+            Expr::Neg(inner) => Ok(-inner.eval(sheet)?),
+            Expr::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.eval(sheet)?, rhs.eval(sheet)?);
+                match op {
+                    '+' => Ok(a + b),
+                    '-' => Ok(a - b),
+                    '*' => Ok(a * b),
+                    _ if b == 0.0 => Err("#DIV/0!".into()),
+                    _ => Ok(a / b),
+                }
+            }
+            Expr::Call(name, args) => {
+                let mut values = Vec::new();
+                for arg in args {
+                    let (from, to) = match arg {
+                        Expr::Range(from, to) => (*from, *to),
+                        Expr::Name(name) => *sheet.names.get(name).ok_or("#NAME?")?,
+                        other => {
+                            values.push(other.eval(sheet)?);
+                            continue;
+                        }
+                    };
+                    // Only stored cells can hold values, so walk those
+                    // rather than every coordinate the range spans.
+                    let mut cells: Vec<(&(u32, u32), &Cell)> = sheet
+                        .cells
+                        .iter()
+                        .filter(|(key, _)| in_range(from, to, **key))
+                        .collect();
+                    cells.sort_by_key(|((col, row), _)| (*row, *col));
+                    for (_, cell) in cells {
+                        match &cell.value {
+                            CellValue::Number(n) => values.push(*n),
+                            CellValue::Error(e) => return Err(e.clone()),
+                            _ => {}
+                        }
+                    }
+                }
+                match name.as_str() {
+                    "SUM" => Ok(values.iter().sum()),
+                    "AVG" if values.is_empty() => Err("#DIV/0!".into()),
+                    "AVG" => Ok(values.iter().sum::<f64>() / values.len() as f64),
+                    "MIN" => Ok(values.into_iter().reduce(f64::min).unwrap_or(0.0)),
+                    "MAX" => Ok(values.into_iter().reduce(f64::max).unwrap_or(0.0)),
+                    _ => Err("#NAME?".into()),
+                }
+            }
+        }
+    }
+}
+
+/// Opposite corners of a rectangular range, as (col, row) pairs.
+type CellRange = ((u32, u32), (u32, u32));
+
+/// Parse `A1` or `A1:B10` into the corners of a range.
+fn parse_range(range: &str) -> Option<CellRange> {
+    let corner = |a1: &str| CellRef::from_a1(a1.trim()).map(|cell| (cell.col, cell.row));
+    match range.split_once(':') {
+        Some((from, to)) => Some((corner(from)?, corner(to)?)),
+        None => corner(range).map(|cell| (cell, cell)),
+    }
+}
+
+/// Cells covered by a rectangular range within `bounds` (columns, rows),
+/// in row-major order.
+fn range_cells(
+    from: (u32, u32),
+    to: (u32, u32),
+    bounds: (u32, u32),
+) -> impl Iterator<Item = (u32, u32)> {
+    let (cols, rows) = (
+        from.0.min(to.0)..from.0.max(to.0).saturating_add(1).min(bounds.0),
+        from.1.min(to.1)..from.1.max(to.1).saturating_add(1).min(bounds.1),
+    );
+    rows.flat_map(move |row| cols.clone().map(move |col| (col, row)))
+}
+
+/// Whether `cell` lies inside the range between `from` and `to`.
+fn in_range(from: (u32, u32), to: (u32, u32), cell: (u32, u32)) -> bool {
+    (from.0.min(to.0)..=from.0.max(to.0)).contains(&cell.0)
+        && (from.1.min(to.1)..=from.1.max(to.1)).contains(&cell.1)
+}
+
+/// Recursive-descent parser for formula expressions.
+struct FormulaParser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl FormulaParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.term()?));
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.eat('-') {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.expr()?;
+                if !self.eat(')') {
+                    return Err("expected ')'".into());
+                }
+                Ok(inner)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == '.');
+                number
+                    .parse()
+                    .map(Expr::Number)
+                    .map_err(|_| format!("invalid number: {number}"))
+            }
+            Some(c) if c.is_ascii_alphabetic() => {
+                let word = self.take_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                if self.eat('(') {
+                    return self.call(word.to_ascii_uppercase());
+                }
+                let Ok(from) = Self::cell(&word) else {
+                    return Ok(Expr::Name(word.to_ascii_uppercase()));
+                };
+                if self.eat(':') {
+                    let to = self.take_while(|c| c.is_ascii_alphanumeric());
+                    return Ok(Expr::Range(from, Self::cell(&to)?));
+                }
+                Ok(Expr::Cell(from.0, from.1))
+            }
+            Some(c) => Err(format!("unexpected '{c}'")),
+            None => Err("unexpected end of formula".into()),
+        }
+    }
+
+    fn call(&mut self, name: String) -> Result<Expr, String> {
+        let mut args = Vec::new();
+        if !self.eat(')') {
+            loop {
+                args.push(self.expr()?);
+                if self.eat(')') {
+                    break;
+                }
+                if !self.eat(',') {
+                    return Err("expected ',' or ')'".into());
+                }
+            }
+        }
+        Ok(Expr::Call(name, args))
+    }
+
+    fn cell(word: &str) -> Result<(u32, u32), String> {
+        CellRef::from_a1(word)
+            .map(|cell| (cell.col, cell.row))
+            .ok_or_else(|| format!("invalid cell reference: {word}"))
+    }
+
+    fn take_while(&mut self, pred: impl Fn(char) -> bool) -> String {
+        let start = self.pos;
+        while self.peek().is_some_and(&pred) {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
 }
 
 /// A single cell in the spreadsheet.
@@ -57,6 +361,14 @@ pub struct Sheet {
     pub cells: HashMap<(u32, u32), Cell>,
     /// Last modified timestamp.
     pub modified_at: Timestamp,
+    /// Cells read by each formula cell.
+    precedents: HashMap<(u32, u32), HashSet<(u32, u32)>>,
+    /// Formula cells that read each cell.
+    dependents: HashMap<(u32, u32), HashSet<(u32, u32)>>,
+    /// Named ranges, keyed by uppercase name.
+    names: HashMap<String, CellRange>,
+    /// Comments attached to cells.
+    comments: HashMap<(u32, u32), String>,
 }
 
 impl Sheet {
@@ -76,6 +388,10 @@ impl Sheet {
             rows,
             cells: HashMap::new(),
             modified_at: now_millis(),
+            precedents: HashMap::new(),
+            dependents: HashMap::new(),
+            names: HashMap::new(),
+            comments: HashMap::new(),
         }
     }
 
@@ -89,20 +405,188 @@ impl Sheet {
     }
 
     /// Set a cell value.
+    ///
+    /// Formula cells are not evaluated here; call [`Sheet::recalculate`]
+    /// with the changed cells to refresh them and their dependents.
     pub fn set_cell(&mut self, col: u32, row: u32, value: CellValue, formula: Option<String>) {
-        self.cells.insert((col, row), Cell { value, formula });
+        let key = (col, row);
+        self.link(key, formula.as_deref());
+        self.cells.insert(key, Cell { value, formula });
         self.modified_at = now_millis();
     }
+
+    /// Replace the recorded precedents of `key` with those `formula` reads.
+    fn link(&mut self, key: (u32, u32), formula: Option<&str>) {
+        for precedent in self.precedents.remove(&key).unwrap_or_default() {
+            if let Some(dependents) = self.dependents.get_mut(&precedent) {
+                dependents.remove(&key);
+            }
+        }
+        if let Some(Ok(expr)) = formula.map(Expr::parse) {
+            let mut refs = HashSet::new();
+            expr.references(&self.names, (self.columns, self.rows), &mut refs);
+            for precedent in &refs {
+                self.dependents.entry(*precedent).or_default().insert(key);
+            }
+            self.precedents.insert(key, refs);
+        }
+    }
+
+    /// Range a name currently refers to, as opposite (col, row) corners.
+    ///
+    /// Names are case-insensitive.
+    pub fn named_range(&self, name: &str) -> Option<((u32, u32), (u32, u32))> {
+        self.names.get(&name.to_ascii_uppercase()).copied()
+    }
+
+    /// Point `name` at the range between `from` and `to`, then recalculate
+    /// every formula that uses it. Returns the recalculated cells.
+    pub fn define_name(&mut self, name: &str, from: (u32, u32), to: (u32, u32)) -> Vec<(u32, u32)> {
+        let name = name.to_ascii_uppercase();
+        self.names.insert(name.clone(), (from, to));
+        let users: Vec<((u32, u32), String)> = self
+            .cells
+            .iter()
+            .filter_map(|(key, cell)| {
+                let formula = cell.formula.as_ref()?;
+                let expr = Expr::parse(formula).ok()?;
+                expr.mentions(&name).then(|| (*key, formula.clone()))
+            })
+            .collect();
+        for (key, formula) in &users {
+            self.link(*key, Some(formula));
+        }
+        let users: Vec<(u32, u32)> = users.into_iter().map(|(key, _)| key).collect();
+        self.recalculate(&users)
+    }
+
+    /// Comment attached to a cell, if any.
+    pub fn comment(&self, col: u32, row: u32) -> Option<&str> {
+        self.comments.get(&(col, row)).map(String::as_str)
+    }
+
+    /// Attach `text` to a cell; empty text removes its comment.
+    pub fn set_comment(&mut self, col: u32, row: u32, text: impl Into<String>) {
+        let text = text.into();
+        if text.is_empty() {
+            self.comments.remove(&(col, row));
+        } else {
+            self.comments.insert((col, row), text);
+        }
+        self.modified_at = now_millis();
+    }
+
+    /// Re-evaluate formula cells among `changed` and everything that
+    /// transitively depends on them, in dependency order.
+    ///
+    /// Cells that cannot be ordered because they lie on (or downstream of)
+    /// a reference cycle are set to [`CIRCULAR_REF_ERROR`]. Returns the
+    /// formula cells that were recalculated.
+    pub fn recalculate(&mut self, changed: &[(u32, u32)]) -> Vec<(u32, u32)> {
+        let mut affected = HashSet::new();
+        let mut queue: VecDeque<(u32, u32)> = changed.iter().copied().collect();
+        while let Some(cell) = queue.pop_front() {
+            if affected.insert(cell) {
+                if let Some(dependents) = self.dependents.get(&cell) {
+                    queue.extend(dependents.iter().copied());
+                }
+            }
+        }
+
+        let formula_cells: Vec<(u32, u32)> = affected
+            .iter()
+            .copied()
+            .filter(|cell| self.get_cell(cell.0, cell.1).formula.is_some())
+            .collect();
+        let mut pending: HashMap<(u32, u32), usize> = formula_cells
+            .iter()
+            .map(|cell| {
+                let waiting = self.precedents.get(cell).map_or(0, |refs| {
+                    refs.iter()
+                        .filter(|r| {
+                            affected.contains(*r)
+                                && self.cells.get(*r).is_some_and(|c| c.formula.is_some())
+                        })
+                        .count()
+                });
+                (*cell, waiting)
+            })
+            .collect();
+
+        let mut ready: VecDeque<(u32, u32)> = pending
+            .iter()
+            .filter(|(_, waiting)| **waiting == 0)
+            .map(|(cell, _)| *cell)
+            .collect();
+        let mut order = Vec::with_capacity(formula_cells.len());
+        while let Some(cell) = ready.pop_front() {
+            pending.remove(&cell);
+            let value = self.evaluate(cell);
+            if let Some(entry) = self.cells.get_mut(&cell) {
+                entry.value = value;
+            }
+            order.push(cell);
+            for dependent in self.dependents.get(&cell).into_iter().flatten() {
+                if let Some(waiting) = pending.get_mut(dependent) {
+                    *waiting -= 1;
+                    if *waiting == 0 {
+                        ready.push_back(*dependent);
+                    }
+                }
+            }
+        }
+
+        for (cell, _) in pending {
+            if let Some(entry) = self.cells.get_mut(&cell) {
+                entry.value = CellValue::Error(CIRCULAR_REF_ERROR.into());
+            }
+            order.push(cell);
+        }
+        if !order.is_empty() {
+            self.modified_at = now_millis();
+        }
+        order
+    }
+
+    /// Evaluate the formula stored at `cell` against current values.
+    fn evaluate(&self, cell: (u32, u32)) -> CellValue {
+        let Some(formula) = self.get_cell(cell.0, cell.1).formula.as_deref() else {
+            return self.get_cell(cell.0, cell.1).value.clone();
+        };
+        match Expr::parse(formula) {
+            Ok(expr) => match expr.eval(self) {
+                Ok(n) => CellValue::Number(n),
+                Err(code) => CellValue::Error(code),
+            },
+            Err(_) => CellValue::Error("#PARSE!".into()),
+        }
+    }
+}
+
+/// Input state of the cells touched by one recorded change.
+#[derive(Debug, Clone)]
+struct CellEdit {
+    sheet_id: Hash,
+    before: Vec<((u32, u32), Cell)>,
+    after: Vec<((u32, u32), Cell)>,
 }
 
+/// A cell update as recorded in office events.
+type CellUpdate = (CellRef, CellValue, Option<String>);
+
 /// Spreadsheet application orchestrator.
 pub struct SpreadsheetApp {
     ledger: Ledger,
     signer: Arc<SigningKey>,
     channel: String,
+    permissions: Permissions,
     schema_version: u16,
     /// In-memory sheet index.
     sheets: HashMap<Hash, Sheet>,
+    /// Changes that can be undone, most recent last.
+    undo_stack: Vec<CellEdit>,
+    /// Undone changes that can be reapplied, most recent last.
+    redo_stack: Vec<CellEdit>,
 }
 
 impl SpreadsheetApp {
@@ -117,35 +601,37 @@ impl SpreadsheetApp {
             ledger,
             signer: Arc::new(signer),
             channel: channel.into(),
+            permissions: Permissions::default(),
             schema_version,
             sheets: HashMap::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
         }
     }
 
+    /// Restrict this instance to `permissions`. A read-only instance still
+    /// evaluates and exports sheets but refuses every edit, undo and redo
+    /// before the sheet is touched.
+    pub fn with_permissions(mut self, permissions: Permissions) -> Self {
+        self.permissions = permissions;
+        self
+    }
+
+    /// Whether this instance may edit sheets.
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+
     /// Append an office event to the ledger.
     fn append_office_event(&self, event: OfficeEvent) -> Result<AppendReceipt, SpreadsheetError> {
-        let payload = serde_json::to_value(&event)?;
-        let body = ledger_spec::EnvelopeBody {
-            payload,
-            payload_type: Some("ea.office.v1".into()),
-        };
-        let body_hash = ledger_spec::hash_body(&body);
-        let mut env = ledger_spec::Envelope {
-            header: ledger_spec::EnvelopeHeader {
-                channel: self.channel.clone(),
-                version: self.schema_version,
-                prev: self.ledger.tail_hash(),
-                body_hash,
-                timestamp: now_millis(),
-            },
-            body,
-            signatures: Vec::new(),
-            attestations: Vec::new(),
-        };
-        ledger_core::signing::sign_envelope(&mut env, &self.signer);
-        self.ledger.append(env).map_err(|e| SpreadsheetError::Ledger(
-            ledger_core::apps::AppError::Ledger(e)
-        ))
+        events::append_office_event(
+            &self.ledger,
+            &self.signer,
+            &self.channel,
+            self.schema_version,
+            self.permissions,
+            event,
+        )
     }
 
     /// Create a new spreadsheet.
@@ -170,6 +656,10 @@ impl SpreadsheetApp {
     }
 
     /// Update a single cell.
+    ///
+    /// Formula cells are evaluated and dependents recalculated. When other
+    /// cells change as a result, all of them are recorded together as one
+    /// `CellBatchUpdated` event.
     pub fn update_cell(
         &mut self,
         sheet_id: Hash,
@@ -178,28 +668,184 @@ impl SpreadsheetApp {
         value: CellValue,
         formula: Option<String>,
     ) -> Result<AppendReceipt, SpreadsheetError> {
-        if !self.sheets.contains_key(&sheet_id) {
-            return Err(SpreadsheetError::NotFound(hex::encode(sheet_id)));
+        let edits = vec![(col, row, value, formula)];
+        let (receipt, edit) = self.commit_edit(sheet_id, edits, |mut updates| {
+            if updates.len() == 1 {
+                let (cell, value, formula) = updates.remove(0);
+                OfficeEvent::CellUpdated {
+                    sheet_id,
+                    cell,
+                    value,
+                    formula,
+                }
+            } else {
+                OfficeEvent::CellBatchUpdated { sheet_id, updates }
+            }
+        })?;
+        self.record_edit(edit);
+        Ok(receipt)
+    }
+
+    /// Apply `edits` to the sheet in place and append the event `event`
+    /// builds from the resulting cell updates.
+    ///
+    /// If the append fails, the inputs saved in the change record are put
+    /// back and their dependents recalculated, leaving the sheet as it was.
+    fn commit_edit(
+        &mut self,
+        sheet_id: Hash,
+        edits: Vec<(u32, u32, CellValue, Option<String>)>,
+        event: impl FnOnce(Vec<CellUpdate>) -> OfficeEvent,
+    ) -> Result<(AppendReceipt, CellEdit), SpreadsheetError> {
+        self.permissions.check_write()?;
+        let sheet = self
+            .sheets
+            .get_mut(&sheet_id)
+            .ok_or_else(|| SpreadsheetError::NotFound(hex::encode(sheet_id)))?;
+        let modified_at = sheet.modified_at;
+        let (edit, updates) = Self::apply_edit(sheet, edits);
+
+        match self.append_office_event(event(updates)) {
+            Ok(receipt) => Ok((receipt, edit)),
+            Err(e) => {
+                if let Some(sheet) = self.sheets.get_mut(&sheet_id) {
+                    Self::apply_edit(sheet, Self::cell_inputs(&edit.before));
+                    sheet.modified_at = modified_at;
+                }
+                Err(e)
+            }
         }
+    }
 
-        // Evaluate formula if present
-        let final_value = if let Some(ref formula_str) = formula {
-            Self::parse_simple_formula(formula_str).unwrap_or(value.clone())
-        } else {
-            value.clone()
-        };
+    /// Set cells and recalculate dependents, and return the change record
+    /// along with every resulting cell update.
+    fn apply_edit(
+        sheet: &mut Sheet,
+        edits: Vec<(u32, u32, CellValue, Option<String>)>,
+    ) -> (CellEdit, Vec<CellUpdate>) {
+        let mut before = Vec::new();
+        let mut edited = Vec::with_capacity(edits.len());
+        for (col, row, value, formula) in edits {
+            if !edited.contains(&(col, row)) {
+                before.push(((col, row), sheet.get_cell(col, row).clone()));
+                edited.push((col, row));
+            }
+            sheet.set_cell(col, row, value, formula);
+        }
+        let recalculated = sheet.recalculate(&edited);
 
-        let sheet = self.sheets.get_mut(&sheet_id).unwrap();
-        sheet.set_cell(col, row, final_value.clone(), formula.clone());
+        let after = edited
+            .iter()
+            .map(|&(col, row)| ((col, row), sheet.get_cell(col, row).clone()))
+            .collect();
+        let updates = Self::collect_updates(sheet, &edited, recalculated);
+        (
+            CellEdit {
+                sheet_id: sheet.id,
+                before,
+                after,
+            },
+            updates,
+        )
+    }
 
-        let event = OfficeEvent::CellUpdated {
-            sheet_id,
-            cell: CellRef::new(col, row),
-            value: final_value,
-            formula,
+    /// Edits that set each recorded cell back to its saved input.
+    fn cell_inputs(cells: &[((u32, u32), Cell)]) -> Vec<(u32, u32, CellValue, Option<String>)> {
+        cells
+            .iter()
+            .map(|((col, row), cell)| (*col, *row, cell.value.clone(), cell.formula.clone()))
+            .collect()
+    }
+
+    /// Push a newly applied change; any undone changes are discarded.
+    fn record_edit(&mut self, edit: CellEdit) {
+        self.undo_stack.push(edit);
+        self.redo_stack.clear();
+    }
+
+    /// Undo the most recent change.
+    ///
+    /// The previous cell contents are restored and recorded as a new
+    /// `CellBatchUpdated` event; ledger history is never rewritten.
+    /// Returns `None` when there is nothing to undo.
+    pub fn undo(&mut self) -> Result<Option<AppendReceipt>, SpreadsheetError> {
+        let Some(edit) = self.undo_stack.pop() else {
+            return Ok(None);
         };
+        match self.replay_cells(edit.sheet_id, &edit.before) {
+            Ok(receipt) => {
+                self.redo_stack.push(edit);
+                Ok(Some(receipt))
+            }
+            Err(e) => {
+                self.undo_stack.push(edit);
+                Err(e)
+            }
+        }
+    }
 
-        self.append_office_event(event)
+    /// Reapply the most recently undone change as a new event.
+    ///
+    /// Returns `None` when there is nothing to redo.
+    pub fn redo(&mut self) -> Result<Option<AppendReceipt>, SpreadsheetError> {
+        let Some(edit) = self.redo_stack.pop() else {
+            return Ok(None);
+        };
+        match self.replay_cells(edit.sheet_id, &edit.after) {
+            Ok(receipt) => {
+                self.undo_stack.push(edit);
+                Ok(Some(receipt))
+            }
+            Err(e) => {
+                self.redo_stack.push(edit);
+                Err(e)
+            }
+        }
+    }
+
+    /// Whether [`SpreadsheetApp::undo`] has a change to revert.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Whether [`SpreadsheetApp::redo`] has a change to reapply.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Restore recorded cell inputs and append the compensating event.
+    fn replay_cells(
+        &mut self,
+        sheet_id: Hash,
+        cells: &[((u32, u32), Cell)],
+    ) -> Result<AppendReceipt, SpreadsheetError> {
+        let (receipt, _) = self.commit_edit(sheet_id, Self::cell_inputs(cells), |updates| {
+            OfficeEvent::CellBatchUpdated { sheet_id, updates }
+        })?;
+        Ok(receipt)
+    }
+
+    /// Snapshot `edited` cells followed by any other recalculated cells.
+    fn collect_updates(
+        sheet: &Sheet,
+        edited: &[(u32, u32)],
+        recalculated: Vec<(u32, u32)>,
+    ) -> Vec<CellUpdate> {
+        let mut seen = HashSet::new();
+        edited
+            .iter()
+            .copied()
+            .chain(recalculated)
+            .filter(|cell| seen.insert(*cell))
+            .map(|(col, row)| {
+                let cell = sheet.get_cell(col, row);
+                (
+                    CellRef::new(col, row),
+                    cell.value.clone(),
+                    cell.formula.clone(),
+                )
+            })
+            .collect()
     }
 
     /// Update multiple cells in a batch.
@@ -208,91 +854,146 @@ impl SpreadsheetApp {
         sheet_id: Hash,
         updates: Vec<(u32, u32, CellValue, Option<String>)>,
     ) -> Result<AppendReceipt, SpreadsheetError> {
-        let sheet = self.sheets.get_mut(&sheet_id)
+        let (receipt, edit) = self.commit_edit(sheet_id, updates, |updates| {
+            OfficeEvent::CellBatchUpdated { sheet_id, updates }
+        })?;
+        self.record_edit(edit);
+        Ok(receipt)
+    }
+
+    /// Import RFC 4180 CSV into a sheet, anchored at A1.
+    ///
+    /// Fields that parse as finite numbers become [`CellValue::Number`],
+    /// other non-empty fields [`CellValue::Text`]. Empty fields clear any
+    /// existing cell. Everything is recorded as one `CellBatchUpdated` event
+    /// and undoes as a single change.
+    pub fn import_csv(
+        &mut self,
+        sheet_id: Hash,
+        csv: &str,
+    ) -> Result<AppendReceipt, SpreadsheetError> {
+        let sheet = self
+            .sheets
+            .get(&sheet_id)
             .ok_or_else(|| SpreadsheetError::NotFound(hex::encode(sheet_id)))?;
+        let records = parse_csv(csv).map_err(SpreadsheetError::Csv)?;
+        let width = records.iter().map(Vec::len).max().unwrap_or(0);
+        if records.len() > sheet.rows as usize || width > sheet.columns as usize {
+            return Err(SpreadsheetError::Csv(format!(
+                "{width}x{} grid does not fit {}x{} sheet",
+                records.len(),
+                sheet.columns,
+                sheet.rows
+            )));
+        }
 
-        let mut batch = Vec::new();
-        for (col, row, value, formula) in updates {
-            let final_value = if let Some(ref formula_str) = formula {
-                // Simple formula evaluation
-                match Self::parse_simple_formula(formula_str) {
-                    Some(v) => v,
-                    None => value.clone(),
+        let mut updates = Vec::new();
+        for (row, record) in (0u32..).zip(&records) {
+            for (col, field) in (0u32..).zip(record) {
+                let value = infer_cell_value(field);
+                let existing = sheet.get_cell(col, row);
+                let unchanged = value == CellValue::Empty
+                    && existing.value == CellValue::Empty
+                    && existing.formula.is_none();
+                if !unchanged {
+                    updates.push((col, row, value, None));
                 }
-            } else {
-                value.clone()
-            };
-
-            sheet.set_cell(col, row, final_value.clone(), formula.clone());
-            batch.push((CellRef::new(col, row), final_value, formula));
+            }
         }
+        self.update_cells_batch(sheet_id, updates)
+    }
 
-        let event = OfficeEvent::CellBatchUpdated {
-            sheet_id,
-            updates: batch,
+    /// Render a sheet's current cell values as RFC 4180 CSV.
+    ///
+    /// The grid spans A1 to the last non-empty row and column; formula
+    /// cells export their computed values.
+    pub fn export_csv(&self, sheet_id: Hash) -> Result<String, SpreadsheetError> {
+        let sheet = self
+            .sheets
+            .get(&sheet_id)
+            .ok_or_else(|| SpreadsheetError::NotFound(hex::encode(sheet_id)))?;
+        let filled = sheet
+            .cells
+            .iter()
+            .filter(|(_, cell)| cell.value != CellValue::Empty)
+            .map(|(key, _)| *key);
+        let Some((cols, rows)) = filled.fold(None, |extent, (col, row)| {
+            let (cols, rows) = extent.unwrap_or((0, 0));
+            Some((cols.max(col + 1), rows.max(row + 1)))
+        }) else {
+            return Ok(String::new());
         };
 
-        self.append_office_event(event)
-    }
-
-    /// Simple formula evaluation (for demo).
-    /// Will be used when cell reference formulas (=A1+B2) are implemented.
-    #[allow(dead_code)]
-    fn evaluate_formula(&self, _sheet: &Sheet, formula: &str) -> Result<CellValue, SpreadsheetError> {
-        if let Some(value) = Self::parse_simple_formula(formula) {
-            Ok(value)
-        } else {
-            Err(SpreadsheetError::FormulaError(format!(
-                "unsupported formula: {}",
-                formula
-            )))
+        let mut out = String::new();
+        for row in 0..rows {
+            let fields: Vec<String> = (0..cols)
+                .map(|col| csv_field(&sheet.get_cell(col, row).value))
+                .collect();
+            out.push_str(&fields.join(","));
+            out.push_str("\r\n");
         }
+        Ok(out)
     }
 
-    /// Parse simple formulas like "=1+2" or "=SUM(1,2,3)".
-    fn parse_simple_formula(formula: &str) -> Option<CellValue> {
-        let formula = formula.trim();
-        if !formula.starts_with('=') {
-            return None;
+    /// Define a named range (`"Revenue"` for `"B2:B13"`) usable in formulas.
+    ///
+    /// Names start with a letter, contain only letters, digits and `_`,
+    /// and must not look like a cell reference. Redefining a name moves it
+    /// and recalculates every formula that uses it.
+    pub fn define_name(
+        &mut self,
+        sheet_id: Hash,
+        name: &str,
+        range: &str,
+    ) -> Result<AppendReceipt, SpreadsheetError> {
+        let valid = name.starts_with(|c: char| c.is_ascii_alphabetic())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && CellRef::from_a1(name).is_none();
+        if !valid {
+            return Err(SpreadsheetError::InvalidName(name.to_string()));
         }
-        let expr = &formula[1..];
-
-        // Try simple number
-        if let Ok(n) = expr.parse::<f64>() {
-            return Some(CellValue::Number(n));
+        let (from, to) =
+            parse_range(range).ok_or_else(|| SpreadsheetError::InvalidCell(range.to_string()))?;
+        if !self.sheets.contains_key(&sheet_id) {
+            return Err(SpreadsheetError::NotFound(hex::encode(sheet_id)));
         }
 
-        // Try simple addition: "1+2"
-        if let Some((a, b)) = expr.split_once('+') {
-            if let (Ok(a), Ok(b)) = (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
-                return Some(CellValue::Number(a + b));
-            }
+        let event = OfficeEvent::NameDefined {
+            sheet_id,
+            name: name.to_string(),
+            range: range.to_string(),
+        };
+        let receipt = self.append_office_event(event)?;
+        if let Some(sheet) = self.sheets.get_mut(&sheet_id) {
+            sheet.define_name(name, from, to);
         }
+        Ok(receipt)
+    }
 
-        // Try SUM(1,2,3)
-        if expr.starts_with("SUM(") && expr.ends_with(')') {
-            let inner = &expr[4..expr.len() - 1];
-            let sum: f64 = inner
-                .split(',')
-                .filter_map(|s| s.trim().parse::<f64>().ok())
-                .sum();
-            return Some(CellValue::Number(sum));
+    /// Attach a comment to a cell, replacing any existing one; empty text
+    /// removes it.
+    pub fn comment_cell(
+        &mut self,
+        sheet_id: Hash,
+        col: u32,
+        row: u32,
+        comment: impl Into<String>,
+    ) -> Result<AppendReceipt, SpreadsheetError> {
+        if !self.sheets.contains_key(&sheet_id) {
+            return Err(SpreadsheetError::NotFound(hex::encode(sheet_id)));
         }
+        let comment = comment.into();
 
-        // Try AVG(1,2,3)
-        if expr.starts_with("AVG(") && expr.ends_with(')') {
-            let inner = &expr[4..expr.len() - 1];
-            let nums: Vec<f64> = inner
-                .split(',')
-                .filter_map(|s| s.trim().parse::<f64>().ok())
-                .collect();
-            if !nums.is_empty() {
-                let avg = nums.iter().sum::<f64>() / nums.len() as f64;
-                return Some(CellValue::Number(avg));
-            }
+        let event = OfficeEvent::CellCommented {
+            sheet_id,
+            cell: CellRef::new(col, row),
+            comment: comment.clone(),
+        };
+        let receipt = self.append_office_event(event)?;
+        if let Some(sheet) = self.sheets.get_mut(&sheet_id) {
+            sheet.set_comment(col, row, comment);
         }
-
-        None
+        Ok(receipt)
     }
 
     /// Delete a spreadsheet.
@@ -312,6 +1013,8 @@ impl SpreadsheetApp {
 
         let receipt = self.append_office_event(event)?;
         self.sheets.remove(&id);
+        self.undo_stack.retain(|edit| edit.sheet_id != id);
+        self.redo_stack.retain(|edit| edit.sheet_id != id);
         Ok(receipt)
     }
 
@@ -326,12 +1029,88 @@ impl SpreadsheetApp {
     }
 }
 
-fn now_millis() -> Timestamp {
-    use std::time::{SystemTime, UNIX_EPOCH};
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+/// Split RFC 4180 CSV into records of unescaped fields.
+///
+/// Accepts CRLF or bare LF record separators and an optional final
+/// separator.
+fn parse_csv(input: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut after_quote = false;
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c != '"' {
+                field.push(c);
+            } else if chars.peek() == Some(&'"') {
+                chars.next();
+                field.push('"');
+            } else {
+                in_quotes = false;
+                after_quote = true;
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() && !after_quote => in_quotes = true,
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                after_quote = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+                after_quote = false;
+            }
+            _ if after_quote => {
+                return Err(format!(
+                    "unexpected {c:?} after closing quote in record {}",
+                    records.len() + 1
+                ))
+            }
+            '"' => return Err(format!("stray quote in record {}", records.len() + 1)),
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err(format!(
+            "unterminated quoted field in record {}",
+            records.len() + 1
+        ));
+    }
+    if after_quote || !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+fn infer_cell_value(field: &str) -> CellValue {
+    if field.is_empty() {
+        return CellValue::Empty;
+    }
+    match field.parse::<f64>() {
+        Ok(n) if n.is_finite() => CellValue::Number(n),
+        _ => CellValue::Text(field.to_string()),
+    }
+}
+
+/// Render one cell value as a CSV field, quoting it when needed.
+fn csv_field(value: &CellValue) -> String {
+    let text = match value {
+        CellValue::Empty => return String::new(),
+        CellValue::Number(n) => return n.to_string(),
+        CellValue::Boolean(b) => return b.to_string().to_uppercase(),
+        CellValue::Text(s) | CellValue::Error(s) => s,
+    };
+    if text.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.clone()
+    }
 }
 
 #[cfg(test)]
@@ -348,9 +1127,11 @@ mod tests {
             policy: ledger_spec::ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: vec![signer.verifying_key().to_bytes()],
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
         let ledger = Ledger::new(registry);
         SpreadsheetApp::new(ledger, signer, "office.spreadsheets", 1)
@@ -436,4 +1217,429 @@ mod tests {
         assert!(matches!(updated.get_cell(1, 0).value, CellValue::Number(n) if (n - 2.0).abs() < 0.001));
         assert!(matches!(updated.get_cell(2, 0).value, CellValue::Number(n) if (n - 3.0).abs() < 0.001));
     }
+
+    fn number_at(app: &SpreadsheetApp, sheet_id: Hash, col: u32, row: u32) -> f64 {
+        match app.get_sheet(&sheet_id).unwrap().get_cell(col, row).value {
+            CellValue::Number(n) => n,
+            ref other => panic!("expected number, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn chained_references_recalculate() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Chain", 5, 5).unwrap();
+
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(2.0), None)
+            .unwrap();
+        app.update_cell(sheet.id, 1, 0, CellValue::Empty, Some("=A1*10".into()))
+            .unwrap();
+        app.update_cell(
+            sheet.id,
+            2,
+            0,
+            CellValue::Empty,
+            Some("=(B1 - A1) / 2 + -1".into()),
+        )
+        .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 1, 0), 20.0);
+        assert_eq!(number_at(&app, sheet.id, 2, 0), 8.0);
+
+        let before = app.ledger.offsets_for_channel("office.spreadsheets").len();
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(4.0), None)
+            .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 1, 0), 40.0);
+        assert_eq!(number_at(&app, sheet.id, 2, 0), 17.0);
+        assert_eq!(
+            app.ledger.offsets_for_channel("office.spreadsheets").len(),
+            before + 1
+        );
+    }
+
+    #[test]
+    fn sum_over_range() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Totals", 5, 20).unwrap();
+
+        let updates = (0..10)
+            .map(|row| (0, row, CellValue::Number(row as f64 + 1.0), None))
+            .collect();
+        app.update_cells_batch(sheet.id, updates).unwrap();
+        app.update_cell(
+            sheet.id,
+            1,
+            0,
+            CellValue::Empty,
+            Some("=SUM(A1:A10)".into()),
+        )
+        .unwrap();
+        app.update_cell(
+            sheet.id,
+            1,
+            1,
+            CellValue::Empty,
+            Some("=AVG(A1:A10)".into()),
+        )
+        .unwrap();
+        app.update_cell(
+            sheet.id,
+            1,
+            2,
+            CellValue::Empty,
+            Some("=MAX(A1:A10) - MIN(A1:A10)".into()),
+        )
+        .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 1, 0), 55.0);
+        assert_eq!(number_at(&app, sheet.id, 1, 1), 5.5);
+        assert_eq!(number_at(&app, sheet.id, 1, 2), 9.0);
+
+        app.update_cell(sheet.id, 0, 9, CellValue::Number(100.0), None)
+            .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 1, 0), 145.0);
+        assert_eq!(number_at(&app, sheet.id, 1, 2), 99.0);
+    }
+
+    #[test]
+    fn huge_range_reads_only_stored_cells() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Sparse", 26, 100).unwrap();
+
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(2.0), None)
+            .unwrap();
+        app.update_cell(
+            sheet.id,
+            1,
+            0,
+            CellValue::Empty,
+            Some("=SUM(A1:A1048576)".into()),
+        )
+        .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 1, 0), 2.0);
+        let tracked = &app.get_sheet(&sheet.id).unwrap().precedents[&(1, 0)];
+        assert_eq!(tracked.len(), 100);
+
+        app.update_cell(sheet.id, 0, 99, CellValue::Number(40.0), None)
+            .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 1, 0), 42.0);
+    }
+
+    #[test]
+    fn circular_reference_detected() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Cycle", 5, 5).unwrap();
+
+        app.update_cell(sheet.id, 0, 0, CellValue::Empty, Some("=B1+1".into()))
+            .unwrap();
+        app.update_cell(sheet.id, 1, 0, CellValue::Empty, Some("=A1+1".into()))
+            .unwrap();
+        app.update_cell(sheet.id, 2, 0, CellValue::Empty, Some("=C1".into()))
+            .unwrap();
+
+        let sheet_state = app.get_sheet(&sheet.id).unwrap();
+        for col in 0..3 {
+            assert_eq!(
+                sheet_state.get_cell(col, 0).value,
+                CellValue::Error(CIRCULAR_REF_ERROR.into())
+            );
+        }
+
+        // Breaking the cycle restores normal evaluation.
+        app.update_cell(sheet.id, 1, 0, CellValue::Number(5.0), None)
+            .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 0, 0), 6.0);
+    }
+
+    fn recorded_event(app: &SpreadsheetApp, receipt: &AppendReceipt) -> OfficeEvent {
+        let resp = app
+            .ledger
+            .query(ledger_core::brainstem::SliceQuery {
+                from: receipt.index,
+                limit: 1,
+                include_payloads: false,
+            })
+            .unwrap();
+        OfficeEvent::from_payload(&resp.envelopes[0].body.payload).unwrap()
+    }
+
+    #[test]
+    fn named_range_in_sum() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Revenue", 5, 20).unwrap();
+
+        let updates = (1..13)
+            .map(|row| (1, row, CellValue::Number(row as f64 * 10.0), None))
+            .collect();
+        app.update_cells_batch(sheet.id, updates).unwrap();
+
+        // Formulas may use a name before it is defined.
+        app.update_cell(
+            sheet.id,
+            2,
+            0,
+            CellValue::Empty,
+            Some("=SUM(Revenue)".into()),
+        )
+        .unwrap();
+        assert_eq!(
+            app.get_sheet(&sheet.id).unwrap().get_cell(2, 0).value,
+            CellValue::Error("#NAME?".into())
+        );
+
+        let receipt = app.define_name(sheet.id, "Revenue", "B2:B13").unwrap();
+        assert!(receipt.merkle.verify());
+        assert_eq!(
+            recorded_event(&app, &receipt),
+            OfficeEvent::NameDefined {
+                sheet_id: sheet.id,
+                name: "Revenue".into(),
+                range: "B2:B13".into(),
+            }
+        );
+        assert_eq!(number_at(&app, sheet.id, 2, 0), 780.0);
+
+        app.update_cell(
+            sheet.id,
+            3,
+            0,
+            CellValue::Empty,
+            Some("=revenue_total*2".into()),
+        )
+        .unwrap();
+        app.define_name(sheet.id, "Revenue_Total", "C1").unwrap();
+        assert_eq!(number_at(&app, sheet.id, 3, 0), 1560.0);
+
+        // Inputs inside the named range still propagate.
+        app.update_cell(sheet.id, 1, 12, CellValue::Number(0.0), None)
+            .unwrap();
+        assert_eq!(number_at(&app, sheet.id, 2, 0), 660.0);
+        assert_eq!(number_at(&app, sheet.id, 3, 0), 1320.0);
+
+        for bad in ["B2", "2nd", "net-revenue", ""] {
+            assert!(matches!(
+                app.define_name(sheet.id, bad, "B2:B13"),
+                Err(SpreadsheetError::InvalidName(_))
+            ));
+        }
+        assert!(matches!(
+            app.define_name(sheet.id, "Costs", "B2:"),
+            Err(SpreadsheetError::InvalidCell(_))
+        ));
+    }
+
+    #[test]
+    fn cell_comment_round_trip() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Notes", 5, 5).unwrap();
+
+        let receipt = app
+            .comment_cell(sheet.id, 1, 2, "Estimate, confirm with finance")
+            .unwrap();
+        assert!(receipt.merkle.verify());
+        assert_eq!(
+            recorded_event(&app, &receipt),
+            OfficeEvent::CellCommented {
+                sheet_id: sheet.id,
+                cell: CellRef::new(1, 2),
+                comment: "Estimate, confirm with finance".into(),
+            }
+        );
+
+        let grid = app.get_sheet(&sheet.id).unwrap();
+        assert_eq!(grid.comment(1, 2), Some("Estimate, confirm with finance"));
+        assert_eq!(grid.comment(0, 0), None);
+
+        app.comment_cell(sheet.id, 1, 2, "").unwrap();
+        assert_eq!(app.get_sheet(&sheet.id).unwrap().comment(1, 2), None);
+    }
+
+    const GRID_CSV: &str = "name,qty,note,extra\r\n\
+                            widget,3,\"red, large\",\r\n\
+                            ,4.5,\"say \"\"hi\"\"\",x\r\n";
+
+    #[test]
+    fn csv_round_trip() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Import", 5, 5).unwrap();
+
+        let receipt = app.import_csv(sheet.id, GRID_CSV).unwrap();
+        assert!(receipt.merkle.verify());
+
+        let grid = app.get_sheet(&sheet.id).unwrap();
+        assert_eq!(grid.get_cell(0, 0).value, CellValue::Text("name".into()));
+        assert_eq!(grid.get_cell(1, 1).value, CellValue::Number(3.0));
+        assert_eq!(
+            grid.get_cell(2, 1).value,
+            CellValue::Text("red, large".into())
+        );
+        assert_eq!(grid.get_cell(3, 1).value, CellValue::Empty);
+        assert_eq!(grid.get_cell(0, 2).value, CellValue::Empty);
+        assert_eq!(grid.get_cell(1, 2).value, CellValue::Number(4.5));
+        assert_eq!(
+            grid.get_cell(2, 2).value,
+            CellValue::Text("say \"hi\"".into())
+        );
+
+        assert_eq!(app.export_csv(sheet.id).unwrap(), GRID_CSV);
+        assert!(app.undo().unwrap().is_some());
+        assert_eq!(app.export_csv(sheet.id).unwrap(), "");
+    }
+
+    #[test]
+    fn csv_import_rejects_bad_input() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Small", 2, 2).unwrap();
+
+        assert!(matches!(
+            app.import_csv(sheet.id, "a,\"open\n"),
+            Err(SpreadsheetError::Csv(_))
+        ));
+        assert!(matches!(
+            app.import_csv(sheet.id, "1,2,3\n"),
+            Err(SpreadsheetError::Csv(_))
+        ));
+        assert!(!app.can_undo());
+    }
+
+    #[test]
+    fn undo_and_redo_round_trip_through_the_ledger() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Budget", 4, 4).unwrap();
+        assert_eq!(app.undo().unwrap(), None);
+
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(1.0), None)
+            .unwrap();
+        app.update_cell(sheet.id, 1, 0, CellValue::Empty, Some("=A1*2".into()))
+            .unwrap();
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(4.0), None)
+            .unwrap();
+        let cells = |app: &SpreadsheetApp| {
+            let grid = app.get_sheet(&sheet.id).unwrap();
+            (
+                grid.get_cell(0, 0).value.clone(),
+                grid.get_cell(1, 0).value.clone(),
+            )
+        };
+        assert_eq!(
+            cells(&app),
+            (CellValue::Number(4.0), CellValue::Number(8.0))
+        );
+        let appended = app.ledger.offsets_for_channel("office.spreadsheets").len();
+
+        // Undo restores A1 and recalculates B1; each step is a new event.
+        assert!(app.undo().unwrap().unwrap().merkle.verify());
+        assert_eq!(
+            cells(&app),
+            (CellValue::Number(1.0), CellValue::Number(2.0))
+        );
+        assert!(app.undo().unwrap().is_some());
+        assert_eq!(cells(&app), (CellValue::Number(1.0), CellValue::Empty));
+        assert!(app.can_redo());
+
+        assert!(app.redo().unwrap().is_some());
+        assert!(app.redo().unwrap().is_some());
+        assert_eq!(
+            cells(&app),
+            (CellValue::Number(4.0), CellValue::Number(8.0))
+        );
+        assert_eq!(app.redo().unwrap(), None);
+        assert_eq!(
+            app.ledger.offsets_for_channel("office.spreadsheets").len(),
+            appended + 4
+        );
+
+        // A fresh edit after undoing drops the redo history.
+        app.undo().unwrap();
+        app.update_cell(sheet.id, 2, 0, CellValue::Text("note".into()), None)
+            .unwrap();
+        assert!(!app.can_redo());
+    }
+
+    #[test]
+    fn failed_appends_leave_the_sheet_unchanged() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Budget", 4, 4).unwrap();
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(1.0), None)
+            .unwrap();
+        app.update_cell(sheet.id, 1, 0, CellValue::Empty, Some("=A1*2".into()))
+            .unwrap();
+        app.undo().unwrap();
+
+        // A key the channel does not accept makes every append fail.
+        app.signer = Arc::new(SigningKey::generate(&mut OsRng));
+        let failed = [
+            app.update_cell(sheet.id, 0, 0, CellValue::Number(5.0), None),
+            app.update_cells_batch(sheet.id, vec![(2, 2, CellValue::Number(3.0), None)]),
+        ];
+        for result in failed {
+            assert!(matches!(result, Err(SpreadsheetError::Ledger(_))));
+        }
+        assert!(app.undo().is_err());
+        assert!(app.redo().is_err());
+
+        let grid = app.get_sheet(&sheet.id).unwrap();
+        assert_eq!(grid.get_cell(0, 0).value, CellValue::Number(1.0));
+        assert_eq!(grid.get_cell(1, 0).value, CellValue::Empty);
+        assert_eq!(grid.get_cell(2, 2).value, CellValue::Empty);
+        assert!(app.can_undo());
+        assert!(app.can_redo());
+    }
+
+    #[test]
+    fn failed_append_rolls_back_recalculated_dependents() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Budget", 4, 4).unwrap();
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(1.0), None)
+            .unwrap();
+        app.update_cell(sheet.id, 1, 0, CellValue::Empty, Some("=A1*2".into()))
+            .unwrap();
+        let modified_at = app.get_sheet(&sheet.id).unwrap().modified_at;
+
+        app.signer = Arc::new(SigningKey::generate(&mut OsRng));
+        assert!(app
+            .update_cell(sheet.id, 0, 0, CellValue::Number(5.0), None)
+            .is_err());
+
+        let grid = app.get_sheet(&sheet.id).unwrap();
+        assert_eq!(grid.get_cell(0, 0).value, CellValue::Number(1.0));
+        assert_eq!(grid.get_cell(1, 0).value, CellValue::Number(2.0));
+        assert_eq!(grid.get_cell(1, 0).formula.as_deref(), Some("=A1*2"));
+        assert_eq!(grid.modified_at, modified_at);
+        assert_eq!(app.undo_stack.len(), 2);
+    }
+
+    #[test]
+    fn read_only_writes_leave_the_sheet_unchanged() {
+        let mut app = test_app();
+        let (sheet, _) = app.create_sheet("Budget", 4, 4).unwrap();
+        app.update_cell(sheet.id, 0, 0, CellValue::Number(1.0), None)
+            .unwrap();
+        app.update_cell(sheet.id, 1, 0, CellValue::Empty, Some("=A1*2".into()))
+            .unwrap();
+        let appended = app.ledger.offsets_for_channel("office.spreadsheets").len();
+
+        let mut app = app.with_permissions(Permissions::ReadOnly);
+        let denied = [
+            app.update_cell(sheet.id, 0, 0, CellValue::Number(5.0), None),
+            app.update_cells_batch(sheet.id, vec![(2, 2, CellValue::Text("x".into()), None)]),
+            app.import_csv(sheet.id, "9,9\r\n"),
+        ];
+        for result in denied {
+            assert!(matches!(result, Err(SpreadsheetError::PermissionDenied)));
+        }
+        assert!(matches!(
+            app.undo(),
+            Err(SpreadsheetError::PermissionDenied)
+        ));
+
+        let sheet = app.get_sheet(&sheet.id).unwrap();
+        assert_eq!(sheet.get_cell(0, 0).value, CellValue::Number(1.0));
+        assert_eq!(sheet.get_cell(1, 0).value, CellValue::Number(2.0));
+        assert_eq!(sheet.get_cell(2, 2).value, CellValue::Empty);
+        assert!(app.can_undo());
+        assert!(!app.can_redo());
+        assert_eq!(
+            app.ledger.offsets_for_channel("office.spreadsheets").len(),
+            appended
+        );
+    }
 }
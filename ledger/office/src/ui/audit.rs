@@ -0,0 +1,266 @@
+//! Audit log widget: the raw ledger history across office channels.
+
+use chrono::TimeZone;
+use crossterm::style::Color;
+
+use super::{colors, pad, Rect};
+use crate::audit::AuditEntry;
+
+/// Audit view state.
+#[derive(Debug, Clone, Default)]
+pub struct AuditState {
+    /// Only show entries on this channel; `None` shows every channel.
+    pub filter: Option<String>,
+    /// Selected row among the visible entries.
+    pub cursor: usize,
+    /// Index of the first visible entry shown.
+    pub scroll_offset: usize,
+}
+
+impl AuditState {
+    /// Entries passing the channel filter, in trail order.
+    pub fn visible<'a>(&self, entries: &'a [AuditEntry]) -> Vec<&'a AuditEntry> {
+        entries
+            .iter()
+            .filter(|e| self.filter.is_none() || self.filter.as_ref() == Some(&e.channel))
+            .collect()
+    }
+
+    /// Show only `channel`, or every channel for `None`.
+    pub fn set_filter(&mut self, channel: Option<String>) {
+        self.filter = channel;
+        self.cursor = 0;
+        self.scroll_offset = 0;
+    }
+
+    /// Advance the filter through all channels, then each of `channels`
+    /// in turn, wrapping back to all channels.
+    pub fn cycle_filter(&mut self, channels: &[&str]) {
+        let next = match &self.filter {
+            None => channels.first(),
+            Some(current) => channels
+                .iter()
+                .position(|c| c == current)
+                .and_then(|i| channels.get(i + 1)),
+        };
+        self.set_filter(next.map(|c| c.to_string()));
+    }
+
+    /// Move selection up.
+    pub fn move_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Move selection down, stopping at the last of `len` visible entries.
+    pub fn move_down(&mut self, len: usize) {
+        if self.cursor + 1 < len {
+            self.cursor += 1;
+        }
+    }
+
+    /// Scroll so the cursor is visible in the list `render_audit` draws
+    /// into `area`.
+    pub fn set_viewport(&mut self, area: &Rect) {
+        self.ensure_visible(list_rows(area));
+    }
+
+    /// Ensure cursor is visible.
+    pub fn ensure_visible(&mut self, visible_lines: usize) {
+        if self.cursor < self.scroll_offset {
+            self.scroll_offset = self.cursor;
+        } else if self.cursor >= self.scroll_offset + visible_lines {
+            self.scroll_offset = self.cursor + 1 - visible_lines;
+        }
+    }
+}
+
+/// Width of the channel column.
+const CHANNEL_COLUMN_WIDTH: usize = 20;
+/// Width of the event type column.
+const EVENT_COLUMN_WIDTH: usize = 22;
+
+/// Number of entries `render_audit` lists in `area`, excluding the
+/// header and hint rows.
+pub fn list_rows(area: &Rect) -> usize {
+    area.height.saturating_sub(2) as usize
+}
+
+/// Format an envelope timestamp as UTC wall-clock time.
+fn format_timestamp(ms: u64) -> String {
+    chrono::Utc
+        .timestamp_millis_opt(ms as i64)
+        .single()
+        .map_or_else(
+            || ms.to_string(),
+            |t| t.format("%Y-%m-%d %H:%M:%S%.3f").to_string(),
+        )
+}
+
+/// Render the audit trail, one envelope per line, under a header row.
+pub fn render_audit(
+    state: &AuditState,
+    area: &Rect,
+    entries: &[AuditEntry],
+) -> Vec<(u16, u16, String, Color)> {
+    let mut output = Vec::new();
+
+    if area.height < 4 || area.width < 40 {
+        return output;
+    }
+
+    let width = area.width as usize;
+    let scope = state.filter.as_deref().unwrap_or("all channels");
+    output.push((
+        area.x,
+        area.y,
+        pad(&format!("Audit: {scope}"), width),
+        colors::ACCENT,
+    ));
+
+    let list_y = area.y + 1;
+    let hint_y = area.y + area.height - 1;
+    let rows = list_rows(area);
+
+    let visible = state.visible(entries);
+    if visible.is_empty() {
+        output.push((
+            area.x,
+            list_y,
+            "No ledger events".to_string(),
+            colors::MUTED,
+        ));
+    }
+    for (row, entry) in visible
+        .iter()
+        .enumerate()
+        .skip(state.scroll_offset)
+        .take(rows)
+    {
+        let line = format!(
+            "{}  {}  {}  {}",
+            format_timestamp(entry.timestamp),
+            pad(&entry.channel, CHANNEL_COLUMN_WIDTH),
+            pad(&entry.event_type, EVENT_COLUMN_WIDTH),
+            entry.short_receipt(),
+        );
+        let color = if row == state.cursor {
+            colors::HIGHLIGHT
+        } else {
+            colors::TEXT
+        };
+        output.push((
+            area.x,
+            list_y + (row - state.scroll_offset) as u16,
+            pad(&line, width),
+            color,
+        ));
+    }
+
+    let hint = format!(
+        "{} events | ↑ ↓ Scroll | f: Filter | r: Reload",
+        visible.len()
+    );
+    output.push((area.x, hint_y, hint, colors::MUTED));
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_core::MerkleReceipt;
+
+    fn entry(index: usize, channel: &str, event_type: &str) -> AuditEntry {
+        AuditEntry {
+            index,
+            channel: channel.into(),
+            timestamp: 1_700_000_000_000 + index as u64,
+            event_type: event_type.into(),
+            receipt: MerkleReceipt {
+                instance: Default::default(),
+                index,
+                leaf_count: 3,
+                leaf: [0xab; 32],
+                root: [0; 32],
+                path: Vec::new(),
+            },
+        }
+    }
+
+    fn trail() -> Vec<AuditEntry> {
+        vec![
+            entry(0, "office.documents", "DocumentCreated"),
+            entry(1, "office.calendar", "EventScheduled"),
+            entry(2, "office.documents", "DocumentUpdated"),
+        ]
+    }
+
+    fn listed(state: &AuditState, entries: &[AuditEntry]) -> Vec<String> {
+        let mut lines = render_audit(state, &Rect::new(0, 0, 100, 8), entries);
+        lines.retain(|(_, y, _, _)| (1..7).contains(y));
+        lines.sort_by_key(|(_, y, _, _)| *y);
+        lines.into_iter().map(|(_, _, text, _)| text).collect()
+    }
+
+    #[test]
+    fn lists_events_across_channels_in_trail_order() {
+        let lines = listed(&AuditState::default(), &trail());
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("2023-11-14 22:13:20.000  office.documents"));
+        assert!(lines[0].contains("DocumentCreated"));
+        assert!(lines[1].contains("office.calendar") && lines[1].contains("EventScheduled"));
+        assert!(lines[2].contains("DocumentUpdated"));
+        assert!(lines.iter().all(|l| l.contains("abababababab")));
+    }
+
+    #[test]
+    fn filtering_to_one_channel_hides_others() {
+        let entries = trail();
+        let channels = ["office.documents", "office.calendar"];
+        let mut state = AuditState::default();
+
+        state.cycle_filter(&channels);
+        let lines = listed(&state, &entries);
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|l| l.contains("office.documents")));
+
+        state.cycle_filter(&channels);
+        let lines = listed(&state, &entries);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("EventScheduled"));
+
+        state.cycle_filter(&channels);
+        assert_eq!(state.filter, None);
+        assert_eq!(listed(&state, &entries).len(), 3);
+    }
+
+    #[test]
+    fn scrolling_keeps_cursor_in_view() {
+        let entries = trail();
+        let mut state = AuditState::default();
+        state.move_down(entries.len());
+        state.move_down(entries.len());
+        state.move_down(entries.len());
+        assert_eq!(state.cursor, 2);
+        state.ensure_visible(2);
+        assert_eq!(state.scroll_offset, 1);
+        assert!(listed(&state, &entries)[0].contains("EventScheduled"));
+    }
+
+    #[test]
+    fn viewport_matches_rendered_rows() {
+        let entries = trail();
+        let mut state = AuditState::default();
+        state.move_down(entries.len());
+        state.move_down(entries.len());
+
+        // Header and hint rows leave two list rows in a four-row area.
+        let area = Rect::new(0, 0, 100, 4);
+        state.set_viewport(&area);
+        assert_eq!(state.scroll_offset, 1);
+        let output = render_audit(&state, &area, &entries);
+        assert!(output
+            .iter()
+            .any(|(_, y, text, _)| *y == 2 && text.contains("DocumentUpdated")));
+    }
+}
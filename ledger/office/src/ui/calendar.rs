@@ -1,6 +1,6 @@
 //! Calendar view widget.
 
-use chrono::{Datelike, NaiveDate};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use crossterm::style::Color;
 use super::{Rect, colors, pad};
 
@@ -13,6 +13,8 @@ pub enum CalendarView {
     Week,
     /// Day view (single day with hours).
     Day,
+    /// Agenda view (chronological list of upcoming events).
+    Agenda,
 }
 
 impl Default for CalendarView {
@@ -34,6 +36,8 @@ pub struct CalendarState {
     pub day: u32,
     /// Selected date (if any).
     pub selected: Option<NaiveDate>,
+    /// Index of the first agenda entry shown.
+    pub agenda_scroll: usize,
 }
 
 impl Default for CalendarState {
@@ -45,6 +49,7 @@ impl Default for CalendarState {
             month: today.month(),
             day: today.day(),
             selected: Some(today),
+            agenda_scroll: 0,
         }
     }
 }
@@ -133,9 +138,22 @@ impl CalendarState {
         self.view = match self.view {
             CalendarView::Month => CalendarView::Week,
             CalendarView::Week => CalendarView::Day,
-            CalendarView::Day => CalendarView::Month,
+            CalendarView::Day => CalendarView::Agenda,
+            CalendarView::Agenda => CalendarView::Month,
         };
     }
+
+    /// Scroll the agenda up one entry.
+    pub fn scroll_agenda_up(&mut self) {
+        self.agenda_scroll = self.agenda_scroll.saturating_sub(1);
+    }
+
+    /// Scroll the agenda down one entry, stopping at the last of `len` entries.
+    pub fn scroll_agenda_down(&mut self, len: usize) {
+        if self.agenda_scroll + 1 < len {
+            self.agenda_scroll += 1;
+        }
+    }
 }
 
 /// Get number of days in a month.
@@ -257,6 +275,81 @@ pub fn render_month(
     output
 }
 
+/// Event entry for agenda rendering.
+#[derive(Debug, Clone)]
+pub struct AgendaEntry {
+    /// Event start.
+    pub start: NaiveDateTime,
+    /// Event end.
+    pub end: NaiveDateTime,
+    /// Event title.
+    pub title: String,
+}
+
+/// Render the agenda view: events in start-time order, one per line.
+///
+/// Entries starting on `today` (in the same timezone as the entries) are
+/// highlighted.
+pub fn render_agenda(
+    state: &CalendarState,
+    area: &Rect,
+    entries: &[AgendaEntry],
+    today: NaiveDate,
+) -> Vec<(u16, u16, String, Color)> {
+    let mut output = Vec::new();
+
+    if area.height < 6 || area.width < 28 {
+        return output;
+    }
+
+    let header = "Agenda";
+    let header_x = area.x + (area.width - header.len() as u16) / 2;
+    output.push((header_x, area.y + 1, header.to_string(), colors::ACCENT));
+
+    let list_y = area.y + 3;
+    let hint_y = area.y + area.height - 1;
+    let rows = (hint_y - list_y) as usize;
+
+    if entries.is_empty() {
+        output.push((
+            area.x + 1,
+            list_y,
+            "No upcoming events".to_string(),
+            colors::MUTED,
+        ));
+    } else {
+        let mut sorted: Vec<&AgendaEntry> = entries.iter().collect();
+        sorted.sort_by_key(|entry| entry.start);
+
+        let scroll = state.agenda_scroll.min(sorted.len() - 1);
+        for (row, entry) in sorted.iter().skip(scroll).take(rows).enumerate() {
+            let line = format!(
+                "{}  {}-{}  {}",
+                entry.start.format("%a %Y-%m-%d"),
+                entry.start.format("%H:%M"),
+                entry.end.format("%H:%M"),
+                entry.title
+            );
+            let color = if entry.start.date() == today {
+                colors::SUCCESS
+            } else {
+                colors::TEXT
+            };
+            output.push((
+                area.x + 1,
+                list_y + row as u16,
+                pad(&line, (area.width - 2) as usize),
+                color,
+            ));
+        }
+    }
+
+    let hint = "↑ ↓ Scroll | v: View";
+    output.push((area.x + 1, hint_y, hint.to_string(), colors::MUTED));
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -293,6 +386,7 @@ mod tests {
             day: 31,
             view: CalendarView::Month,
             selected: None,
+            agenda_scroll: 0,
         };
 
         // March has 31 days, April has 30
@@ -317,7 +411,85 @@ mod tests {
         cal.toggle_view();
         assert_eq!(cal.view, CalendarView::Day);
 
+        cal.toggle_view();
+        assert_eq!(cal.view, CalendarView::Agenda);
+
         cal.toggle_view();
         assert_eq!(cal.view, CalendarView::Month);
     }
+
+    fn entry(day: u32, hour: u32, title: &str) -> AgendaEntry {
+        let start = NaiveDate::from_ymd_opt(2024, 5, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap();
+        AgendaEntry {
+            start,
+            end: start + chrono::Duration::hours(1),
+            title: title.into(),
+        }
+    }
+
+    fn agenda_lines(state: &CalendarState, entries: &[AgendaEntry]) -> Vec<String> {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let mut lines = render_agenda(state, &Rect::new(0, 0, 60, 12), entries, today);
+        lines.retain(|(_, y, _, _)| (3..11).contains(y));
+        lines.sort_by_key(|(_, y, _, _)| *y);
+        lines
+            .into_iter()
+            .map(|(_, _, text, _)| text.trim_end().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn agenda_lists_events_in_start_order() {
+        let mut cal = CalendarState {
+            view: CalendarView::Agenda,
+            ..CalendarState::default()
+        };
+        let entries = [
+            entry(9, 14, "Review"),
+            entry(3, 9, "Standup"),
+            entry(9, 8, "Breakfast"),
+        ];
+
+        let lines = agenda_lines(&cal, &entries);
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "Fri 2024-05-03  09:00-10:00  Standup");
+        assert!(lines[1].ends_with("08:00-09:00  Breakfast"));
+        assert!(lines[2].ends_with("14:00-15:00  Review"));
+
+        cal.scroll_agenda_down(entries.len());
+        cal.scroll_agenda_down(entries.len());
+        cal.scroll_agenda_down(entries.len());
+        assert_eq!(cal.agenda_scroll, 2);
+        assert!(agenda_lines(&cal, &entries)[0].ends_with("Review"));
+        cal.scroll_agenda_up();
+        assert!(agenda_lines(&cal, &entries)[0].ends_with("Breakfast"));
+
+        cal.toggle_view();
+        assert_eq!(cal.view, CalendarView::Month);
+    }
+
+    #[test]
+    fn empty_agenda_renders_placeholder() {
+        let cal = CalendarState::default();
+        assert_eq!(agenda_lines(&cal, &[]), vec!["No upcoming events"]);
+    }
+
+    #[test]
+    fn agenda_highlights_entries_on_the_given_day() {
+        let cal = CalendarState::default();
+        let entries = [entry(3, 9, "Standup"), entry(4, 9, "Retro")];
+        let today = NaiveDate::from_ymd_opt(2024, 5, 4).unwrap();
+        let lines = render_agenda(&cal, &Rect::new(0, 0, 60, 12), &entries, today);
+        let color_of = |title: &str| {
+            lines
+                .iter()
+                .find(|(_, _, text, _)| text.trim_end().ends_with(title))
+                .map(|(_, _, _, color)| *color)
+        };
+        assert_eq!(color_of("Retro"), Some(colors::SUCCESS));
+        assert_eq!(color_of("Standup"), Some(colors::TEXT));
+    }
 }
@@ -1,19 +1,29 @@
 //! Text editor widget for document editing.
 
+use std::time::{Duration, Instant};
+
 use crossterm::style::Color;
+use unicode_width::UnicodeWidthStr;
 use super::{Rect, colors, truncate};
 
+use crate::document::{SpanKind, StyledSpan};
+
+/// Lines kept between the cursor and the viewport edge when scrolling.
+const SCROLL_MARGIN: usize = 2;
+
 /// Text editor state.
 #[derive(Debug, Clone)]
 pub struct EditorState {
     /// Lines of text.
     pub lines: Vec<String>,
-    /// Cursor column.
+    /// Cursor column, in characters (not bytes) from the line start.
     pub cursor_x: usize,
     /// Cursor row.
     pub cursor_y: usize,
     /// Scroll offset (first visible line).
     pub scroll_offset: usize,
+    /// Number of text lines visible in the pane (0 until known).
+    pub viewport_height: usize,
     /// Whether the editor is in insert mode.
     pub insert_mode: bool,
     /// Whether the content has been modified.
@@ -27,6 +37,7 @@ impl Default for EditorState {
             cursor_x: 0,
             cursor_y: 0,
             scroll_offset: 0,
+            viewport_height: 0,
             insert_mode: false,
             modified: false,
         }
@@ -43,6 +54,7 @@ impl EditorState {
             cursor_x: 0,
             cursor_y: 0,
             scroll_offset: 0,
+            viewport_height: 0,
             insert_mode: false,
             modified: false,
         }
@@ -67,6 +79,7 @@ impl EditorState {
             self.cursor_y -= 1;
             self.clamp_cursor_x();
         }
+        self.follow_cursor();
     }
 
     /// Move cursor down.
@@ -75,6 +88,7 @@ impl EditorState {
             self.cursor_y += 1;
             self.clamp_cursor_x();
         }
+        self.follow_cursor();
     }
 
     /// Move cursor left.
@@ -83,19 +97,21 @@ impl EditorState {
             self.cursor_x -= 1;
         } else if self.cursor_y > 0 {
             self.cursor_y -= 1;
-            self.cursor_x = self.current_line().len();
+            self.cursor_x = char_len(self.current_line());
         }
+        self.follow_cursor();
     }
 
     /// Move cursor right.
     pub fn move_right(&mut self) {
-        let line_len = self.current_line().len();
+        let line_len = char_len(self.current_line());
         if self.cursor_x < line_len {
             self.cursor_x += 1;
         } else if self.cursor_y < self.lines.len() - 1 {
             self.cursor_y += 1;
             self.cursor_x = 0;
         }
+        self.follow_cursor();
     }
 
     /// Move to start of line.
@@ -105,16 +121,15 @@ impl EditorState {
 
     /// Move to end of line.
     pub fn move_to_line_end(&mut self) {
-        self.cursor_x = self.current_line().len();
+        self.cursor_x = char_len(self.current_line());
     }
 
     /// Insert a character at cursor.
     pub fn insert_char(&mut self, c: char) {
-        let cursor_x = self.cursor_x;
+        let cursor_x = self.cursor_x.min(char_len(self.current_line()));
         let line = self.current_line_mut();
-        let insert_pos = cursor_x.min(line.len());
-        line.insert(insert_pos, c);
-        self.cursor_x = insert_pos + 1;
+        line.insert(byte_index(line, cursor_x), c);
+        self.cursor_x = cursor_x + 1;
         self.modified = true;
     }
 
@@ -123,42 +138,45 @@ impl EditorState {
         if self.cursor_x > 0 {
             let cursor_x = self.cursor_x;
             let line = self.current_line_mut();
-            line.remove(cursor_x - 1);
+            line.remove(byte_index(line, cursor_x - 1));
             self.cursor_x -= 1;
             self.modified = true;
         } else if self.cursor_y > 0 {
             let current_line = self.lines.remove(self.cursor_y);
             self.cursor_y -= 1;
-            self.cursor_x = self.lines[self.cursor_y].len();
+            self.cursor_x = char_len(&self.lines[self.cursor_y]);
             self.lines[self.cursor_y].push_str(&current_line);
             self.modified = true;
         }
+        self.follow_cursor();
     }
 
     /// Delete character at cursor.
     pub fn delete(&mut self) {
-        let line_len = self.current_line().len();
+        let line_len = char_len(self.current_line());
         let cursor_x = self.cursor_x;
         if cursor_x < line_len {
-            self.current_line_mut().remove(cursor_x);
+            let line = self.current_line_mut();
+            line.remove(byte_index(line, cursor_x));
             self.modified = true;
         } else if self.cursor_y < self.lines.len() - 1 {
             let next_line = self.lines.remove(self.cursor_y + 1);
             self.lines[self.cursor_y].push_str(&next_line);
             self.modified = true;
         }
+        self.follow_cursor();
     }
 
     /// Insert a newline at cursor.
     pub fn newline(&mut self) {
         let cursor_x = self.cursor_x;
         let line = self.current_line_mut();
-        let split_pos = cursor_x.min(line.len());
-        let remainder = line.split_off(split_pos);
+        let remainder = line.split_off(byte_index(line, cursor_x));
         self.lines.insert(self.cursor_y + 1, remainder);
         self.cursor_y += 1;
         self.cursor_x = 0;
         self.modified = true;
+        self.follow_cursor();
     }
 
     /// Get content as a single string.
@@ -168,7 +186,7 @@ impl EditorState {
 
     /// Ensure cursor X is within line bounds.
     fn clamp_cursor_x(&mut self) {
-        let line_len = self.current_line().len();
+        let line_len = char_len(self.current_line());
         if self.cursor_x > line_len {
             self.cursor_x = line_len;
         }
@@ -176,10 +194,106 @@ impl EditorState {
 
     /// Ensure scroll keeps cursor visible.
     pub fn ensure_cursor_visible(&mut self, visible_lines: usize) {
-        if self.cursor_y < self.scroll_offset {
-            self.scroll_offset = self.cursor_y;
-        } else if self.cursor_y >= self.scroll_offset + visible_lines {
-            self.scroll_offset = self.cursor_y - visible_lines + 1;
+        self.scroll_offset = self.scroll_for(visible_lines);
+    }
+
+    /// Record the pane height and re-scroll to keep the cursor visible.
+    pub fn set_viewport_height(&mut self, visible_lines: usize) {
+        self.viewport_height = visible_lines;
+        self.follow_cursor();
+    }
+
+    /// Record the area the editor is rendered into and scroll to the cursor.
+    pub fn set_viewport(&mut self, area: &Rect) {
+        self.set_viewport_height(text_rows(area));
+    }
+
+    /// Scroll offset that keeps the cursor at least `SCROLL_MARGIN` lines
+    /// from either edge of a `visible_lines` window, without scrolling
+    /// past the end of the document.
+    pub fn scroll_for(&self, visible_lines: usize) -> usize {
+        if visible_lines == 0 {
+            return self.scroll_offset;
+        }
+        let margin = SCROLL_MARGIN.min(visible_lines.saturating_sub(1) / 2);
+        let mut offset = self.scroll_offset;
+        if self.cursor_y < offset + margin {
+            offset = self.cursor_y.saturating_sub(margin);
+        } else if self.cursor_y + margin >= offset + visible_lines {
+            offset = self.cursor_y + margin + 1 - visible_lines;
+        }
+        offset.min(self.lines.len().saturating_sub(visible_lines))
+    }
+
+    /// Scroll offset [`render_editor`] uses when drawing into `area`.
+    pub fn scroll_for_area(&self, area: &Rect) -> usize {
+        self.scroll_for(text_rows(area))
+    }
+
+    /// Keep the cursor in view once the viewport height is known.
+    fn follow_cursor(&mut self) {
+        if self.viewport_height > 0 {
+            self.ensure_cursor_visible(self.viewport_height);
+        }
+    }
+}
+
+/// Number of text lines `render_editor` draws in `area`, excluding the
+/// top padding row and the status line.
+pub fn text_rows(area: &Rect) -> usize {
+    area.height.saturating_sub(2) as usize
+}
+
+/// Number of characters in `line`.
+fn char_len(line: &str) -> usize {
+    line.chars().count()
+}
+
+/// Byte offset of the `column`th character of `line`, or its length when
+/// `column` is past the end.
+fn byte_index(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column)
+        .map_or(line.len(), |(index, _)| index)
+}
+
+/// Idle debounce for autosaving a modified buffer.
+///
+/// Every edit restarts the timer, so a burst of keystrokes produces a single
+/// save once typing pauses for the full debounce period.
+#[derive(Debug, Clone)]
+pub struct AutosaveTimer {
+    debounce: Duration,
+    last_edit: Option<Instant>,
+}
+
+impl AutosaveTimer {
+    /// Create a timer that fires after `debounce` without edits.
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            debounce,
+            last_edit: None,
+        }
+    }
+
+    /// Record an edit made at `now`, restarting the idle period.
+    pub fn record_edit(&mut self, now: Instant) {
+        self.last_edit = Some(now);
+    }
+
+    /// Whether to autosave at `now`. Returns true at most once per idle
+    /// period, and never while the buffer is clean.
+    pub fn poll(&mut self, now: Instant, modified: bool) -> bool {
+        if !modified {
+            self.last_edit = None;
+            return false;
+        }
+        match self.last_edit {
+            Some(edit) if now.saturating_duration_since(edit) >= self.debounce => {
+                self.last_edit = None;
+                true
+            }
+            _ => false,
         }
     }
 }
@@ -196,12 +310,13 @@ pub fn render_editor(
     }
 
     // Calculate visible region
-    let content_height = (area.height - 2) as usize;
+    let content_height = text_rows(area);
     let content_width = (area.width - 6) as usize; // Leave room for line numbers
     let line_num_width = 4;
 
-    // Render visible lines
-    for (i, line_idx) in (state.scroll_offset..state.scroll_offset + content_height).enumerate() {
+    // Render visible lines, scrolled so the cursor is always on screen
+    let scroll_offset = state.scroll_for_area(area);
+    for (i, line_idx) in (scroll_offset..scroll_offset + content_height).enumerate() {
         let y = area.y + 1 + i as u16;
 
         if line_idx < state.lines.len() {
@@ -216,9 +331,12 @@ pub fn render_editor(
 
             // Cursor
             if line_idx == state.cursor_y && state.insert_mode {
-                let cursor_x = area.x + 1 + line_num_width + state.cursor_x as u16;
+                // Cursor column is the display width of the text before it
+                let (before, after) = line.split_at(byte_index(line, state.cursor_x));
+                let column = UnicodeWidthStr::width(before) as u16;
+                let cursor_x = area.x + 1 + line_num_width + column;
                 if cursor_x < area.x + area.width - 1 {
-                    let cursor_char = line.chars().nth(state.cursor_x).unwrap_or(' ');
+                    let cursor_char = after.chars().next().unwrap_or(' ');
                     output.push((cursor_x, y, cursor_char.to_string(), colors::HIGHLIGHT));
                 }
             }
@@ -242,10 +360,87 @@ pub fn render_editor(
     output
 }
 
+/// Render markdown spans from [`crate::document::render_markdown`] as a
+/// read-only preview, one source line per row, starting at source line
+/// `scroll_offset` so it tracks the editor beside it.
+pub fn render_preview(
+    spans: &[StyledSpan],
+    area: &Rect,
+    scroll_offset: usize,
+) -> Vec<(u16, u16, String, Color)> {
+    let mut output = Vec::new();
+    let right = area.x + area.width;
+    let mut line = None;
+    let mut x = area.x;
+
+    for span in spans {
+        if span.line < scroll_offset {
+            continue;
+        }
+        if span.line >= scroll_offset + area.height as usize {
+            break;
+        }
+        if line != Some(span.line) {
+            line = Some(span.line);
+            x = area.x;
+        }
+        let y = area.y + (span.line - scroll_offset) as u16;
+        if x >= right {
+            continue;
+        }
+
+        let color = match span.kind {
+            SpanKind::Text => colors::TEXT,
+            SpanKind::Heading(_) => colors::ACCENT,
+            SpanKind::Bold => colors::WARNING,
+            SpanKind::Italic => colors::SECONDARY,
+            SpanKind::Code => colors::SUCCESS,
+            SpanKind::Bullet => colors::MUTED,
+        };
+        let text = truncate(&span.text, (right - x) as usize);
+        let width = UnicodeWidthStr::width(text.as_str()) as u16;
+        output.push((x, y, text, color));
+        x += width;
+    }
+
+    output
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn autosave_waits_for_idle_period() {
+        let debounce = Duration::from_secs(3);
+        let mut timer = AutosaveTimer::new(debounce);
+        let start = Instant::now();
+
+        // A keystroke every second never leaves a full idle period
+        for i in 0..10 {
+            let now = start + Duration::from_secs(i);
+            timer.record_edit(now);
+            assert!(!timer.poll(now + Duration::from_millis(999), true));
+        }
+
+        let last_edit = start + Duration::from_secs(9);
+        assert!(!timer.poll(last_edit + Duration::from_secs(2), true));
+        assert!(timer.poll(last_edit + debounce, true));
+        // Fires once per idle period, even if the save failed
+        assert!(!timer.poll(last_edit + debounce * 2, true));
+    }
+
+    #[test]
+    fn clean_buffer_never_autosaves() {
+        let mut timer = AutosaveTimer::new(Duration::from_secs(3));
+        let start = Instant::now();
+        assert!(!timer.poll(start + Duration::from_secs(60), false));
+
+        timer.record_edit(start);
+        assert!(!timer.poll(start + Duration::from_secs(60), false));
+        assert!(!timer.poll(start + Duration::from_secs(120), true));
+    }
+
     #[test]
     fn editor_basic_operations() {
         let mut editor = EditorState::default();
@@ -298,4 +493,126 @@ mod tests {
         assert_eq!(editor.lines.len(), 2);
         assert_eq!(editor.content(), "Hello\nWorld");
     }
+
+    fn numbered_lines(n: usize) -> EditorState {
+        let content: Vec<String> = (1..=n).map(|i| format!("line {}", i)).collect();
+        EditorState::with_content(&content.join("\n"))
+    }
+
+    #[test]
+    fn moving_below_viewport_scrolls() {
+        let mut editor = numbered_lines(100);
+        editor.set_viewport_height(10);
+
+        for _ in 0..7 {
+            editor.move_down();
+        }
+        // Cursor on line 7 with a 2-line margin stays inside the window.
+        assert_eq!(editor.scroll_offset, 0);
+
+        editor.move_down();
+        assert_eq!(editor.cursor_y, 8);
+        assert_eq!(editor.scroll_offset, 1);
+
+        for _ in 0..20 {
+            editor.move_down();
+        }
+        assert_eq!(editor.scroll_offset, 28 + SCROLL_MARGIN + 1 - 10);
+
+        // Moving back up scrolls once the cursor nears the top edge.
+        for _ in 0..20 {
+            editor.move_up();
+        }
+        assert_eq!(editor.cursor_y, 8);
+        assert_eq!(editor.scroll_offset, 8 - SCROLL_MARGIN);
+    }
+
+    #[test]
+    fn scroll_clamps_at_document_end() {
+        let mut editor = numbered_lines(30);
+        editor.set_viewport_height(10);
+
+        for _ in 0..100 {
+            editor.move_down();
+        }
+        assert_eq!(editor.cursor_y, 29);
+        assert_eq!(editor.scroll_offset, 20);
+
+        // A document shorter than the pane never scrolls.
+        let mut short = numbered_lines(5);
+        short.set_viewport_height(10);
+        for _ in 0..10 {
+            short.move_down();
+        }
+        assert_eq!(short.scroll_offset, 0);
+    }
+
+    #[test]
+    fn render_follows_cursor_and_wide_characters() {
+        let mut editor = numbered_lines(50);
+        editor.cursor_y = 40;
+        editor.insert_mode = true;
+        let area = Rect::new(0, 0, 40, 12);
+
+        // Even without a recorded viewport the cursor line is rendered.
+        let output = render_editor(&editor, &area);
+        assert!(output.iter().any(|(_, _, text, _)| text == "line 41"));
+        assert!(!output.iter().any(|(_, _, text, _)| text == "line 1"));
+
+        editor.lines[40] = "日本語".into();
+        editor.cursor_x = 2;
+        let output = render_editor(&editor, &area);
+        let cursor = output
+            .iter()
+            .find(|(_, _, _, color)| *color == colors::HIGHLIGHT)
+            .unwrap();
+        assert_eq!(cursor.0, area.x + 1 + 4 + 4);
+        assert_eq!(cursor.2, "語");
+    }
+
+    #[test]
+    fn preview_scrolls_with_the_editor() {
+        let mut editor = numbered_lines(50);
+        editor.cursor_y = 40;
+        let area = Rect::new(0, 0, 40, 12);
+        let scroll_offset = editor.scroll_for_area(&area);
+        assert!(scroll_offset > 0);
+
+        let spans = crate::document::render_markdown(&editor.content());
+        let output = render_preview(&spans, &area, scroll_offset);
+        let first = output.iter().min_by_key(|(_, y, _, _)| *y).unwrap();
+        assert_eq!(first.1, area.y);
+        assert_eq!(first.2, format!("line {}", scroll_offset + 1));
+        assert!(output.iter().any(|(_, _, text, _)| text == "line 41"));
+    }
+
+    #[test]
+    fn editing_non_ascii_lines_moves_by_character() {
+        let mut editor = EditorState::with_content("café 🎉");
+        editor.insert_mode = true;
+        editor.move_to_line_end();
+        assert_eq!(editor.cursor_x, 6);
+
+        editor.move_left();
+        editor.move_left();
+        editor.backspace();
+        assert_eq!(editor.content(), "caf 🎉");
+        editor.insert_char('é');
+        editor.insert_char('e');
+        assert_eq!(editor.content(), "cafée 🎉");
+        editor.move_right();
+        editor.delete();
+        assert_eq!(editor.content(), "cafée ");
+
+        let area = Rect::new(0, 0, 40, 6);
+        for column in 0..=char_len(editor.current_line()) {
+            editor.cursor_x = column;
+            // Every character position renders without slicing a char.
+            assert!(!render_editor(&editor, &area).is_empty());
+        }
+
+        editor.cursor_x = 4;
+        editor.newline();
+        assert_eq!(editor.lines, vec!["café", "e "]);
+    }
 }
@@ -4,6 +4,23 @@ use crossterm::style::Color;
 use crate::events::{CellRef, CellValue};
 use super::{Rect, colors, pad, truncate};
 
+/// Width of a column without an explicit entry in `col_widths`.
+const DEFAULT_COL_WIDTH: u16 = 10;
+/// Width of the row-number gutter.
+const ROW_HEADER_WIDTH: u16 = 5;
+/// Marker drawn in the last column of a commented cell.
+const COMMENT_MARKER: &str = "◥";
+
+/// Size (width, height) of the scrollable data region `render_grid`
+/// uses for `area`, excluding the header row, separator, status line and
+/// row-number gutter.
+pub fn data_region(area: &Rect) -> (u16, u16) {
+    (
+        area.width.saturating_sub(ROW_HEADER_WIDTH + 2),
+        area.height.saturating_sub(4),
+    )
+}
+
 /// Grid state for spreadsheet display.
 #[derive(Debug, Clone)]
 pub struct GridState {
@@ -25,6 +42,14 @@ pub struct GridState {
     pub total_cols: u32,
     /// Total rows.
     pub total_rows: u32,
+    /// Leading columns pinned while scrolling horizontally.
+    pub frozen_cols: u32,
+    /// Leading rows pinned while scrolling vertically.
+    pub frozen_rows: u32,
+    /// Data region size (width, height) last passed to `set_viewport`.
+    pub viewport: (u16, u16),
+    /// Whether the cursor cell's comment is shown in the status bar.
+    pub show_comment: bool,
 }
 
 impl GridState {
@@ -40,9 +65,26 @@ impl GridState {
             col_widths: vec![10; cols as usize],
             total_cols: cols,
             total_rows: rows,
+            frozen_cols: 0,
+            frozen_rows: 0,
+            viewport: (0, 0),
+            show_comment: false,
         }
     }
 
+    /// Pin the first `cols` columns and `rows` rows.
+    pub fn set_frozen(&mut self, cols: u32, rows: u32) {
+        self.frozen_cols = cols.min(self.total_cols);
+        self.frozen_rows = rows.min(self.total_rows);
+        self.follow_cursor();
+    }
+
+    /// Record the area the grid is rendered into and scroll to the cursor.
+    pub fn set_viewport(&mut self, area: &Rect) {
+        self.viewport = data_region(area);
+        self.follow_cursor();
+    }
+
     /// Get current cell reference.
     pub fn current_cell(&self) -> CellRef {
         CellRef::new(self.cursor_col, self.cursor_row)
@@ -53,6 +95,7 @@ impl GridState {
         if self.cursor_row > 0 {
             self.cursor_row -= 1;
         }
+        self.follow_cursor();
     }
 
     /// Move selection down.
@@ -60,6 +103,7 @@ impl GridState {
         if self.cursor_row < self.total_rows - 1 {
             self.cursor_row += 1;
         }
+        self.follow_cursor();
     }
 
     /// Move selection left.
@@ -67,6 +111,7 @@ impl GridState {
         if self.cursor_col > 0 {
             self.cursor_col -= 1;
         }
+        self.follow_cursor();
     }
 
     /// Move selection right.
@@ -74,6 +119,7 @@ impl GridState {
         if self.cursor_col < self.total_cols - 1 {
             self.cursor_col += 1;
         }
+        self.follow_cursor();
     }
 
     /// Start editing the current cell.
@@ -82,6 +128,11 @@ impl GridState {
         self.edit_buffer = initial.to_string();
     }
 
+    /// Show or hide the cursor cell's comment.
+    pub fn toggle_comment(&mut self) {
+        self.show_comment = !self.show_comment;
+    }
+
     /// Cancel editing.
     pub fn cancel_edit(&mut self) {
         self.editing = false;
@@ -108,16 +159,107 @@ impl GridState {
             self.scroll_row = self.cursor_row - visible_rows + 1;
         }
     }
+
+    /// Width of a column.
+    pub fn col_width(&self, col: u32) -> u16 {
+        self.col_widths
+            .get(col as usize)
+            .copied()
+            .unwrap_or(DEFAULT_COL_WIDTH)
+    }
+
+    /// Scroll origin (first scrolled column, first scrolled row) that keeps
+    /// the cursor inside a `width` x `height` data region. Frozen columns
+    /// and rows are always shown ahead of the scrolled region.
+    pub fn scroll_origin(&self, width: u16, height: u16) -> (u32, u32) {
+        let mut col = self.scroll_col.max(self.frozen_cols);
+        if self.cursor_col >= self.frozen_cols {
+            if self.cursor_col < col {
+                col = self.cursor_col;
+            }
+            let frozen_width: u32 = (0..self.frozen_cols)
+                .map(|c| self.col_width(c) as u32 + 1)
+                .sum();
+            let span = |from: u32| -> u32 {
+                frozen_width
+                    + (from..=self.cursor_col)
+                        .map(|c| self.col_width(c) as u32 + 1)
+                        .sum::<u32>()
+            };
+            while col < self.cursor_col && span(col) > width as u32 + 1 {
+                col += 1;
+            }
+        }
+
+        let mut row = self.scroll_row.max(self.frozen_rows);
+        if self.cursor_row >= self.frozen_rows {
+            let scrolled_rows = (height as u32).saturating_sub(self.frozen_rows).max(1);
+            if self.cursor_row < row {
+                row = self.cursor_row;
+            } else if self.cursor_row >= row + scrolled_rows {
+                row = self.cursor_row + 1 - scrolled_rows;
+            }
+        }
+        (col, row)
+    }
+
+    /// Columns drawn for a data region `width` wide: frozen columns, then
+    /// scrolled columns from `origin`.
+    fn visible_cols(&self, origin: u32, width: u16) -> Vec<u32> {
+        let mut cols = Vec::new();
+        let mut used = 0u16;
+        for col in (0..self.frozen_cols).chain(origin..self.total_cols) {
+            if used >= width {
+                break;
+            }
+            used = used.saturating_add(self.col_width(col) + 1);
+            cols.push(col);
+        }
+        cols
+    }
+
+    /// Rows drawn for a data region `height` tall: frozen rows, then
+    /// scrolled rows from `origin`.
+    fn visible_rows(&self, origin: u32, height: u16) -> Vec<u32> {
+        (0..self.frozen_rows)
+            .chain(origin..self.total_rows)
+            .take(height as usize)
+            .collect()
+    }
+
+    /// Keep the cursor in view once the viewport is known.
+    fn follow_cursor(&mut self) {
+        let (width, height) = self.viewport;
+        if width > 0 && height > 0 {
+            (self.scroll_col, self.scroll_row) = self.scroll_origin(width, height);
+        }
+    }
 }
 
 /// Cell getter function type.
 pub type CellGetter<'a> = &'a dyn Fn(u32, u32) -> CellValue;
 
+/// Comment getter function type.
+pub type CommentGetter<'a> = &'a dyn Fn(u32, u32) -> Option<String>;
+
 /// Render the grid.
 pub fn render_grid<'a>(
     state: &GridState,
     area: &Rect,
     get_cell: CellGetter<'a>,
+) -> Vec<(u16, u16, String, Color)> {
+    render_grid_with_comments(state, area, get_cell, &|_, _| None)
+}
+
+/// Render the grid, marking commented cells.
+///
+/// With [`GridState::show_comment`] set, the cursor cell's comment is
+/// appended to the status bar.
+pub fn render_grid_with_comments<'a>(
+    state: &GridState,
+    area: &Rect,
+    get_cell: CellGetter<'a>,
+    get_comment: CommentGetter<'a>,
 ) -> Vec<(u16, u16, String, Color)> {
     let mut output = Vec::new();
 
@@ -125,31 +267,22 @@ pub fn render_grid<'a>(
         return output;
     }
 
-    let row_header_width = 5u16;
-    let content_width = area.width.saturating_sub(row_header_width + 2);
-    let content_height = area.height.saturating_sub(3);
-
-    // Calculate visible columns
-    let default_col_width = 10u16;
-    let mut visible_cols = 0u32;
-    let mut x_offset = 0u16;
-    while x_offset < content_width && visible_cols < state.total_cols - state.scroll_col {
-        let col_idx = state.scroll_col + visible_cols;
-        let col_width = state.col_widths.get(col_idx as usize).copied().unwrap_or(default_col_width);
-        x_offset += col_width + 1;
-        visible_cols += 1;
-    }
+    let row_header_width = ROW_HEADER_WIDTH;
+    let (content_width, content_height) = data_region(area);
 
-    let visible_rows = content_height.min(state.total_rows as u16 - state.scroll_row as u16);
+    // Visible columns and rows: frozen panes first, then the scrolled
+    // region positioned so the cursor is always on screen
+    let (origin_col, origin_row) = state.scroll_origin(content_width, content_height);
+    let cols = state.visible_cols(origin_col, content_width);
+    let rows = state.visible_rows(origin_row, content_height);
 
-    // Column headers
+    // Column headers (pinned)
     let header_y = area.y + 1;
     output.push((area.x + 1, header_y, " ".repeat(row_header_width as usize), colors::MUTED));
 
-    x_offset = row_header_width + 1;
-    for col_offset in 0..visible_cols {
-        let col_idx = state.scroll_col + col_offset;
-        let col_width = state.col_widths.get(col_idx as usize).copied().unwrap_or(default_col_width);
+    let mut x_offset = row_header_width + 1;
+    for &col_idx in &cols {
+        let col_width = state.col_width(col_idx);
         let col_name = CellRef::new(col_idx, 0).to_a1();
         let col_name = col_name.trim_end_matches(char::is_numeric);
         let header = pad(col_name, col_width as usize);
@@ -170,11 +303,10 @@ pub fn render_grid<'a>(
     output.push((area.x + 1, sep_y, sep, colors::MUTED));
 
     // Rows
-    for row_offset in 0..visible_rows as u32 {
-        let row_idx = state.scroll_row + row_offset;
+    for (row_offset, &row_idx) in rows.iter().enumerate() {
         let y = area.y + 3 + row_offset as u16;
 
-        // Row header
+        // Row header (pinned)
         let row_num = format!("{:>4} ", row_idx + 1);
         let row_color = if row_idx == state.cursor_row {
             colors::ACCENT
@@ -185,9 +317,8 @@ pub fn render_grid<'a>(
 
         // Cells
         x_offset = row_header_width + 1;
-        for col_offset in 0..visible_cols {
-            let col_idx = state.scroll_col + col_offset;
-            let col_width = state.col_widths.get(col_idx as usize).copied().unwrap_or(default_col_width);
+        for &col_idx in &cols {
+            let col_width = state.col_width(col_idx);
 
             let is_cursor = col_idx == state.cursor_col && row_idx == state.cursor_row;
 
@@ -214,6 +345,10 @@ pub fn render_grid<'a>(
             };
 
             output.push((area.x + 1 + x_offset, y, padded, color));
+            if col_width > 0 && get_comment(col_idx, row_idx).is_some() {
+                let marker_x = area.x + x_offset + col_width;
+                output.push((marker_x, y, COMMENT_MARKER.into(), colors::WARNING));
+            }
             x_offset += col_width + 1;
         }
     }
@@ -222,7 +357,12 @@ pub fn render_grid<'a>(
     let status_y = area.y + area.height - 1;
     let cell_ref = state.current_cell().to_a1();
     let mode = if state.editing { "EDIT" } else { "NAV" };
-    let status = format!(" {} | Cell: {} ", mode, cell_ref);
+    let mut status = format!(" {} | Cell: {} ", mode, cell_ref);
+    if state.show_comment {
+        if let Some(comment) = get_comment(state.cursor_col, state.cursor_row) {
+            status.push_str(&format!("| Comment: {} ", comment));
+        }
+    }
     output.push((area.x + 1, status_y, status, colors::ACCENT));
 
     output
@@ -274,4 +414,91 @@ mod tests {
         let cell = grid.current_cell();
         assert_eq!(cell.to_a1(), "C5");
     }
+
+    fn empty_cell(_col: u32, _row: u32) -> CellValue {
+        CellValue::Empty
+    }
+
+    fn texts_at(output: &[(u16, u16, String, Color)], y: u16) -> Vec<String> {
+        output
+            .iter()
+            .filter(|(_, row, _, _)| *row == y)
+            .map(|(_, _, text, _)| text.trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn scrolling_past_z_renders_multi_letter_headers() {
+        let area = Rect::new(0, 0, 60, 20);
+        let mut grid = GridState::new(40, 100);
+        grid.set_viewport(&area);
+
+        for _ in 0..27 {
+            grid.move_right();
+        }
+        assert_eq!(grid.current_cell().to_a1(), "AB1");
+        assert!(grid.scroll_col > 0);
+
+        let output = render_grid(&grid, &area, &empty_cell);
+        let headers = texts_at(&output, area.y + 1);
+        assert!(headers.contains(&"AA".to_string()));
+        assert!(headers.contains(&"AB".to_string()));
+        assert!(!headers.contains(&"A".to_string()));
+
+        // Row-number gutter is still drawn for every visible row.
+        assert_eq!(texts_at(&output, area.y + 3)[0], "1");
+    }
+
+    fn comment_on_a1(col: u32, row: u32) -> Option<String> {
+        (col == 0 && row == 0).then(|| "check total".into())
+    }
+
+    #[test]
+    fn commented_cells_are_marked_and_shown_on_demand() {
+        let area = Rect::new(0, 0, 60, 20);
+        let mut grid = GridState::new(10, 10);
+        grid.set_viewport(&area);
+        let status_y = area.y + area.height - 1;
+
+        let output = render_grid_with_comments(&grid, &area, &empty_cell, &comment_on_a1);
+        let markers: Vec<_> = output
+            .iter()
+            .filter(|(_, _, text, _)| text == COMMENT_MARKER)
+            .collect();
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].1, area.y + 3);
+        assert!(!texts_at(&output, status_y)[0].contains("check total"));
+
+        grid.toggle_comment();
+        let output = render_grid_with_comments(&grid, &area, &empty_cell, &comment_on_a1);
+        assert!(texts_at(&output, status_y)[0].contains("Comment: check total"));
+    }
+
+    #[test]
+    fn frozen_rows_and_cols_stay_visible() {
+        let area = Rect::new(0, 0, 60, 20);
+        let mut grid = GridState::new(40, 100);
+        grid.set_viewport(&area);
+        grid.set_frozen(1, 2);
+
+        for _ in 0..50 {
+            grid.move_down();
+        }
+        for _ in 0..30 {
+            grid.move_right();
+        }
+
+        let output = render_grid(&grid, &area, &empty_cell);
+        let headers = texts_at(&output, area.y + 1);
+        assert_eq!(headers[1], "A");
+        assert!(headers.contains(&"AE".to_string()));
+        assert!(!headers.contains(&"B".to_string()));
+
+        let gutter: Vec<String> = (0..data_region(&area).1)
+            .map(|i| texts_at(&output, area.y + 3 + i)[0].clone())
+            .collect();
+        assert_eq!(&gutter[..2], &["1".to_string(), "2".to_string()]);
+        assert_eq!(gutter.last().unwrap(), "51");
+        assert!(!gutter.contains(&"3".to_string()));
+    }
 }
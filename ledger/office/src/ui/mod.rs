@@ -6,6 +6,7 @@ pub mod editor;
 pub mod grid;
 pub mod tree;
 pub mod calendar;
+pub mod audit;
 
 use crossterm::style::Color;
 
@@ -1,7 +1,7 @@
 //! File tree widget for the file manager.
 
 use crossterm::style::Color;
-use super::{Rect, colors, truncate};
+use super::{Rect, colors, pad, truncate};
 
 /// A node in the file tree.
 #[derive(Debug, Clone)]
@@ -128,20 +128,23 @@ impl TreeState {
     }
 }
 
-/// Format file size in human-readable form.
+/// Width of the right-aligned size column, wide enough for "1023.9 MiB".
+const SIZE_COLUMN_WIDTH: usize = 10;
+
+/// Format file size in human-readable binary units.
 fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1}G", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1}M", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1}K", bytes as f64 / KB as f64)
+    const KIB: u64 = 1024;
+    const MIB: u64 = KIB * 1024;
+    const GIB: u64 = MIB * 1024;
+
+    if bytes >= GIB {
+        format!("{:.1} GiB", bytes as f64 / GIB as f64)
+    } else if bytes >= MIB {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    } else if bytes >= KIB {
+        format!("{:.1} KiB", bytes as f64 / KIB as f64)
     } else {
-        format!("{}B", bytes)
+        format!("{} B", bytes)
     }
 }
 
@@ -178,11 +181,27 @@ pub fn render_tree(
             "📄"
         };
 
-        let size_str = node.size.map(format_size).unwrap_or_default();
-        let name_width = content_width.saturating_sub(indent.len() + 3 + size_str.len() + 2);
-        let name = truncate(&node.name, name_width);
-
-        let line = format!("{}{} {} {}", indent, icon, name, size_str);
+        // Icons are two columns wide, followed by a space
+        let prefix = format!("{}{} ", indent, icon);
+        let prefix_width = indent.len() + 3;
+
+        // Directories show no size; files pad the name so sizes line up
+        let line = match node.size.filter(|_| !node.is_directory) {
+            Some(size) => {
+                let name_width = content_width.saturating_sub(prefix_width + 1 + SIZE_COLUMN_WIDTH);
+                format!(
+                    "{}{} {:>width$}",
+                    prefix,
+                    pad(&node.name, name_width),
+                    format_size(size),
+                    width = SIZE_COLUMN_WIDTH
+                )
+            }
+            None => {
+                let name_width = content_width.saturating_sub(prefix_width);
+                format!("{}{}", prefix, truncate(&node.name, name_width))
+            }
+        };
         let display = truncate(&line, content_width);
 
         let color = if is_selected {
@@ -242,11 +261,43 @@ mod tests {
 
     #[test]
     fn format_size_test() {
-        assert_eq!(format_size(500), "500B");
-        assert_eq!(format_size(1024), "1.0K");
-        assert_eq!(format_size(1536), "1.5K");
-        assert_eq!(format_size(1048576), "1.0M");
-        assert_eq!(format_size(1073741824), "1.0G");
+        assert_eq!(format_size(500), "500 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(1048576), "1.0 MiB");
+        assert_eq!(format_size(1073741824), "1.0 GiB");
+    }
+
+    #[test]
+    fn render_aligns_file_sizes_and_omits_directory_size() {
+        use unicode_width::UnicodeWidthStr;
+
+        let tree = TreeState {
+            nodes: vec![
+                TreeNode::directory("docs", "/docs", 0),
+                TreeNode::file("notes.txt", "/docs/notes.txt", 1, 1536),
+                TreeNode::file(
+                    "a-very-long-file-name-that-needs-truncating.txt",
+                    "/docs/a-very-long-file-name-that-needs-truncating.txt",
+                    1,
+                    12,
+                ),
+            ],
+            cursor: 0,
+            scroll_offset: 0,
+        };
+        let rows: Vec<String> = render_tree(&tree, &Rect::new(0, 0, 40, 6))
+            .into_iter()
+            .map(|(_, _, text, _)| text)
+            .collect();
+
+        assert!(rows[0].contains("docs"));
+        assert!(!rows[0].contains(" B"));
+        assert!(rows[1].ends_with("   1.5 KiB"));
+        assert!(rows[2].ends_with("      12 B"));
+        assert!(rows[2].contains("..."));
+        assert_eq!(rows[1].width(), rows[2].width());
+        assert_eq!(rows[1].width(), 38);
     }
 
     #[test]
@@ -0,0 +1,98 @@
+//! Canonical JSON encoding for content hashing.
+//!
+//! A hash over JSON must not depend on how the value was built or on which
+//! `serde_json` features the final binary enables (`preserve_order` keeps
+//! insertion order). Hashed JSON is therefore re-encoded here with object
+//! keys sorted by their UTF-8 bytes, no insignificant whitespace, and one
+//! spelling per number: integral values, including floats such as `1.0` or
+//! `-0.0`, are written as plain integers and every other float in shortest
+//! round-trip form.
+
+use serde_json::{Number, Value};
+
+/// Largest magnitude below which every integral `f64` is exactly an integer
+/// (2^53).
+const MAX_EXACT_FLOAT_INT: f64 = 9_007_199_254_740_992.0;
+
+/// Encode `value` as canonical JSON bytes.
+pub fn to_canonical_json(value: &Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_value(value, &mut out);
+    out
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.extend_from_slice(b"null"),
+        Value::Bool(true) => out.extend_from_slice(b"true"),
+        Value::Bool(false) => out.extend_from_slice(b"false"),
+        Value::Number(number) => write_number(number, out),
+        Value::String(s) => write_string(s, out),
+        Value::Array(items) => {
+            out.push(b'[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_value(item, out);
+            }
+            out.push(b']');
+        }
+        Value::Object(map) => {
+            let mut members: Vec<_> = map.iter().collect();
+            members.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            out.push(b'{');
+            for (i, (key, item)) in members.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(b',');
+                }
+                write_string(key, out);
+                out.push(b':');
+                write_value(item, out);
+            }
+            out.push(b'}');
+        }
+    }
+}
+
+fn write_number(number: &Number, out: &mut Vec<u8>) {
+    if number.is_f64() {
+        let float = number.as_f64().unwrap_or_default();
+        if float.fract() == 0.0 && float.abs() < MAX_EXACT_FLOAT_INT {
+            out.extend_from_slice((float as i64).to_string().as_bytes());
+            return;
+        }
+    }
+    out.extend_from_slice(number.to_string().as_bytes());
+}
+
+fn write_string(s: &str, out: &mut Vec<u8>) {
+    serde_json::to_writer(&mut *out, s).expect("writing a JSON string to a Vec cannot fail");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canonical(src: &str) -> String {
+        let value: Value = serde_json::from_str(src).unwrap();
+        String::from_utf8(to_canonical_json(&value)).unwrap()
+    }
+
+    #[test]
+    fn sorts_keys_and_drops_whitespace() {
+        assert_eq!(
+            canonical(r#"{ "b": [1, {"z": null, "a": "é\n"}], "a": true }"#),
+            r#"{"a":true,"b":[1,{"a":"é\n","z":null}]}"#
+        );
+    }
+
+    #[test]
+    fn normalizes_numbers() {
+        assert_eq!(
+            canonical("[1.0, -0.0, 1e2, 2.5, -7, 18446744073709551615]"),
+            "[1,0,100,2.5,-7,18446744073709551615]"
+        );
+        assert_eq!(canonical("1e300"), "1e300");
+    }
+}
@@ -311,6 +311,9 @@ pub struct TransportAdapterCapability {
     /// Optional attestation handshake material.
     #[serde(default)]
     pub attestation: Option<CapabilityAttestation>,
+    /// Broadcast queue depth requested for the adapter.
+    #[serde(default)]
+    pub queue_depth: Option<usize>,
 }
 
 /// Binding selected after capability negotiation.
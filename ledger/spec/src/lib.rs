@@ -10,6 +10,8 @@ use serde_big_array::BigArray;
 use std::collections::HashMap;
 use thiserror::Error;
 
+/// Canonical JSON encoding used wherever JSON is hashed.
+pub mod canonical;
 /// Event and workflow schema layered on top of envelopes.
 pub mod events;
 /// Declarative policy model shared across ledger components.
@@ -101,6 +103,24 @@ pub enum AttestationKind {
     },
 }
 
+impl AttestationKind {
+    /// Reject a `Policy` statement whose `expires_at` is before `now`.
+    ///
+    /// A bundle is still valid at the exact millisecond it expires; other
+    /// statement kinds carry no expiry.
+    pub fn check_expiry(&self, now: Timestamp) -> Result<(), ValidationError> {
+        match self {
+            AttestationKind::Policy { expires_at, .. } if *expires_at < now => {
+                Err(ValidationError::AttestationExpired {
+                    expires_at: *expires_at,
+                    now,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Attestation attached to an envelope.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Attestation {
@@ -128,7 +148,13 @@ pub struct Envelope {
     pub attestations: Vec<Attestation>,
 }
 
-/// Compute the hash of an envelope body.
+/// First schema version whose body hash covers the payload in canonical JSON.
+pub const CANONICAL_BODY_VERSION: SchemaVersion = 3;
+
+/// Compute the hash of an envelope body in its plain serde encoding.
+///
+/// This is the body hash of envelopes older than [`CANONICAL_BODY_VERSION`];
+/// validators should use [`hash_body_for`] with the header's version.
 pub fn hash_body(body: &EnvelopeBody) -> Hash {
     let mut hasher = Hasher::new();
     hasher.update(b"ea-ledger:body");
@@ -138,6 +164,31 @@ pub fn hash_body(body: &EnvelopeBody) -> Hash {
     *hasher.finalize().as_bytes()
 }
 
+/// Compute the hash of an envelope body as committed to by a header of
+/// schema `version`.
+///
+/// From [`CANONICAL_BODY_VERSION`] on, the body is hashed in its serde shape
+/// with the payload in canonical JSON (see [`canonical`]), so logically equal
+/// payloads hash equally whatever their key order or number spelling. Older
+/// versions keep the [`hash_body`] encoding they were signed over.
+pub fn hash_body_for(body: &EnvelopeBody, version: SchemaVersion) -> Hash {
+    if version < CANONICAL_BODY_VERSION {
+        return hash_body(body);
+    }
+    let payload_type = body
+        .payload_type
+        .clone()
+        .map_or(serde_json::Value::Null, serde_json::Value::String);
+    let mut hasher = Hasher::new();
+    hasher.update(b"ea-ledger:body");
+    hasher.update(b"{\"payload\":");
+    hasher.update(&canonical::to_canonical_json(&body.payload));
+    hasher.update(b",\"payload_type\":");
+    hasher.update(&canonical::to_canonical_json(&payload_type));
+    hasher.update(b"}");
+    *hasher.finalize().as_bytes()
+}
+
 /// Compute the canonical hash of an envelope header.
 pub fn hash_header(header: &EnvelopeHeader) -> Hash {
     let mut hasher = Hasher::new();
@@ -169,13 +220,26 @@ pub fn hash_attestation_statement(statement: &AttestationKind) -> Hash {
     *hasher.finalize().as_bytes()
 }
 
+/// Signing weight granted to a specific signer.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SignerWeight {
+    /// Signer public key.
+    pub signer: PublicKey,
+    /// Weight contributed towards the policy threshold.
+    pub weight: usize,
+}
+
 /// Channel policy definition.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ChannelPolicy {
-    /// Minimum required distinct signers.
+    /// Required signing weight; with no `signer_weights` this is the minimum
+    /// number of distinct signers.
     pub min_signers: usize,
     /// Allowed signers (whitelist). Empty means any.
     pub allowed_signers: Vec<PublicKey>,
+    /// Per-signer weights. Signers not listed weigh 1.
+    #[serde(default)]
+    pub signer_weights: Vec<SignerWeight>,
     /// Whether attestations are required.
     pub require_attestations: bool,
     /// Enforce monotonically increasing timestamps.
@@ -187,12 +251,28 @@ impl Default for ChannelPolicy {
         Self {
             min_signers: 1,
             allowed_signers: Vec::new(),
+            signer_weights: Vec::new(),
             require_attestations: false,
             enforce_timestamp_ordering: true,
         }
     }
 }
 
+impl ChannelPolicy {
+    /// Weight threshold valid signatures must reach.
+    pub fn required_weight(&self) -> usize {
+        self.min_signers
+    }
+
+    /// Weight contributed by `signer`.
+    pub fn weight_of(&self, signer: &PublicKey) -> usize {
+        self.signer_weights
+            .iter()
+            .find(|w| &w.signer == signer)
+            .map_or(1, |w| w.weight)
+    }
+}
+
 /// Channel registry entry.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ChannelSpec {
@@ -200,12 +280,199 @@ pub struct ChannelSpec {
     pub name: Channel,
     /// Policy rules.
     pub policy: ChannelPolicy,
+    /// Payload schemas declared for the channel.
+    #[serde(default)]
+    pub schemas: ChannelSchemas,
+}
+
+/// Payload schemas declared for a channel in the registry file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ChannelSchemas {
+    /// Reject envelopes whose version has no schema.
+    #[serde(default)]
+    pub strict: bool,
+    /// One schema per accepted version.
+    #[serde(default)]
+    pub versions: Vec<PayloadSchema>,
+}
+
+/// Declarative payload schema for one channel version.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PayloadSchema {
+    /// Schema version the payload is checked against.
+    pub version: SchemaVersion,
+    /// Fields the payload object must carry.
+    #[serde(default)]
+    pub required: Vec<FieldSchema>,
+}
+
+/// Required payload field and the JSON type it must have.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// Field name.
+    pub name: String,
+    /// Expected JSON type.
+    #[serde(rename = "type", default)]
+    pub kind: FieldKind,
+}
+
+/// JSON type of a payload field.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FieldKind {
+    /// Any value, as long as the field is present.
+    #[default]
+    Any,
+    /// JSON string.
+    String,
+    /// JSON number.
+    Number,
+    /// JSON boolean.
+    Bool,
+    /// JSON object.
+    Object,
+    /// JSON array.
+    Array,
+}
+
+impl FieldKind {
+    fn matches(self, value: &serde_json::Value) -> bool {
+        match self {
+            FieldKind::Any => true,
+            FieldKind::String => value.is_string(),
+            FieldKind::Number => value.is_number(),
+            FieldKind::Bool => value.is_boolean(),
+            FieldKind::Object => value.is_object(),
+            FieldKind::Array => value.is_array(),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            FieldKind::Any => "any",
+            FieldKind::String => "string",
+            FieldKind::Number => "number",
+            FieldKind::Bool => "bool",
+            FieldKind::Object => "object",
+            FieldKind::Array => "array",
+        }
+    }
+}
+
+impl PayloadSchema {
+    /// Check `payload` against this schema; returns a description of the
+    /// first problem found.
+    pub fn validate(&self, payload: &serde_json::Value) -> Result<(), String> {
+        let Some(object) = payload.as_object() else {
+            return Err("payload is not an object".into());
+        };
+        for field in &self.required {
+            match object.get(&field.name) {
+                Some(value) if field.kind.matches(value) => {}
+                _ => {
+                    return Err(format!(
+                        "missing {} field `{}`",
+                        field.kind.name(),
+                        field.name
+                    ))
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Payload validator for one `(channel, version)` pair; returns a
+/// description of the first problem found.
+pub type PayloadValidator = fn(&serde_json::Value) -> Result<(), String>;
+
+/// Validator registered for a channel version, in code or from a file.
+#[derive(Debug, Clone)]
+enum SchemaCheck {
+    Code(PayloadValidator),
+    Declared(PayloadSchema),
+}
+
+/// Registry of payload schemas keyed by channel and schema version.
+///
+/// Channels marked strict reject envelopes whose version has no registered
+/// schema; other channels accept them after the structural checks only.
+#[derive(Debug, Default, Clone)]
+pub struct SchemaRegistry {
+    validators: HashMap<(Channel, SchemaVersion), SchemaCheck>,
+    strict: std::collections::HashSet<Channel>,
+}
+
+impl SchemaRegistry {
+    /// Create an empty schema registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register or replace the validator for a channel version.
+    pub fn register(
+        &mut self,
+        channel: impl Into<Channel>,
+        version: SchemaVersion,
+        validator: PayloadValidator,
+    ) {
+        self.validators
+            .insert((channel.into(), version), SchemaCheck::Code(validator));
+    }
+
+    /// Register or replace a declarative schema for a channel version.
+    pub fn register_schema(&mut self, channel: impl Into<Channel>, schema: PayloadSchema) {
+        self.validators.insert(
+            (channel.into(), schema.version),
+            SchemaCheck::Declared(schema),
+        );
+    }
+
+    /// Mark a channel strict (unknown versions rejected) or lenient.
+    pub fn set_strict(&mut self, channel: impl Into<Channel>, strict: bool) {
+        let channel = channel.into();
+        if strict {
+            self.strict.insert(channel);
+        } else {
+            self.strict.remove(&channel);
+        }
+    }
+
+    /// Whether unknown versions are rejected on this channel.
+    pub fn is_strict(&self, channel: &str) -> bool {
+        self.strict.contains(channel)
+    }
+
+    /// Check an envelope body against the schema for its channel version.
+    pub fn check(
+        &self,
+        header: &EnvelopeHeader,
+        body: &EnvelopeBody,
+    ) -> Result<(), ValidationError> {
+        match self
+            .validators
+            .get(&(header.channel.clone(), header.version))
+        {
+            Some(SchemaCheck::Code(validator)) => {
+                validator(&body.payload).map_err(ValidationError::SchemaMismatch)
+            }
+            Some(SchemaCheck::Declared(schema)) => schema
+                .validate(&body.payload)
+                .map_err(ValidationError::SchemaMismatch),
+            None if self.is_strict(&header.channel) => Err(ValidationError::UnknownSchema {
+                channel: header.channel.clone(),
+                version: header.version,
+            }),
+            None => Ok(()),
+        }
+    }
 }
 
 /// Registry of channels.
 #[derive(Debug, Default, Clone)]
 pub struct ChannelRegistry {
     policies: HashMap<Channel, ChannelPolicy>,
+    schemas: SchemaRegistry,
 }
 
 impl ChannelRegistry {
@@ -214,15 +481,32 @@ impl ChannelRegistry {
         Self::default()
     }
 
-    /// Register or replace a channel spec.
+    /// Register or replace a channel spec, along with any payload schemas
+    /// it declares.
     pub fn upsert(&mut self, spec: ChannelSpec) {
-        self.policies.insert(spec.name.clone(), spec.policy);
+        for schema in spec.schemas.versions {
+            self.schemas.register_schema(spec.name.clone(), schema);
+        }
+        if spec.schemas.strict {
+            self.schemas.set_strict(spec.name.clone(), true);
+        }
+        self.policies.insert(spec.name, spec.policy);
     }
 
     /// Fetch a policy for a channel.
     pub fn policy_for(&self, channel: &str) -> Option<&ChannelPolicy> {
         self.policies.get(channel)
     }
+
+    /// Payload schemas consulted during validation.
+    pub fn schemas(&self) -> &SchemaRegistry {
+        &self.schemas
+    }
+
+    /// Mutable access to the payload schemas.
+    pub fn schemas_mut(&mut self) -> &mut SchemaRegistry {
+        &mut self.schemas
+    }
 }
 
 /// Validation errors.
@@ -231,7 +515,7 @@ pub enum ValidationError {
     /// Missing chain link.
     #[error("previous hash mismatch")]
     ChainMismatch,
-    /// Not enough signatures.
+    /// Not enough signing weight (carries the weight collected).
     #[error("insufficient signatures: {0}")]
     InsufficientSignatures(usize),
     /// Signer not allowed.
@@ -243,15 +527,43 @@ pub enum ValidationError {
     /// Timestamp regressed.
     #[error("timestamp regression")]
     TimestampRegression,
+    /// Timestamp further ahead of the validating clock than
+    /// [`MAX_CLOCK_SKEW_MS`] allows.
+    #[error("timestamp {timestamp} is ahead of the clock (now {now})")]
+    TimestampInFuture {
+        /// Timestamp carried in the header.
+        timestamp: Timestamp,
+        /// Time validation was performed at.
+        now: Timestamp,
+    },
     /// Body hash mismatch.
     #[error("body hash mismatch")]
     BodyHashMismatch,
     /// Invalid attestation signature.
     #[error("attestation verification failed")]
     AttestationInvalid,
+    /// Policy attestation past its expiry.
+    #[error("attestation expired at {expires_at} (now {now})")]
+    AttestationExpired {
+        /// Expiry carried in the statement.
+        expires_at: Timestamp,
+        /// Time validation was performed at.
+        now: Timestamp,
+    },
     /// Envelope signature verification failed.
     #[error("signature verification failed")]
     SignatureInvalid,
+    /// No schema registered for this version on a strict channel.
+    #[error("unknown schema version {version} for channel {channel}")]
+    UnknownSchema {
+        /// Channel the envelope was sent on.
+        channel: Channel,
+        /// Version carried in the header.
+        version: SchemaVersion,
+    },
+    /// Payload does not match the registered schema.
+    #[error("payload does not match schema: {0}")]
+    SchemaMismatch(String),
 }
 
 /// Validation context across a channel (previous hash + timestamp).
@@ -263,14 +575,34 @@ pub struct ChannelState {
     pub last_timestamp: Option<Timestamp>,
 }
 
+/// How far an envelope's timestamp may run ahead of the validating clock.
+pub const MAX_CLOCK_SKEW_MS: Timestamp = 5 * 60 * 1000;
+
 /// Verify an envelope against the registry and previous state.
+///
+/// Attestation expiry is checked as of the envelope's own timestamp, so a
+/// log replays the same way whenever it is read. The signer controls that
+/// timestamp, so admitting new envelopes must use [`validate_envelope_at`]
+/// with the wall clock instead.
 pub fn validate_envelope(
     env: &Envelope,
     registry: &ChannelRegistry,
     prev_state: &ChannelState,
+) -> Result<ChannelState, ValidationError> {
+    validate_envelope_at(env, registry, prev_state, env.header.timestamp)
+}
+
+/// Verify an envelope like [`validate_envelope`], rejecting attestations
+/// that have expired by `now` (milliseconds) and timestamps more than
+/// [`MAX_CLOCK_SKEW_MS`] ahead of it.
+pub fn validate_envelope_at(
+    env: &Envelope,
+    registry: &ChannelRegistry,
+    prev_state: &ChannelState,
+    now: Timestamp,
 ) -> Result<ChannelState, ValidationError> {
     // Body hash check
-    let computed_body = hash_body(&env.body);
+    let computed_body = hash_body_for(&env.body, env.header.version);
     if computed_body != env.header.body_hash {
         return Err(ValidationError::BodyHashMismatch);
     }
@@ -286,6 +618,15 @@ pub fn validate_envelope(
             return Err(ValidationError::TimestampRegression);
         }
     }
+    if env.header.timestamp > now.saturating_add(MAX_CLOCK_SKEW_MS) {
+        return Err(ValidationError::TimestampInFuture {
+            timestamp: env.header.timestamp,
+            now,
+        });
+    }
+
+    // Payload schema
+    registry.schemas().check(&env.header, &env.body)?;
 
     // Policy lookup
     let policy = registry
@@ -293,11 +634,15 @@ pub fn validate_envelope(
         .cloned()
         .unwrap_or_default();
 
-    // Signature check
-    if env.signatures.len() < policy.min_signers {
-        return Err(ValidationError::InsufficientSignatures(
-            env.signatures.len(),
-        ));
+    // Signature check: bail early if even every signature counted can't
+    // reach the threshold.
+    let offered_weight: usize = env
+        .signatures
+        .iter()
+        .map(|sig| policy.weight_of(&sig.signer))
+        .sum();
+    if offered_weight < policy.required_weight() {
+        return Err(ValidationError::InsufficientSignatures(offered_weight));
     }
     let env_hash = envelope_hash(env);
     let mut seen_signers = std::collections::HashSet::new();
@@ -312,10 +657,10 @@ pub fn validate_envelope(
         }
         seen_signers.insert(sig.signer);
     }
-    if seen_signers.len() < policy.min_signers {
-        return Err(ValidationError::InsufficientSignatures(
-            env.signatures.len(),
-        ));
+    // Each distinct signer counts once, however many times it signed.
+    let signed_weight: usize = seen_signers.iter().map(|pk| policy.weight_of(pk)).sum();
+    if signed_weight < policy.required_weight() {
+        return Err(ValidationError::InsufficientSignatures(signed_weight));
     }
 
     // Attestations
@@ -332,6 +677,7 @@ pub fn validate_envelope(
         let signature = ed25519_dalek::Signature::from_bytes(&att.signature);
         pk.verify_strict(&att.statement_hash, &signature)
             .map_err(|_| ValidationError::AttestationInvalid)?;
+        att.statement.check_expiry(now)?;
     }
 
     Ok(ChannelState {
@@ -388,9 +734,11 @@ mod tests {
             policy: ChannelPolicy {
                 min_signers: 1,
                 allowed_signers: vec![],
+                signer_weights: Vec::new(),
                 require_attestations: false,
                 enforce_timestamp_ordering: true,
             },
+            schemas: Default::default(),
         });
 
         let state = validate_envelope(&env, &registry, &ChannelState::default()).unwrap();
@@ -411,4 +759,300 @@ mod tests {
         let err = validate_envelope(&env, &registry, &ChannelState::default()).unwrap_err();
         assert_eq!(err, ValidationError::BodyHashMismatch);
     }
+
+    fn sign(env: &mut Envelope, sk: &SigningKey) {
+        let sig = sk.sign(&envelope_hash(env));
+        env.signatures.push(Signature {
+            signer: sk.verifying_key().to_bytes(),
+            signature: sig.to_bytes(),
+        });
+    }
+
+    /// "2 of these admins, or the single root key".
+    fn weighted_registry(admins: &[&SigningKey], root: &SigningKey) -> ChannelRegistry {
+        let root_pk = root.verifying_key().to_bytes();
+        let mut allowed: Vec<_> = admins
+            .iter()
+            .map(|sk| sk.verifying_key().to_bytes())
+            .collect();
+        allowed.push(root_pk);
+        let mut registry = ChannelRegistry::new();
+        registry.upsert(ChannelSpec {
+            name: "muscle_io".into(),
+            policy: ChannelPolicy {
+                min_signers: 2,
+                allowed_signers: allowed,
+                signer_weights: vec![SignerWeight {
+                    signer: root_pk,
+                    weight: 2,
+                }],
+                ..ChannelPolicy::default()
+            },
+            schemas: Default::default(),
+        });
+        registry
+    }
+
+    #[test]
+    fn weighted_signers_meet_threshold() {
+        let (base, admin_a) = base_envelope();
+        let (admin_b, root) = (signing_key(), signing_key());
+        let registry = weighted_registry(&[&admin_a, &admin_b], &root);
+
+        let mut by_root = base.clone();
+        sign(&mut by_root, &root);
+        assert!(validate_envelope(&by_root, &registry, &ChannelState::default()).is_ok());
+
+        let mut by_admins = base;
+        sign(&mut by_admins, &admin_a);
+        sign(&mut by_admins, &admin_b);
+        assert!(validate_envelope(&by_admins, &registry, &ChannelState::default()).is_ok());
+    }
+
+    #[test]
+    fn rejects_insufficient_weight() {
+        let (mut env, admin_a) = base_envelope();
+        let registry = weighted_registry(&[&admin_a, &signing_key()], &signing_key());
+        sign(&mut env, &admin_a);
+        let err = validate_envelope(&env, &registry, &ChannelState::default()).unwrap_err();
+        assert_eq!(err, ValidationError::InsufficientSignatures(1));
+    }
+
+    #[test]
+    fn duplicate_signer_counts_once() {
+        let (mut env, admin_a) = base_envelope();
+        let registry = weighted_registry(&[&admin_a, &signing_key()], &signing_key());
+        sign(&mut env, &admin_a);
+        sign(&mut env, &admin_a);
+        let err = validate_envelope(&env, &registry, &ChannelState::default()).unwrap_err();
+        assert_eq!(err, ValidationError::InsufficientSignatures(1));
+    }
+
+    fn attest_policy(env: &mut Envelope, issuer: &SigningKey, expires_at: Timestamp) {
+        let statement = AttestationKind::Policy {
+            bundle_hash: [7; 32],
+            expires_at,
+        };
+        let statement_hash = hash_attestation_statement(&statement);
+        env.attestations.push(Attestation {
+            issuer: issuer.verifying_key().to_bytes(),
+            statement,
+            statement_hash,
+            signature: issuer.sign(&statement_hash).to_bytes(),
+        });
+    }
+
+    #[test]
+    fn policy_attestation_expiry_is_enforced() {
+        let (mut env, sk) = base_envelope();
+        attest_policy(&mut env, &signing_key(), 1_000);
+        sign(&mut env, &sk);
+        let registry = ChannelRegistry::new();
+        let state = ChannelState::default();
+
+        assert!(validate_envelope_at(&env, &registry, &state, 999).is_ok());
+        assert!(validate_envelope_at(&env, &registry, &state, 1_000).is_ok());
+        assert_eq!(
+            validate_envelope_at(&env, &registry, &state, 1_001).unwrap_err(),
+            ValidationError::AttestationExpired {
+                expires_at: 1_000,
+                now: 1_001,
+            }
+        );
+        // Without an explicit clock the envelope's own timestamp is used.
+        assert!(validate_envelope(&env, &registry, &state).is_ok());
+    }
+
+    #[test]
+    fn timestamps_beyond_clock_skew_are_rejected() {
+        let (mut env, sk) = base_envelope();
+        env.header.timestamp = 1_000 + MAX_CLOCK_SKEW_MS + 1;
+        sign(&mut env, &sk);
+        let registry = ChannelRegistry::new();
+        let state = ChannelState::default();
+
+        assert!(validate_envelope_at(&env, &registry, &state, 1_001).is_ok());
+        assert_eq!(
+            validate_envelope_at(&env, &registry, &state, 1_000).unwrap_err(),
+            ValidationError::TimestampInFuture {
+                timestamp: 1_000 + MAX_CLOCK_SKEW_MS + 1,
+                now: 1_000,
+            }
+        );
+    }
+
+    fn require_hello(payload: &serde_json::Value) -> Result<(), String> {
+        match payload.get("hello") {
+            Some(serde_json::Value::String(_)) => Ok(()),
+            _ => Err("missing string field `hello`".into()),
+        }
+    }
+
+    fn signed(mut env: Envelope, sk: &SigningKey) -> Envelope {
+        env.header.body_hash = hash_body(&env.body);
+        sign(&mut env, sk);
+        env
+    }
+
+    #[test]
+    fn registered_schema_accepts_conforming_payload() {
+        let (env, sk) = base_envelope();
+        let mut registry = ChannelRegistry::new();
+        registry
+            .schemas_mut()
+            .register("muscle_io", 1, require_hello);
+        registry.schemas_mut().set_strict("muscle_io", true);
+        let env = signed(env, &sk);
+        assert!(validate_envelope(&env, &registry, &ChannelState::default()).is_ok());
+    }
+
+    #[test]
+    fn registered_schema_rejects_malformed_payload() {
+        let (mut env, sk) = base_envelope();
+        env.body.payload = serde_json::json!({"hello": 42});
+        let mut registry = ChannelRegistry::new();
+        registry
+            .schemas_mut()
+            .register("muscle_io", 1, require_hello);
+        let env = signed(env, &sk);
+        let err = validate_envelope(&env, &registry, &ChannelState::default()).unwrap_err();
+        assert!(matches!(err, ValidationError::SchemaMismatch(_)));
+    }
+
+    #[test]
+    fn schemas_load_from_registry_file() {
+        let specs: Vec<ChannelSpec> = serde_json::from_value(serde_json::json!([{
+            "name": "muscle_io",
+            "policy": {
+                "min_signers": 1,
+                "allowed_signers": [],
+                "require_attestations": false,
+                "enforce_timestamp_ordering": true
+            },
+            "schemas": {
+                "strict": true,
+                "versions": [{"version": 1, "required": [{"name": "hello", "type": "string"}]}]
+            }
+        }]))
+        .unwrap();
+        let mut registry = ChannelRegistry::new();
+        for spec in specs {
+            registry.upsert(spec);
+        }
+        assert!(registry.schemas().is_strict("muscle_io"));
+
+        let (env, sk) = base_envelope();
+        let good = signed(env.clone(), &sk);
+        assert!(validate_envelope(&good, &registry, &ChannelState::default()).is_ok());
+
+        let mut bad = env.clone();
+        bad.body.payload = serde_json::json!({"hello": 42});
+        let err =
+            validate_envelope(&signed(bad, &sk), &registry, &ChannelState::default()).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::SchemaMismatch("missing string field `hello`".into())
+        );
+
+        let mut unknown = env;
+        unknown.header.version = 2;
+        let err = validate_envelope(&signed(unknown, &sk), &registry, &ChannelState::default())
+            .unwrap_err();
+        assert!(matches!(err, ValidationError::UnknownSchema { .. }));
+    }
+
+    #[test]
+    fn unknown_version_rejected_only_on_strict_channel() {
+        let (mut env, sk) = base_envelope();
+        env.header.version = 2;
+        let env = signed(env, &sk);
+        let mut registry = ChannelRegistry::new();
+        registry
+            .schemas_mut()
+            .register("muscle_io", 1, require_hello);
+        assert!(validate_envelope(&env, &registry, &ChannelState::default()).is_ok());
+
+        registry.schemas_mut().set_strict("muscle_io", true);
+        let err = validate_envelope(&env, &registry, &ChannelState::default()).unwrap_err();
+        assert_eq!(
+            err,
+            ValidationError::UnknownSchema {
+                channel: "muscle_io".into(),
+                version: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn body_hash_ignores_key_order() {
+        let mut forward = serde_json::Map::new();
+        forward.insert("amount".into(), serde_json::json!(1.0));
+        forward.insert(
+            "meta".into(),
+            serde_json::json!({"a": 1, "b": [true, null]}),
+        );
+        let mut reverse = serde_json::Map::new();
+        reverse.insert(
+            "meta".into(),
+            serde_json::json!({"b": [true, null], "a": 1}),
+        );
+        reverse.insert("amount".into(), serde_json::json!(1));
+
+        let body = |payload: serde_json::Map<String, serde_json::Value>| EnvelopeBody {
+            payload: serde_json::Value::Object(payload),
+            payload_type: Some("test".into()),
+        };
+        let reparsed: serde_json::Value =
+            serde_json::from_str(r#"{ "meta": {"b": [true, null], "a": 1}, "amount": 1 }"#)
+                .unwrap();
+
+        let expected = hash_body_for(&body(forward), CANONICAL_BODY_VERSION);
+        assert_eq!(
+            hash_body_for(&body(reverse), CANONICAL_BODY_VERSION),
+            expected
+        );
+        assert_eq!(
+            hash_body_for(
+                &EnvelopeBody {
+                    payload: reparsed,
+                    payload_type: Some("test".into()),
+                },
+                CANONICAL_BODY_VERSION
+            ),
+            expected
+        );
+        assert_ne!(
+            hash_body_for(
+                &EnvelopeBody {
+                    payload: serde_json::json!({"amount": 1, "meta": {"a": 1}}),
+                    payload_type: Some("test".into()),
+                },
+                CANONICAL_BODY_VERSION
+            ),
+            expected
+        );
+    }
+    #[test]
+    fn pre_canonical_versions_keep_the_serde_body_hash() {
+        let (mut env, sk) = base_envelope();
+        env.body.payload = serde_json::json!({"amount": 1.0});
+        // Body hash as computed before canonical JSON, which signed envelopes commit to
+        let mut hasher = Hasher::new();
+        hasher.update(b"ea-ledger:body");
+        hasher.update(&serde_json::to_vec(&env.body).unwrap());
+        let legacy = *hasher.finalize().as_bytes();
+        assert_eq!(hash_body_for(&env.body, 1), legacy);
+        assert_ne!(hash_body_for(&env.body, CANONICAL_BODY_VERSION), legacy);
+
+        env.header.body_hash = legacy;
+        sign(&mut env, &sk);
+        let registry = ChannelRegistry::new();
+        assert!(validate_envelope(&env, &registry, &ChannelState::default()).is_ok());
+
+        env.header.version = CANONICAL_BODY_VERSION;
+        assert_eq!(
+            validate_envelope(&env, &registry, &ChannelState::default()).unwrap_err(),
+            ValidationError::BodyHashMismatch
+        );
+    }
 }
@@ -21,6 +21,8 @@
 #![warn(clippy::all, clippy::pedantic)]
 
 extern crate alloc;
+#[cfg(any(test, feature = "std"))]
+extern crate std;
 
 use blake3::Hasher;
 
@@ -39,6 +41,9 @@ pub type LatticeRoot = [u8; 32];
 /// QR proof (48 bytes)
 pub type QrProof = [u8; 48];
 
+/// Root of the empty lattice, as embedded by the muscle compiler
+pub const GENESIS_ROOT: LatticeRoot = [0xEA; 32];
+
 /// Muscle update structure
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct MuscleUpdate {
@@ -174,87 +179,62 @@ fn mod_n(x: BigInt) -> BigInt {
 /// Reduces x mod N using precomputed μ = floor(2^4096 / N)
 ///
 /// Algorithm:
-/// 1. q = floor((x * μ) / 2^4096)  ≈ floor(x / N)
-/// 2. r = x - q * N
+/// 1. q = floor((x * μ) / 2^4096)  ≈ floor(x / N), never above it
+/// 2. r = x - q * N, so 0 <= r < 3N
 /// 3. while r >= N: r = r - N (at most 2 iterations)
 fn barrett_reduce_64(x: &[u64; 64]) -> BigInt {
-    // Step 1: Compute q = floor((x * μ) / 2^4096)
-    // We need the high limbs of x * μ (shifted right by 4096 bits = 64 limbs)
-    // Since μ has 33 limbs and x has 64 limbs, the product has up to 97 limbs
-    // We only need limbs 64..97 of the product (the quotient estimate)
-
-    // First, compute x * μ, keeping only what we need
-    // We compute the partial product and extract high limbs
-    let mut q = [0u64; 33]; // quotient estimate (33 limbs max)
-
-    // For efficiency, we only compute the portion that contributes to q
-    // q[i] receives contributions from x[j] * μ[k] where j + k = 64 + i
-    for i in 0..33 {
+    // r < 3N fits in one limb more than N
+    const R_LIMBS: usize = LIMBS + 1;
+
+    // Step 1: Compute the full product x * μ (64 + 33 limbs); q is its
+    // high 33 limbs. Every column contributes carries to the ones above,
+    // so none of the low half can be skipped.
+    let mut xmu = [0u64; 64 + R_LIMBS];
+    for (j, &x_j) in x.iter().enumerate() {
         let mut carry = 0u128;
-        for k in 0..33 {
-            let j = 64 + i - k;
-            if j < 64 {
-                let prod = (x[j] as u128) * (MU_LIMBS[k] as u128) + (q[i] as u128) + carry;
-                q[i] = prod as u64;
-                carry = prod >> 64;
-            }
-        }
-        // Propagate carry to next limb if possible
-        if i + 1 < 33 {
-            q[i + 1] = carry as u64;
+        for (k, &mu_k) in MU_LIMBS.iter().enumerate() {
+            let prod = (x_j as u128) * (mu_k as u128) + (xmu[j + k] as u128) + carry;
+            xmu[j + k] = prod as u64;
+            carry = prod >> 64;
         }
+        xmu[j + R_LIMBS] = carry as u64;
     }
+    let q = &xmu[64..];
 
-    // Step 2: Compute r = x - q * N
-    // q has up to 33 limbs, N has 32 limbs, so q*N has up to 65 limbs
-    // But we only care about the low 64 limbs (since x is 64 limbs)
-    let mut qn = [0u64; 64]; // q * N
-    for i in 0..33 {
-        if q[i] == 0 {
-            continue;
-        }
+    // Step 2: Compute r = x - q * N. The true r is below 3N < 2^(64 * 33),
+    // so it is exact to work modulo 2^(64 * 33) and drop higher limbs.
+    let mut qn = [0u64; R_LIMBS];
+    for (i, &q_i) in q.iter().enumerate() {
         let mut carry = 0u128;
-        for j in 0..LIMBS {
-            if i + j >= 64 {
+        for (j, &n_j) in N_LIMBS.iter().enumerate() {
+            if i + j >= R_LIMBS {
                 break;
             }
-            let prod = (q[i] as u128) * (N_LIMBS[j] as u128) + (qn[i + j] as u128) + carry;
+            let prod = (q_i as u128) * (n_j as u128) + (qn[i + j] as u128) + carry;
             qn[i + j] = prod as u64;
             carry = prod >> 64;
         }
-        // Propagate carry
-        let mut k = i + LIMBS;
-        while carry > 0 && k < 64 {
-            let sum = (qn[k] as u128) + carry;
-            qn[k] = sum as u64;
-            carry = sum >> 64;
-            k += 1;
+        if i + LIMBS < R_LIMBS {
+            qn[i + LIMBS] = carry as u64;
         }
     }
-
-    // r = x - qn (mod 2^4096, which is automatic since both are 64 limbs)
-    let mut r = [0u64; 64];
-    let mut borrow = 0u64;
-    for i in 0..64 {
-        let x_val = x[i] as u128;
-        let qn_val = qn[i] as u128;
-        let borrow_val = borrow as u128;
-        let tmp = x_val + (1u128 << 64) - qn_val - borrow_val;
-        r[i] = tmp as u64;
-        borrow = 1 - ((tmp >> 64) as u64);
+    let mut r = [0u64; R_LIMBS];
+    let mut borrow = false;
+    for i in 0..R_LIMBS {
+        let (diff, b1) = x[i].overflowing_sub(qn[i]);
+        let (diff, b2) = diff.overflowing_sub(borrow as u64);
+        r[i] = diff;
+        borrow = b1 || b2;
     }
 
-    // Step 3: Extract low 32 limbs and correct if r >= N
+    // Step 3: Correct the estimate; q is at most 2 short of floor(x / N)
     let mut result = [0u64; LIMBS];
     result.copy_from_slice(&r[..LIMBS]);
-
-    // r might be slightly larger than N (by at most 2*N)
-    // Subtract N at most twice
-    for _ in 0..2 {
-        if bigint_cmp(&result, &N_LIMBS) != core::cmp::Ordering::Less {
-            let (diff, _) = bigint_sub(&result, &N_LIMBS);
-            result = diff;
-        }
+    let mut high = r[LIMBS];
+    while high != 0 || bigint_cmp(&result, &N_LIMBS) != core::cmp::Ordering::Less {
+        let (diff, no_borrow) = bigint_sub(&result, &N_LIMBS);
+        result = diff;
+        high -= u64::from(!no_borrow);
     }
 
     result
@@ -439,14 +419,171 @@ pub fn verify_update(current_root: LatticeRoot, update: &MuscleUpdate) -> bool {
     qr_verify_membership(&alleged_new_root, &challenge, &update.proof)
 }
 
+/// Verify an update against `current_root` and return the resulting root
+///
+/// # Returns
+/// * `Option<LatticeRoot>` - New root, or `None` if the update does not verify
+#[must_use]
+pub fn apply_update(current_root: LatticeRoot, update: &MuscleUpdate) -> Option<LatticeRoot> {
+    if !verify_update(current_root, update) {
+        return None;
+    }
+    let pos = position(&update.muscle_id, update.version);
+    Some(xor_32(&current_root, &commit(&pos, &update.blob)))
+}
+
+/// Recompute the current root by applying `updates` in order from `genesis`
+///
+/// # Returns
+/// * `Option<LatticeRoot>` - Final root, or `None` if any update fails to verify
+#[must_use]
+pub fn fold_updates(genesis: LatticeRoot, updates: &[MuscleUpdate]) -> Option<LatticeRoot> {
+    updates
+        .iter()
+        .try_fold(genesis, |root, update| apply_update(root, update))
+}
+
+/// Leading and trailing blob bytes shown by [`MuscleUpdate::debug_dump`]
+#[cfg(feature = "std")]
+const DUMP_EDGE_BYTES: usize = 16;
+
+/// Lowercase hex encoding for diagnostics
+#[cfg(feature = "std")]
+fn to_hex(bytes: &[u8]) -> std::string::String {
+    use core::fmt::Write;
+
+    let mut out = std::string::String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(out, "{byte:02x}");
+    }
+    out
+}
+
+#[cfg(feature = "std")]
+impl MuscleUpdate {
+    /// Multi-line hex dump for diagnosing `verify_update` failures
+    ///
+    /// Shows the muscle id, version, blob length with its first and last
+    /// bytes, the full proof, and the lattice position and commitment
+    /// recomputed from the update.
+    #[must_use]
+    pub fn debug_dump(&self) -> std::string::String {
+        use core::fmt::Write;
+
+        let pos = position(&self.muscle_id, self.version);
+        let value_hash = commit(&pos, &self.blob);
+        let mut out = std::string::String::from("MuscleUpdate {\n");
+        let _ = writeln!(out, "  muscle_id: {}", to_hex(&self.muscle_id));
+        let _ = writeln!(out, "  version:   {}", self.version);
+        let _ = writeln!(out, "  blob_len:  {}", self.blob.len());
+        let _ = writeln!(
+            out,
+            "  blob_head: {}",
+            to_hex(&self.blob[..DUMP_EDGE_BYTES])
+        );
+        let _ = writeln!(
+            out,
+            "  blob_tail: {}",
+            to_hex(&self.blob[MAX_BLOB - DUMP_EDGE_BYTES..])
+        );
+        let _ = writeln!(out, "  proof:     {}", to_hex(&self.proof));
+        let _ = writeln!(out, "  position:  {}", to_hex(&pos));
+        let _ = writeln!(out, "  commit:    {}", to_hex(&value_hash));
+        out.push('}');
+        out
+    }
+}
+
 #[cfg(feature = "std")]
 impl std::fmt::Display for MuscleUpdate {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
             "MuscleUpdate(id: {}, version: {})",
-            hex::encode(self.muscle_id),
+            to_hex(&self.muscle_id),
             self.version
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+    use std::vec::Vec;
+
+    /// Randomized cases per property; shrinking runs on top of these
+    const CASES: u32 = 10_000;
+
+    fn modulus() -> BigUint {
+        BigUint::from_bytes_be(&N)
+    }
+
+    fn limbs_to_biguint(limbs: &BigInt) -> BigUint {
+        BigUint::from_bytes_be(&store_be_bytes(limbs))
+    }
+
+    /// Left-pad a reference result to the 256-byte width `square_mod_n` emits
+    fn to_fixed_be(n: &BigUint) -> Vec<u8> {
+        let bytes = n.to_bytes_be();
+        let mut out = std::vec![0u8; 256 - bytes.len()];
+        out.extend_from_slice(&bytes);
+        out
+    }
+
+    /// Little-endian limbs of any width as a reference integer
+    fn le_limbs_to_biguint(limbs: &[u64]) -> BigUint {
+        limbs
+            .iter()
+            .rev()
+            .fold(BigUint::from(0u8), |acc, &limb| (acc << 64u32) + limb)
+    }
+
+    #[test]
+    fn n_limbs_match_published_modulus() {
+        assert_eq!(le_limbs_to_biguint(&N_LIMBS), modulus());
+        assert_eq!(load_be_bytes(&N), N_LIMBS);
+        assert_eq!(store_be_bytes(&load_be_bytes(&N)), N);
+    }
+
+    #[test]
+    fn mu_limbs_is_barrett_quotient_of_n() {
+        let mu = (BigUint::from(1u8) << 4096u32) / le_limbs_to_biguint(&N_LIMBS);
+
+        assert_eq!(le_limbs_to_biguint(&MU_LIMBS), mu);
+    }
+
+    #[test]
+    fn square_mod_n_handles_leading_zero_bytes() {
+        for leading in [1, 8, 16, 31] {
+            let mut x = [0xffu8; 32];
+            x[..leading].fill(0);
+            let expanded: Vec<u8> = x.iter().copied().cycle().take(256).collect();
+            let base = BigUint::from_bytes_be(&expanded);
+            let expected = (&base * &base) % modulus();
+
+            assert_eq!(square_mod_n(&x).to_vec(), to_fixed_be(&expected));
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+        #[test]
+        fn prop_square_mod_n_matches_reference(x in prop::array::uniform32(any::<u8>())) {
+            let expanded: Vec<u8> = x.iter().copied().cycle().take(256).collect();
+            let base = BigUint::from_bytes_be(&expanded);
+            let expected = (&base * &base) % modulus();
+
+            prop_assert_eq!(square_mod_n(&x).to_vec(), to_fixed_be(&expected));
+        }
+
+        #[test]
+        fn prop_mod_n_matches_reference(limbs in prop::array::uniform32(any::<u64>())) {
+            let expected = limbs_to_biguint(&limbs) % modulus();
+
+            prop_assert_eq!(limbs_to_biguint(&mod_n(limbs)), expected);
+        }
+    }
+}
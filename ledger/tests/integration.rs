@@ -12,6 +12,45 @@ fn test_basic_update_cycle() {
     assert!(verify_update(root, &update));
 }
 
+#[test]
+fn test_fold_updates_matches_incremental_apply() {
+    let mut root = GENESIS_ROOT;
+    let mut updates = Vec::new();
+    for version in 1..=4u64 {
+        let blob = [version as u8; MAX_BLOB];
+        let update = generate_update([0x11; 32], version, blob, root);
+        root = apply_update(root, &update).expect("update verifies against its base root");
+        updates.push(update);
+    }
+
+    assert_ne!(root, GENESIS_ROOT);
+    assert_eq!(fold_updates(GENESIS_ROOT, &updates), Some(root));
+    assert_eq!(fold_updates(GENESIS_ROOT, &[]), Some(GENESIS_ROOT));
+
+    // Out of order the proofs no longer verify
+    updates.swap(1, 2);
+    assert_eq!(fold_updates(GENESIS_ROOT, &updates), None);
+}
+
+#[test]
+fn test_debug_dump_renders_hex() {
+    let root = [0u8; 32];
+    let mut blob = [0x5Au8; MAX_BLOB];
+    blob[MAX_BLOB - 1] = 0xC3;
+    let update = generate_update([0xEA; 32], 7, blob, root);
+
+    let dump = update.debug_dump();
+    assert!(dump.contains(&format!("muscle_id: {}", "ea".repeat(32))));
+    assert!(dump.contains("version:   7"));
+    assert!(dump.contains("blob_len:  8256"));
+    assert!(dump.contains(&format!("blob_head: {}", "5a".repeat(16))));
+    assert!(dump.contains(&format!("blob_tail: {}c3", "5a".repeat(15))));
+    let proof_hex: String = update.proof.iter().map(|b| format!("{b:02x}")).collect();
+    assert!(dump.contains(&format!("proof:     {proof_hex}")));
+    // Position is the id followed by the little-endian version
+    assert!(dump.contains(&format!("position:  {}0700000000000000", "ea".repeat(32))));
+}
+
 #[test]
 fn test_version_rollback_prevention() {
     let root = [0u8; 32];
@@ -55,9 +55,11 @@ fn append_validate_checkpoint_and_receipts_across_channels(
         policy: ChannelPolicy {
             min_signers: 1,
             allowed_signers: vec![signer_alpha.verifying_key().to_bytes()],
+            signer_weights: Vec::new(),
             require_attestations: true,
             enforce_timestamp_ordering: true,
         },
+        schemas: Default::default(),
     });
     registry.upsert(ChannelSpec {
         name: "beta".into(),
@@ -67,9 +69,11 @@ fn append_validate_checkpoint_and_receipts_across_channels(
                 signer_beta_one.verifying_key().to_bytes(),
                 signer_beta_two.verifying_key().to_bytes(),
             ],
+            signer_weights: Vec::new(),
             require_attestations: false,
             enforce_timestamp_ordering: true,
         },
+        schemas: Default::default(),
     });
 
     let log = AppendLog::new();
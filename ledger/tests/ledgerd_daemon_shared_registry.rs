@@ -20,6 +20,7 @@ fn daemon_and_cli_share_log_with_registry_enforcement() -> Result<(), Box<dyn st
     let registry = vec![ChannelSpec {
         name: "ipc_demo".into(),
         policy: ChannelPolicy::default(),
+        schemas: Default::default(),
     }];
     let mut reg_file = File::create(&registry_path)?;
     reg_file.write_all(serde_json::to_string(&registry)?.as_bytes())?;
@@ -19,6 +19,7 @@ fn daemon_append_and_read_share_ipc_log() -> Result<(), Box<dyn std::error::Erro
     let registry = vec![ChannelSpec {
         name: "ipc_demo".into(),
         policy: ChannelPolicy::default(),
+        schemas: Default::default(),
     }];
     let mut reg_file = File::create(&registry_path)?;
     reg_file.write_all(serde_json::to_string(&registry)?.as_bytes())?;
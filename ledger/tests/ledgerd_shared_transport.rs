@@ -19,6 +19,7 @@ fn env_configured_daemon_serves_shared_log() -> Result<(), Box<dyn std::error::E
     let registry = vec![ChannelSpec {
         name: "ipc_demo".into(),
         policy: ChannelPolicy::default(),
+        schemas: Default::default(),
     }];
     let mut reg_file = File::create(&registry_path)?;
     reg_file.write_all(serde_json::to_string(&registry)?.as_bytes())?;
@@ -26,6 +26,7 @@ fn metrics_and_health_endpoints_respond() -> Result<(), Box<dyn std::error::Erro
     let registry = vec![ChannelSpec {
         name: "ipc_demo".into(),
         policy: ChannelPolicy::default(),
+        schemas: Default::default(),
     }];
     let mut reg_file = File::create(&registry_path)?;
     reg_file.write_all(serde_json::to_string(&registry)?.as_bytes())?;
@@ -2,12 +2,14 @@
 //! mailbox bridge for enclaves/accelerators, and loopback for single-VM paths.
 #![deny(missing_docs)]
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::net::SocketAddr;
+use std::ops::Range;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, PoisonError};
 use std::task::{Context, Poll};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use futures::StreamExt;
@@ -20,13 +22,17 @@ use tokio::sync::broadcast;
 use tokio::sync::broadcast::{Receiver, Sender};
 use tokio::sync::Mutex;
 use tokio::task::JoinHandle;
-use tokio_stream::wrappers::BroadcastStream;
 use tonic::{transport::Server, Request, Response, Status};
 use tower::service_fn;
-use tracing::{info, warn};
-
-use ledger_core::{AppendLogStorage, PersistentAppendLog};
-use ledger_spec::{hash_attestation_statement, ChannelRegistry, Envelope};
+use tracing::{info, warn, Instrument};
+
+use ledger_core::{
+    checkpoint_covers, AppendError, AppendLog, AppendLogStorage, Checkpoint, LedgerInstance,
+    PersistentAppendLog,
+};
+use ledger_spec::{
+    envelope_hash, hash_attestation_statement, ChannelRegistry, ChannelState, Envelope,
+};
 use quinn::{ClientConfig, Endpoint, RecvStream, SendStream, ServerConfig};
 use rcgen::generate_simple_self_signed;
 use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName as RustlsServerName};
@@ -39,8 +45,114 @@ pub mod proto {
     tonic::include_proto!("ledger.transport");
 }
 
-/// Transport error.
-pub type TransportResult<T> = Result<T, anyhow::Error>;
+/// Transport failure kinds, so callers can tell retryable failures apart.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum TransportError {
+    /// Subscriber queue is full; the envelope was not published.
+    #[error("backpressure: subscriber queue is full")]
+    Backpressure,
+    /// Attestation evidence was missing or did not match expectations.
+    #[error("attestation denied: {0}")]
+    AttestationDenied(String),
+    /// A frame, message, or field could not be decoded.
+    #[error("decode error: {0}")]
+    Decode(String),
+    /// The underlying connection failed or was lost.
+    #[error("connection error: {0}")]
+    Connection(String),
+    /// Every mailbox slot is occupied.
+    #[error("mailbox buffer full")]
+    MailboxFull,
+    /// Envelope is larger than the adapter accepts.
+    #[error("envelope exceeds mailbox slot: {got} > {max} bytes")]
+    SizeExceeded {
+        /// Serialized envelope size in bytes.
+        got: usize,
+        /// Maximum accepted size in bytes.
+        max: usize,
+    },
+    /// Envelopes offered for ingest are not the log the checkpoint covers.
+    #[error("ingest does not match checkpoint at length {length}")]
+    CheckpointMismatch {
+        /// Log length the checkpoint was taken at.
+        length: usize,
+    },
+    /// The adapter faces untrusted peers and only accepts validated input.
+    #[error("pre-verified trust is not allowed on the {0} adapter")]
+    PreVerifiedDenied(&'static str),
+    /// Local and remote capabilities have no protocol version or adapter in common.
+    #[error(transparent)]
+    Negotiation(#[from] NegotiationError),
+    /// Any other failure (storage, validation, TLS setup).
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl From<std::io::Error> for TransportError {
+    fn from(err: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+        match err.kind() {
+            ErrorKind::ConnectionRefused
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::TimedOut
+            | ErrorKind::UnexpectedEof => Self::Connection(err.to_string()),
+            ErrorKind::InvalidData => Self::Decode(err.to_string()),
+            _ => Self::Other(err.into()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for TransportError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
+
+impl From<bincode::Error> for TransportError {
+    fn from(err: bincode::Error) -> Self {
+        Self::Decode(err.to_string())
+    }
+}
+
+impl From<ledger_core::AppendError> for TransportError {
+    fn from(err: ledger_core::AppendError) -> Self {
+        Self::Other(err.into())
+    }
+}
+
+/// Map a gRPC status onto the matching transport failure kind.
+fn status_error(status: Status) -> TransportError {
+    match status.code() {
+        tonic::Code::ResourceExhausted => TransportError::Backpressure,
+        tonic::Code::PermissionDenied | tonic::Code::Unauthenticated => {
+            TransportError::AttestationDenied(status.message().to_string())
+        }
+        tonic::Code::Unavailable | tonic::Code::Cancelled | tonic::Code::Aborted => {
+            TransportError::Connection(status.message().to_string())
+        }
+        _ => TransportError::Other(anyhow::anyhow!(status.to_string())),
+    }
+}
+
+/// Transport result.
+pub type TransportResult<T> = Result<T, TransportError>;
+
+/// How far an adapter trusts envelopes re-ingested from another log.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustLevel {
+    /// Every envelope is validated against channel policy.
+    #[default]
+    Validated,
+    /// Ingested envelopes come from an already-validated log. Once the batch
+    /// matches its checkpoint only chain linkage is checked per envelope.
+    /// Only in-process adapters accept this level.
+    PreVerified,
+}
 
 /// Transport trait for append/read/subscribe semantics.
 #[async_trait]
@@ -51,10 +163,125 @@ pub trait Transport: Send + Sync {
     async fn read(&self, offset: usize, limit: usize) -> TransportResult<Vec<Envelope>>;
     /// Subscribe to new envelopes (broadcast).
     async fn subscribe(&self) -> TransportResult<Receiver<Envelope>>;
+    /// Replay `envs`, the log `checkpoint` was taken over, and return how
+    /// many were appended. The batch is checked against the checkpoint once
+    /// up front; each envelope is then appended as by [`Transport::append`].
+    /// The default implementation has no log to read an instance from and
+    /// checks under the default [`LedgerInstance`]; log-backed adapters use
+    /// their log's instance.
+    async fn ingest(&self, envs: Vec<Envelope>, checkpoint: &Checkpoint) -> TransportResult<usize> {
+        ensure_checkpoint(&envs, checkpoint, &LedgerInstance::default())?;
+        let count = envs.len();
+        for env in envs {
+            self.append(env).await?;
+        }
+        Ok(count)
+    }
+}
+
+fn ensure_checkpoint(
+    envs: &[Envelope],
+    checkpoint: &Checkpoint,
+    instance: &LedgerInstance,
+) -> TransportResult<()> {
+    if checkpoint_covers(envs, checkpoint, instance) {
+        Ok(())
+    } else {
+        Err(TransportError::CheckpointMismatch {
+            length: checkpoint.length,
+        })
+    }
+}
+
+/// Log inspection for adapters backed by a local append log.
+pub trait LogInspect: Transport {
+    /// Number of envelopes currently in the log.
+    fn log_len(&self) -> usize;
+    /// Hash of the last appended envelope, if any.
+    fn tail_hash(&self) -> Option<ledger_spec::Hash>;
+    /// Hash of the last envelope appended to `channel`, i.e. the `prev` for
+    /// that channel's next envelope.
+    fn channel_head(&self, channel: &str) -> Option<ledger_spec::Hash>;
+    /// Sequence number the next appended envelope will be published with.
+    fn next_sequence(&self) -> u64 {
+        self.log_len() as u64
+    }
+    /// Subscribe to new envelopes tagged with their sequence numbers.
+    ///
+    /// Events a slow subscriber misses are dropped rather than applying
+    /// backpressure, leaving a gap in the sequence the subscriber can close
+    /// with a catch-up [`Transport::read`].
+    fn subscribe_sequenced(&self) -> Receiver<SequencedEnvelope>;
+}
+
+/// Broadcast event tagged with its per-transport sequence number.
+///
+/// The sequence is the envelope's index in the transport's log, so it
+/// doubles as the `read` offset for catching up after a gap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequencedEnvelope {
+    /// Position of the envelope in the transport's log.
+    pub seq: u64,
+    /// Published envelope.
+    pub envelope: Envelope,
+}
+
+impl SequencedEnvelope {
+    /// Sequence numbers skipped between `last_seen` and this event.
+    ///
+    /// An empty range means nothing was missed; otherwise the subscriber
+    /// should read from `range.start` for `range.end - range.start` entries
+    /// to catch up.
+    pub fn missed_since(&self, last_seen: Option<u64>) -> Range<u64> {
+        let expected = last_seen.map_or(0, |seq| seq + 1);
+        expected.min(self.seq)..self.seq
+    }
+}
+
+/// Item delivered by a lag-aware subscription.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubscriptionEvent {
+    /// Published envelope.
+    Envelope(Envelope),
+    /// Envelopes were dropped because the subscriber fell behind; resync
+    /// via `read` before relying on the stream again.
+    Lagged {
+        /// Number of envelopes skipped.
+        skipped: u64,
+    },
 }
 
 const DEFAULT_QUEUE_DEPTH: usize = 1024;
 
+/// How often a lag-aware subscription retries reporting pending lag while
+/// its receiver's queue is full.
+const LAG_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Default bound on a single client RPC.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Transport protocol version spoken by this crate's adapters.
+pub const PROTOCOL_VERSION: &str = "1.0.x";
+
+/// Run a client RPC, failing with a connection timeout once `timeout` elapses.
+async fn with_timeout<T>(
+    timeout: Option<Duration>,
+    fut: impl std::future::Future<Output = TransportResult<T>>,
+) -> TransportResult<T> {
+    match timeout {
+        Some(limit) => tokio::time::timeout(limit, fut)
+            .await
+            .map_err(|_| TransportError::Connection("timeout".into()))?,
+        None => fut.await,
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64)
+}
+
 fn temp_log_dir(label: &str) -> PathBuf {
     let mut path = std::env::temp_dir();
     let nanos = SystemTime::now()
@@ -71,9 +298,162 @@ fn default_persistent_log(label: &str) -> TransportResult<Arc<dyn AppendLogStora
     Ok(Arc::new(log))
 }
 
+/// Channel registry shared between a transport and whoever configured it.
+///
+/// Clones share one registry, so a policy swapped in with
+/// [`SharedRegistry::replace`] (e.g. on a daemon's SIGHUP reload) applies to
+/// the next append on every transport built from it.
+#[derive(Debug, Clone, Default)]
+pub struct SharedRegistry(Arc<std::sync::RwLock<ChannelRegistry>>);
+
+impl SharedRegistry {
+    /// Share `registry`.
+    pub fn new(registry: ChannelRegistry) -> Self {
+        Self(Arc::new(std::sync::RwLock::new(registry)))
+    }
+
+    /// Current registry. Hold the guard only for the append it validates.
+    pub fn read(&self) -> std::sync::RwLockReadGuard<'_, ChannelRegistry> {
+        self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Swap in `registry` for every holder of this handle.
+    pub fn replace(&self, registry: ChannelRegistry) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = registry;
+    }
+}
+
+impl From<ChannelRegistry> for SharedRegistry {
+    fn from(registry: ChannelRegistry) -> Self {
+        Self::new(registry)
+    }
+}
+
+/// Per-channel chain heads for a log that interleaves several channels.
+///
+/// `prev` links envelopes within a channel, so each append is validated
+/// against its own channel's head instead of the shared log tail; an
+/// envelope whose `prev` names another channel's envelope is rejected.
+#[derive(Debug, Clone, Default)]
+struct ChannelHeads(Arc<std::sync::Mutex<HashMap<String, ChannelState>>>);
+
+impl ChannelHeads {
+    /// Recover each channel's head from an existing log.
+    fn from_log(log: &dyn AppendLogStorage) -> Self {
+        let mut heads = HashMap::new();
+        log.for_each(0, log.len(), &mut |_, env| {
+            heads.insert(env.header.channel.clone(), chain_state_after(env));
+        });
+        Self(Arc::new(std::sync::Mutex::new(heads)))
+    }
+
+    fn head(&self, channel: &str) -> Option<ledger_spec::Hash> {
+        let heads = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        heads.get(channel).and_then(|state| state.last_hash)
+    }
+
+    /// Append `env` onto its channel's chain. Its `prev` must already name
+    /// the channel head: the signature covers the header, so a missing or
+    /// stale `prev` is rejected with `ChainMismatch` for the client to
+    /// re-sign rather than patched here. Returns the log index and the
+    /// envelope as stored.
+    fn append(
+        &self,
+        log: &dyn AppendLogStorage,
+        env: Envelope,
+        registry: &ChannelRegistry,
+    ) -> TransportResult<(usize, Envelope)> {
+        self.append_via(env, |env, head| {
+            log.append_on_chain_with_index(env, registry, head)
+        })
+    }
+
+    /// Like [`Self::append`] for an envelope validated before it reached
+    /// this node; only its linkage onto the channel head is checked.
+    fn append_pre_verified(
+        &self,
+        log: &dyn AppendLogStorage,
+        env: Envelope,
+    ) -> TransportResult<(usize, Envelope)> {
+        self.append_via(env, |env, head| log.append_pre_verified_on_chain(env, head))
+    }
+
+    fn append_via(
+        &self,
+        env: Envelope,
+        append: impl FnOnce(Envelope, &ChannelState) -> Result<usize, AppendError>,
+    ) -> TransportResult<(usize, Envelope)> {
+        let mut heads = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let head = heads.get(&env.header.channel).cloned().unwrap_or_default();
+        let index = append(env.clone(), &head)?;
+        tracing::Span::current().record("offset", index);
+        heads.insert(env.header.channel.clone(), chain_state_after(&env));
+        Ok((index, env))
+    }
+}
+
+fn chain_state_after(env: &Envelope) -> ChannelState {
+    ChannelState {
+        last_hash: Some(envelope_hash(env)),
+        last_timestamp: Some(env.header.timestamp),
+    }
+}
+
+/// Source of the ids that tie a subscriber's log lines together.
+static NEXT_SUBSCRIBER_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Span for one append. `offset` is filled in once the log assigns it, and
+/// `runtime_id` by adapters that know the peer's attestation.
+fn append_span(transport: &'static str, env: &Envelope) -> tracing::Span {
+    tracing::span!(
+        tracing::Level::INFO,
+        "append",
+        transport,
+        channel = %env.header.channel,
+        envelope_hash = %to_hex(&envelope_hash(env)),
+        offset = tracing::field::Empty,
+        runtime_id = tracing::field::Empty,
+    )
+}
+
+fn read_span(transport: &'static str, offset: usize, limit: usize) -> tracing::Span {
+    tracing::span!(
+        tracing::Level::INFO,
+        "read",
+        transport,
+        offset,
+        limit,
+        runtime_id = tracing::field::Empty,
+    )
+}
+
+/// Span for one subscriber, kept open by whatever delivers its events.
+fn subscribe_span(transport: &'static str) -> tracing::Span {
+    tracing::span!(
+        tracing::Level::INFO,
+        "subscribe",
+        transport,
+        subscriber_id = NEXT_SUBSCRIBER_ID.fetch_add(1, Ordering::Relaxed),
+        runtime_id = tracing::field::Empty,
+    )
+}
+
+/// Runtime id from the attestation a gRPC peer presented, if any.
+fn presented_runtime_id(handshake: &Option<proto::Handshake>) -> Option<String> {
+    let presented = handshake.as_ref()?.presented.clone()?;
+    match attestation_from_proto(presented).ok()?.statement {
+        ledger_spec::AttestationKind::Runtime { runtime_id, .. } => Some(runtime_id),
+        _ => None,
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 fn publish_event(tx: &Sender<Envelope>, queue_depth: usize, env: Envelope) -> TransportResult<()> {
     if tx.len() >= queue_depth {
-        anyhow::bail!("backpressure: subscriber queue is full");
+        return Err(TransportError::Backpressure);
     }
     // Ignore errors when there are no subscribers - the message is simply dropped.
     // This is expected behavior for a broadcast channel in pub/sub patterns.
@@ -81,6 +461,92 @@ fn publish_event(tx: &Sender<Envelope>, queue_depth: usize, env: Envelope) -> Tr
     Ok(())
 }
 
+/// Plain subscribers of a log-owning adapter, each with its own queue.
+///
+/// Every subscriber keeps the span it was opened under, so delivery and
+/// backpressure events are recorded against its `subscriber_id`.
+#[derive(Clone)]
+struct Subscribers {
+    transport: &'static str,
+    queue_depth: usize,
+    queues: Arc<std::sync::Mutex<Vec<(Sender<Envelope>, tracing::Span)>>>,
+}
+
+impl Subscribers {
+    fn new(transport: &'static str, queue_depth: usize) -> Self {
+        Self {
+            transport,
+            queue_depth,
+            queues: Arc::default(),
+        }
+    }
+
+    /// Open a queue for a new subscriber.
+    fn subscribe(&self) -> Receiver<Envelope> {
+        let span = subscribe_span(self.transport);
+        let (tx, rx) = broadcast::channel(self.queue_depth);
+        span.in_scope(|| tracing::debug!("subscriber attached"));
+        let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+        queues.push((tx, span));
+        rx
+    }
+
+    /// Deliver `env` to every subscriber still listening. If any of their
+    /// queues is full nothing is delivered and the append sees
+    /// [`TransportError::Backpressure`].
+    fn publish(&self, env: Envelope) -> TransportResult<()> {
+        let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+        queues.retain(|(tx, _)| tx.receiver_count() > 0);
+        if let Some((tx, span)) = queues.iter().find(|(tx, _)| tx.len() >= self.queue_depth) {
+            span.in_scope(|| {
+                warn!(
+                    queued = tx.len(),
+                    "subscriber queue full; applying backpressure"
+                )
+            });
+            return Err(TransportError::Backpressure);
+        }
+        for (tx, span) in queues.iter() {
+            let _ = tx.send(env.clone());
+            span.in_scope(|| tracing::trace!("envelope delivered"));
+        }
+        Ok(())
+    }
+}
+
+/// Report `skipped` envelopes to a lag-aware subscriber if its queue has
+/// room, resetting the count once sent.
+///
+/// Returns `false` once the subscriber's receiver has been dropped.
+fn flush_lag(tx: &Sender<SubscriptionEvent>, queue_depth: usize, skipped: &mut u64) -> bool {
+    if *skipped > 0 && tx.len() < queue_depth {
+        if tx
+            .send(SubscriptionEvent::Lagged { skipped: *skipped })
+            .is_err()
+        {
+            return false;
+        }
+        *skipped = 0;
+    }
+    true
+}
+
+fn publish_sequenced(
+    tx: &Sender<SequencedEnvelope>,
+    queue_depth: usize,
+    seq: usize,
+    envelope: &Envelope,
+) {
+    // Sequenced subscribers detect drops themselves, so a full queue skips
+    // the event instead of failing the append.
+    if tx.len() < queue_depth {
+        let _ = tx.send(SequencedEnvelope {
+            seq: seq as u64,
+            envelope: envelope.clone(),
+        });
+    }
+}
+
 /// Logical domain that publishes capability advertisements.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TransportDomain {
@@ -124,6 +590,25 @@ pub enum AdapterKind {
     EnclaveProxy,
 }
 
+impl AdapterKind {
+    /// Short adapter name for logs and errors.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AdapterKind::Loopback => "loopback",
+            AdapterKind::QuicGrpc { .. } => "quic_grpc",
+            AdapterKind::Mailbox { .. } => "mailbox",
+            AdapterKind::UnixIpc { .. } => "unix_ipc",
+            AdapterKind::EnclaveProxy => "enclave_proxy",
+        }
+    }
+
+    /// Whether the adapter only ever carries envelopes from its own process,
+    /// the one case where [`TrustLevel::PreVerified`] is allowed.
+    pub fn is_in_process(&self) -> bool {
+        matches!(self, AdapterKind::Loopback)
+    }
+}
+
 /// Attestation handshake parameters enforced per adapter.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AttestationHandshake {
@@ -139,13 +624,25 @@ pub struct AttestationHandshake {
 }
 
 impl AttestationHandshake {
-    /// Verify that the presented attestation satisfies expectations.
+    /// Verify that the presented attestation satisfies expectations as of
+    /// the current time.
     pub fn verify(&self) -> TransportResult<()> {
+        self.verify_at(now_millis())
+    }
+
+    /// Verify like [`Self::verify`], treating `now` (milliseconds) as the
+    /// current time when checking attestation expiry.
+    pub fn verify_at(&self, now: u64) -> TransportResult<()> {
         if let Some(att) = &self.presented {
+            att.statement
+                .check_expiry(now)
+                .map_err(|err| TransportError::AttestationDenied(err.to_string()))?;
             let computed = hash_attestation_statement(&att.statement);
             if let Some(expected) = &self.expected_statement_hash {
                 if expected != &computed {
-                    anyhow::bail!("attestation statement hash mismatch");
+                    return Err(TransportError::AttestationDenied(
+                        "attestation statement hash mismatch".into(),
+                    ));
                 }
             }
             if let (
@@ -154,7 +651,9 @@ impl AttestationHandshake {
             ) = (&self.expected_runtime_id, &att.statement)
             {
                 if runtime_id != expected_runtime {
-                    anyhow::bail!("attestation runtime id mismatch");
+                    return Err(TransportError::AttestationDenied(
+                        "attestation runtime id mismatch".into(),
+                    ));
                 }
             }
         }
@@ -164,7 +663,10 @@ impl AttestationHandshake {
 
 fn hash_from_vec(bytes: &[u8]) -> TransportResult<ledger_spec::Hash> {
     if bytes.len() != 32 {
-        anyhow::bail!("expected 32 byte hash, got {}", bytes.len());
+        return Err(TransportError::Decode(format!(
+            "expected 32 byte hash, got {}",
+            bytes.len()
+        )));
     }
     let mut hash = [0u8; 32];
     hash.copy_from_slice(bytes);
@@ -173,7 +675,10 @@ fn hash_from_vec(bytes: &[u8]) -> TransportResult<ledger_spec::Hash> {
 
 fn signature_from_vec(bytes: &[u8]) -> TransportResult<ledger_spec::SignatureBytes> {
     if bytes.len() != 64 {
-        anyhow::bail!("expected 64 byte signature, got {}", bytes.len());
+        return Err(TransportError::Decode(format!(
+            "expected 64 byte signature, got {}",
+            bytes.len()
+        )));
     }
     let mut sig = [0u8; 64];
     sig.copy_from_slice(bytes);
@@ -184,7 +689,7 @@ fn attestation_from_proto(att: proto::Attestation) -> TransportResult<ledger_spe
     let statement = match att
         .statement
         .and_then(|s| s.kind)
-        .ok_or_else(|| anyhow::anyhow!("attestation statement missing"))?
+        .ok_or_else(|| TransportError::Decode("attestation statement missing".into()))?
     {
         proto::attestation_kind::Kind::Build(b) => ledger_spec::AttestationKind::Build {
             artifact_hash: hash_from_vec(&b.artifact_hash)?,
@@ -257,10 +762,10 @@ fn attestation_to_proto(att: &ledger_spec::Attestation) -> proto::Attestation {
 fn envelope_from_proto(env: proto::Envelope) -> TransportResult<Envelope> {
     let header = env
         .header
-        .ok_or_else(|| anyhow::anyhow!("envelope header missing"))?;
+        .ok_or_else(|| TransportError::Decode("envelope header missing".into()))?;
     let body = env
         .body
-        .ok_or_else(|| anyhow::anyhow!("envelope body missing"))?;
+        .ok_or_else(|| TransportError::Decode("envelope body missing".into()))?;
     let payload: serde_json::Value = serde_json::from_str(&body.payload_json)?;
     let prev = if header.prev.is_empty() {
         None
@@ -399,6 +904,30 @@ fn envelope_to_proto(env: &Envelope) -> TransportResult<proto::Envelope> {
     })
 }
 
+fn subscription_event_from_proto(
+    event: proto::SubscribeEvent,
+) -> TransportResult<SubscriptionEvent> {
+    match event.event {
+        Some(proto::subscribe_event::Event::Envelope(env)) => {
+            Ok(SubscriptionEvent::Envelope(envelope_from_proto(env)?))
+        }
+        Some(proto::subscribe_event::Event::Lagged(lagged)) => Ok(SubscriptionEvent::Lagged {
+            skipped: lagged.skipped,
+        }),
+        None => Err(TransportError::Decode("missing subscribe event".into())),
+    }
+}
+
+/// Subscribe event reporting `skipped` envelopes a subscriber missed.
+fn lagged_event(skipped: u64) -> proto::SubscribeEvent {
+    proto::SubscribeEvent {
+        event: Some(proto::subscribe_event::Event::Lagged(proto::Lagged {
+            skipped,
+        })),
+        seq: 0,
+    }
+}
+
 fn handshake_from_proto(
     handshake: Option<proto::Handshake>,
 ) -> TransportResult<Option<AttestationHandshake>> {
@@ -455,7 +984,9 @@ fn verify_with_expected(
     if handshake.presented.is_none()
         && (handshake.expected_runtime_id.is_some() || handshake.expected_statement_hash.is_some())
     {
-        anyhow::bail!("attestation required but not provided");
+        return Err(TransportError::AttestationDenied(
+            "attestation required but not provided".into(),
+        ));
     }
     handshake.verify()
 }
@@ -477,7 +1008,7 @@ impl QuicHandshakeFrame {
     fn decode(&self) -> TransportResult<proto::Handshake> {
         use prost::Message;
         proto::Handshake::decode(self.handshake_bytes.as_slice())
-            .map_err(|e| anyhow::anyhow!("failed to decode handshake: {}", e))
+            .map_err(|e| TransportError::Decode(format!("failed to decode handshake: {}", e)))
     }
 }
 
@@ -488,7 +1019,8 @@ enum QuicHandshakeResponse {
 }
 
 fn quic_server_config(alpn: Option<String>) -> TransportResult<(ServerConfig, Vec<u8>)> {
-    let cert = generate_simple_self_signed(vec!["localhost".into()])?;
+    let cert =
+        generate_simple_self_signed(vec!["localhost".into()]).map_err(anyhow::Error::from)?;
     let cert_der = cert.cert.der().to_vec();
     let key_der = cert.key_pair.serialize_der();
     let cert_chain = vec![CertificateDer::from(cert_der.clone())];
@@ -496,10 +1028,12 @@ fn quic_server_config(alpn: Option<String>) -> TransportResult<(ServerConfig, Ve
         .map_err(|e| anyhow::anyhow!("invalid private key: {:?}", e))?;
     let mut tls_config = rustls::ServerConfig::builder()
         .with_no_client_auth()
-        .with_single_cert(cert_chain, private_key)?;
+        .with_single_cert(cert_chain, private_key)
+        .map_err(anyhow::Error::from)?;
     tls_config.alpn_protocols = vec![alpn.unwrap_or_else(|| "h2".into()).into_bytes()];
     let mut server_config = ServerConfig::with_crypto(Arc::new(
-        quinn::crypto::rustls::QuicServerConfig::try_from(tls_config)?,
+        quinn::crypto::rustls::QuicServerConfig::try_from(tls_config)
+            .map_err(anyhow::Error::from)?,
     ));
     let mut transport_config = quinn::TransportConfig::default();
     transport_config.keep_alive_interval(Some(std::time::Duration::from_secs(5)));
@@ -562,7 +1096,9 @@ fn quic_client_config(
 ) -> TransportResult<ClientConfig> {
     let mut tls = if let Some(der) = cert_der {
         let mut roots = RootCertStore::empty();
-        roots.add(CertificateDer::from(der))?;
+        roots
+            .add(CertificateDer::from(der))
+            .map_err(anyhow::Error::from)?;
         RustlsClientConfig::builder()
             .with_root_certificates(roots)
             .with_no_client_auth()
@@ -573,7 +1109,8 @@ fn quic_client_config(
             .with_no_client_auth()
     };
     tls.alpn_protocols = vec![alpn.unwrap_or_else(|| "h2".into()).into_bytes()];
-    let quic_config = quinn::crypto::rustls::QuicClientConfig::try_from(tls)?;
+    let quic_config =
+        quinn::crypto::rustls::QuicClientConfig::try_from(tls).map_err(anyhow::Error::from)?;
     Ok(ClientConfig::new(Arc::new(quic_config)))
 }
 
@@ -599,6 +1136,9 @@ async fn server_verify_quic_handshake(
     let verify_res = verify_with_expected(expected, provided);
     let resp = match &verify_res {
         Ok(_) => QuicHandshakeResponse::Ok,
+        Err(TransportError::AttestationDenied(reason)) => {
+            QuicHandshakeResponse::Error(reason.clone())
+        }
         Err(err) => QuicHandshakeResponse::Error(err.to_string()),
     };
     let resp_bytes = bincode::serialize(&resp)?;
@@ -614,7 +1154,10 @@ async fn client_send_quic_handshake(
     if let Some(hs) = handshake {
         hs.verify()?;
     }
-    let (mut send, mut recv) = connection.open_bi().await?;
+    let (mut send, mut recv) = connection
+        .open_bi()
+        .await
+        .map_err(|e| TransportError::Connection(e.to_string()))?;
     let frame = QuicHandshakeFrame::new(proto_handshake_or_default(handshake)?);
     let bytes = bincode::serialize(&frame)?;
     write_len_prefixed(&mut send, &bytes).await?;
@@ -623,7 +1166,7 @@ async fn client_send_quic_handshake(
     let _ = send.finish();
     match resp {
         QuicHandshakeResponse::Ok => Ok(()),
-        QuicHandshakeResponse::Error(err) => anyhow::bail!(err),
+        QuicHandshakeResponse::Error(err) => Err(TransportError::AttestationDenied(err)),
     }
 }
 
@@ -638,6 +1181,16 @@ pub struct AdapterCapability {
     /// Optional attestation handshake requirements.
     #[serde(default)]
     pub attestation: Option<AttestationHandshake>,
+    /// Broadcast queue depth for the adapter; the default when unset.
+    #[serde(default)]
+    pub queue_depth: Option<usize>,
+}
+
+impl AdapterCapability {
+    /// Queue depth the adapter is bound with.
+    pub fn effective_queue_depth(&self) -> usize {
+        self.queue_depth.unwrap_or(DEFAULT_QUEUE_DEPTH)
+    }
 }
 
 /// Capability advertisement for a node.
@@ -658,31 +1211,129 @@ impl CapabilityAdvertisement {
     pub fn loopback(domain: TransportDomain) -> Self {
         Self {
             domain,
-            supported_versions: vec!["1.0.x".into()],
+            supported_versions: vec![PROTOCOL_VERSION.into()],
             max_message_bytes: 1_048_576,
             adapters: vec![AdapterCapability {
                 adapter: AdapterKind::Loopback,
                 features: vec!["inproc".into(), "latency-opt".into()],
                 attestation: None,
+                queue_depth: None,
             }],
         }
     }
 }
 
+/// Why two capability advertisements could not agree on a transport.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum NegotiationError {
+    /// A `supported_versions` entry is not a version pattern.
+    #[error("invalid protocol version pattern {0:?}")]
+    InvalidVersion(String),
+    /// No local version range overlaps a remote one.
+    #[error("no common protocol version: local {local:?}, remote {remote:?}")]
+    VersionMismatch {
+        /// Versions advertised locally.
+        local: Vec<String>,
+        /// Versions advertised by the peer.
+        remote: Vec<String>,
+    },
+    /// The two sides advertise no adapter kind in common.
+    #[error("no common adapter: local {local:?}, remote {remote:?}")]
+    AdapterMismatch {
+        /// Adapter kinds advertised locally.
+        local: Vec<&'static str>,
+        /// Adapter kinds advertised by the peer.
+        remote: Vec<&'static str>,
+    },
+}
+
+/// Parse a `supported_versions` entry, `MAJOR[.MINOR[.PATCH]]`, into its
+/// components. `x` or `*` and omitted trailing components match any value.
+fn version_pattern(raw: &str) -> Result<[Option<u64>; 3], NegotiationError> {
+    let invalid = || NegotiationError::InvalidVersion(raw.to_string());
+    let parts: Vec<&str> = raw.trim().split('.').collect();
+    if parts.len() > 3 {
+        return Err(invalid());
+    }
+    let mut pattern = [None; 3];
+    for (slot, part) in pattern.iter_mut().zip(parts) {
+        *slot = match part {
+            "x" | "X" | "*" => None,
+            digits => Some(digits.parse().map_err(|_| invalid())?),
+        };
+    }
+    Ok(pattern)
+}
+
+/// Agree on a transport with a peer before binding one.
+///
+/// Succeeds when some local version range overlaps a remote one and both
+/// sides advertise a common adapter kind. Returns the local entry for the
+/// first such kind in local preference order, as it carries this node's
+/// endpoint and settings.
+pub fn negotiate(
+    local: &CapabilityAdvertisement,
+    remote: &CapabilityAdvertisement,
+) -> Result<AdapterCapability, NegotiationError> {
+    let patterns = |advert: &CapabilityAdvertisement| {
+        advert
+            .supported_versions
+            .iter()
+            .map(|raw| version_pattern(raw))
+            .collect::<Result<Vec<_>, _>>()
+    };
+    let (ours, theirs) = (patterns(local)?, patterns(remote)?);
+    let overlap = ours.iter().any(|a| {
+        theirs.iter().any(|b| {
+            a.iter()
+                .zip(b)
+                .all(|(x, y)| x.is_none() || y.is_none() || x == y)
+        })
+    });
+    if !overlap {
+        return Err(NegotiationError::VersionMismatch {
+            local: local.supported_versions.clone(),
+            remote: remote.supported_versions.clone(),
+        });
+    }
+
+    let kinds = |advert: &CapabilityAdvertisement| -> Vec<&'static str> {
+        advert
+            .adapters
+            .iter()
+            .map(|cap| cap.adapter.name())
+            .collect()
+    };
+    let remote_kinds = kinds(remote);
+    local
+        .adapters
+        .iter()
+        .find(|cap| remote_kinds.contains(&cap.adapter.name()))
+        .cloned()
+        .ok_or_else(|| NegotiationError::AdapterMismatch {
+            local: kinds(local),
+            remote: remote_kinds,
+        })
+}
+
 /// In-VM queue transport using broadcast + local log.
 #[derive(Clone)]
 pub struct InVmQueue {
     /// Append-only log.
     pub log: Arc<dyn AppendLogStorage>,
-    registry: ChannelRegistry,
-    tx: Sender<Envelope>,
+    registry: SharedRegistry,
+    heads: ChannelHeads,
+    subscribers: Subscribers,
+    sequenced: Sender<SequencedEnvelope>,
     queue_depth: usize,
+    trust: TrustLevel,
 }
 
 impl std::fmt::Debug for InVmQueue {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("InVmQueue")
             .field("queue_depth", &self.queue_depth)
+            .field("trust", &self.trust)
             .finish_non_exhaustive()
     }
 }
@@ -694,7 +1345,7 @@ impl InVmQueue {
     }
 
     /// Create a queue with explicit channel registry (policy enforcement).
-    pub fn with_registry(registry: ChannelRegistry) -> TransportResult<Self> {
+    pub fn with_registry(registry: impl Into<SharedRegistry>) -> TransportResult<Self> {
         let log = default_persistent_log("invm")?;
         Self::with_log(log, registry, DEFAULT_QUEUE_DEPTH)
     }
@@ -702,35 +1353,90 @@ impl InVmQueue {
     /// Create a queue backed by a provided log implementation.
     pub fn with_log(
         log: Arc<dyn AppendLogStorage>,
-        registry: ChannelRegistry,
+        registry: impl Into<SharedRegistry>,
         queue_depth: usize,
     ) -> TransportResult<Self> {
         let depth = queue_depth.max(1);
-        let (tx, _) = broadcast::channel(depth);
+        let (sequenced, _) = broadcast::channel(depth);
         Ok(Self {
+            heads: ChannelHeads::from_log(log.as_ref()),
             log,
-            registry,
-            tx,
+            registry: registry.into(),
+            subscribers: Subscribers::new("in_vm", depth),
+            sequenced,
             queue_depth: depth,
+            trust: TrustLevel::default(),
         })
     }
+
+    /// Trust re-ingested envelopes at `trust`.
+    pub fn with_trust_level(mut self, trust: TrustLevel) -> Self {
+        self.trust = trust;
+        self
+    }
+
+    /// Trust level applied to re-ingested envelopes.
+    pub fn trust_level(&self) -> TrustLevel {
+        self.trust
+    }
 }
 
 #[async_trait]
 impl Transport for InVmQueue {
     async fn append(&self, env: Envelope) -> TransportResult<()> {
-        self.log
-            .append(env.clone(), &self.registry)
-            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
-        publish_event(&self.tx, self.queue_depth, env)
+        let _span = append_span("in_vm", &env).entered();
+        let (seq, env) = self
+            .heads
+            .append(self.log.as_ref(), env, &self.registry.read())?;
+        publish_sequenced(&self.sequenced, self.queue_depth, seq, &env);
+        self.subscribers.publish(env)
     }
 
     async fn read(&self, offset: usize, limit: usize) -> TransportResult<Vec<Envelope>> {
+        let _span = read_span("in_vm", offset, limit).entered();
         Ok(self.log.read(offset, limit))
     }
 
     async fn subscribe(&self) -> TransportResult<Receiver<Envelope>> {
-        Ok(self.tx.subscribe())
+        Ok(self.subscribers.subscribe())
+    }
+
+    async fn ingest(&self, envs: Vec<Envelope>, checkpoint: &Checkpoint) -> TransportResult<usize> {
+        ensure_checkpoint(&envs, checkpoint, &self.log.instance())?;
+        let count = envs.len();
+        for env in envs {
+            let _span = append_span("in_vm", &env).entered();
+            let (seq, env) = match self.trust {
+                TrustLevel::Validated => {
+                    self.heads
+                        .append(self.log.as_ref(), env, &self.registry.read())?
+                }
+                TrustLevel::PreVerified => {
+                    self.heads.append_pre_verified(self.log.as_ref(), env)?
+                }
+            };
+            publish_sequenced(&self.sequenced, self.queue_depth, seq, &env);
+            self.subscribers.publish(env)?;
+        }
+        Ok(count)
+    }
+}
+
+impl LogInspect for InVmQueue {
+    fn log_len(&self) -> usize {
+        self.log.len()
+    }
+
+    fn tail_hash(&self) -> Option<ledger_spec::Hash> {
+        self.log.tail_hash()
+    }
+
+    fn channel_head(&self, channel: &str) -> Option<ledger_spec::Hash> {
+        self.heads.head(channel)
+    }
+
+    fn subscribe_sequenced(&self) -> Receiver<SequencedEnvelope> {
+        self.sequenced.subscribe()
     }
 }
 
@@ -744,7 +1450,7 @@ pub struct Loopback {
 impl Loopback {
     /// Create a loopback adapter with a registry and optional attestation handshake.
     pub fn new(
-        registry: ChannelRegistry,
+        registry: impl Into<SharedRegistry>,
         attestation: Option<AttestationHandshake>,
     ) -> TransportResult<Self> {
         if let Some(handshake) = &attestation {
@@ -755,6 +1461,37 @@ impl Loopback {
             _attestation: attestation,
         })
     }
+
+    /// Create a loopback adapter backed by a provided log implementation.
+    pub fn with_log(
+        registry: impl Into<SharedRegistry>,
+        attestation: Option<AttestationHandshake>,
+        log: Arc<dyn AppendLogStorage>,
+    ) -> TransportResult<Self> {
+        Self::with_queue_depth(registry, attestation, log, DEFAULT_QUEUE_DEPTH)
+    }
+
+    /// Create a loopback adapter with an explicit log and queue depth.
+    pub fn with_queue_depth(
+        registry: impl Into<SharedRegistry>,
+        attestation: Option<AttestationHandshake>,
+        log: Arc<dyn AppendLogStorage>,
+        queue_depth: usize,
+    ) -> TransportResult<Self> {
+        if let Some(handshake) = &attestation {
+            handshake.verify()?;
+        }
+        Ok(Self {
+            queue: InVmQueue::with_log(log, registry, queue_depth)?,
+            _attestation: attestation,
+        })
+    }
+
+    /// Trust re-ingested envelopes at `trust`.
+    pub fn with_trust_level(mut self, trust: TrustLevel) -> Self {
+        self.queue = self.queue.with_trust_level(trust);
+        self
+    }
 }
 
 #[async_trait]
@@ -770,6 +1507,28 @@ impl Transport for Loopback {
     async fn subscribe(&self) -> TransportResult<Receiver<Envelope>> {
         self.queue.subscribe().await
     }
+
+    async fn ingest(&self, envs: Vec<Envelope>, checkpoint: &Checkpoint) -> TransportResult<usize> {
+        self.queue.ingest(envs, checkpoint).await
+    }
+}
+
+impl LogInspect for Loopback {
+    fn log_len(&self) -> usize {
+        self.queue.log_len()
+    }
+
+    fn tail_hash(&self) -> Option<ledger_spec::Hash> {
+        self.queue.tail_hash()
+    }
+
+    fn channel_head(&self, channel: &str) -> Option<ledger_spec::Hash> {
+        self.queue.channel_head(channel)
+    }
+
+    fn subscribe_sequenced(&self) -> Receiver<SequencedEnvelope> {
+        self.queue.subscribe_sequenced()
+    }
 }
 
 /// Unix IPC request/response frames.
@@ -780,6 +1539,13 @@ enum IpcRequest {
     Subscribe,
 }
 
+impl IpcRequest {
+    /// Whether the server can safely act on this request twice.
+    fn is_idempotent(&self) -> bool {
+        !matches!(self, IpcRequest::Append(_))
+    }
+}
+
 /// Server-originated IPC messages.
 #[derive(Debug, Serialize, Deserialize)]
 enum IpcResponse {
@@ -840,16 +1606,19 @@ where
 pub struct UnixIpc {
     listener: UnixListener,
     log: Arc<dyn AppendLogStorage>,
-    broadcast: Sender<Envelope>,
-    registry: ledger_spec::ChannelRegistry,
+    subscribers: Subscribers,
+    sequenced: Sender<SequencedEnvelope>,
+    registry: SharedRegistry,
+    heads: ChannelHeads,
     queue_depth: usize,
+    connections: AtomicUsize,
 }
 
 impl UnixIpc {
     /// Bind a new Unix socket transport.
     pub async fn bind<P: AsRef<Path>>(
         path: P,
-        registry: ledger_spec::ChannelRegistry,
+        registry: impl Into<SharedRegistry>,
     ) -> TransportResult<Self> {
         Self::bind_with_log(
             path,
@@ -863,7 +1632,7 @@ impl UnixIpc {
     /// Bind a Unix socket transport with a provided log.
     pub async fn bind_with_log<P: AsRef<Path>>(
         path: P,
-        registry: ledger_spec::ChannelRegistry,
+        registry: impl Into<SharedRegistry>,
         log: Arc<dyn AppendLogStorage>,
         queue_depth: usize,
     ) -> TransportResult<Self> {
@@ -872,21 +1641,31 @@ impl UnixIpc {
         }
         let listener = UnixListener::bind(path)?;
         let depth = queue_depth.max(1);
-        let (tx, _) = broadcast::channel(depth);
+        let (sequenced, _) = broadcast::channel(depth);
         Ok(Self {
             listener,
+            heads: ChannelHeads::from_log(log.as_ref()),
             log,
-            broadcast: tx,
-            registry,
+            subscribers: Subscribers::new("unix_ipc", depth),
+            sequenced,
+            registry: registry.into(),
             queue_depth: depth,
+            connections: AtomicUsize::new(0),
         })
     }
 
+    /// Number of client connections accepted since the listener started.
+    pub fn connections_accepted(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
     async fn append_env(&self, env: Envelope) -> TransportResult<()> {
-        self.log
-            .append(env.clone(), &self.registry)
-            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
-        publish_event(&self.broadcast, self.queue_depth, env)
+        let _span = append_span("unix_ipc", &env).entered();
+        let (seq, env) = self
+            .heads
+            .append(self.log.as_ref(), env, &self.registry.read())?;
+        publish_sequenced(&self.sequenced, self.queue_depth, seq, &env);
+        self.subscribers.publish(env)
     }
 
     /// Start accepting connections.
@@ -895,6 +1674,7 @@ impl UnixIpc {
             loop {
                 match self.listener.accept().await {
                     Ok((stream, _addr)) => {
+                        self.connections.fetch_add(1, Ordering::Relaxed);
                         info!("unix ipc: client connected");
                         let this = self.clone();
                         tokio::spawn(async move {
@@ -950,43 +1730,68 @@ impl UnixIpc {
                     }
                 }
                 IpcRequest::Subscribe => {
+                    // Attach before snapshotting the log so nothing appended
+                    // in between is missed; sequence numbers drop overlap.
+                    let rx = self.sequenced.subscribe();
+                    let backlog = self.log.read(0, self.log.len());
                     let resp = serialize_frame(&IpcResponse::SubscribeAck)?;
                     if let Err(err) = write_half.lock().await.write_all(&resp).await {
                         warn!("unix ipc subscribe ack error: {err:?}");
                         break;
                     }
-                    let mut rx = self.broadcast.subscribe();
+                    let this = self.clone();
                     let write_half = write_half.clone();
-                    tokio::spawn(async move {
-                        loop {
-                            match rx.recv().await {
-                                Ok(env) => {
-                                    let evt = serialize_frame(&IpcEvent::Envelope(env));
-                                    match evt {
-                                        Ok(bytes) => {
-                                            if let Err(err) = write_half.lock().await.write_all(&bytes).await {
-                                                warn!("unix ipc event send error: {err:?}");
-                                                break;
-                                            }
-                                        }
-                                        Err(err) => {
-                                            warn!("unix ipc event serialize error: {err:?}");
-                                            break;
-                                        }
-                                    }
-                                }
-                                Err(err) => {
-                                    warn!("unix ipc subscriber error: {err:?}");
-                                    break;
-                                }
+                    tokio::spawn(
+                        async move {
+                            if let Err(err) = this.forward_events(rx, backlog, &write_half).await {
+                                warn!("unix ipc subscriber error: {err:?}");
                             }
                         }
-                    });
+                        .instrument(subscribe_span("unix_ipc")),
+                    );
                 }
             }
         }
         Ok(())
     }
+
+    /// Stream `backlog` followed by live events to a subscriber, exactly
+    /// once each and in log order.
+    ///
+    /// Live events already covered by the backlog are skipped, and any the
+    /// sequenced queue dropped are re-read from the log.
+    async fn forward_events(
+        &self,
+        mut rx: Receiver<SequencedEnvelope>,
+        backlog: Vec<Envelope>,
+        write_half: &Mutex<tokio::net::unix::OwnedWriteHalf>,
+    ) -> TransportResult<()> {
+        let mut next = backlog.len();
+        let mut pending = backlog;
+        loop {
+            for env in pending.drain(..) {
+                let bytes = serialize_frame(&IpcEvent::Envelope(env))?;
+                write_half.lock().await.write_all(&bytes).await?;
+            }
+            match rx.recv().await {
+                Ok(event) => {
+                    let seq = event.seq as usize;
+                    if seq < next {
+                        continue;
+                    }
+                    pending.extend(self.log.read(next, seq - next));
+                    pending.push(event.envelope);
+                    next = seq + 1;
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!(skipped, "subscriber lagged; re-reading from the log");
+                    pending = self.log.read(next, self.log.len().saturating_sub(next));
+                    next += pending.len();
+                }
+                Err(broadcast::error::RecvError::Closed) => return Ok(()),
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -996,40 +1801,123 @@ impl Transport for UnixIpc {
     }
 
     async fn read(&self, offset: usize, limit: usize) -> TransportResult<Vec<Envelope>> {
+        let _span = read_span("unix_ipc", offset, limit).entered();
         Ok(self.log.read(offset, limit))
     }
 
     async fn subscribe(&self) -> TransportResult<Receiver<Envelope>> {
-        Ok(self.broadcast.subscribe())
+        Ok(self.subscribers.subscribe())
+    }
+}
+
+impl LogInspect for UnixIpc {
+    fn log_len(&self) -> usize {
+        self.log.len()
+    }
+
+    fn tail_hash(&self) -> Option<ledger_spec::Hash> {
+        self.log.tail_hash()
+    }
+
+    fn channel_head(&self, channel: &str) -> Option<ledger_spec::Hash> {
+        self.heads.head(channel)
+    }
+
+    fn subscribe_sequenced(&self) -> Receiver<SequencedEnvelope> {
+        self.sequenced.subscribe()
     }
 }
 
 /// Unix IPC client transport that talks to a running daemon.
+///
+/// Appends and reads share one persistent connection, serialized by a
+/// mutex so responses pair with their requests; it is re-established only
+/// after an I/O error. Subscriptions open their own long-lived stream.
 #[derive(Debug, Clone)]
 pub struct UnixIpcClient {
     path: String,
-    _registry: ChannelRegistry,
+    conn: Arc<Mutex<Option<UnixStream>>>,
+    timeout: Option<Duration>,
+    _registry: SharedRegistry,
 }
 
 impl UnixIpcClient {
     /// Connect to an existing Unix IPC listener.
-    pub async fn connect(path: String, registry: ChannelRegistry) -> TransportResult<Self> {
-        // Try a simple connection to validate the server is reachable.
-        let _ = UnixStream::connect(&path).await?;
-        Ok(Self {
-            path,
-            _registry: registry,
-        })
+    pub async fn connect(
+        path: String,
+        registry: impl Into<SharedRegistry>,
+    ) -> TransportResult<Self> {
+        Self::connect_with_timeout(path, registry, Some(DEFAULT_REQUEST_TIMEOUT)).await
+    }
+
+    /// Connect with an explicit per-request timeout (`None` waits forever).
+    pub async fn connect_with_timeout(
+        path: String,
+        registry: impl Into<SharedRegistry>,
+        timeout: Option<Duration>,
+    ) -> TransportResult<Self> {
+        let stream = with_timeout(timeout, async { Ok(UnixStream::connect(&path).await?) }).await?;
+        Ok(Self {
+            path,
+            conn: Arc::new(Mutex::new(Some(stream))),
+            timeout,
+            _registry: registry.into(),
+        })
     }
 
     async fn send_request(&self, req: IpcRequest) -> TransportResult<IpcResponse> {
-        let mut stream = UnixStream::connect(&self.path).await?;
         let bytes = serialize_frame(&req)?;
-        stream.write_all(&bytes).await?;
-        let body = read_frame(&mut stream).await?;
+        let mut conn = self.conn.lock().await;
+        let result = with_timeout(self.timeout, async {
+            let reused = conn.is_some();
+            let mut written = false;
+            match self.exchange(&mut conn, &bytes, &mut written).await {
+                // A cached connection may have gone stale (e.g. daemon
+                // restart); retry once on a fresh one. A request the server
+                // may already have acted on is only resent if that is safe.
+                Err(TransportError::Connection(_))
+                    if reused && (!written || req.is_idempotent()) =>
+                {
+                    self.exchange(&mut conn, &bytes, &mut written).await
+                }
+                other => other,
+            }
+        })
+        .await;
+        if result.is_err() {
+            // A timed-out exchange may leave a reply in flight.
+            *conn = None;
+        }
+        let body = result?;
         let resp: IpcResponse = serde_json::from_slice(&body)?;
         Ok(resp)
     }
+
+    /// Write one request frame and read its response over the persistent
+    /// connection, connecting first if needed and dropping it on failure.
+    /// `written` is set once the frame has been handed to the server.
+    async fn exchange(
+        &self,
+        conn: &mut Option<UnixStream>,
+        frame: &[u8],
+        written: &mut bool,
+    ) -> TransportResult<Vec<u8>> {
+        let stream = match conn {
+            Some(stream) => stream,
+            None => conn.insert(UnixStream::connect(&self.path).await?),
+        };
+        let result = match stream.write_all(frame).await {
+            Ok(()) => {
+                *written = true;
+                read_frame(stream).await
+            }
+            Err(err) => Err(err.into()),
+        };
+        if result.is_err() {
+            *conn = None;
+        }
+        result
+    }
 }
 
 #[async_trait]
@@ -1037,8 +1925,8 @@ impl Transport for UnixIpcClient {
     async fn append(&self, env: Envelope) -> TransportResult<()> {
         match self.send_request(IpcRequest::Append(env)).await? {
             IpcResponse::AppendOk => Ok(()),
-            IpcResponse::Error(e) => Err(anyhow::anyhow!(e)),
-            other => Err(anyhow::anyhow!(format!(
+            IpcResponse::Error(e) => Err(anyhow::anyhow!(e).into()),
+            other => Err(TransportError::Decode(format!(
                 "unexpected response for append: {other:?}"
             ))),
         }
@@ -1050,22 +1938,28 @@ impl Transport for UnixIpcClient {
             .await?
         {
             IpcResponse::ReadOk(items) => Ok(items),
-            IpcResponse::Error(e) => Err(anyhow::anyhow!(e)),
-            other => Err(anyhow::anyhow!(format!(
+            IpcResponse::Error(e) => Err(anyhow::anyhow!(e).into()),
+            other => Err(TransportError::Decode(format!(
                 "unexpected response for read: {other:?}"
             ))),
         }
     }
 
     async fn subscribe(&self) -> TransportResult<Receiver<Envelope>> {
-        let mut stream = UnixStream::connect(&self.path).await?;
-        let bytes = serialize_frame(&IpcRequest::Subscribe)?;
-        stream.write_all(&bytes).await?;
-        // Expect an ack
-        let resp_frame = read_frame(&mut stream).await?;
-        let resp: IpcResponse = serde_json::from_slice(&resp_frame)?;
+        let (stream, resp) = with_timeout(self.timeout, async {
+            let mut stream = UnixStream::connect(&self.path).await?;
+            let bytes = serialize_frame(&IpcRequest::Subscribe)?;
+            stream.write_all(&bytes).await?;
+            // Expect an ack
+            let resp_frame = read_frame(&mut stream).await?;
+            let resp: IpcResponse = serde_json::from_slice(&resp_frame)?;
+            Ok((stream, resp))
+        })
+        .await?;
         if !matches!(resp, IpcResponse::SubscribeAck) {
-            anyhow::bail!("unexpected subscribe response: {resp:?}");
+            return Err(TransportError::Decode(format!(
+                "unexpected subscribe response: {resp:?}"
+            )));
         }
 
         let (tx, rx) = broadcast::channel(DEFAULT_QUEUE_DEPTH);
@@ -1100,7 +1994,31 @@ pub struct EnclaveProxyStub;
 impl EnclaveProxyStub {
     /// Placeholder for enclave-bound append.
     pub async fn append(&self, _env: Envelope) -> TransportResult<()> {
-        Err(anyhow::anyhow!("Enclave proxy not implemented"))
+        Err(anyhow::anyhow!("Enclave proxy not implemented").into())
+    }
+}
+
+/// Liveness report returned by a gRPC transport server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransportHealth {
+    /// Whether the server is accepting requests.
+    pub serving: bool,
+    /// Time since the server was spawned, truncated to whole seconds.
+    pub uptime: Duration,
+    /// Number of envelopes in the server's log.
+    pub log_len: usize,
+    /// Protocol version the server speaks.
+    pub protocol_version: String,
+}
+
+impl From<proto::HealthResponse> for TransportHealth {
+    fn from(resp: proto::HealthResponse) -> Self {
+        Self {
+            serving: resp.status() == proto::ServingStatus::Serving,
+            uptime: Duration::from_secs(resp.uptime_secs),
+            log_len: resp.log_len as usize,
+            protocol_version: resp.protocol_version,
+        }
     }
 }
 
@@ -1108,26 +2026,33 @@ impl EnclaveProxyStub {
 struct GrpcTransportService {
     log: Arc<dyn AppendLogStorage>,
     broadcast: Sender<Envelope>,
-    registry: ChannelRegistry,
+    sequenced: Sender<SequencedEnvelope>,
+    registry: SharedRegistry,
+    heads: ChannelHeads,
     _attestation: Option<AttestationHandshake>,
     queue_depth: usize,
+    started: Instant,
 }
 
 impl GrpcTransportService {
     fn new(
         log: Arc<dyn AppendLogStorage>,
-        registry: ChannelRegistry,
+        registry: impl Into<SharedRegistry>,
         attestation: Option<AttestationHandshake>,
         queue_depth: usize,
     ) -> Self {
         let depth = queue_depth.max(1);
         let (tx, _) = broadcast::channel(depth);
+        let (sequenced, _) = broadcast::channel(depth);
         Self {
+            heads: ChannelHeads::from_log(log.as_ref()),
             log,
             broadcast: tx,
-            registry,
+            sequenced,
+            registry: registry.into(),
             _attestation: attestation,
             queue_depth: depth,
+            started: Instant::now(),
         }
     }
 }
@@ -1145,11 +2070,19 @@ impl proto::transport_server::Transport for GrpcTransportService {
         )
         .map_err(|e| Status::invalid_argument(e.to_string()))?;
 
-        self.log
-            .append(env.clone(), &self.registry)
+        let span = append_span("grpc", &env);
+        span.record(
+            "runtime_id",
+            presented_runtime_id(&req.handshake).as_deref(),
+        );
+        let _span = span.entered();
+        let (seq, env) = self
+            .heads
+            .append(self.log.as_ref(), env, &self.registry.read())
             .map_err(|err| Status::invalid_argument(err.to_string()))?;
+        publish_sequenced(&self.sequenced, self.queue_depth, seq, &env);
         publish_event(&self.broadcast, self.queue_depth, env)
-            .map_err(|err| Status::failed_precondition(err.to_string()))?;
+            .map_err(|err| Status::resource_exhausted(err.to_string()))?;
         Ok(Response::new(proto::AppendResponse {}))
     }
 
@@ -1160,55 +2093,108 @@ impl proto::transport_server::Transport for GrpcTransportService {
         request: Request<proto::ReadRequest>,
     ) -> Result<Response<Self::ReadStream>, Status> {
         let req = request.into_inner();
-        let items = self.log.read(req.offset as usize, req.limit as usize);
-        let (tx, rx) = tokio::sync::mpsc::channel(items.len().max(1));
-        for env in items {
-            let proto_env = envelope_to_proto(&env)
-                .map_err(|e| Status::internal(format!("encode envelope: {e}")))?;
-            if tx.send(Ok(proto_env)).await.is_err() {
-                break;
+        let span = read_span("grpc", req.offset as usize, req.limit as usize);
+        span.record(
+            "runtime_id",
+            presented_runtime_id(&req.handshake).as_deref(),
+        );
+        async move {
+            let items = self.log.read(req.offset as usize, req.limit as usize);
+            let (tx, rx) = tokio::sync::mpsc::channel(items.len().max(1));
+            for env in items {
+                let proto_env = envelope_to_proto(&env)
+                    .map_err(|e| Status::internal(format!("encode envelope: {e}")))?;
+                if tx.send(Ok(proto_env)).await.is_err() {
+                    break;
+                }
             }
+            Ok(Response::new(tokio_stream::wrappers::ReceiverStream::new(
+                rx,
+            )))
         }
-        Ok(Response::new(tokio_stream::wrappers::ReceiverStream::new(
-            rx,
-        )))
+        .instrument(span)
+        .await
     }
 
-    type SubscribeStream = tokio_stream::wrappers::ReceiverStream<Result<proto::Envelope, Status>>;
+    type SubscribeStream =
+        tokio_stream::wrappers::ReceiverStream<Result<proto::SubscribeEvent, Status>>;
 
     async fn subscribe(
         &self,
         request: Request<proto::SubscribeRequest>,
     ) -> Result<Response<Self::SubscribeStream>, Status> {
-        let rx = self.broadcast.subscribe();
-        let stream = BroadcastStream::new(rx).filter_map(|res| {
-            futures::future::ready(match res {
-                Ok(env) => match envelope_to_proto(&env) {
-                    Ok(proto) => Some(Ok(proto)),
-                    Err(err) => Some(Err(Status::internal(err.to_string()))),
-                },
-                Err(err) => Some(Err(Status::internal(err.to_string()))),
-            })
-        });
+        let span = subscribe_span("grpc");
+        span.record(
+            "runtime_id",
+            presented_runtime_id(&request.get_ref().handshake).as_deref(),
+        );
+        // Attach before reading the log length so nothing appended in
+        // between is reported as missed.
+        let mut events = self.sequenced.subscribe();
+        let mut next = self.log.len() as u64;
         let (tx, rx) = tokio::sync::mpsc::channel(self.queue_depth);
-        tokio::spawn(async move {
-            tokio::pin!(stream);
-            while let Some(item) = stream.next().await {
-                if tx.send(item).await.is_err() {
-                    break;
+        tokio::spawn(
+            async move {
+                loop {
+                    // A lagging subscriber is told how much it missed rather
+                    // than having the stream fail, whether the events were
+                    // dropped by its receiver or skipped by a full queue.
+                    let items = match events.recv().await {
+                        Ok(event) => {
+                            let missed = event.missed_since(next.checked_sub(1));
+                            next = next.max(event.seq + 1);
+                            let mut items = Vec::with_capacity(2);
+                            if !missed.is_empty() {
+                                items.push(Ok(lagged_event(missed.end - missed.start)));
+                            }
+                            let envelope = envelope_to_proto(&event.envelope)
+                                .map_err(|err| Status::internal(err.to_string()))
+                                .map(|env| proto::SubscribeEvent {
+                                    event: Some(proto::subscribe_event::Event::Envelope(env)),
+                                    seq: event.seq,
+                                });
+                            items.push(envelope);
+                            items
+                        }
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            next += skipped;
+                            vec![Ok(lagged_event(skipped))]
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    };
+                    for item in items {
+                        if tx.send(item).await.is_err() {
+                            return;
+                        }
+                    }
                 }
             }
-        });
+            .instrument(span),
+        );
         Ok(Response::new(tokio_stream::wrappers::ReceiverStream::new(
             rx,
         )))
     }
+
+    /// Liveness probe; attestation was already enforced when the QUIC
+    /// connection was accepted, so no per-request handshake is needed.
+    async fn health(
+        &self,
+        _request: Request<proto::HealthRequest>,
+    ) -> Result<Response<proto::HealthResponse>, Status> {
+        Ok(Response::new(proto::HealthResponse {
+            status: proto::ServingStatus::Serving.into(),
+            uptime_secs: self.started.elapsed().as_secs(),
+            log_len: self.log.len() as u64,
+            protocol_version: PROTOCOL_VERSION.into(),
+        }))
+    }
 }
 
 /// Spawn a gRPC server bound to the provided endpoint (host:port) over QUIC.
 pub async fn spawn_quic_grpc_server(
     endpoint: String,
-    registry: ChannelRegistry,
+    registry: impl Into<SharedRegistry>,
     attestation: Option<AttestationHandshake>,
 ) -> TransportResult<(JoinHandle<()>, std::net::SocketAddr, Vec<u8>)> {
     spawn_quic_grpc_server_with_log(
@@ -1225,13 +2211,13 @@ pub async fn spawn_quic_grpc_server(
 /// Spawn a gRPC server with an explicit log and queue depth over QUIC.
 pub async fn spawn_quic_grpc_server_with_log(
     endpoint: String,
-    registry: ChannelRegistry,
+    registry: impl Into<SharedRegistry>,
     attestation: Option<AttestationHandshake>,
     log: Arc<dyn AppendLogStorage>,
     queue_depth: usize,
     alpn: Option<String>,
 ) -> TransportResult<(JoinHandle<()>, std::net::SocketAddr, Vec<u8>)> {
-    let addr: SocketAddr = endpoint.parse()?;
+    let addr: SocketAddr = endpoint.parse().map_err(anyhow::Error::from)?;
     let (server_config, cert_der) = quic_server_config(alpn.clone())?;
     let endpoint = Endpoint::server(server_config, addr)?;
     let local_addr = endpoint.local_addr()?;
@@ -1323,6 +2309,7 @@ pub struct QuicGrpcAdapter {
     connection: quinn::Connection,
     attestation: Option<AttestationHandshake>,
     queue_depth: usize,
+    timeout: Option<Duration>,
 }
 
 impl std::fmt::Debug for QuicGrpcAdapter {
@@ -1330,6 +2317,7 @@ impl std::fmt::Debug for QuicGrpcAdapter {
         f.debug_struct("QuicGrpcAdapter")
             .field("endpoint", &self.endpoint.local_addr())
             .field("queue_depth", &self.queue_depth)
+            .field("timeout", &self.timeout)
             .finish()
     }
 }
@@ -1351,14 +2339,15 @@ impl QuicGrpcAdapter {
         server_cert: Option<Vec<u8>>,
         alpn: Option<String>,
     ) -> TransportResult<Self> {
-        let server_addr: SocketAddr = endpoint.parse()?;
+        let server_addr: SocketAddr = endpoint.parse().map_err(anyhow::Error::from)?;
         let client_cfg = quic_client_config(server_cert, alpn.clone())?;
-        let mut endpoint = Endpoint::client("[::]:0".parse()?)?;
+        let mut endpoint = Endpoint::client("[::]:0".parse().map_err(anyhow::Error::from)?)?;
         endpoint.set_default_client_config(client_cfg);
         let connection = endpoint
-            .connect(server_addr, "localhost")?
+            .connect(server_addr, "localhost")
+            .map_err(|e| TransportError::Connection(e.to_string()))?
             .await
-            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
+            .map_err(|e| TransportError::Connection(e.to_string()))?;
         if let Err(err) = client_send_quic_handshake(&connection, &attestation).await {
             connection.close(0u32.into(), b"handshake failed");
             return Err(err);
@@ -1377,19 +2366,148 @@ impl QuicGrpcAdapter {
         });
         let channel = tonic::transport::Endpoint::from_static("http://quic.transport")
             .connect_with_connector(connector)
-            .await?;
+            .await
+            .map_err(|e| TransportError::Connection(e.to_string()))?;
         Ok(Self {
             client: proto::transport_client::TransportClient::new(channel),
             endpoint,
             connection: connection.clone(),
             attestation,
             queue_depth: queue_depth.max(1),
+            timeout: Some(DEFAULT_REQUEST_TIMEOUT),
         })
     }
 
+    /// Override the per-request timeout (`None` waits forever).
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
     fn handshake(&self) -> Option<proto::Handshake> {
         handshake_to_proto(&self.attestation)
     }
+
+    async fn open_subscription(&self) -> TransportResult<tonic::Streaming<proto::SubscribeEvent>> {
+        let req = proto::SubscribeRequest {
+            handshake: self.handshake(),
+        };
+        let mut client = self.client.clone();
+        with_timeout(self.timeout, async {
+            Ok(client
+                .subscribe(Request::new(req))
+                .await
+                .map_err(status_error)?
+                .into_inner())
+        })
+        .await
+    }
+
+    /// Subscribe to envelopes, with lag reported in-band.
+    ///
+    /// Envelopes the server or the local queue had to drop are replaced by
+    /// a single [`SubscriptionEvent::Lagged`] carrying the skip count, sent
+    /// as soon as the receiver has room again, whether or not further
+    /// envelopes arrive.
+    pub async fn subscribe_events(&self) -> TransportResult<Receiver<SubscriptionEvent>> {
+        let mut stream = self.open_subscription().await?;
+        let (tx, rx) = broadcast::channel(self.queue_depth);
+        let depth = self.queue_depth;
+        tokio::spawn(async move {
+            let mut skipped = 0u64;
+            let mut flush = tokio::time::interval(LAG_FLUSH_INTERVAL);
+            loop {
+                let msg = tokio::select! {
+                    msg = stream.next() => msg,
+                    _ = flush.tick(), if skipped > 0 => {
+                        if !flush_lag(&tx, depth, &mut skipped) {
+                            break;
+                        }
+                        continue;
+                    }
+                };
+                let Some(msg) = msg else { break };
+                let env = match msg
+                    .map_err(status_error)
+                    .and_then(subscription_event_from_proto)
+                {
+                    Ok(SubscriptionEvent::Envelope(env)) => env,
+                    Ok(SubscriptionEvent::Lagged { skipped: n }) => {
+                        skipped += n;
+                        if !flush_lag(&tx, depth, &mut skipped) {
+                            break;
+                        }
+                        continue;
+                    }
+                    Err(err) => {
+                        warn!("gRPC subscribe stream error: {err:?}");
+                        break;
+                    }
+                };
+                // Stop forwarding once the subscriber has gone away.
+                if !flush_lag(&tx, depth, &mut skipped) {
+                    break;
+                }
+                if skipped == 0 && tx.len() < depth {
+                    if tx.send(SubscriptionEvent::Envelope(env)).is_err() {
+                        break;
+                    }
+                } else {
+                    skipped += 1;
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    /// Subscribe to envelopes tagged with their server log sequence numbers.
+    ///
+    /// As with [`LogInspect::subscribe_sequenced`], events this subscriber
+    /// misses are dropped rather than applying backpressure, leaving a gap
+    /// in the sequence to close with a catch-up [`Transport::read`].
+    pub async fn subscribe_sequenced(&self) -> TransportResult<Receiver<SequencedEnvelope>> {
+        let mut stream = self.open_subscription().await?;
+        let (tx, rx) = broadcast::channel(self.queue_depth);
+        let depth = self.queue_depth;
+        tokio::spawn(async move {
+            while let Some(msg) = stream.next().await {
+                let event = match msg.map_err(status_error) {
+                    Ok(event) => event,
+                    Err(err) => {
+                        warn!("gRPC subscribe stream error: {err:?}");
+                        break;
+                    }
+                };
+                let seq = event.seq;
+                match subscription_event_from_proto(event) {
+                    Ok(SubscriptionEvent::Envelope(env)) => {
+                        publish_sequenced(&tx, depth, seq as usize, &env);
+                    }
+                    // The gap in the sequence already reports the lag.
+                    Ok(SubscriptionEvent::Lagged { .. }) => {}
+                    Err(err) => {
+                        warn!("gRPC subscribe stream error: {err:?}");
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(rx)
+    }
+
+    /// Probe the server's liveness without touching the log.
+    pub async fn health(&self) -> TransportResult<TransportHealth> {
+        let mut client = self.client.clone();
+        with_timeout(self.timeout, async {
+            let resp = client
+                .health(Request::new(proto::HealthRequest {}))
+                .await
+                .map_err(status_error)?
+                .into_inner();
+            Ok(resp.into())
+        })
+        .await
+    }
 }
 
 #[async_trait]
@@ -1399,12 +2517,15 @@ impl Transport for QuicGrpcAdapter {
             envelope: Some(envelope_to_proto(&env)?),
             handshake: self.handshake(),
         };
-        self.client
-            .clone()
-            .append(Request::new(req))
-            .await
-            .map_err(|e| anyhow::anyhow!(e.to_string()))?;
-        Ok(())
+        let mut client = self.client.clone();
+        with_timeout(self.timeout, async {
+            client
+                .append(Request::new(req))
+                .await
+                .map_err(status_error)?;
+            Ok(())
+        })
+        .await
     }
 
     async fn read(&self, offset: usize, limit: usize) -> TransportResult<Vec<Envelope>> {
@@ -1413,49 +2534,47 @@ impl Transport for QuicGrpcAdapter {
             limit: limit as u64,
             handshake: self.handshake(),
         };
-        let mut stream = self
-            .client
-            .clone()
-            .read(Request::new(req))
-            .await
-            .map_err(|e| anyhow::anyhow!(e.to_string()))?
-            .into_inner();
-        let mut out = Vec::new();
-        while let Some(item) = stream.next().await {
-            let env = envelope_from_proto(item.map_err(|e| anyhow::anyhow!(e.to_string()))?)?;
-            out.push(env);
-        }
-        Ok(out)
+        let mut client = self.client.clone();
+        with_timeout(self.timeout, async {
+            let mut stream = client
+                .read(Request::new(req))
+                .await
+                .map_err(status_error)?
+                .into_inner();
+            let mut out = Vec::new();
+            while let Some(item) = stream.next().await {
+                let env = envelope_from_proto(item.map_err(status_error)?)?;
+                out.push(env);
+            }
+            Ok(out)
+        })
+        .await
     }
 
+    /// Subscribe to envelopes without lag reporting.
+    ///
+    /// Lag the server reports is logged and otherwise dropped, and the
+    /// subscription ends if the local queue overflows; use
+    /// [`QuicGrpcAdapter::subscribe_events`] to observe gaps and keep going.
     async fn subscribe(&self) -> TransportResult<Receiver<Envelope>> {
-        let req = proto::SubscribeRequest {
-            handshake: self.handshake(),
-        };
-        let mut stream = self
-            .client
-            .clone()
-            .subscribe(Request::new(req))
-            .await
-            .map_err(|e| anyhow::anyhow!(e.to_string()))?
-            .into_inner();
+        let mut stream = self.open_subscription().await?;
         let (tx, rx) = broadcast::channel(self.queue_depth);
         let depth = self.queue_depth;
         tokio::spawn(async move {
             while let Some(msg) = stream.next().await {
-                match msg {
-                    Ok(env) => match envelope_from_proto(env) {
-                        Ok(env) => {
-                            if let Err(err) = publish_event(&tx, depth, env) {
-                                warn!("gRPC subscribe backpressure: {err:?}");
-                                break;
-                            }
-                        }
-                        Err(err) => {
-                            warn!("gRPC subscribe envelope decode error: {err:?}");
+                match msg
+                    .map_err(status_error)
+                    .and_then(subscription_event_from_proto)
+                {
+                    Ok(SubscriptionEvent::Envelope(env)) => {
+                        if let Err(err) = publish_event(&tx, depth, env) {
+                            warn!("gRPC subscribe backpressure: {err:?}");
                             break;
                         }
-                    },
+                    }
+                    Ok(SubscriptionEvent::Lagged { skipped }) => {
+                        warn!("gRPC subscriber lagged; {skipped} envelopes skipped");
+                    }
                     Err(err) => {
                         warn!("gRPC subscribe stream error: {err:?}");
                         break;
@@ -1474,8 +2593,10 @@ pub struct MailboxTransport {
     slot_bytes: usize,
     slots: usize,
     log: Arc<dyn AppendLogStorage>,
-    broadcast: Sender<Envelope>,
-    registry: ChannelRegistry,
+    subscribers: Subscribers,
+    sequenced: Sender<SequencedEnvelope>,
+    registry: SharedRegistry,
+    heads: ChannelHeads,
     buffer: Arc<Mutex<VecDeque<Envelope>>>,
     _attestation: Option<AttestationHandshake>,
     queue_depth: usize,
@@ -1498,7 +2619,7 @@ impl MailboxTransport {
         mailbox: String,
         slot_bytes: usize,
         slots: usize,
-        registry: ChannelRegistry,
+        registry: impl Into<SharedRegistry>,
         attestation: Option<AttestationHandshake>,
     ) -> TransportResult<Self> {
         let log = default_persistent_log("mailbox")?;
@@ -1518,7 +2639,7 @@ impl MailboxTransport {
         mailbox: String,
         slot_bytes: usize,
         slots: usize,
-        registry: ChannelRegistry,
+        registry: impl Into<SharedRegistry>,
         attestation: Option<AttestationHandshake>,
         log: Arc<dyn AppendLogStorage>,
         queue_depth: usize,
@@ -1527,14 +2648,16 @@ impl MailboxTransport {
             handshake.verify()?;
         }
         let depth = queue_depth.max(1);
-        let (tx, _) = broadcast::channel(depth);
+        let (sequenced, _) = broadcast::channel(depth);
         Ok(Self {
             _mailbox: mailbox,
             slot_bytes,
             slots,
+            heads: ChannelHeads::from_log(log.as_ref()),
             log,
-            broadcast: tx,
-            registry,
+            subscribers: Subscribers::new("mailbox", depth),
+            sequenced,
+            registry: registry.into(),
             buffer: Arc::new(Mutex::new(VecDeque::with_capacity(slots))),
             _attestation: attestation,
             queue_depth: depth,
@@ -1544,11 +2667,10 @@ impl MailboxTransport {
     fn enforce_mailbox_limits(&self, env: &Envelope) -> TransportResult<()> {
         let serialized = bincode::serialize(env)?;
         if serialized.len() > self.slot_bytes {
-            anyhow::bail!(
-                "envelope exceeds mailbox slot: {} > {} bytes",
-                serialized.len(),
-                self.slot_bytes
-            );
+            return Err(TransportError::SizeExceeded {
+                got: serialized.len(),
+                max: self.slot_bytes,
+            });
         }
         Ok(())
     }
@@ -1557,26 +2679,78 @@ impl MailboxTransport {
 #[async_trait]
 impl Transport for MailboxTransport {
     async fn append(&self, env: Envelope) -> TransportResult<()> {
-        self.enforce_mailbox_limits(&env)?;
-        self.log
-            .append(env.clone(), &self.registry)
-            .map_err(|err| anyhow::anyhow!(err.to_string()))?;
-        {
-            let mut buf = self.buffer.lock().await;
-            if buf.len() == self.slots {
-                anyhow::bail!("mailbox buffer full");
+        let span = append_span("mailbox", &env);
+        async move {
+            self.enforce_mailbox_limits(&env)?;
+            let (seq, env) = self
+                .heads
+                .append(self.log.as_ref(), env, &self.registry.read())?;
+            {
+                let mut buf = self.buffer.lock().await;
+                if buf.len() == self.slots {
+                    return Err(TransportError::MailboxFull);
+                }
+                buf.push_back(env.clone());
             }
-            buf.push_back(env.clone());
+            publish_sequenced(&self.sequenced, self.queue_depth, seq, &env);
+            self.subscribers.publish(env)
         }
-        publish_event(&self.broadcast, self.queue_depth, env)
+        .instrument(span)
+        .await
     }
 
     async fn read(&self, offset: usize, limit: usize) -> TransportResult<Vec<Envelope>> {
+        let _span = read_span("mailbox", offset, limit).entered();
         Ok(self.log.read(offset, limit))
     }
 
     async fn subscribe(&self) -> TransportResult<Receiver<Envelope>> {
-        Ok(self.broadcast.subscribe())
+        Ok(self.subscribers.subscribe())
+    }
+}
+
+impl LogInspect for MailboxTransport {
+    fn log_len(&self) -> usize {
+        self.log.len()
+    }
+
+    fn tail_hash(&self) -> Option<ledger_spec::Hash> {
+        self.log.tail_hash()
+    }
+
+    fn channel_head(&self, channel: &str) -> Option<ledger_spec::Hash> {
+        self.heads.head(channel)
+    }
+
+    fn subscribe_sequenced(&self) -> Receiver<SequencedEnvelope> {
+        self.sequenced.subscribe()
+    }
+}
+
+/// Log storage backing a locally bound adapter.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LogBackend {
+    /// Fresh persistent log in a unique directory under the system temp dir.
+    #[default]
+    Temporary,
+    /// In-memory log; contents are lost when the adapter is dropped.
+    Memory,
+    /// Persistent log at a known directory, reopened (and recovered) on bind.
+    Persistent {
+        /// Directory holding the WAL, segments, and metadata.
+        path: PathBuf,
+    },
+}
+
+impl LogBackend {
+    /// Open the log this backend describes; `label` names temporary directories.
+    pub fn open(&self, label: &str) -> TransportResult<Arc<dyn AppendLogStorage>> {
+        match self {
+            LogBackend::Temporary => default_persistent_log(label),
+            LogBackend::Memory => Ok(Arc::new(AppendLog::new())),
+            LogBackend::Persistent { path } => Ok(Arc::new(PersistentAppendLog::open(path)?)),
+        }
     }
 }
 
@@ -1587,6 +2761,18 @@ pub struct TransportConfig {
     pub advertisement: CapabilityAdvertisement,
     /// Adapter selected after negotiation.
     pub selected: AdapterCapability,
+    /// Log backing the adapter when it is served locally. Ignored by client
+    /// adapters whose log lives on the remote end.
+    #[serde(default)]
+    pub log_backend: LogBackend,
+    /// Trust applied to re-ingested envelopes; [`TrustLevel::PreVerified`]
+    /// is refused for any adapter that is not in-process.
+    #[serde(default)]
+    pub trust_level: TrustLevel,
+    /// Peer advertisement, when known. [`bind_transport`] then negotiates
+    /// against it and binds the agreed adapter instead of `selected`.
+    #[serde(default)]
+    pub remote: Option<CapabilityAdvertisement>,
 }
 
 impl TransportConfig {
@@ -1601,8 +2787,29 @@ impl TransportConfig {
         Self {
             advertisement,
             selected,
+            log_backend: LogBackend::default(),
+            trust_level: TrustLevel::default(),
+            remote: None,
         }
     }
+
+    /// Use `log_backend` for locally served adapters.
+    pub fn with_log_backend(mut self, log_backend: LogBackend) -> Self {
+        self.log_backend = log_backend;
+        self
+    }
+
+    /// Trust re-ingested envelopes at `trust_level`.
+    pub fn with_trust_level(mut self, trust_level: TrustLevel) -> Self {
+        self.trust_level = trust_level;
+        self
+    }
+
+    /// Negotiate with `remote` when binding; see [`negotiate`].
+    pub fn with_remote(mut self, remote: CapabilityAdvertisement) -> Self {
+        self.remote = Some(remote);
+        self
+    }
 }
 
 impl From<CapabilityAdvertisement> for ledger_spec::events::TransportCapability {
@@ -1651,6 +2858,7 @@ impl From<AdapterCapability> for ledger_spec::events::TransportAdapterCapability
             adapter: value.adapter.into(),
             features: value.features,
             attestation: value.attestation.map(|a| a.into()),
+            queue_depth: value.queue_depth,
         }
     }
 }
@@ -1668,6 +2876,7 @@ impl TryFrom<ledger_spec::events::TransportAdapterCapability> for AdapterCapabil
                 .attestation
                 .map(AttestationHandshake::try_from)
                 .transpose()?,
+            queue_depth: value.queue_depth,
         })
     }
 }
@@ -1747,26 +2956,36 @@ impl TryFrom<ledger_spec::events::CapabilityAttestation> for AttestationHandshak
 }
 
 /// Bind a concrete transport implementation from configuration.
+///
+/// When the configuration names a remote advertisement, the adapter is
+/// negotiated first and a peer without a common version or adapter is
+/// refused before anything is constructed.
 pub async fn bind_transport(
-    registry: ChannelRegistry,
-    cfg: TransportConfig,
+    registry: impl Into<SharedRegistry>,
+    mut cfg: TransportConfig,
 ) -> TransportResult<Arc<dyn Transport>> {
+    if let Some(remote) = &cfg.remote {
+        cfg.selected = negotiate(&cfg.advertisement, remote)?;
+    }
+    let queue_depth = cfg.selected.effective_queue_depth();
+    if cfg.trust_level == TrustLevel::PreVerified && !cfg.selected.adapter.is_in_process() {
+        return Err(TransportError::PreVerifiedDenied(
+            cfg.selected.adapter.name(),
+        ));
+    }
     match cfg.selected.adapter {
         AdapterKind::Loopback => {
             let att = cfg.selected.attestation;
-            let loopback = Loopback::new(registry, att)?;
+            let log = cfg.log_backend.open("loopback")?;
+            let loopback = Loopback::with_queue_depth(registry, att, log, queue_depth)?
+                .with_trust_level(cfg.trust_level);
             Ok(Arc::new(loopback))
         }
         AdapterKind::QuicGrpc { endpoint, alpn } => {
             let att = cfg.selected.attestation;
-            let adapter = QuicGrpcAdapter::connect_with_queue_depth(
-                endpoint,
-                att,
-                DEFAULT_QUEUE_DEPTH,
-                None,
-                alpn,
-            )
-            .await?;
+            let adapter =
+                QuicGrpcAdapter::connect_with_queue_depth(endpoint, att, queue_depth, None, alpn)
+                    .await?;
             Ok(Arc::new(adapter))
         }
         AdapterKind::Mailbox {
@@ -1775,7 +2994,16 @@ pub async fn bind_transport(
             slots,
         } => {
             let att = cfg.selected.attestation;
-            let adapter = MailboxTransport::new(mailbox, slot_bytes, slots, registry, att)?;
+            let log = cfg.log_backend.open("mailbox")?;
+            let adapter = MailboxTransport::with_log(
+                mailbox,
+                slot_bytes,
+                slots,
+                registry,
+                att,
+                log,
+                queue_depth,
+            )?;
             Ok(Arc::new(adapter))
         }
         AdapterKind::UnixIpc { path } => match UnixStream::connect(&path).await {
@@ -1784,13 +3012,14 @@ pub async fn bind_transport(
                 Ok(Arc::new(client))
             }
             Err(_) => {
-                let ipc = Arc::new(UnixIpc::bind(path, registry).await?);
+                let log = cfg.log_backend.open("unix-ipc")?;
+                let ipc = Arc::new(UnixIpc::bind_with_log(path, registry, log, queue_depth).await?);
                 let _handle = ipc.clone().start();
                 Ok(ipc)
             }
         },
         AdapterKind::EnclaveProxy => {
-            Err(anyhow::anyhow!("enclave proxy adapter not yet implemented"))
+            Err(anyhow::anyhow!("enclave proxy adapter not yet implemented").into())
         }
     }
 }
@@ -1800,12 +3029,21 @@ mod tests {
     use super::*;
     use ed25519_dalek::SigningKey;
     use ledger_core::{signing, AppendLog};
-    use ledger_spec::envelope_hash;
+    use ledger_spec::{envelope_hash, ValidationError};
     use rand_core::OsRng;
     use std::sync::Arc;
-    use tokio::time::{sleep, Duration};
+    use tokio::time::sleep;
 
     fn sample_env(sk: &SigningKey, ts: u64, prev: Option<ledger_spec::Hash>) -> Envelope {
+        channel_env(sk, "muscle_io", ts, prev)
+    }
+
+    fn channel_env(
+        sk: &SigningKey,
+        channel: &str,
+        ts: u64,
+        prev: Option<ledger_spec::Hash>,
+    ) -> Envelope {
         let body = ledger_spec::EnvelopeBody {
             payload: serde_json::json!({"ts": ts}),
             payload_type: Some("test".into()),
@@ -1813,7 +3051,7 @@ mod tests {
         let body_hash = ledger_spec::hash_body(&body);
         let mut env = Envelope {
             header: ledger_spec::EnvelopeHeader {
-                channel: "muscle_io".into(),
+                channel: channel.into(),
                 version: 1,
                 prev,
                 body_hash,
@@ -1906,6 +3144,179 @@ mod tests {
         assert_eq!(out[0].header.timestamp, 1);
     }
 
+    #[tokio::test]
+    async fn bind_loopback_with_persistent_backend_survives_rebind() {
+        let dir = temp_log_dir("loopback-backend");
+        let cfg = TransportConfig::loopback(TransportDomain::Ledger)
+            .with_log_backend(LogBackend::Persistent { path: dir.clone() });
+        let sk = SigningKey::generate(&mut OsRng);
+        let first = sample_env(&sk, 1, None);
+        let second = sample_env(&sk, 2, Some(envelope_hash(&first)));
+
+        let transport = bind_transport(ChannelRegistry::new(), cfg.clone())
+            .await
+            .unwrap();
+        transport.append(first.clone()).await.unwrap();
+        transport.append(second.clone()).await.unwrap();
+        drop(transport);
+        let wal = std::fs::read(dir.join("append.wal")).unwrap();
+        assert!(
+            !wal.is_empty(),
+            "envelopes should land in the configured directory"
+        );
+
+        let rebound = bind_transport(ChannelRegistry::new(), cfg).await.unwrap();
+        let out = rebound.read(0, 10).await.unwrap();
+        assert_eq!(out, vec![first, second.clone()]);
+        rebound
+            .append(sample_env(&sk, 3, Some(envelope_hash(&second))))
+            .await
+            .unwrap();
+        assert_eq!(rebound.read(0, 10).await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unix_ipc_client_reuses_connection() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let path = temp_log_dir("ipc-sock");
+        let server = Arc::new(
+            UnixIpc::bind_with_log(&path, ChannelRegistry::new(), Arc::new(AppendLog::new()), 4)
+                .await
+                .unwrap(),
+        );
+        let _handle = server.clone().start();
+        let client =
+            UnixIpcClient::connect(path.to_string_lossy().into_owned(), ChannelRegistry::new())
+                .await
+                .unwrap();
+
+        let mut prev = None;
+        for ts in 1..=100 {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            client.append(env).await.unwrap();
+        }
+        assert_eq!(client.read(0, 200).await.unwrap().len(), 100);
+        assert_eq!(server.log_len(), 100);
+        assert_eq!(server.connections_accepted(), 1);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn unix_ipc_subscribe_replays_history_then_live_once() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let path = temp_log_dir("ipc-replay");
+        let server = Arc::new(
+            UnixIpc::bind_with_log(&path, ChannelRegistry::new(), Arc::new(AppendLog::new()), 4)
+                .await
+                .unwrap(),
+        );
+        let _handle = server.clone().start();
+        let client =
+            UnixIpcClient::connect(path.to_string_lossy().into_owned(), ChannelRegistry::new())
+                .await
+                .unwrap();
+
+        let mut prev = None;
+        for ts in 1..=2 {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            client.append(env).await.unwrap();
+        }
+        let mut rx = client.subscribe().await.unwrap();
+        for ts in 3..=4 {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            client.append(env).await.unwrap();
+        }
+
+        for ts in 1..=4 {
+            let env = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(env.header.timestamp, ts);
+        }
+        let extra = tokio::time::timeout(Duration::from_millis(100), rx.recv()).await;
+        assert!(extra.is_err(), "unexpected duplicate event");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn unix_ipc_client_resends_only_idempotent_requests() {
+        let path = temp_log_dir("ipc-hangup");
+        let listener = UnixListener::bind(&path).unwrap();
+        let received = Arc::new(AtomicUsize::new(0));
+        let counter = received.clone();
+        let _server = tokio::spawn(async move {
+            // Take each request, then hang up without replying, as a
+            // daemon crashing mid-request would.
+            while let Ok((mut stream, _)) = listener.accept().await {
+                if read_frame(&mut stream).await.is_ok() {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        });
+        let client =
+            UnixIpcClient::connect(path.to_string_lossy().into_owned(), ChannelRegistry::new())
+                .await
+                .unwrap();
+
+        let sk = SigningKey::generate(&mut OsRng);
+        assert!(client.append(sample_env(&sk, 1, None)).await.is_err());
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+
+        // The failed append dropped the connection, so cache a fresh one
+        // before checking that a read is retried on it.
+        *client.conn.lock().await = Some(UnixStream::connect(&path).await.unwrap());
+        assert!(client.read(0, 1).await.is_err());
+        assert_eq!(received.load(Ordering::SeqCst), 3);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn unix_ipc_client_times_out_on_silent_server() {
+        let path = temp_log_dir("ipc-silent");
+        let listener = UnixListener::bind(&path).unwrap();
+        let _server = tokio::spawn(async move {
+            // Accept and hold connections without ever replying.
+            let mut held = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                held.push(stream);
+            }
+        });
+        let timeout = Duration::from_millis(100);
+        let client = UnixIpcClient::connect_with_timeout(
+            path.to_string_lossy().into_owned(),
+            ChannelRegistry::new(),
+            Some(timeout),
+        )
+        .await
+        .unwrap();
+
+        let sk = SigningKey::generate(&mut OsRng);
+        let started = std::time::Instant::now();
+        let err = client.append(sample_env(&sk, 1, None)).await.unwrap_err();
+        let elapsed = started.elapsed();
+        assert!(matches!(err, TransportError::Connection(ref msg) if msg == "timeout"));
+        assert!(elapsed >= timeout);
+        assert!(elapsed < Duration::from_secs(2));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn io_errors_map_by_kind() {
+        use std::io::{Error, ErrorKind};
+        for kind in [ErrorKind::ConnectionReset, ErrorKind::UnexpectedEof] {
+            let err = TransportError::from(Error::new(kind, "peer gone"));
+            assert!(matches!(err, TransportError::Connection(_)));
+        }
+        let err = TransportError::from(Error::new(ErrorKind::InvalidData, "bad frame"));
+        assert!(matches!(err, TransportError::Decode(_)));
+        let err = TransportError::from(Error::new(ErrorKind::PermissionDenied, "log dir"));
+        assert!(matches!(err, TransportError::Other(_)));
+    }
+
     #[test]
     fn advertisement_roundtrip() {
         let cap = CapabilityAdvertisement {
@@ -1920,12 +3331,375 @@ mod tests {
                 },
                 features: vec!["sealed".into()],
                 attestation: None,
+                queue_depth: Some(16),
             }],
         };
         let spec_cap: ledger_spec::events::TransportCapability = cap.clone().into();
         let roundtrip = CapabilityAdvertisement::try_from(spec_cap).unwrap();
         assert_eq!(roundtrip.domain, cap.domain);
-        assert_eq!(roundtrip.adapters.len(), 1);
+        assert_eq!(roundtrip.adapters, cap.adapters);
+    }
+
+    #[tokio::test]
+    async fn bound_adapter_honours_advertised_queue_depth() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let mut cfg = TransportConfig::loopback(TransportDomain::Ledger);
+        cfg.selected.queue_depth = Some(2);
+        cfg.log_backend = LogBackend::Memory;
+        let transport = bind_transport(ChannelRegistry::new(), cfg).await.unwrap();
+        let _rx = transport.subscribe().await.unwrap();
+
+        let mut prev = None;
+        for ts in 1..=2 {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            transport.append(env).await.unwrap();
+        }
+        let err = transport
+            .append(sample_env(&sk, 3, prev))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TransportError::Backpressure));
+    }
+
+    /// A checkpointed log of `len` envelopes on `muscle_io`, plus a registry
+    /// under which none of them would pass policy.
+    fn checkpointed_log(len: u64) -> (Vec<Envelope>, Checkpoint, ChannelRegistry) {
+        checkpointed_log_in(len, LedgerInstance::default())
+    }
+
+    /// [`checkpointed_log`] with the source log bound to `instance`.
+    fn checkpointed_log_in(
+        len: u64,
+        instance: LedgerInstance,
+    ) -> (Vec<Envelope>, Checkpoint, ChannelRegistry) {
+        let sk = SigningKey::generate(&mut OsRng);
+        let source = AppendLog::for_instance(instance);
+        let mut prev = None;
+        for ts in 1..=len {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            source.append(env, &ChannelRegistry::new()).unwrap();
+        }
+        let checkpoint = ledger_core::CheckpointWriter::new()
+            .maybe_checkpoint(&source, len as usize)
+            .unwrap();
+
+        let stranger = SigningKey::generate(&mut OsRng);
+        let mut registry = ChannelRegistry::new();
+        registry.upsert(ledger_spec::ChannelSpec {
+            name: "muscle_io".into(),
+            policy: ledger_spec::ChannelPolicy {
+                min_signers: 1,
+                allowed_signers: vec![stranger.verifying_key().to_bytes()],
+                signer_weights: Vec::new(),
+                require_attestations: false,
+                enforce_timestamp_ordering: true,
+            },
+            schemas: Default::default(),
+        });
+        (source.read(0, len as usize), checkpoint, registry)
+    }
+
+    #[tokio::test]
+    async fn pre_verified_ingest_skips_policy_after_checkpoint_check() {
+        let (envs, checkpoint, registry) = checkpointed_log(4);
+
+        let validated =
+            Loopback::with_log(registry.clone(), None, Arc::new(AppendLog::new())).unwrap();
+        assert!(validated.ingest(envs.clone(), &checkpoint).await.is_err());
+
+        let trusted = Loopback::with_log(registry, None, Arc::new(AppendLog::new()))
+            .unwrap()
+            .with_trust_level(TrustLevel::PreVerified);
+        let err = trusted
+            .ingest(envs[..3].to_vec(), &checkpoint)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TransportError::CheckpointMismatch { length: 4 }
+        ));
+        assert_eq!(trusted.log_len(), 0);
+
+        assert_eq!(trusted.ingest(envs.clone(), &checkpoint).await.unwrap(), 4);
+        assert_eq!(trusted.read(0, 10).await.unwrap(), envs);
+        assert_eq!(
+            trusted.channel_head("muscle_io"),
+            Some(envelope_hash(&envs[3]))
+        );
+    }
+
+    #[tokio::test]
+    async fn pre_verified_ingest_rejects_tampered_body() {
+        let (mut envs, checkpoint, registry) = checkpointed_log(3);
+        // Same header, so the envelope hash and checkpoint still match.
+        envs[1].body.payload = serde_json::json!({"ts": 99});
+        assert!(checkpoint_covers(
+            &envs,
+            &checkpoint,
+            &LedgerInstance::default()
+        ));
+
+        let trusted = Loopback::with_log(registry, None, Arc::new(AppendLog::new()))
+            .unwrap()
+            .with_trust_level(TrustLevel::PreVerified);
+        let err = trusted.ingest(envs, &checkpoint).await.unwrap_err();
+        assert!(err.to_string().contains("body hash mismatch"), "{err}");
+        assert_eq!(trusted.log_len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pre_verified_ingest_uses_the_log_instance() {
+        let instance = LedgerInstance::new("replica-a");
+        let (envs, checkpoint, registry) = checkpointed_log_in(3, instance.clone());
+
+        let foreign = Loopback::with_log(registry.clone(), None, Arc::new(AppendLog::new()))
+            .unwrap()
+            .with_trust_level(TrustLevel::PreVerified);
+        assert!(matches!(
+            foreign.ingest(envs.clone(), &checkpoint).await.unwrap_err(),
+            TransportError::CheckpointMismatch { length: 3 }
+        ));
+
+        let log = Arc::new(AppendLog::for_instance(instance));
+        let trusted = Loopback::with_log(registry, None, log.clone())
+            .unwrap()
+            .with_trust_level(TrustLevel::PreVerified);
+        assert_eq!(trusted.ingest(envs, &checkpoint).await.unwrap(), 3);
+        assert_eq!(log.merkle_root(), Some(checkpoint.root));
+    }
+
+    #[tokio::test]
+    async fn network_adapters_refuse_pre_verified_trust() {
+        let remote = [
+            AdapterKind::QuicGrpc {
+                endpoint: "127.0.0.1:1".into(),
+                alpn: None,
+            },
+            AdapterKind::UnixIpc {
+                path: "/nonexistent/ledger.sock".into(),
+            },
+            AdapterKind::Mailbox {
+                mailbox: "mbox".into(),
+                slot_bytes: 1024,
+                slots: 4,
+            },
+            AdapterKind::EnclaveProxy,
+        ];
+        for adapter in remote {
+            let mut cfg = TransportConfig::loopback(TransportDomain::Ledger)
+                .with_trust_level(TrustLevel::PreVerified);
+            cfg.selected.adapter = adapter.clone();
+            let err = bind_transport(ChannelRegistry::new(), cfg)
+                .await
+                .err()
+                .expect("pre-verified trust must be refused");
+            assert!(
+                matches!(err, TransportError::PreVerifiedDenied(name) if name == adapter.name()),
+                "{adapter:?} accepted pre-verified trust"
+            );
+        }
+
+        let cfg = TransportConfig::loopback(TransportDomain::Ledger)
+            .with_log_backend(LogBackend::Memory)
+            .with_trust_level(TrustLevel::PreVerified);
+        assert!(bind_transport(ChannelRegistry::new(), cfg).await.is_ok());
+    }
+
+    fn advertisement(versions: &[&str], adapters: Vec<AdapterKind>) -> CapabilityAdvertisement {
+        CapabilityAdvertisement {
+            domain: TransportDomain::Ledger,
+            supported_versions: versions.iter().map(|v| v.to_string()).collect(),
+            max_message_bytes: 1_048_576,
+            adapters: adapters
+                .into_iter()
+                .map(|adapter| AdapterCapability {
+                    adapter,
+                    features: Vec::new(),
+                    attestation: None,
+                    queue_depth: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn unix_ipc() -> AdapterKind {
+        AdapterKind::UnixIpc {
+            path: "/nonexistent/ledger.sock".into(),
+        }
+    }
+
+    #[test]
+    fn negotiation_picks_first_shared_adapter_across_overlapping_versions() {
+        let quic = AdapterKind::QuicGrpc {
+            endpoint: "127.0.0.1:1".into(),
+            alpn: None,
+        };
+        let local = advertisement(
+            &["2.x", "1.0.x"],
+            vec![quic, unix_ipc(), AdapterKind::Loopback],
+        );
+        let remote = advertisement(&["1"], vec![AdapterKind::Loopback, unix_ipc()]);
+        let selected = negotiate(&local, &remote).unwrap();
+        assert_eq!(selected, local.adapters[1]);
+
+        let exact = advertisement(&["1.2.3"], vec![AdapterKind::Loopback]);
+        let wildcard = advertisement(&["1.*.3"], vec![AdapterKind::Loopback]);
+        assert!(negotiate(&exact, &wildcard).is_ok());
+    }
+
+    #[tokio::test]
+    async fn negotiation_rejects_version_mismatch() {
+        let local = advertisement(&["2.x"], vec![AdapterKind::Loopback]);
+        let remote = advertisement(&["1.0.x"], vec![AdapterKind::Loopback]);
+        assert_eq!(
+            negotiate(&local, &remote),
+            Err(NegotiationError::VersionMismatch {
+                local: vec!["2.x".into()],
+                remote: vec!["1.0.x".into()],
+            })
+        );
+        assert_eq!(
+            negotiate(
+                &local,
+                &advertisement(&["1.0.beta"], vec![AdapterKind::Loopback])
+            ),
+            Err(NegotiationError::InvalidVersion("1.0.beta".into()))
+        );
+
+        let mut cfg = TransportConfig::loopback(TransportDomain::Ledger)
+            .with_log_backend(LogBackend::Memory)
+            .with_remote(remote);
+        cfg.advertisement = local;
+        let err = bind_transport(ChannelRegistry::new(), cfg)
+            .await
+            .err()
+            .expect("mismatched versions must not bind");
+        assert!(matches!(
+            err,
+            TransportError::Negotiation(NegotiationError::VersionMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn negotiation_rejects_adapter_kind_mismatch() {
+        let local = advertisement(&[PROTOCOL_VERSION], vec![AdapterKind::Loopback]);
+        let remote = advertisement(&[PROTOCOL_VERSION], vec![unix_ipc()]);
+        assert_eq!(
+            negotiate(&local, &remote),
+            Err(NegotiationError::AdapterMismatch {
+                local: vec!["loopback"],
+                remote: vec!["unix_ipc"],
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn log_inspect_tracks_len_and_tail() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let queue =
+            InVmQueue::with_log(Arc::new(AppendLog::new()), ChannelRegistry::new(), 4).unwrap();
+        assert_eq!(queue.log_len(), 0);
+        assert_eq!(queue.tail_hash(), None);
+
+        let first = sample_env(&sk, 1, None);
+        queue.append(first.clone()).await.unwrap();
+        assert_eq!(queue.log_len(), 1);
+        assert_eq!(queue.tail_hash(), Some(envelope_hash(&first)));
+
+        let second = sample_env(&sk, 2, Some(envelope_hash(&first)));
+        queue.append(second.clone()).await.unwrap();
+        assert_eq!(queue.log_len(), 2);
+        assert_eq!(queue.tail_hash(), Some(envelope_hash(&second)));
+
+        let mailbox = MailboxTransport::with_log(
+            "mb0".into(),
+            4096,
+            4,
+            ChannelRegistry::new(),
+            None,
+            Arc::new(AppendLog::new()),
+            4,
+        )
+        .unwrap();
+        mailbox.append(first.clone()).await.unwrap();
+        assert_eq!(mailbox.log_len(), 1);
+        assert_eq!(mailbox.tail_hash(), Some(envelope_hash(&first)));
+    }
+
+    #[tokio::test]
+    async fn interleaved_channels_chain_independently() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let registry = ChannelRegistry::new();
+        let queue = InVmQueue::with_log(Arc::new(AppendLog::new()), registry.clone(), 8).unwrap();
+
+        let a1 = channel_env(&sk, "alpha", 10, None);
+        let b1 = channel_env(&sk, "beta", 1, None);
+        let a2 = channel_env(&sk, "alpha", 11, Some(envelope_hash(&a1)));
+        let b2 = channel_env(&sk, "beta", 2, Some(envelope_hash(&b1)));
+        for env in [&a1, &b1, &a2, &b2] {
+            queue.append(env.clone()).await.unwrap();
+        }
+        assert_eq!(queue.channel_head("alpha"), Some(envelope_hash(&a2)));
+        assert_eq!(queue.channel_head("beta"), Some(envelope_hash(&b2)));
+
+        // Linking onto the other channel's head is rejected.
+        let crossed = channel_env(&sk, "alpha", 12, Some(envelope_hash(&b2)));
+        assert!(queue.append(crossed).await.is_err());
+
+        let log = queue.read(0, 8).await.unwrap();
+        assert_eq!(log.len(), 4);
+        let validator = ledger_core::ReplayValidator::new(registry);
+        for channel in ["alpha", "beta"] {
+            let chain: Vec<_> = log
+                .iter()
+                .filter(|env| env.header.channel == channel)
+                .cloned()
+                .collect();
+            validator.validate_sequence(&chain).unwrap();
+        }
+
+        // A queue reopened over the same log resumes each channel's chain.
+        let reopened = InVmQueue::with_log(queue.log.clone(), ChannelRegistry::new(), 8).unwrap();
+        assert_eq!(reopened.channel_head("beta"), Some(envelope_hash(&b2)));
+    }
+
+    fn is_chain_mismatch(err: &TransportError) -> bool {
+        matches!(
+            err,
+            TransportError::Other(inner) if matches!(
+                inner.downcast_ref::<AppendError>(),
+                Some(AppendError::Validation(ValidationError::ChainMismatch))
+            )
+        )
+    }
+
+    #[tokio::test]
+    async fn missing_or_stale_prev_is_rejected_not_filled() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let queue =
+            InVmQueue::with_log(Arc::new(AppendLog::new()), ChannelRegistry::new(), 8).unwrap();
+        let first = channel_env(&sk, "alpha", 1, None);
+        let second = channel_env(&sk, "alpha", 2, Some(envelope_hash(&first)));
+        queue.append(first.clone()).await.unwrap();
+        queue.append(second.clone()).await.unwrap();
+
+        // Filling `prev` in would change the signed header, so both are
+        // refused and the stored chain is left as it was.
+        let missing = channel_env(&sk, "alpha", 3, None);
+        let stale = channel_env(&sk, "alpha", 3, Some(envelope_hash(&first)));
+        for env in [missing, stale] {
+            let err = queue.append(env).await.unwrap_err();
+            assert!(is_chain_mismatch(&err), "{err:?}");
+        }
+        assert_eq!(queue.log_len(), 2);
+        assert_eq!(queue.channel_head("alpha"), Some(envelope_hash(&second)));
+
+        // Re-signed against the current head, the envelope is accepted as sent.
+        let resigned = channel_env(&sk, "alpha", 3, queue.channel_head("alpha"));
+        queue.append(resigned.clone()).await.unwrap();
+        assert_eq!(queue.read(2, 1).await.unwrap(), vec![resigned]);
     }
 
     #[tokio::test]
@@ -1942,6 +3716,168 @@ mod tests {
             .await
             .unwrap_err();
         assert!(err.to_string().contains("backpressure"));
+        assert!(matches!(err, TransportError::Backpressure));
+    }
+
+    #[tokio::test]
+    async fn sequenced_subscriber_observes_dropped_events() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let queue =
+            InVmQueue::with_log(Arc::new(AppendLog::new()), ChannelRegistry::new(), 2).unwrap();
+        let mut rx = queue.subscribe_sequenced();
+
+        let mut prev = None;
+        for ts in 1..=3 {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            queue.append(env).await.unwrap();
+        }
+        assert_eq!(rx.recv().await.unwrap().seq, 0);
+        assert_eq!(rx.recv().await.unwrap().seq, 1);
+
+        queue.append(sample_env(&sk, 4, prev)).await.unwrap();
+        let event = rx.recv().await.unwrap();
+        assert_eq!(event.seq, 3);
+        assert_eq!(event.missed_since(Some(1)), 2..3);
+        assert_eq!(queue.next_sequence(), 4);
+
+        let missed = event.missed_since(Some(1));
+        let recovered = queue
+            .read(missed.start as usize, (missed.end - missed.start) as usize)
+            .await
+            .unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].header.timestamp, 3);
+    }
+
+    /// Layer that keeps the fields of every span, keyed by span id.
+    #[derive(Clone, Default)]
+    struct SpanCapture(Arc<std::sync::Mutex<HashMap<u64, (String, HashMap<String, String>)>>>);
+
+    struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+    impl tracing::field::Visit for FieldVisitor<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().into(), value.into());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().into(), format!("{value:?}"));
+        }
+    }
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpanCapture {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = HashMap::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            let name = attrs.metadata().name().to_string();
+            self.0.lock().unwrap().insert(id.into_u64(), (name, fields));
+        }
+
+        fn on_record(
+            &self,
+            id: &tracing::span::Id,
+            values: &tracing::span::Record<'_>,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            if let Some((_, fields)) = self.0.lock().unwrap().get_mut(&id.into_u64()) {
+                values.record(&mut FieldVisitor(fields));
+            }
+        }
+    }
+
+    /// Layer that keeps each event's message and the span it was raised in.
+    #[derive(Clone, Default)]
+    struct EventCapture(Arc<std::sync::Mutex<Vec<(String, Option<u64>)>>>);
+
+    impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for EventCapture {
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = HashMap::new();
+            event.record(&mut FieldVisitor(&mut fields));
+            let span = ctx.current_span().id().map(|id| id.into_u64());
+            let message = fields.remove("message").unwrap_or_default();
+            self.0.lock().unwrap().push((message, span));
+        }
+    }
+
+    #[tokio::test]
+    async fn append_span_carries_envelope_context() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let capture = SpanCapture::default();
+        let subscriber = tracing_subscriber::registry().with(capture.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let sk = SigningKey::generate(&mut OsRng);
+        let queue =
+            InVmQueue::with_log(Arc::new(AppendLog::new()), ChannelRegistry::new(), 4).unwrap();
+        let first = channel_env(&sk, "audit", 1, None);
+        let second = channel_env(&sk, "audit", 2, Some(envelope_hash(&first)));
+        let hash: String = envelope_hash(&second)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        queue.append(first).await.unwrap();
+        queue.append(second).await.unwrap();
+
+        let spans = capture.0.lock().unwrap();
+        let (_, fields) = spans
+            .values()
+            .find(|(name, fields)| name == "append" && fields.get("envelope_hash") == Some(&hash))
+            .expect("append span recorded");
+        assert_eq!(fields["transport"], "in_vm");
+        assert_eq!(fields["channel"], "audit");
+        assert_eq!(fields["offset"], "1");
+    }
+
+    #[tokio::test]
+    async fn subscriber_events_carry_subscriber_id() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let spans = SpanCapture::default();
+        let events = EventCapture::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(spans.clone())
+            .with(events.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let sk = SigningKey::generate(&mut OsRng);
+        let queue =
+            InVmQueue::with_log(Arc::new(AppendLog::new()), ChannelRegistry::new(), 1).unwrap();
+        let _rx = queue.subscribe().await.unwrap();
+        let first = sample_env(&sk, 1, None);
+        queue.append(first.clone()).await.unwrap();
+        let err = queue
+            .append(sample_env(&sk, 2, Some(envelope_hash(&first))))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, TransportError::Backpressure));
+
+        let spans = spans.0.lock().unwrap();
+        let events = events.0.lock().unwrap();
+        let subscriber_of = |message: &str| {
+            let (_, span) = events
+                .iter()
+                .find(|(m, _)| m.starts_with(message))
+                .unwrap_or_else(|| panic!("no {message:?} event"));
+            let (name, fields) = &spans[&span.expect("raised inside a span")];
+            assert_eq!(name, "subscribe");
+            assert_eq!(fields["transport"], "in_vm");
+            fields["subscriber_id"].clone()
+        };
+        assert_eq!(
+            subscriber_of("envelope delivered"),
+            subscriber_of("subscriber queue full")
+        );
     }
 
     #[tokio::test]
@@ -1958,6 +3894,24 @@ mod tests {
             .await
             .unwrap_err();
         assert!(err.to_string().contains("buffer full"));
+        assert!(matches!(err, TransportError::MailboxFull));
+    }
+
+    #[tokio::test]
+    async fn mailbox_rejects_oversized_envelope() {
+        let sk = SigningKey::generate(&mut OsRng);
+        let log = Arc::new(AppendLog::new());
+        let mailbox =
+            MailboxTransport::with_log("mb0".into(), 16, 4, ChannelRegistry::new(), None, log, 4)
+                .unwrap();
+        let err = mailbox.append(sample_env(&sk, 1, None)).await.unwrap_err();
+        match err {
+            TransportError::SizeExceeded { got, max } => {
+                assert_eq!(max, 16);
+                assert!(got > max);
+            }
+            other => panic!("expected SizeExceeded, got {other:?}"),
+        }
     }
 
     #[tokio::test]
@@ -2059,6 +4013,143 @@ mod tests {
         handle.abort();
     }
 
+    #[tokio::test]
+    async fn quic_grpc_slow_subscriber_observes_lag() {
+        let registry = ChannelRegistry::new();
+        let (handle, addr, cert_der) = spawn_quic_grpc_server_with_log(
+            "127.0.0.1:0".into(),
+            registry.clone(),
+            None,
+            Arc::new(AppendLog::new()),
+            DEFAULT_QUEUE_DEPTH,
+            None,
+        )
+        .await
+        .unwrap();
+        let adapter = QuicGrpcAdapter::connect_with_queue_depth(
+            format!("{}", addr),
+            None,
+            4,
+            Some(cert_der.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+        let mut rx = adapter.subscribe_events().await.unwrap();
+
+        // Append far more than the subscriber's queue holds without reading.
+        let sk = SigningKey::generate(&mut OsRng);
+        let mut prev = None;
+        for ts in 1..=10 {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            adapter.append(env).await.unwrap();
+        }
+        sleep(Duration::from_millis(200)).await;
+        // The lag is reported once there is room, without waiting for
+        // another envelope to arrive.
+        let mut delivered = 0;
+        let skipped = loop {
+            match tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .unwrap()
+                .unwrap()
+            {
+                SubscriptionEvent::Envelope(_) => delivered += 1,
+                SubscriptionEvent::Lagged { skipped } => break skipped,
+            }
+        };
+        assert!(skipped > 0);
+        assert_eq!(delivered + skipped, 10);
+
+        adapter.append(sample_env(&sk, 11, prev)).await.unwrap();
+        match rx.recv().await.unwrap() {
+            SubscriptionEvent::Envelope(env) => assert_eq!(env.header.timestamp, 11),
+            other => panic!("expected envelope, got {other:?}"),
+        }
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn quic_grpc_subscription_carries_log_sequence() {
+        let (handle, addr, cert_der) = spawn_quic_grpc_server_with_log(
+            "127.0.0.1:0".into(),
+            ChannelRegistry::new(),
+            None,
+            Arc::new(AppendLog::new()),
+            DEFAULT_QUEUE_DEPTH,
+            None,
+        )
+        .await
+        .unwrap();
+        let adapter = QuicGrpcAdapter::connect_with_queue_depth(
+            format!("{}", addr),
+            None,
+            DEFAULT_QUEUE_DEPTH,
+            Some(cert_der.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+        let sk = SigningKey::generate(&mut OsRng);
+        let first = sample_env(&sk, 1, None);
+        adapter.append(first.clone()).await.unwrap();
+
+        let mut rx = adapter.subscribe_sequenced().await.unwrap();
+        let mut prev = Some(envelope_hash(&first));
+        for ts in 2..=3 {
+            let env = sample_env(&sk, ts, prev);
+            prev = Some(envelope_hash(&env));
+            adapter.append(env).await.unwrap();
+        }
+        let second = rx.recv().await.unwrap();
+        assert_eq!((second.seq, second.envelope.header.timestamp), (1, 2));
+        let third = rx.recv().await.unwrap();
+        assert_eq!(third.seq, 2);
+        assert!(third.missed_since(Some(second.seq)).is_empty());
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn quic_grpc_health_reports_log_len() {
+        let registry = ChannelRegistry::new();
+        let (handle, addr, cert_der) = spawn_quic_grpc_server_with_log(
+            "127.0.0.1:0".into(),
+            registry.clone(),
+            None,
+            default_persistent_log("quic-health").unwrap(),
+            DEFAULT_QUEUE_DEPTH,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let adapter = QuicGrpcAdapter::connect_with_queue_depth(
+            format!("{}", addr),
+            None,
+            DEFAULT_QUEUE_DEPTH,
+            Some(cert_der.clone()),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let health = adapter.health().await.unwrap();
+        assert!(health.serving);
+        assert_eq!(health.log_len, 0);
+        assert_eq!(health.protocol_version, PROTOCOL_VERSION);
+
+        let sk = SigningKey::generate(&mut OsRng);
+        let first = sample_env(&sk, 1, None);
+        adapter.append(first.clone()).await.unwrap();
+        adapter
+            .append(sample_env(&sk, 2, Some(envelope_hash(&first))))
+            .await
+            .unwrap();
+        assert_eq!(adapter.health().await.unwrap().log_len, 2);
+        handle.abort();
+    }
+
     #[tokio::test]
     async fn quic_grpc_attestation_rejects_mismatch() {
         let registry = ChannelRegistry::new();
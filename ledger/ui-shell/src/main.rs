@@ -133,7 +133,7 @@ async fn submit(path: PathBuf) -> Result<()> {
         payload: payload.clone(),
         payload_type: Some("ui-command".into()),
     };
-    let body_hash = ledger_spec::hash_body(&body);
+    let body_hash = ledger_spec::hash_body_for(&body, 1);
     let env = Envelope {
         header: ledger_spec::EnvelopeHeader {
             channel: "ui_commands".into(),
@@ -241,9 +241,11 @@ fn default_registry() -> ChannelRegistry {
         policy: ChannelPolicy {
             min_signers: 0,
             allowed_signers: vec![],
+            signer_weights: Vec::new(),
             require_attestations: false,
             enforce_timestamp_ordering: true,
         },
+        schemas: Default::default(),
     });
     registry
 }
@@ -369,10 +371,13 @@ mod tests {
                 channel: "ui_commands".into(),
                 version: 1,
                 prev: None,
-                body_hash: ledger_spec::hash_body(&EnvelopeBody {
-                    payload: body.clone(),
-                    payload_type: Some("ui-command".into()),
-                }),
+                body_hash: ledger_spec::hash_body_for(
+                    &EnvelopeBody {
+                        payload: body.clone(),
+                        payload_type: Some("ui-command".into()),
+                    },
+                    1,
+                ),
                 timestamp: 1,
             },
             body: EnvelopeBody {
@@ -0,0 +1,76 @@
+// muscle-compiler/src/codegen/disasm.rs
+// Eä AArch64 Disassembly Listing — `--emit-asm` debugging output
+
+use std::fmt::Write;
+
+/// AArch64 NOP instruction encoding, used to pad Nucleus images
+const AARCH64_NOP: u32 = 0xD503201F;
+
+/// Disassemble AArch64 machine code into a listing annotated with `labels`
+/// (name, byte offset). Trailing NOP padding is collapsed into one line and
+/// words that don't decode (e.g. the data section) are shown as `.inst`.
+pub fn disassemble(code: &[u8], labels: &[(String, usize)]) -> String {
+    let words: Vec<u32> = code
+        .chunks_exact(4)
+        .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    let body_len = words
+        .iter()
+        .rposition(|&word| word != AARCH64_NOP)
+        .map_or(0, |last| last + 1);
+
+    let mut labels: Vec<_> = labels.iter().collect();
+    labels.sort_by_key(|(_, offset)| *offset);
+    let mut labels = labels.into_iter().peekable();
+
+    let mut listing = String::new();
+    for (index, &word) in words[..body_len].iter().enumerate() {
+        let offset = index * 4;
+        while let Some((name, _)) = labels.next_if(|(_, at)| *at <= offset) {
+            let _ = writeln!(listing, "{}:", name);
+        }
+        let text = match bad64::decode(word, offset as u64) {
+            Ok(instruction) => instruction.to_string(),
+            Err(_) => format!(".inst 0x{:08x}", word),
+        };
+        let _ = writeln!(listing, "  {:#06x}:  {:08x}  {}", offset, word, text);
+    }
+    for (name, _) in labels {
+        let _ = writeln!(listing, "{}:", name);
+    }
+    if body_len < words.len() {
+        let _ = writeln!(
+            listing,
+            "  {:#06x}:  {:08x}  nop  ; x{} padding",
+            body_len * 4,
+            AARCH64_NOP,
+            words.len() - body_len
+        );
+    }
+    listing
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listing_labels_and_padding() {
+        let mut code = Vec::new();
+        code.extend_from_slice(&0xD65F03C0u32.to_le_bytes()); // RET
+        code.extend_from_slice(&0x00000000u32.to_le_bytes()); // data word
+        for _ in 0..3 {
+            code.extend_from_slice(&AARCH64_NOP.to_le_bytes());
+        }
+
+        let labels = vec![("_start".to_string(), 0), ("data".to_string(), 4)];
+        let listing = disassemble(&code, &labels);
+        let lines: Vec<_> = listing.lines().collect();
+
+        assert_eq!(lines[0], "_start:");
+        assert!(lines[1].ends_with("d65f03c0  ret"), "{}", lines[1]);
+        assert_eq!(lines[2], "data:");
+        assert!(lines[4].contains("x3 padding"), "{}", lines[4]);
+        assert_eq!(lines.len(), 5);
+    }
+}
@@ -2,6 +2,8 @@
 // Eä Code Generation v5.0 — Platform Abstraction
 
 pub mod aarch64;
+#[cfg(feature = "disasm")]
+pub mod disasm;
 pub mod nucleus;
 pub mod x86_64;
 
@@ -4,6 +4,23 @@
 use crate::ast::full_ast::*;
 use crate::error::CompileError;
 
+/// Size of a Nucleus code image in bytes
+const NUCLEUS_SIZE: usize = 8192;
+
+/// AArch64 NOP instruction encoding
+const AARCH64_NOP: u32 = 0xD503201F;
+
+/// Handler frame offset of the first local variable slot; `[SP, #0]` holds
+/// the value loaded for names without a `let` binding
+const LOCALS_BASE: u32 = 8;
+
+/// Runtime entry points that may be referenced before (or without) a local
+/// definition; the loader links them, so unresolved branches fall through
+const EXTERNAL_STUBS: &[&str] = &["muscle_loader", "scheduler", "lattice_emitter"];
+
+/// Largest frame encodable in a single SUB/ADD SP immediate
+const MAX_FRAME_SIZE: u32 = 0xFFF;
+
 /// Branch fixup entry - records a branch site that needs patching
 #[derive(Debug, Clone)]
 struct BranchFixup {
@@ -25,6 +42,11 @@ enum BranchKind {
     Unconditional,
     /// BL - 26-bit immediate (bits 0-25)
     BranchLink,
+    /// ADRP - 21-bit page delta (immlo bits 29-30, immhi bits 5-23), assuming
+    /// the image is loaded at a 4KiB-aligned base
+    PageAddress,
+    /// ADD (immediate) - low 12 bits of the target address (bits 10-21)
+    PageOffset,
 }
 
 /// Code builder with position tracking and branch fixups
@@ -32,6 +54,14 @@ struct CodeBuilder {
     code: Vec<u8>,
     labels: Vec<(String, usize)>,
     fixups: Vec<BranchFixup>,
+    /// Counter for generated label suffixes, so labels depend only on the program
+    next_label_id: usize,
+    /// String literals to place in the data section, labelled `str_{index}`
+    strings: Vec<String>,
+    /// Variables in scope for the current handler, innermost last
+    locals: Vec<(String, u32)>,
+    /// Number of stack slots allocated in the current handler frame
+    slots_used: u32,
 }
 
 impl CodeBuilder {
@@ -40,9 +70,54 @@ impl CodeBuilder {
             code: Vec::with_capacity(8192),
             labels: Vec::new(),
             fixups: Vec::new(),
+            next_label_id: 0,
+            strings: Vec::new(),
+            locals: Vec::new(),
+            slots_used: 0,
         }
     }
 
+    /// Reset the symbol table for a new handler frame
+    fn begin_frame(&mut self) {
+        self.locals.clear();
+        self.slots_used = 0;
+    }
+
+    /// Allocate a fresh slot for `name`, shadowing any earlier binding, and
+    /// return its offset from SP
+    fn bind_local(&mut self, name: &str) -> u32 {
+        let offset = LOCALS_BASE + 8 * self.slots_used;
+        self.slots_used += 1;
+        self.locals.push((name.to_string(), offset));
+        offset
+    }
+
+    fn lookup_local(&self, name: &str) -> Option<u32> {
+        self.locals
+            .iter()
+            .rev()
+            .find(|(local, _)| local == name)
+            .map(|(_, offset)| *offset)
+    }
+
+    fn fresh_label_id(&mut self) -> usize {
+        let id = self.next_label_id;
+        self.next_label_id += 1;
+        id
+    }
+
+    /// Intern a string literal for the data section, returning its label
+    fn intern_string(&mut self, value: &str) -> String {
+        let index = match self.strings.iter().position(|s| s == value) {
+            Some(index) => index,
+            None => {
+                self.strings.push(value.to_string());
+                self.strings.len() - 1
+            }
+        };
+        format!("str_{}", index)
+    }
+
     fn pos(&self) -> usize {
         self.code.len()
     }
@@ -106,6 +181,28 @@ impl CodeBuilder {
         self.code.extend(&0x94000000u32.to_le_bytes());
     }
 
+    fn adrp(&mut self, target: &str, reg: u8) {
+        self.fixups.push(BranchFixup {
+            site: self.pos(),
+            target: target.to_string(),
+            kind: BranchKind::PageAddress,
+        });
+        // ADRP Xd: 0x90000000 | Rd
+        let instr = 0x90000000u32 | (reg as u32);
+        self.code.extend(&instr.to_le_bytes());
+    }
+
+    fn add_page_offset(&mut self, target: &str, reg: u8) {
+        self.fixups.push(BranchFixup {
+            site: self.pos(),
+            target: target.to_string(),
+            kind: BranchKind::PageOffset,
+        });
+        // ADD Xd, Xd, #imm12: 0x91000000 | Rn << 5 | Rd
+        let instr = 0x91000000u32 | ((reg as u32) << 5) | (reg as u32);
+        self.code.extend(&instr.to_le_bytes());
+    }
+
     /// Apply all fixups after code generation is complete
     fn apply_fixups(&mut self) -> Result<(), CompileError> {
         for fixup in &self.fixups {
@@ -115,16 +212,26 @@ impl CodeBuilder {
 
             let target_pos = match target_pos {
                 Some(pos) => pos,
+                // Forward-declared runtime stub: jump to the next instruction
+                None if EXTERNAL_STUBS.contains(&fixup.target.as_str()) => fixup.site + 4,
                 None => {
-                    // If target not found, use a forward jump to current position (NOP-like)
-                    // This handles internal function calls that go to stubs
-                    fixup.site + 4
+                    return Err(CompileError::CodegenError(format!(
+                        "unresolved branch target: {}",
+                        fixup.target
+                    )))
                 }
             };
 
             // Calculate offset in instructions (4-byte units), PC-relative from branch site
             let offset = (target_pos as i64 - fixup.site as i64) / 4;
 
+            let base = u32::from_le_bytes([
+                self.code[fixup.site],
+                self.code[fixup.site + 1],
+                self.code[fixup.site + 2],
+                self.code[fixup.site + 3],
+            ]);
+
             let patched = match fixup.kind {
                 BranchKind::Conditional | BranchKind::CompareAndBranch => {
                     // 19-bit signed immediate in bits 5-23
@@ -133,12 +240,6 @@ impl CodeBuilder {
                             "Branch offset {} out of 19-bit range", offset
                         )));
                     }
-                    let base = u32::from_le_bytes([
-                        self.code[fixup.site],
-                        self.code[fixup.site + 1],
-                        self.code[fixup.site + 2],
-                        self.code[fixup.site + 3],
-                    ]);
                     let imm19 = ((offset as u32) & 0x7FFFF) << 5;
                     base | imm19
                 }
@@ -149,15 +250,19 @@ impl CodeBuilder {
                             "Branch offset {} out of 26-bit range", offset
                         )));
                     }
-                    let base = u32::from_le_bytes([
-                        self.code[fixup.site],
-                        self.code[fixup.site + 1],
-                        self.code[fixup.site + 2],
-                        self.code[fixup.site + 3],
-                    ]);
                     let imm26 = (offset as u32) & 0x3FFFFFF;
                     base | imm26
                 }
+                BranchKind::PageAddress => {
+                    // Page delta between the ADRP site and the target, in 4KiB pages
+                    let pages = ((target_pos & !0xFFF) as i64 - (fixup.site & !0xFFF) as i64) >> 12;
+                    let imm21 = (pages as u32) & 0x1FFFFF;
+                    base | ((imm21 & 0x3) << 29) | ((imm21 >> 2) << 5)
+                }
+                BranchKind::PageOffset => {
+                    let imm12 = (target_pos & 0xFFF) as u32;
+                    base | (imm12 << 10)
+                }
             };
 
             let bytes = patched.to_le_bytes();
@@ -178,6 +283,13 @@ pub struct NucleusCodegen;
 impl NucleusCodegen {
     /// Generate 8KiB AArch64 machine code with capability enforcement
     pub fn generate(program: &Program) -> Result<Vec<u8>, CompileError> {
+        Self::generate_with_labels(program).map(|(code, _)| code)
+    }
+
+    /// Generate machine code along with its labels (name, byte offset)
+    pub fn generate_with_labels(
+        program: &Program,
+    ) -> Result<(Vec<u8>, Vec<(String, usize)>), CompileError> {
         let mut builder = CodeBuilder::new();
 
         // 1. Entry point and capability security setup
@@ -193,7 +305,7 @@ impl NucleusCodegen {
         Self::generate_builtin_functions(&mut builder);
 
         // 5. Event handlers
-        Self::generate_event_handlers(&mut builder, &program.rules, program);
+        Self::generate_event_handlers(&mut builder, &program.rules, program)?;
 
         // 6. Data section with constants and security tokens
         Self::generate_data_section(&mut builder, program);
@@ -202,18 +314,23 @@ impl NucleusCodegen {
         Self::generate_capability_tables(&mut builder, program);
 
         // Apply branch fixups
+        let labels = builder.labels.clone();
         let mut code = builder.into_code()?;
 
         // Pad to exactly 8KiB
-        if code.len() > 8192 {
+        if code.len() > NUCLEUS_SIZE {
             return Err(CompileError::CodegenError(format!(
                 "Nucleus code size {} exceeds 8KiB limit",
                 code.len()
             )));
         }
-        code.resize(8192, 0x00); // Fill with zeros (NOP equivalent)
+        // Word-align with zeros, then fill the rest with encoded NOPs
+        code.resize(code.len().next_multiple_of(4), 0x00);
+        while code.len() < NUCLEUS_SIZE {
+            code.extend_from_slice(&AARCH64_NOP.to_le_bytes());
+        }
 
-        Ok(code)
+        Ok((code, labels))
     }
 
     fn generate_security_header(builder: &mut CodeBuilder) {
@@ -394,18 +511,39 @@ impl NucleusCodegen {
         builder.extend(&[0xC0, 0x03, 0x5F, 0xD6]); // RET
     }
 
-    fn generate_event_handlers(builder: &mut CodeBuilder, rules: &[Rule], program: &Program) {
+    fn generate_event_handlers(
+        builder: &mut CodeBuilder,
+        rules: &[Rule],
+        program: &Program,
+    ) -> Result<(), CompileError> {
         for (i, rule) in rules.iter().enumerate() {
-            Self::generate_rule_handler(builder, rule, i, program);
+            Self::generate_rule_handler(builder, rule, i, program)?;
         }
+        Ok(())
     }
 
-    fn generate_rule_handler(builder: &mut CodeBuilder, rule: &Rule, index: usize, program: &Program) {
+    fn generate_rule_handler(
+        builder: &mut CodeBuilder,
+        rule: &Rule,
+        index: usize,
+        program: &Program,
+    ) -> Result<(), CompileError> {
         // handler_{index}:
         let handler_label = format!("handler_{}", index);
         builder.label(&handler_label);
 
-        builder.extend(&[0xFF, 0x43, 0x00, 0xD1]); // SUB SP, SP, #16
+        // Frame: reserved slot plus one slot per binding, 16-byte aligned
+        let frame = (LOCALS_BASE + 8 * Self::count_bindings(&rule.body)).next_multiple_of(16);
+        if frame > MAX_FRAME_SIZE {
+            return Err(CompileError::CodegenError(format!(
+                "Handler {} needs a {}-byte frame, limit is {}",
+                index, frame, MAX_FRAME_SIZE
+            )));
+        }
+        builder.begin_frame();
+
+        let sub_instr = 0xD10003FFu32 | (frame << 10); // SUB SP, SP, #frame
+        builder.extend(&sub_instr.to_le_bytes());
 
         // Generate body statements
         for statement in &rule.body {
@@ -413,8 +551,32 @@ impl NucleusCodegen {
         }
 
         builder.extend(&[0x20, 0x00, 0x80, 0x52]); // MOV W0, #1 (success)
-        builder.extend(&[0xFF, 0x43, 0x00, 0x91]); // ADD SP, SP, #16
+        let add_instr = 0x910003FFu32 | (frame << 10); // ADD SP, SP, #frame
+        builder.extend(&add_instr.to_le_bytes());
         builder.extend(&[0xC0, 0x03, 0x5F, 0xD6]); // RET
+        Ok(())
+    }
+
+    /// Number of stack slots needed by `let` and `if` bindings in a block
+    fn count_bindings(statements: &[Statement]) -> u32 {
+        statements
+            .iter()
+            .map(|statement| match statement {
+                Statement::Let(_) => 1,
+                Statement::If(stmt) => {
+                    stmt.binding.is_some() as u32
+                        + Self::count_bindings(&stmt.then_branch)
+                        + stmt.else_branch.as_deref().map_or(0, Self::count_bindings)
+                }
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// STR X0, [SP, #offset]
+    fn store_local(builder: &mut CodeBuilder, offset: u32) {
+        let instr = 0xF90003E0u32 | ((offset / 8) << 10);
+        builder.extend(&instr.to_le_bytes());
     }
 
     fn generate_statement(builder: &mut CodeBuilder, statement: &Statement, program: &Program, handler_idx: usize) {
@@ -430,8 +592,7 @@ impl NucleusCodegen {
     }
 
     fn generate_verify_statement(builder: &mut CodeBuilder, stmt: &VerifyStmt, handler_idx: usize) {
-        static VERIFY_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
-        let verify_id = VERIFY_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let verify_id = builder.fresh_label_id();
         let ok_label = format!("verify_ok_{}_{}", handler_idx, verify_id);
 
         // Generate condition expression
@@ -448,16 +609,17 @@ impl NucleusCodegen {
     }
 
     fn generate_let_statement(builder: &mut CodeBuilder, stmt: &LetStmt) {
-        if let Some(expr) = &stmt.value {
-            Self::generate_expression(builder, expr);
-            // Store result to local variable slot
-            // In real implementation, track variable locations
+        // Evaluate before binding so `let x = x + 1` reads the outer `x`
+        match &stmt.value {
+            Some(expr) => Self::generate_expression(builder, expr),
+            None => builder.extend(&[0x00, 0x00, 0x80, 0xD2]), // MOVZ X0, #0
         }
+        let offset = builder.bind_local(&stmt.name);
+        Self::store_local(builder, offset);
     }
 
     fn generate_if_statement(builder: &mut CodeBuilder, stmt: &IfStmt, program: &Program, handler_idx: usize) {
-        static IF_COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
-        let if_id = IF_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let if_id = builder.fresh_label_id();
         let else_label = format!("else_{}_{}", handler_idx, if_id);
         let end_label = format!("endif_{}_{}", handler_idx, if_id);
 
@@ -467,10 +629,16 @@ impl NucleusCodegen {
         // CBZ X0, else_branch
         builder.cbz(&else_label, 0);
 
-        // Then branch
+        // Then branch, in its own scope; `-> binding:` names the condition result
+        let scope = builder.locals.len();
+        if let Some(binding) = &stmt.binding {
+            let offset = builder.bind_local(binding);
+            Self::store_local(builder, offset);
+        }
         for then_stmt in &stmt.then_branch {
             Self::generate_statement(builder, then_stmt, program, handler_idx);
         }
+        builder.locals.truncate(scope);
 
         // B end_if
         builder.branch(&end_label);
@@ -481,6 +649,7 @@ impl NucleusCodegen {
             for else_stmt in else_branch {
                 Self::generate_statement(builder, else_stmt, program, handler_idx);
             }
+            builder.locals.truncate(scope);
         }
 
         // end_if: continue
@@ -547,6 +716,11 @@ impl NucleusCodegen {
             Expression::Variable(var) => Self::generate_variable(builder, var),
             Expression::SelfRef(self_ref) => Self::generate_self_reference(builder, self_ref),
             Expression::Call(call) => Self::generate_call_expression(builder, call),
+            // Fields of a bound value (e.g. `healing.blob`) are resolved by
+            // the runtime from the handle itself
+            Expression::FieldAccess(access) if builder.lookup_local(&access.object).is_some() => {
+                Self::generate_variable(builder, &access.object)
+            }
             Expression::FieldAccess(access) => Self::generate_field_access(builder, access),
             Expression::Binary(bin) => Self::generate_binary_expression(builder, bin),
         }
@@ -555,54 +729,41 @@ impl NucleusCodegen {
     fn generate_literal(builder: &mut CodeBuilder, literal: &Literal) {
         match literal {
             Literal::Hex(_) => {
-                if let Some(value) = literal.as_u64() {
-                    if value <= 0xFFFF {
-                        // MOVZ X0, #value
-                        let mov_instr = 0xD2800000u32 | ((value as u32 & 0xFFFF) << 5);
-                        builder.extend(&mov_instr.to_le_bytes());
-                    } else {
-                        // For larger values, use MOVZ + MOVK sequence
-                        let low = (value & 0xFFFF) as u32;
-                        let high = ((value >> 16) & 0xFFFF) as u32;
-                        let movz = 0xD2800000u32 | (low << 5);
-                        builder.extend(&movz.to_le_bytes());
-                        if high > 0 {
-                            let movk = 0xF2A00000u32 | (high << 5);
-                            builder.extend(&movk.to_le_bytes());
-                        }
-                    }
-                } else {
-                    builder.extend(&[0x00, 0x00, 0x80, 0xD2]); // MOVZ X0, #0
-                }
+                // Hex literals wider than 64 bits are not materialized
+                Self::generate_immediate(builder, literal.as_u64().unwrap_or(0));
             }
-            Literal::Integer(n) => {
-                if *n <= 0xFFFF {
-                    // MOVZ X0, #n
-                    let mov_instr = 0xD2800000u32 | ((*n as u32) << 5);
-                    builder.extend(&mov_instr.to_le_bytes());
-                } else {
-                    // For larger values, use MOVZ + MOVK
-                    let low = (*n as u32) & 0xFFFF;
-                    let high = ((*n as u32) >> 16) & 0xFFFF;
-                    let movz = 0xD2800000u32 | (low << 5);
-                    builder.extend(&movz.to_le_bytes());
-                    if high > 0 {
-                        let movk = 0xF2A00000u32 | (high << 5);
-                        builder.extend(&movk.to_le_bytes());
-                    }
-                }
+            Literal::Integer(n) => Self::generate_immediate(builder, *n),
+            Literal::String(value) => {
+                // X0 = address of the interned bytes, X1 = length
+                let label = builder.intern_string(value);
+                builder.adrp(&label, 0); // ADRP X0, str
+                builder.add_page_offset(&label, 0); // ADD X0, X0, #:lo12:str
+                let len = (value.len() as u32).min(0xFFFF);
+                let mov_instr = 0xD2800001u32 | (len << 5); // MOVZ X1, #len
+                builder.extend(&mov_instr.to_le_bytes());
             }
-            Literal::String(_) => {
-                // String literals: for now, load 0
-                builder.extend(&[0x00, 0x00, 0x80, 0xD2]); // MOVZ X0, #0
+        }
+    }
+
+    /// Materialize a 64-bit immediate in X0: MOVZ for bits 0-15, then a MOVK
+    /// (with `hw` selecting the 16-bit lane) for each nonzero higher lane
+    fn generate_immediate(builder: &mut CodeBuilder, value: u64) {
+        let movz = 0xD2800000u32 | (((value & 0xFFFF) as u32) << 5); // MOVZ X0, #imm16
+        builder.extend(&movz.to_le_bytes());
+        for hw in 1..4u32 {
+            let imm16 = ((value >> (16 * hw)) & 0xFFFF) as u32;
+            if imm16 != 0 {
+                let movk = 0xF2800000u32 | (hw << 21) | (imm16 << 5); // MOVK X0, #imm16, LSL #(16*hw)
+                builder.extend(&movk.to_le_bytes());
             }
         }
     }
 
-    fn generate_variable(builder: &mut CodeBuilder, _var: &str) {
-        // Load from local variable slot
-        // In real implementation, track variable locations
-        builder.extend(&[0xE0, 0x07, 0x40, 0xF9]); // LDR X0, [SP, #0] (example)
+    fn generate_variable(builder: &mut CodeBuilder, var: &str) {
+        // Unbound names (inputs, event parameters) read the reserved slot
+        let offset = builder.lookup_local(var).unwrap_or(0);
+        let instr = 0xF94003E0u32 | ((offset / 8) << 10); // LDR X0, [SP, #offset]
+        builder.extend(&instr.to_le_bytes());
     }
 
     fn generate_self_reference(builder: &mut CodeBuilder, self_ref: &SelfReference) {
@@ -646,6 +807,8 @@ impl NucleusCodegen {
         let target = match call.function.as_str() {
             "hardware_attestation.verify" => "verify_attestation",
             "symbiote.process_update" => "symbiote_process_update",
+            // Spelling used by muscles/nucleus.ea
+            "symbiote.process" => "symbiote_process_update",
             "referee.self_check_failed" => "self_check_failed",
             _ => &call.function,
         };
@@ -726,6 +889,17 @@ impl NucleusCodegen {
         builder.extend(&[0xEAu8; 32]); // genesis_root
         builder.extend(&0xFFFF_FFFF_FFFF_FFFFu64.to_le_bytes()); // symbiote_id
         builder.extend(&1u64.to_le_bytes()); // self.version
+
+        // Interned string literals, NUL-terminated
+        let strings = std::mem::take(&mut builder.strings);
+        for (i, value) in strings.iter().enumerate() {
+            builder.label(&format!("str_{}", i));
+            builder.extend(value.as_bytes());
+            builder.extend(&[0x00]);
+        }
+        while builder.pos() % 8 != 0 {
+            builder.extend(&[0x00]);
+        }
     }
 
     fn generate_capability_tables(builder: &mut CodeBuilder, program: &Program) {
@@ -772,3 +946,271 @@ impl NucleusCodegen {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::languages::formal_grammar::FormalParser;
+
+    const SOURCE: &str = r#"
+input lattice_stream<MuscleUpdate>
+input hardware_attestation<DeviceProof>
+input symbiote<SealedBlob>
+
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    verify hardware_attestation.verify()
+    emit heartbeat(self.id, self.version)
+
+rule on_lattice_update(update: MuscleUpdate):
+    if symbiote.process_update(update) -> healing:
+        emit_update(healing.blob)
+"#;
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        let program = FormalParser::parse_program(SOURCE).unwrap();
+
+        let first = NucleusCodegen::generate(&program).unwrap();
+        let second = NucleusCodegen::generate(&program).unwrap();
+
+        assert_eq!(first.len(), NUCLEUS_SIZE);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_string_literal_is_interned_and_addressed() {
+        let source = r#"
+input lattice_stream<MuscleUpdate>
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    emit heartbeat("I am alive")
+"#;
+        let program = FormalParser::parse_program(source).unwrap();
+        let code = NucleusCodegen::generate(&program).unwrap();
+
+        let needle = b"I am alive\0";
+        let data_pos = code
+            .windows(needle.len())
+            .position(|w| w == needle)
+            .expect("string bytes missing from data section");
+
+        let word = |at: usize| u32::from_le_bytes(code[at..at + 4].try_into().unwrap());
+        // Find ADRP X0 followed by ADD X0, X0, #imm12
+        let site = (0..data_pos)
+            .step_by(4)
+            .find(|&at| {
+                word(at) & 0x9F00001F == 0x90000000 && word(at + 4) & 0xFFC003FF == 0x91000000
+            })
+            .expect("no ADRP/ADD pair for string literal");
+
+        let adrp = word(site);
+        let imm21 = ((adrp >> 29) & 0x3) | (((adrp >> 5) & 0x7FFFF) << 2);
+        let pages = ((imm21 << 11) as i32 >> 11) as i64;
+        let page = (site & !0xFFF) as i64 + (pages << 12);
+        let lo12 = ((word(site + 4) >> 10) & 0xFFF) as i64;
+        assert_eq!((page + lo12) as usize, data_pos);
+
+        // MOVZ X1, #len
+        assert_eq!(
+            word(site + 8),
+            0xD2800001 | (("I am alive".len() as u32) << 5)
+        );
+    }
+
+    fn words(code: &[u8]) -> Vec<u32> {
+        code.chunks_exact(4)
+            .map(|w| u32::from_le_bytes(w.try_into().unwrap()))
+            .collect()
+    }
+
+    fn contains_sequence(haystack: &[u32], needle: &[u32]) -> bool {
+        haystack.windows(needle.len()).any(|w| w == needle)
+    }
+
+    #[test]
+    fn test_let_bindings_use_distinct_slots() {
+        let source = r#"
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    let a = 5
+    let b = a + 3
+    emit result(b)
+"#;
+        let program = FormalParser::parse_program(source).unwrap();
+        let code = words(&NucleusCodegen::generate(&program).unwrap());
+
+        let expected = [
+            0xD28000A0, // MOVZ X0, #5
+            0xF90007E0, // STR X0, [SP, #8]   ; a
+            0xF94007E0, // LDR X0, [SP, #8]   ; a
+            0xAA0003E8, // MOV X8, X0
+            0xD2800060, // MOVZ X0, #3
+            0xAA0003E9, // MOV X9, X0
+            0x8B090100, // ADD X0, X8, X9
+            0xF9000BE0, // STR X0, [SP, #16]  ; b
+            0xF9400BE0, // LDR X0, [SP, #16]  ; b
+        ];
+        assert!(contains_sequence(&code, &expected));
+        // Two slots plus the reserved one round up to a 32-byte frame
+        assert!(code.contains(&0xD10083FF)); // SUB SP, SP, #32
+    }
+
+    #[test]
+    fn test_shadowing_and_if_binding() {
+        let source = r#"
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    let a = 1
+    let a = a + 1
+    if a == 2 -> found:
+        emit result(found)
+    emit result(a)
+"#;
+        let program = FormalParser::parse_program(source).unwrap();
+        let code = words(&NucleusCodegen::generate(&program).unwrap());
+
+        // The shadowing `a` reads the outer slot and gets a new one
+        assert!(contains_sequence(
+            &code,
+            &[0xF94007E0, 0xAA0003E8, 0xD2800020, 0xAA0003E9, 0x8B090100, 0xF9000BE0]
+        ));
+        // The `-> found:` binding stores the condition result in the third slot
+        assert!(contains_sequence(&code, &[0xF9000FE0, 0xF9400FE0]));
+        // Later reads of `a` use the shadowing slot
+        assert!(code.contains(&0xF9400BE0));
+    }
+
+    #[test]
+    fn test_field_of_bound_value_loads_its_slot() {
+        let source = r#"
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    let healing = 7
+    emit_update(healing.blob)
+"#;
+        let program = FormalParser::parse_program(source).unwrap();
+        let code = words(&NucleusCodegen::generate(&program).unwrap());
+
+        assert!(contains_sequence(
+            &code,
+            &[
+                0xF90007E0, // STR X0, [SP, #8]   ; healing
+                0xF94007E0, // LDR X0, [SP, #8]   ; healing.blob
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_symbiote_process_resolves_to_the_update_hook() {
+        let source = r#"
+input symbiote<SealedBlob>
+
+capability emit_update(blob: SealedBlob)
+
+rule on_lattice_update(update: MuscleUpdate):
+    if symbiote.process(update) -> healing:
+        emit_update(healing.blob)
+"#;
+        let program = FormalParser::parse_program(source).unwrap();
+        assert!(NucleusCodegen::generate(&program).is_ok());
+    }
+
+    #[test]
+    fn test_call_to_undefined_function_fails() {
+        let source = r#"
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    let x = undefined_fn(1)
+    emit result(x)
+"#;
+        let program = FormalParser::parse_program(source).unwrap();
+        match NucleusCodegen::generate(&program) {
+            Err(CompileError::CodegenError(msg)) => {
+                assert_eq!(msg, "unresolved branch target: undefined_fn")
+            }
+            other => panic!("expected codegen error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_external_stubs_may_be_forward_declared() {
+        let mut builder = CodeBuilder::new();
+        for stub in EXTERNAL_STUBS {
+            builder.branch_link(stub);
+        }
+        assert!(builder.into_code().is_ok());
+
+        let mut builder = CodeBuilder::new();
+        builder.branch_link("not_a_stub");
+        assert!(builder.into_code().is_err());
+
+        // Capabilities calling into the runtime stubs still compile
+        let source = r#"
+capability load_muscle(id: muscle_id) -> ExecutableMuscle
+capability schedule(muscle: ExecutableMuscle, priority: u8)
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    let m = load_muscle(1)
+    schedule(m, priority: 7)
+    emit ready(m)
+"#;
+        let program = FormalParser::parse_program(source).unwrap();
+        assert!(NucleusCodegen::generate(&program).is_ok());
+    }
+
+    /// Decode a MOVZ/MOVK X0 sequence back into the value it builds
+    fn disassemble_immediate(words: &[u32]) -> u64 {
+        let mut value = 0u64;
+        for (i, &word) in words.iter().enumerate() {
+            let opcode = word & 0xFF800000;
+            assert_eq!(word & 0x1F, 0, "destination must be X0");
+            assert_eq!(opcode, if i == 0 { 0xD2800000 } else { 0xF2800000 });
+            let hw = (word >> 21) & 0x3;
+            let imm16 = ((word >> 5) & 0xFFFF) as u64;
+            value |= imm16 << (16 * hw);
+        }
+        value
+    }
+
+    fn immediate_words(value: u64) -> Vec<u32> {
+        let mut builder = CodeBuilder::new();
+        NucleusCodegen::generate_literal(&mut builder, &Literal::Hex(format!("0x{:x}", value)));
+        words(&builder.into_code().unwrap())
+    }
+
+    #[test]
+    fn test_materialize_all_ones() {
+        let code = immediate_words(0xFFFF_FFFF_FFFF_FFFF);
+        assert_eq!(code, vec![0xD29FFFE0, 0xF2BFFFE0, 0xF2DFFFE0, 0xF2FFFFE0]);
+        assert_eq!(disassemble_immediate(&code), 0xFFFF_FFFF_FFFF_FFFF);
+    }
+
+    #[test]
+    fn test_materialize_mixed_lanes() {
+        let code = immediate_words(0x1234_5678_9ABC_DEF0);
+        assert_eq!(code, vec![0xD29BDE00, 0xF2B35780, 0xF2CACF00, 0xF2E24680]);
+        assert_eq!(disassemble_immediate(&code), 0x1234_5678_9ABC_DEF0);
+
+        // Zero lanes are skipped
+        let code = immediate_words(0x0001_0000_0000_0002);
+        assert_eq!(code.len(), 2);
+        assert_eq!(disassemble_immediate(&code), 0x0001_0000_0000_0002);
+    }
+
+    #[test]
+    fn test_padding_is_nops() {
+        let program = FormalParser::parse_program(SOURCE).unwrap();
+        let code = NucleusCodegen::generate(&program).unwrap();
+
+        let tail = &code[NUCLEUS_SIZE - 4..];
+        assert_eq!(u32::from_le_bytes(tail.try_into().unwrap()), AARCH64_NOP);
+    }
+}
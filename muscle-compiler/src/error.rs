@@ -5,6 +5,11 @@ use std::fmt;
 pub enum CompileError {
     IoError(String),
     SyntaxError(String),
+    ParseError {
+        message: String,
+        line: usize,
+        column: usize,
+    },
     CapabilityError(String),
     CompileError(String),
     CodegenError(String),
@@ -16,6 +21,11 @@ impl fmt::Display for CompileError {
         match self {
             CompileError::IoError(msg) => write!(f, "I/O error: {}", msg),
             CompileError::SyntaxError(msg) => write!(f, "Syntax error: {}", msg),
+            CompileError::ParseError {
+                message,
+                line,
+                column,
+            } => write!(f, "Parse error at {}:{}: {}", line, column, message),
             CompileError::CapabilityError(msg) => write!(f, "Capability error: {}", msg),
             CompileError::CompileError(msg) => write!(f, "Compile error: {}", msg),
             CompileError::CodegenError(msg) => write!(f, "Codegen error: {}", msg),
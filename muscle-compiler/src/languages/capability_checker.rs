@@ -11,6 +11,45 @@ pub struct CapabilityChecker {
     declared_inputs: HashSet<String>,
     builtin_objects: HashSet<String>,
     local_variables: HashSet<String>,
+    /// Capabilities each event's rule may invoke; events without an entry may invoke none
+    event_capabilities: HashMap<String, HashSet<String>>,
+    /// Events explicitly allowed to invoke any declared capability
+    unrestricted_events: HashSet<String>,
+    /// Event of the rule currently being verified
+    current_event: String,
+}
+
+/// Default per-event allowlists following the Sacred Rules: only lattice
+/// updates may emit lattice updates, boot may load and schedule, and timers
+/// and integrity failures may only emit events.
+const DEFAULT_EVENT_CAPABILITIES: &[(&str, &[&str])] = &[
+    ("on_boot", &["load_muscle", "schedule"]),
+    (
+        "on_lattice_update",
+        &["load_muscle", "schedule", "emit_update"],
+    ),
+    ("on_timer_1hz", &[]),
+    ("on_self_integrity_failure", &[]),
+];
+
+fn event_name(event: &Event) -> &str {
+    match event {
+        Event::OnBoot => "on_boot",
+        Event::OnLatticeUpdate { .. } => "on_lattice_update",
+        Event::OnTimer1Hz => "on_timer_1hz",
+        Event::OnSelfIntegrityFailure => "on_self_integrity_failure",
+        Event::Custom(name) => name,
+    }
+}
+
+/// Capability invoked by a call or emit target named `name`, if any
+fn gated_capability(name: &str) -> Option<&'static str> {
+    match name {
+        "load_muscle" => Some("load_muscle"),
+        "emit_update" => Some("emit_update"),
+        "schedule" | "unschedule" => Some("schedule"),
+        _ => None,
+    }
 }
 
 impl CapabilityChecker {
@@ -20,13 +59,56 @@ impl CapabilityChecker {
         builtin_objects.insert("referee".to_string());  // Hardware security module interface
         builtin_objects.insert("self".to_string());     // Self-reference for muscle identity
 
+        let event_capabilities = DEFAULT_EVENT_CAPABILITIES
+            .iter()
+            .map(|(event, caps)| {
+                let caps = caps.iter().map(|cap| cap.to_string()).collect();
+                (event.to_string(), caps)
+            })
+            .collect();
+
         Self {
             declared_capabilities: HashSet::new(),
             used_capabilities: HashSet::new(),
             declared_inputs: HashSet::new(),
             builtin_objects,
             local_variables: HashSet::new(),
+            event_capabilities,
+            unrestricted_events: HashSet::new(),
+            current_event: String::new(),
+        }
+    }
+
+    /// Replace the capabilities rules for `event` may invoke
+    pub fn allow_for_event(&mut self, event: &str, capabilities: &[&str]) {
+        let caps = capabilities.iter().map(|cap| cap.to_string()).collect();
+        self.unrestricted_events.remove(event);
+        self.event_capabilities.insert(event.to_string(), caps);
+    }
+
+    /// Let rules for `event` invoke any declared capability
+    pub fn unrestrict_event(&mut self, event: &str) {
+        self.event_capabilities.remove(event);
+        self.unrestricted_events.insert(event.to_string());
+    }
+
+    /// Record a direct capability invocation, checking the current event's
+    /// allowlist; events with no allowlist may invoke nothing unless they
+    /// were explicitly unrestricted
+    fn invoke_capability(&mut self, capability: &str) -> Result<(), CompileError> {
+        let permitted = self.unrestricted_events.contains(&self.current_event)
+            || self
+                .event_capabilities
+                .get(&self.current_event)
+                .is_some_and(|allowed| allowed.contains(capability));
+        if !permitted {
+            return Err(CompileError::CapabilityError(format!(
+                "Capability '{}' is not permitted in event '{}'",
+                capability, self.current_event
+            )));
         }
+        self.used_capabilities.insert(capability.to_string());
+        Ok(())
     }
 
     /// Verify capability security for entire program
@@ -65,6 +147,7 @@ impl CapabilityChecker {
     fn verify_rule(&mut self, rule: &Rule) -> Result<(), CompileError> {
         // Verify event input is declared
         self.verify_event(&rule.event)?;
+        self.current_event = event_name(&rule.event).to_string();
 
         for statement in &rule.body {
             self.verify_statement(statement)?;
@@ -126,7 +209,12 @@ impl CapabilityChecker {
                 }
             }
             Statement::Emit(stmt) => {
-                // emit requires emit_update capability
+                // emit requires emit_update capability; emitting an event is
+                // not a lattice update, so it is allowed in every event, but
+                // a target naming a capability is held to the allowlist
+                if let Some(capability) = gated_capability(&stmt.event) {
+                    self.invoke_capability(capability)?;
+                }
                 self.used_capabilities.insert("emit_update".to_string());
                 for arg in &stmt.arguments {
                     self.verify_expression(arg)?;
@@ -134,13 +222,13 @@ impl CapabilityChecker {
             }
             Statement::Schedule(stmt) => {
                 // schedule requires schedule capability
-                self.used_capabilities.insert("schedule".to_string());
+                self.invoke_capability("schedule")?;
                 self.verify_expression(&stmt.muscle)?;
                 self.verify_expression(&Expression::Literal(stmt.priority.clone()))?;
             }
             Statement::Unschedule(stmt) => {
                 // unschedule requires schedule capability
-                self.used_capabilities.insert("schedule".to_string());
+                self.invoke_capability("schedule")?;
                 self.verify_expression(&stmt.muscle_id)?;
             }
             Statement::Expr(expr) => {
@@ -154,20 +242,9 @@ impl CapabilityChecker {
         match expr {
             Expression::Call(call) => {
                 // Check if this is a capability call
-                match call.function.as_str() {
-                    "load_muscle" => {
-                        self.used_capabilities.insert("load_muscle".to_string());
-                    }
-                    "emit_update" => {
-                        self.used_capabilities.insert("emit_update".to_string());
-                    }
-                    "schedule" => {
-                        self.used_capabilities.insert("schedule".to_string());
-                    }
-                    "unschedule" => {
-                        self.used_capabilities.insert("schedule".to_string());
-                    }
-                    _ => {
+                match gated_capability(&call.function) {
+                    Some(capability) => self.invoke_capability(capability)?,
+                    None => {
                         // Regular function call - verify inputs are declared
                         if !self.declared_inputs.contains(&call.function) {
                             // Check if it's a method call on declared input, local variable, or built-in
@@ -231,6 +308,40 @@ pub fn verify_sacred_rules(program: &Program) -> Result<(), CompileError> {
     // Additional: No polling constructs
     verify_no_polling(program)?;
 
+    // Additional: A single, unambiguous entry point
+    verify_single_boot(program)?;
+
+    Ok(())
+}
+
+/// Non-fatal findings about a program's liveness, one message per finding
+pub fn liveness_warnings(program: &Program) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !program
+        .rules
+        .iter()
+        .any(|rule| matches!(rule.event, Event::OnTimer1Hz))
+    {
+        warnings.push(
+            "no 'on_timer_1hz' rule: muscle emits no heartbeat and cannot be monitored for liveness"
+                .to_string(),
+        );
+    }
+    warnings
+}
+
+fn verify_single_boot(program: &Program) -> Result<(), CompileError> {
+    let boot_rules = program
+        .rules
+        .iter()
+        .filter(|rule| matches!(rule.event, Event::OnBoot))
+        .count();
+    if boot_rules != 1 {
+        return Err(CompileError::CompileError(format!(
+            "Muscle must declare exactly one 'on_boot' rule, found {}",
+            boot_rules
+        )));
+    }
     Ok(())
 }
 
@@ -306,6 +417,156 @@ rule on_boot:
         assert!(checker.verify_program(&program).is_err());
     }
 
+    const LATTICE_HEADER: &str = r#"
+input lattice_stream<MuscleUpdate>
+input hardware_attestation<DeviceProof>
+input symbiote<SealedBlob>
+capability emit_update(blob: SealedBlob)
+"#;
+
+    #[test]
+    fn test_emit_update_rejected_in_timer_event() {
+        let source = format!(
+            "{}\nrule on_timer_1hz:\n    emit_update(symbiote.latest())\n",
+            LATTICE_HEADER
+        );
+
+        let program = FormalParser::parse_program(&source).unwrap();
+        let mut checker = CapabilityChecker::new();
+        match checker.verify_program(&program) {
+            Err(CompileError::CapabilityError(msg)) => {
+                assert!(
+                    msg.contains("'emit_update'") && msg.contains("'on_timer_1hz'"),
+                    "{}",
+                    msg
+                )
+            }
+            other => panic!("expected capability error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_emit_target_naming_capability_is_held_to_allowlist() {
+        for target in ["emit_update", "load_muscle", "schedule"] {
+            let source = format!(
+                "{}\nrule on_timer_1hz:\n    emit {}(symbiote.latest())\n",
+                LATTICE_HEADER, target
+            );
+
+            let program = FormalParser::parse_program(&source).unwrap();
+            let mut checker = CapabilityChecker::new();
+            match checker.verify_program(&program) {
+                Err(CompileError::CapabilityError(msg)) => {
+                    assert!(msg.contains("'on_timer_1hz'"), "{}", msg)
+                }
+                other => panic!("expected capability error for {}, got {:?}", target, other),
+            }
+        }
+
+        let source = format!(
+            "{}\nrule on_lattice_update(update: MuscleUpdate):\n    emit emit_update(symbiote.latest())\n",
+            LATTICE_HEADER
+        );
+        let program = FormalParser::parse_program(&source).unwrap();
+        let mut checker = CapabilityChecker::new();
+        assert!(checker.verify_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_emit_update_allowed_in_lattice_update() {
+        let source = format!(
+            "{}\nrule on_lattice_update(update: MuscleUpdate):\n    emit_update(symbiote.latest())\n",
+            LATTICE_HEADER
+        );
+
+        let program = FormalParser::parse_program(&source).unwrap();
+        let mut checker = CapabilityChecker::new();
+        assert!(checker.verify_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_event_allowlist_is_configurable() {
+        let source = format!(
+            "{}\nrule on_timer_1hz:\n    emit_update(symbiote.latest())\n",
+            LATTICE_HEADER
+        );
+        let program = FormalParser::parse_program(&source).unwrap();
+
+        let mut checker = CapabilityChecker::new();
+        checker.allow_for_event("on_timer_1hz", &["emit_update"]);
+        assert!(checker.verify_program(&program).is_ok());
+
+        let mut checker = CapabilityChecker::new();
+        checker.unrestrict_event("on_timer_1hz");
+        assert!(checker.verify_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_custom_event_has_empty_allowlist_by_default() {
+        let source = format!(
+            "{}\nrule on_boot:\n    emit heartbeat(\"up\")\n\nrule on_custom_signal:\n    emit_update(symbiote.latest())\n",
+            LATTICE_HEADER
+        );
+        let program = FormalParser::parse_program(&source).unwrap();
+
+        let mut checker = CapabilityChecker::new();
+        match checker.verify_program(&program) {
+            Err(CompileError::CapabilityError(msg)) => {
+                assert!(msg.contains("'on_custom_signal'"), "{}", msg)
+            }
+            other => panic!("expected capability error, got {:?}", other),
+        }
+
+        let mut checker = CapabilityChecker::new();
+        checker.unrestrict_event("on_custom_signal");
+        assert!(checker.verify_program(&program).is_ok());
+    }
+
+    #[test]
+    fn test_program_without_on_boot_is_rejected() {
+        let source = format!(
+            "{}\nrule on_timer_1hz:\n    emit heartbeat(\"tick\")\n",
+            LATTICE_HEADER
+        );
+        let program = FormalParser::parse_program(&source).unwrap();
+        match verify_sacred_rules(&program) {
+            Err(CompileError::CompileError(msg)) => assert!(msg.contains("found 0"), "{}", msg),
+            other => panic!("expected missing on_boot error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_program_with_two_on_boot_rules_is_rejected() {
+        let source = format!(
+            "{}\nrule on_boot:\n    emit heartbeat(\"one\")\n\nrule on_boot:\n    emit heartbeat(\"two\")\n",
+            LATTICE_HEADER
+        );
+        let program = FormalParser::parse_program(&source).unwrap();
+        match verify_sacred_rules(&program) {
+            Err(CompileError::CompileError(msg)) => assert!(msg.contains("found 2"), "{}", msg),
+            other => panic!("expected duplicate on_boot error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_program_with_single_on_boot_is_accepted() {
+        let source = format!(
+            "{}\nrule on_boot:\n    emit heartbeat(\"up\")\n",
+            LATTICE_HEADER
+        );
+        let program = FormalParser::parse_program(&source).unwrap();
+        assert!(verify_sacred_rules(&program).is_ok());
+        assert_eq!(liveness_warnings(&program).len(), 1);
+
+        let source = format!(
+            "{}\nrule on_boot:\n    emit heartbeat(\"up\")\n\nrule on_timer_1hz:\n    emit heartbeat(\"tick\")\n",
+            LATTICE_HEADER
+        );
+        let program = FormalParser::parse_program(&source).unwrap();
+        assert!(verify_sacred_rules(&program).is_ok());
+        assert!(liveness_warnings(&program).is_empty());
+    }
+
     #[test]
     fn test_undeclared_input_access() {
         let source = r#"
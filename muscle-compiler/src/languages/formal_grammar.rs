@@ -18,20 +18,78 @@ use std::collections::HashMap;
 
 pub struct FormalParser;
 
+/// Maximum number of characters of offending source quoted in a span snippet
+const SNIPPET_LEN: usize = 24;
+
+/// Source location of a parse failure (1-based line and column)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    /// Source text starting at the failure, up to the end of its line
+    pub snippet: String,
+}
+
+impl Span {
+    /// Locate `rest`, a suffix of `source` left over by the parser
+    fn locate(source: &str, rest: &str) -> Self {
+        let offset = source.len() - rest.len();
+        let consumed = &source[..offset];
+        let line = consumed.matches('\n').count() + 1;
+        let line_start = consumed.rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let column = consumed[line_start..].chars().count() + 1;
+        let snippet = rest
+            .lines()
+            .next()
+            .unwrap_or("")
+            .trim_end()
+            .chars()
+            .take(SNIPPET_LEN)
+            .collect();
+
+        Span {
+            line,
+            column,
+            snippet,
+        }
+    }
+
+    fn into_error(self, message: &str) -> CompileError {
+        let message = if self.snippet.is_empty() {
+            format!("{} at end of input", message)
+        } else {
+            format!("{} near '{}'", message, self.snippet)
+        };
+        CompileError::ParseError {
+            message,
+            line: self.line,
+            column: self.column,
+        }
+    }
+}
+
 impl FormalParser {
     /// Parse complete Muscle.ea program according to EBNF specification
     pub fn parse_program(source: &str) -> Result<Program, CompileError> {
-        // Preprocess: strip comments (# ... to end of line)
+        // Preprocess: strip comments (# ... to end of line). Lines are only
+        // truncated, so offsets into the stripped source keep their line and
+        // column in the original.
         let source = strip_comments(source);
 
-        let (remaining, program) = parse_program(&source)
-            .map_err(|e| CompileError::SyntaxError(format!("Parse error: {:?}", e)))?;
-
-        if !remaining.trim().is_empty() {
-            return Err(CompileError::SyntaxError(format!(
-                "Unexpected content after program: '{}'",
-                remaining
-            )));
+        let (remaining, program) = parse_program(&source).map_err(|e| match e {
+            nom::Err::Error(e) | nom::Err::Failure(e) => {
+                Span::locate(&source, e.input).into_error("syntax error")
+            }
+            nom::Err::Incomplete(_) => CompileError::ParseError {
+                message: "unexpected end of input".to_string(),
+                line: source.lines().count().max(1),
+                column: 1,
+            },
+        })?;
+
+        let trailing = remaining.trim_start();
+        if !trailing.is_empty() {
+            return Err(Span::locate(&source, trailing).into_error("unexpected input"));
         }
 
         Ok(program)
@@ -604,6 +662,40 @@ fn parse_operator(input: &str) -> IResult<&str, BinaryOperator> {
 mod tests {
     use super::*;
 
+    fn parse_error_location(source: &str) -> (usize, usize, String) {
+        match FormalParser::parse_program(source) {
+            Err(CompileError::ParseError {
+                message,
+                line,
+                column,
+            }) => (line, column, message),
+            other => panic!("expected parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_error_reports_unclosed_emit() {
+        let source = r#"
+input lattice_stream<MuscleUpdate>
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    emit heartbeat("I am alive"
+"#;
+
+        let (line, column, message) = parse_error_location(source);
+        assert_eq!((line, column), (6, 19));
+        assert!(message.contains("(\"I am alive\""), "message: {}", message);
+    }
+
+    #[test]
+    fn test_parse_error_location_survives_comments() {
+        let source = "# header comment\ninput x<Bogus> # trailing\n";
+
+        let (line, column, message) = parse_error_location(source);
+        assert_eq!((line, column), (2, 1));
+        assert!(message.contains("input x<Bogus>"), "message: {}", message);
+    }
 
     #[test]
     fn test_parse_complete_nucleus() {
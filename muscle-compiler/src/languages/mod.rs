@@ -8,7 +8,7 @@ pub mod capability_checker;
 pub mod formal_grammar;
 
 // Re-export the main components for easy access
-pub use capability_checker::{verify_sacred_rules, CapabilityChecker};
+pub use capability_checker::{liveness_warnings, verify_sacred_rules, CapabilityChecker};
 pub use formal_grammar::FormalParser;
 
 use crate::ast::{
@@ -1,4 +1,4 @@
-use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use clap::{ArgAction, Parser};
 use std::fs;
 use std::path::PathBuf;
 use std::process;
@@ -23,88 +23,80 @@ impl From<parser::ParseError> for CompileError {
         CompileError::SyntaxError(error.to_string())
     }
 }
-use languages::capability_checker::{verify_sacred_rules, CapabilityChecker};
+use languages::capability_checker::{liveness_warnings, verify_sacred_rules, CapabilityChecker};
 use languages::formal_grammar::FormalParser;
 use parser::PythonParser;
 
-fn main() {
-    let matches = build_cli().get_matches();
+/// Compiles Python NN definitions or Muscle.ea sources to encrypted muscle blobs
+#[derive(Debug, Parser, PartialEq, Eq)]
+#[command(
+    name = "Muscle Compiler v5.0 - Wizard Stack",
+    version = "5.0.0",
+    author = "Eä Foundation"
+)]
+struct Cli {
+    /// Input Python file (.py) or Nucleus source (.ea)
+    #[arg(short = 'i', long, value_name = "FILE")]
+    input: String,
+
+    /// Output encrypted blob file
+    #[arg(short = 'o', long, value_name = "FILE")]
+    output: String,
+
+    /// Target architecture (aarch64, x86_64, nucleus)
+    #[arg(short = 't', long, value_name = "ARCH", default_value = "aarch64")]
+    target: String,
+
+    /// 32-byte hex chaos master key for encryption
+    #[arg(long = "chaos-master", value_name = "KEY")]
+    chaos_master: String,
+
+    /// Enable verbose output
+    #[arg(short = 'v', long, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Only verify the source code, don't compile
+    #[arg(long = "verify-only")]
+    verify_only: bool,
 
-    if let Err(e) = run(&matches) {
-        eprintln!("❌ Error: {}", e);
-        process::exit(1);
-    }
+    /// Dump the parsed AST for debugging
+    #[arg(long = "dump-ast")]
+    dump_ast: bool,
+
+    /// Print a labelled disassembly of the generated Nucleus code
+    #[cfg(feature = "disasm")]
+    #[arg(long = "emit-asm")]
+    emit_asm: bool,
 }
 
-fn build_cli() -> Command {
-    Command::new("Muscle Compiler v5.0 - Wizard Stack")
-        .version("5.0.0")
-        .author("Eä Foundation")
-        .about("Compiles Python NN definitions or Muscle.ea sources to encrypted muscle blobs")
-        .arg(
-            Arg::new("input")
-                .short('i')
-                .long("input")
-                .value_name("FILE")
-                .help("Input Python file (.py) or Nucleus source (.ea)")
-                .required(true)
-                .value_parser(value_parser!(String)),
-        )
-        .arg(
-            Arg::new("output")
-                .short('o')
-                .long("output")
-                .value_name("FILE")
-                .help("Output encrypted blob file")
-                .required(true)
-                .value_parser(value_parser!(String)),
-        )
-        .arg(
-            Arg::new("target")
-                .short('t')
-                .long("target")
-                .value_name("ARCH")
-                .help("Target architecture (aarch64, x86_64, nucleus)")
-                .default_value("aarch64")
-                .value_parser(value_parser!(String)),
-        )
-        .arg(
-            Arg::new("chaos-master")
-                .long("chaos-master")
-                .value_name("KEY")
-                .help("32-byte hex chaos master key for encryption")
-                .required(true)
-                .value_parser(value_parser!(String)),
-        )
-        .arg(
-            Arg::new("verbose")
-                .short('v')
-                .long("verbose")
-                .help("Enable verbose output")
-                .action(ArgAction::Count),
-        )
-        .arg(
-            Arg::new("verify-only")
-                .long("verify-only")
-                .help("Only verify the source code, don't compile")
-                .action(ArgAction::SetTrue),
-        )
-        .arg(
-            Arg::new("dump-ast")
-                .long("dump-ast")
-                .help("Dump the parsed AST for debugging")
-                .action(ArgAction::SetTrue),
-        )
+fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(&cli) {
+        match e {
+            CompileError::ParseError {
+                message,
+                line,
+                column,
+            } => eprintln!("❌ {}:{}:{}: {}", cli.input, line, column, message),
+            e => eprintln!("❌ Error: {}", e),
+        }
+        process::exit(1);
+    }
 }
 
-fn run(matches: &ArgMatches) -> Result<(), CompileError> {
-    let input_file = matches.get_one::<String>("input").unwrap();
-    let output_file = matches.get_one::<String>("output").unwrap();
-    let target_arch = matches.get_one::<String>("target").unwrap();
-    let chaos_master_hex = matches.get_one::<String>("chaos-master").unwrap();
-    let verbose = matches.get_count("verbose") > 0;
-    let verify_only = matches.get_flag("verify-only");
-    let dump_ast = matches.get_flag("dump-ast");
+fn run(cli: &Cli) -> Result<(), CompileError> {
+    let input_file = &cli.input;
+    let output_file = &cli.output;
+    let target_arch = &cli.target;
+    let chaos_master_hex = &cli.chaos_master;
+    let verbose = cli.verbose > 0;
+    let verify_only = cli.verify_only;
+    let dump_ast = cli.dump_ast;
+    #[cfg(feature = "disasm")]
+    let emit_asm = cli.emit_asm;
+    #[cfg(not(feature = "disasm"))]
+    let emit_asm = false;
 
     if verbose {
         println!("🔧 Muscle Compiler v5.0 - Wizard Stack Specification");
@@ -148,6 +140,7 @@ fn run(matches: &ArgMatches) -> Result<(), CompileError> {
             verbose,
             verify_only,
             dump_ast,
+            emit_asm,
         )
     } else if input_path
         .extension()
@@ -172,6 +165,7 @@ fn compile_ea_source_full_spec(
     verbose: bool,
     verify_only: bool,
     dump_ast: bool,
+    emit_asm: bool,
 ) -> Result<(), CompileError> {
     if verbose {
         println!("🎯 Compiling .ea source with Wizard Stack Specification");
@@ -249,6 +243,11 @@ fn compile_ea_source_full_spec(
         println!("      - Event-driven architecture");
         println!("      - Capability-security enforced");
         println!("      - No polling constructs");
+        println!("      - Exactly one on_boot rule");
+    }
+
+    for warning in liveness_warnings(&program) {
+        eprintln!("⚠️  Warning: {}", warning);
     }
 
     if verify_only {
@@ -261,7 +260,11 @@ fn compile_ea_source_full_spec(
         println!("   🔨 Generating machine code with capability enforcement...");
     }
 
-    let machine_code = NucleusCodegen::generate(&program)?;
+    let (machine_code, labels) = NucleusCodegen::generate_with_labels(&program)?;
+
+    if emit_asm {
+        print_listing(&machine_code, &labels)?;
+    }
 
     if verbose {
         println!("   ✅ Generated machine code: {} bytes", machine_code.len());
@@ -303,6 +306,21 @@ fn compile_ea_source_full_spec(
     Ok(())
 }
 
+/// Print the labelled disassembly requested by `--emit-asm`
+#[cfg(feature = "disasm")]
+fn print_listing(machine_code: &[u8], labels: &[(String, usize)]) -> Result<(), CompileError> {
+    print!("{}", codegen::disasm::disassemble(machine_code, labels));
+    Ok(())
+}
+
+/// Listings need the bad64 decoder behind the `disasm` feature
+#[cfg(not(feature = "disasm"))]
+fn print_listing(_machine_code: &[u8], _labels: &[(String, usize)]) -> Result<(), CompileError> {
+    Err(CompileError::CodegenError(
+        "--emit-asm requires musclec built with the disasm feature".to_string(),
+    ))
+}
+
 /// EXISTING: Compile Python source file to neural network muscle blob
 fn compile_python_source(
     input_file: &str,
@@ -426,6 +444,7 @@ rule on_self_integrity_failure:
             false,
             false,
             false,
+            false,
         );
 
         assert!(result.is_ok(), "Compilation failed: {:?}", result.err());
@@ -471,6 +490,150 @@ rule on_boot:
         assert!(checker.verify_program(&program).is_err());
     }
 
+    #[test]
+    fn test_cli_parses_all_flags() {
+        let key = "ab".repeat(32);
+        let cli = Cli::try_parse_from([
+            "musclec",
+            "-i",
+            "cell.ea",
+            "-o",
+            "cell.blob",
+            "-t",
+            "nucleus",
+            "--chaos-master",
+            &key,
+            "-vv",
+            "--verify-only",
+            "--dump-ast",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli,
+            Cli {
+                input: "cell.ea".to_string(),
+                output: "cell.blob".to_string(),
+                target: "nucleus".to_string(),
+                chaos_master: key.clone(),
+                verbose: 2,
+                verify_only: true,
+                dump_ast: true,
+                #[cfg(feature = "disasm")]
+                emit_asm: false,
+            }
+        );
+
+        let cli = Cli::try_parse_from([
+            "musclec",
+            "--input",
+            "net.py",
+            "--output",
+            "net.blob",
+            "--chaos-master",
+            &key,
+        ])
+        .unwrap();
+        assert_eq!(cli.target, "aarch64");
+        assert_eq!(cli.verbose, 0);
+        assert!(!cli.verify_only && !cli.dump_ast);
+
+        // --chaos-master is required
+        assert!(Cli::try_parse_from(["musclec", "-i", "a.ea", "-o", "a.blob"]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "disasm")]
+    fn test_emit_asm_lists_labelled_instructions() {
+        let source = r#"
+input lattice_stream<MuscleUpdate>
+input hardware_attestation<DeviceProof>
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    emit heartbeat("I am alive")
+"#;
+
+        let program = FormalParser::parse_program(source).unwrap();
+        let (code, labels) = NucleusCodegen::generate_with_labels(&program).unwrap();
+        let listing = codegen::disasm::disassemble(&code, &labels);
+
+        assert!(listing.starts_with("_start:\n"), "{}", listing);
+        let has_ret = listing.lines().any(|line| line.ends_with("  ret"));
+        assert!(has_ret, "no ret in listing:\n{}", listing);
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), source).unwrap();
+        let output_file = NamedTempFile::new().unwrap();
+        let result = compile_ea_source_full_spec(
+            temp_file.path().to_str().unwrap(),
+            output_file.path().to_str().unwrap(),
+            "nucleus",
+            &[0u8; 32],
+            false,
+            false,
+            false,
+            true, // emit-asm
+        );
+        assert!(result.is_ok(), "Compilation failed: {:?}", result.err());
+
+        let key = "ab".repeat(32);
+        let cli = Cli::try_parse_from([
+            "musclec",
+            "-i",
+            "a.ea",
+            "-o",
+            "a.blob",
+            "--chaos-master",
+            &key,
+            "--emit-asm",
+        ])
+        .unwrap();
+        assert!(cli.emit_asm);
+    }
+
+    #[test]
+    #[cfg(not(feature = "disasm"))]
+    fn test_emit_asm_needs_disasm_feature() {
+        let source = r#"
+input lattice_stream<MuscleUpdate>
+input hardware_attestation<DeviceProof>
+capability emit_update(blob: SealedBlob)
+
+rule on_boot:
+    emit heartbeat("I am alive")
+"#;
+
+        let temp_file = NamedTempFile::new().unwrap();
+        fs::write(temp_file.path(), source).unwrap();
+        let output_file = NamedTempFile::new().unwrap();
+        let result = compile_ea_source_full_spec(
+            temp_file.path().to_str().unwrap(),
+            output_file.path().to_str().unwrap(),
+            "nucleus",
+            &[0u8; 32],
+            false,
+            false,
+            false,
+            true, // emit-asm
+        );
+        assert!(matches!(result, Err(CompileError::CodegenError(_))));
+
+        // The flag itself is only accepted when the feature is built in
+        let key = "ab".repeat(32);
+        let args = [
+            "musclec",
+            "-i",
+            "a.ea",
+            "-o",
+            "a.blob",
+            "--chaos-master",
+            &key,
+            "--emit-asm",
+        ];
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+
     #[test]
     fn test_chaos_key_parsing() {
         let valid_key = "a".repeat(64);
@@ -507,6 +670,7 @@ rule on_boot:
             false,
             true, // verify-only
             false,
+            false,
         );
 
         assert!(result.is_ok());
@@ -42,10 +42,24 @@ fn encode_hex(bytes: &[u8]) -> String {
 pub struct AxonSignal {
     /// Sealed organelles to execute in parallel (synaptic terminals)
     pub organelles: Vec<SealedBlob>,
+    /// Per-terminal firing priority, parallel to `organelles`; terminals
+    /// without an entry take the signal's urgency
+    pub priorities: Vec<u8>,
     /// Neural signal metadata (neurotransmitter profile)
     pub metadata: SignalMetadata,
 }
 
+impl AxonSignal {
+    /// Firing priority of the terminal at `index` (higher fires first)
+    #[must_use]
+    pub fn priority(&self, index: usize) -> u8 {
+        self.priorities
+            .get(index)
+            .copied()
+            .unwrap_or(self.metadata.urgency)
+    }
+}
+
 /// Outgoing action potential — carries transformed signal downstream
 #[derive(Debug, Clone)]
 pub struct AxonPulse {
@@ -79,12 +93,55 @@ impl SignalMetadata {
     }
 }
 
+/// Dendritic fan-in — how fired organelle outputs combine into the pulse payload
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AggregationMode {
+    /// Append outputs in firing order
+    #[default]
+    Concat,
+    /// XOR outputs byte-wise; shorter outputs are zero-padded to the longest
+    XorFold,
+    /// Add outputs byte-wise (wrapping); shorter outputs are zero-padded to the longest
+    SumBytes,
+    /// Keep the lexicographically largest output
+    TakeMax,
+}
+
+impl AggregationMode {
+    /// Fold one organelle output into the running payload
+    fn absorb(self, payload: &mut Vec<u8>, output: &[u8]) {
+        match self {
+            Self::Concat => payload.extend_from_slice(output),
+            Self::XorFold | Self::SumBytes => {
+                if payload.len() < output.len() {
+                    payload.resize(output.len(), 0);
+                }
+                for (acc, &byte) in payload.iter_mut().zip(output) {
+                    *acc = if self == Self::XorFold {
+                        *acc ^ byte
+                    } else {
+                        acc.wrapping_add(byte)
+                    };
+                }
+            }
+            Self::TakeMax => {
+                if output > payload.as_slice() {
+                    payload.clear();
+                    payload.extend_from_slice(output);
+                }
+            }
+        }
+    }
+}
+
 /// AxonWasmMuscle v1 "Giant Squid Axon" — the first true streaming neural fiber
 pub struct AxonWasmMuscle<R: RngCore + CryptoRng = rand_core::OsRng> {
     /// Maximum concurrent organelles (synaptic terminals)
     max_parallelism: usize,
     /// Metabolic budget per action potential
     fuel_per_pulse: u64,
+    /// Fan-in mode for fired organelle outputs
+    aggregation: AggregationMode,
     _phantom: PhantomData<R>,
 }
 
@@ -93,6 +150,7 @@ impl<R: RngCore + CryptoRng> Default for AxonWasmMuscle<R> {
         Self {
             max_parallelism: 8, // Reduced for no-std compatibility
             fuel_per_pulse: 1_000_000,
+            aggregation: AggregationMode::default(),
             _phantom: PhantomData,
         }
     }
@@ -104,9 +162,17 @@ impl<R: RngCore + CryptoRng> AxonWasmMuscle<R> {
         Self {
             max_parallelism,
             fuel_per_pulse,
+            aggregation: AggregationMode::default(),
             _phantom: PhantomData,
         }
     }
+
+    /// Select how fired organelle outputs combine into the pulse payload
+    #[must_use]
+    pub fn with_aggregation(mut self, aggregation: AggregationMode) -> Self {
+        self.aggregation = aggregation;
+        self
+    }
 }
 
 impl<R: RngCore + CryptoRng> Muscle<R> for AxonWasmMuscle<R> {
@@ -143,6 +209,7 @@ impl<'a, R: RngCore + CryptoRng, R2: RngCore + CryptoRng> AxonFiber<'a, R, R2> {
         ctx: &'a mut MuscleContext<R2>,
         signal: AxonSignal,
     ) -> Result<Self, MuscleError> {
+        ctx.current_blob().validate_for_version()?;
         Ok(Self {
             muscle,
             ctx,
@@ -155,30 +222,39 @@ impl<'a, R: RngCore + CryptoRng, R2: RngCore + CryptoRng> AxonFiber<'a, R, R2> {
 
     /// Propagate the action potential — synchronous parallel organelle execution
     fn propagate(&mut self) -> Result<AxonPulse, MuscleError> {
-        // Execute organelles with limited parallelism (synaptic firing)
-        for blob in self
-            .incoming
-            .organelles
-            .iter()
-            .take(self.muscle.max_parallelism)
-        {
+        self.fire_terminals(|fiber, blob| fiber.fire_organelle_sync(blob));
+        self.summate_pulse()
+    }
+
+    /// Terminal indices in firing order: highest priority first, ties in
+    /// arrival order, limited to the muscle's parallelism
+    fn firing_order(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.incoming.organelles.len()).collect();
+        order.sort_by_key(|&index| core::cmp::Reverse(self.incoming.priority(index)));
+        order.truncate(self.muscle.max_parallelism);
+        order
+    }
+
+    /// Fire terminals in priority order until the fuel budget runs out, so
+    /// under a tight budget the most urgent terminals are the ones that fire
+    fn fire_terminals<F>(&mut self, mut fire: F)
+    where
+        F: FnMut(&Self, &SealedBlob) -> Result<MuscleOutput<Vec<u8>>, MuscleError>,
+    {
+        for index in self.firing_order() {
             if self.fuel_remaining == 0 {
                 break; // Refractory period - no more firing
             }
 
-            match self.fire_organelle_sync(blob) {
+            match fire(self, &self.incoming.organelles[index]) {
                 Ok(output) => {
                     self.fuel_remaining = self.fuel_remaining.saturating_sub(50_000);
                     self.fired_organelles.push_back(output);
                 }
-                Err(e) => {
-                    // Failed organelles don't propagate but don't stop others
-                    continue;
-                }
+                // Failed organelles don't propagate but don't stop others
+                Err(_) => continue,
             }
         }
-
-        self.summate_pulse()
     }
 
     /// Fire a single organelle synchronously (synaptic terminal)
@@ -202,7 +278,7 @@ impl<'a, R: RngCore + CryptoRng, R2: RngCore + CryptoRng> AxonFiber<'a, R, R2> {
 
         // Summate outputs from all fired organelles
         while let Some(output) = self.fired_organelles.pop_front() {
-            payload.extend_from_slice(&output.output);
+            self.muscle.aggregation.absorb(&mut payload, &output.output);
             self.successors.extend(output.successors);
         }
 
@@ -279,6 +355,7 @@ mod tests {
     fn test_axon_signal_creation() {
         let signal = AxonSignal {
             organelles: Vec::new(),
+            priorities: Vec::new(),
             metadata: SignalMetadata::new(1, 150, [0xAA; 8]),
         };
 
@@ -302,6 +379,7 @@ mod tests {
 
         let signal = AxonSignal {
             organelles: Vec::new(),
+            priorities: Vec::new(),
             metadata: SignalMetadata::new(0, 0, [0; 8]),
         };
 
@@ -309,6 +387,30 @@ mod tests {
         assert_eq!(fiber.fuel_remaining, muscle.fuel_per_pulse);
     }
 
+    #[test]
+    fn test_oversized_axon_blob_is_rejected() {
+        use muscle_ea_core::constants::{AXON_VERSION, MAX_MUSCLE_SIZE};
+
+        let muscle = AxonWasmMuscle::<OsRng>::default();
+        let blob = SealedBlob::new(
+            vec![0; MAX_MUSCLE_SIZE + 1],
+            MuscleSalt::new([0; 16]),
+            AXON_VERSION,
+        );
+        let mut ctx = MuscleContext::new(blob, [0; 32], OsRng);
+
+        let signal = AxonSignal {
+            organelles: Vec::new(),
+            priorities: Vec::new(),
+            metadata: SignalMetadata::new(0, 0, [0; 8]),
+        };
+
+        assert!(matches!(
+            muscle.execute(&mut ctx, signal),
+            Err(MuscleError::InvalidBlob)
+        ));
+    }
+
     #[test]
     fn test_refractory_trace_generation() {
         let muscle = AxonWasmMuscle::<OsRng>::default();
@@ -317,6 +419,7 @@ mod tests {
 
         let signal = AxonSignal {
             organelles: Vec::new(),
+            priorities: Vec::new(),
             metadata: SignalMetadata::new(0, 0, [0xBB; 8]),
         };
 
@@ -335,6 +438,7 @@ mod tests {
         // High urgency signal should trigger myelinated continuation
         let signal = AxonSignal {
             organelles: Vec::new(),
+            priorities: Vec::new(),
             metadata: SignalMetadata::new(0, 250, [0xCC; 8]), // High urgency
         };
 
@@ -349,4 +453,85 @@ mod tests {
             "myelinated_continuation"
         );
     }
+
+    #[test]
+    fn test_highest_priority_terminals_fire_under_tight_fuel() {
+        // Budget for exactly two firings across five terminals
+        let muscle = AxonWasmMuscle::<OsRng>::new(8, 100_000);
+        let blob = SealedBlob::new(Vec::new(), MuscleSalt::new([0; 16]), 1);
+        let mut ctx = MuscleContext::new(blob, [0; 32], OsRng);
+        let signal = AxonSignal {
+            organelles: (0..5u8)
+                .map(|id| SealedBlob::new(vec![id], MuscleSalt::new([id; 16]), 1))
+                .collect(),
+            priorities: vec![10, 250, 30, 200],
+            metadata: SignalMetadata::new(0, 90, [0; 8]),
+        };
+        assert_eq!(signal.priority(4), 90);
+
+        let mut fiber = AxonFiber::new(&muscle, &mut ctx, signal).unwrap();
+        fiber.fire_terminals(|_, blob| {
+            Ok(MuscleOutput {
+                output: blob.payload.clone(),
+                successors: Vec::new(),
+            })
+        });
+        let pulse = fiber.summate_pulse().unwrap();
+
+        assert_eq!(pulse.intensity, 2);
+        assert_eq!(pulse.payload.to_vec(), [1, 3]);
+    }
+
+    fn summate_with(mode: AggregationMode, outputs: [&[u8]; 3]) -> Vec<u8> {
+        let muscle = AxonWasmMuscle::<OsRng>::default().with_aggregation(mode);
+        let blob = SealedBlob::new(Vec::new(), MuscleSalt::new([0; 16]), 1);
+        let mut ctx = MuscleContext::new(blob, [0; 32], OsRng);
+        let signal = AxonSignal {
+            organelles: Vec::new(),
+            priorities: Vec::new(),
+            metadata: SignalMetadata::new(0, 0, [0; 8]),
+        };
+
+        let mut fiber = AxonFiber::new(&muscle, &mut ctx, signal).unwrap();
+        for output in outputs {
+            fiber.fired_organelles.push_back(MuscleOutput {
+                output: output.to_vec(),
+                successors: Vec::new(),
+            });
+        }
+        let pulse = fiber.summate_pulse().unwrap();
+        assert_eq!(pulse.intensity, 3);
+        pulse.payload.to_vec()
+    }
+
+    #[test]
+    fn test_concat_aggregation() {
+        let payload = summate_with(AggregationMode::Concat, [&[1, 2], &[3], &[4, 5, 6]]);
+        assert_eq!(payload, [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_xor_fold_aggregation() {
+        let payload = summate_with(
+            AggregationMode::XorFold,
+            [&[0x0F, 0xF0], &[0xFF, 0xFF], &[0x01, 0x02]],
+        );
+        assert_eq!(payload, [0xF1, 0x0D]);
+
+        // Ragged outputs are zero-padded, so the longest tail passes through
+        let ragged = summate_with(AggregationMode::XorFold, [&[0x01], &[0x03, 0x07], &[]]);
+        assert_eq!(ragged, [0x02, 0x07]);
+    }
+
+    #[test]
+    fn test_sum_bytes_aggregation() {
+        let payload = summate_with(AggregationMode::SumBytes, [&[200, 1], &[100, 2, 9], &[1]]);
+        assert_eq!(payload, [45, 3, 9]);
+    }
+
+    #[test]
+    fn test_take_max_aggregation() {
+        let payload = summate_with(AggregationMode::TakeMax, [&[1, 9, 9], &[2], &[1, 9]]);
+        assert_eq!(payload, [2]);
+    }
 }
@@ -6,6 +6,11 @@
 use core::fmt;
 use zeroize::Zeroize;
 
+use crate::constants::{
+    AXON_VERSION, DENDRITE_VERSION, MAX_MUSCLE_SIZE, PATHFINDER_HEADER_SIZE, PATHFINDER_VERSION,
+};
+use crate::error::MuscleError;
+
 /// Salt for muscle derivation - ensures unique encryption per muscle
 #[derive(Clone, PartialEq, Eq, Hash, Zeroize)]
 #[zeroize(drop)]
@@ -73,6 +78,28 @@ impl SealedBlob {
     pub fn version(&self) -> u32 {
         self.version
     }
+
+    /// Check the payload length against the layout implied by the version.
+    ///
+    /// Muscles call this before unsealing so a truncated or oversized blob
+    /// surfaces as an error rather than an out-of-bounds slice. Versions
+    /// without a known layout accept any length.
+    ///
+    /// # Errors
+    /// Returns [`MuscleError::InvalidBlob`] if the payload is outside the
+    /// size class of its version.
+    pub fn validate_for_version(&self) -> Result<(), MuscleError> {
+        let (min, max) = match self.version {
+            PATHFINDER_VERSION => (PATHFINDER_HEADER_SIZE, usize::MAX),
+            AXON_VERSION | DENDRITE_VERSION => (0, MAX_MUSCLE_SIZE),
+            _ => (0, usize::MAX),
+        };
+        if (min..=max).contains(&self.payload.len()) {
+            Ok(())
+        } else {
+            Err(MuscleError::InvalidBlob)
+        }
+    }
 }
 
 impl fmt::Debug for SealedBlob {
@@ -156,4 +183,26 @@ mod tests {
         assert_eq!(blob.salt(), &salt);
         assert_eq!(blob.payload, payload);
     }
+
+    #[test]
+    fn test_undersized_pathfinder_blob_rejected() {
+        let salt = MuscleSalt::new([0; 16]);
+        let blob = SealedBlob::new(alloc::vec![0; 10], salt, PATHFINDER_VERSION);
+        assert!(matches!(
+            blob.validate_for_version(),
+            Err(MuscleError::InvalidBlob)
+        ));
+    }
+
+    #[test]
+    fn test_sized_pathfinder_blob_accepted() {
+        let salt = MuscleSalt::new([0; 16]);
+        let payload = alloc::vec![0; PATHFINDER_HEADER_SIZE + 32];
+        let blob = SealedBlob::new(payload, salt, PATHFINDER_VERSION);
+        assert!(blob.validate_for_version().is_ok());
+
+        let salt = MuscleSalt::new([0; 16]);
+        let oversized = SealedBlob::new(alloc::vec![0; MAX_MUSCLE_SIZE + 1], salt, AXON_VERSION);
+        assert!(oversized.validate_for_version().is_err());
+    }
 }
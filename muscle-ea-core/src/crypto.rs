@@ -4,3 +4,242 @@
 //! verification, and secure execution.
 
 pub use crate::biology::{MuscleSalt, SealedBlob, SuccessorKey};
+
+use crate::error::MuscleError;
+use aes_gcm::aead::{generic_array::GenericArray, Aead as _, KeyInit};
+use aes_gcm::Aes256Gcm;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use chacha20poly1305::ChaCha20Poly1305;
+use hmac::{Hmac, Mac};
+use sha3::Sha3_256;
+
+/// Nonce size shared by every supported AEAD suite
+pub const AEAD_NONCE_SIZE: usize = 12;
+
+/// Derive a 32-byte key from `master` for a domain and context.
+///
+/// This is a single-block HKDF-Expand over HMAC-SHA3-256 with `master` as
+/// the pseudorandom key. The info string is the domain followed by each
+/// context item, every one prefixed with its little-endian `u32` length so
+/// that no two (domain, context) pairs share an encoding.
+///
+/// # Panics
+///
+/// Panics if the domain or a context item is longer than `u32::MAX` bytes.
+#[must_use]
+pub fn derive_key(master: &[u8; 32], domain: &[u8], context: &[&[u8]]) -> [u8; 32] {
+    let mut mac =
+        <Hmac<Sha3_256> as Mac>::new_from_slice(master).expect("HMAC accepts keys of any length");
+    for part in core::iter::once(&domain).chain(context) {
+        let len = u32::try_from(part.len()).expect("KDF input exceeds u32::MAX bytes");
+        mac.update(&len.to_le_bytes());
+        mac.update(part);
+    }
+    mac.update(&[1]);
+    mac.finalize().into_bytes().into()
+}
+
+/// Identifier of the AEAD construction a blob was sealed with.
+///
+/// The discriminant is the one-byte suite id recorded in sealed blob headers.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AeadSuite {
+    /// AES-256-GCM, preferred where AES hardware is available
+    #[default]
+    Aes256Gcm = 1,
+    /// ChaCha20-Poly1305, preferred on cores without AES instructions
+    ChaCha20Poly1305 = 2,
+}
+
+impl AeadSuite {
+    /// One-byte suite id recorded in blob headers
+    #[must_use]
+    pub fn id(self) -> u8 {
+        self as u8
+    }
+
+    /// Parse a suite id read from a blob header
+    #[must_use]
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            1 => Some(Self::Aes256Gcm),
+            2 => Some(Self::ChaCha20Poly1305),
+            _ => None,
+        }
+    }
+
+    /// Cipher implementing this suite
+    #[must_use]
+    pub fn cipher(self) -> &'static dyn Aead {
+        match self {
+            Self::Aes256Gcm => &AesGcmAead,
+            Self::ChaCha20Poly1305 => &ChaChaPolyAead,
+        }
+    }
+}
+
+/// Authenticated encryption used to seal muscle blobs
+pub trait Aead {
+    /// Suite id this cipher records in blob headers
+    fn suite(&self) -> AeadSuite;
+
+    /// Encrypt and authenticate `plaintext`, returning ciphertext with tag
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MuscleError::Crypto`] if encryption fails.
+    fn seal(
+        &self,
+        key: &[u8; 32],
+        nonce: &[u8; AEAD_NONCE_SIZE],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, MuscleError>;
+
+    /// Authenticate and decrypt `ciphertext`
+    ///
+    /// # Errors
+    ///
+    /// Returns [`MuscleError::Crypto`] if the tag does not verify.
+    fn open(
+        &self,
+        key: &[u8; 32],
+        nonce: &[u8; AEAD_NONCE_SIZE],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, MuscleError>;
+}
+
+/// AES-256-GCM sealing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AesGcmAead;
+
+/// ChaCha20-Poly1305 sealing
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChaChaPolyAead;
+
+impl Aead for AesGcmAead {
+    fn suite(&self) -> AeadSuite {
+        AeadSuite::Aes256Gcm
+    }
+
+    fn seal(
+        &self,
+        key: &[u8; 32],
+        nonce: &[u8; AEAD_NONCE_SIZE],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, MuscleError> {
+        Aes256Gcm::new(GenericArray::from_slice(key))
+            .encrypt(GenericArray::from_slice(nonce), plaintext)
+            .map_err(|_| MuscleError::Crypto("encryption failed".to_string()))
+    }
+
+    fn open(
+        &self,
+        key: &[u8; 32],
+        nonce: &[u8; AEAD_NONCE_SIZE],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, MuscleError> {
+        Aes256Gcm::new(GenericArray::from_slice(key))
+            .decrypt(GenericArray::from_slice(nonce), ciphertext)
+            .map_err(|_| MuscleError::Crypto("decryption failed".to_string()))
+    }
+}
+
+impl Aead for ChaChaPolyAead {
+    fn suite(&self) -> AeadSuite {
+        AeadSuite::ChaCha20Poly1305
+    }
+
+    fn seal(
+        &self,
+        key: &[u8; 32],
+        nonce: &[u8; AEAD_NONCE_SIZE],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, MuscleError> {
+        ChaCha20Poly1305::new(GenericArray::from_slice(key))
+            .encrypt(GenericArray::from_slice(nonce), plaintext)
+            .map_err(|_| MuscleError::Crypto("encryption failed".to_string()))
+    }
+
+    fn open(
+        &self,
+        key: &[u8; 32],
+        nonce: &[u8; AEAD_NONCE_SIZE],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, MuscleError> {
+        ChaCha20Poly1305::new(GenericArray::from_slice(key))
+            .decrypt(GenericArray::from_slice(nonce), ciphertext)
+            .map_err(|_| MuscleError::Crypto("decryption failed".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7u8; 32];
+    const NONCE: [u8; AEAD_NONCE_SIZE] = [9u8; AEAD_NONCE_SIZE];
+
+    fn roundtrip(suite: AeadSuite) {
+        let cipher = suite.cipher();
+        assert_eq!(cipher.suite(), suite);
+        assert_eq!(AeadSuite::from_id(suite.id()), Some(suite));
+
+        let sealed = cipher.seal(&KEY, &NONCE, b"muscle payload").unwrap();
+        assert_ne!(&sealed[..14], b"muscle payload");
+        let opened = cipher.open(&KEY, &NONCE, &sealed).unwrap();
+        assert_eq!(opened, b"muscle payload");
+    }
+
+    #[test]
+    fn test_aes_gcm_roundtrip() {
+        roundtrip(AeadSuite::Aes256Gcm);
+    }
+
+    #[test]
+    fn test_chacha_poly_roundtrip() {
+        roundtrip(AeadSuite::ChaCha20Poly1305);
+    }
+
+    #[test]
+    fn test_derive_key_vectors() {
+        assert_eq!(
+            hex::encode(derive_key(&[0u8; 32], b"EA_TEST", &[])),
+            "671f6ce8849a7d3b27bfd90d7a08ccdf6d71a653a7bfc14036e496cce0bac1a5"
+        );
+
+        let master: [u8; 32] = core::array::from_fn(|i| u8::try_from(i).unwrap());
+        assert_eq!(
+            hex::encode(derive_key(
+                &master,
+                b"MUSCLE_PATHFINDER_V1_ENC",
+                &[&[2u8; 16], &[3u8; 12]]
+            )),
+            "593446ca1821df6bccb3d8229679fbd21b0fee9088fe770083f0492e18f7611b"
+        );
+    }
+
+    #[test]
+    fn test_derive_key_separates_domains_and_contexts() {
+        let base = derive_key(&KEY, b"EA_A", &[b"ctx"]);
+        assert_ne!(base, derive_key(&KEY, b"EA_B", &[b"ctx"]));
+        assert_ne!(base, derive_key(&KEY, b"EA_A", &[b"other"]));
+        assert_ne!(base, derive_key(&[8u8; 32], b"EA_A", &[b"ctx"]));
+        // Length prefixes keep shifted boundaries apart
+        assert_ne!(
+            derive_key(&KEY, b"EA_A", &[b"ab", b"c"]),
+            derive_key(&KEY, b"EA_A", &[b"a", b"bc"])
+        );
+        assert_ne!(base, derive_key(&KEY, b"EA_Actx", &[]));
+    }
+
+    #[test]
+    fn test_chacha_blob_does_not_open_as_aes() {
+        let sealed = ChaChaPolyAead
+            .seal(&KEY, &NONCE, b"muscle payload")
+            .unwrap();
+        assert!(AesGcmAead.open(&KEY, &NONCE, &sealed).is_err());
+        assert_eq!(AeadSuite::from_id(0), None);
+    }
+}
@@ -14,11 +14,13 @@ pub mod biology;
 pub mod crypto;
 pub mod error;
 pub mod runtime;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 
 // Re-export main types for easy access
 pub use biology::{MuscleSalt, SealedBlob, SuccessorKey};
 pub use error::MuscleError;
-pub use runtime::{Muscle, MuscleContext, MuscleOutput, MuscleSuccessor};
+pub use runtime::{Muscle, MuscleContext, MuscleOutput, MuscleSuccessor, Pipe};
 
 /// Core biological constants for the Eä ecosystem
 pub mod constants {
@@ -33,6 +35,18 @@ pub mod constants {
 
     /// Key size for all cryptographic operations
     pub const KEY_SIZE: usize = 32;
+
+    /// Blob version of pathfinder (WASM organelle) muscles
+    pub const PATHFINDER_VERSION: u32 = 3;
+
+    /// Blob version of axon muscles
+    pub const AXON_VERSION: u32 = 4;
+
+    /// Blob version of dendrite muscles
+    pub const DENDRITE_VERSION: u32 = 5;
+
+    /// Size of the header leading every pathfinder blob payload
+    pub const PATHFINDER_HEADER_SIZE: usize = 64;
 }
 
 /// Prelude for easy importing of core functionality
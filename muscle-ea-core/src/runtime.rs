@@ -140,6 +140,52 @@ impl<R: RngCore + CryptoRng, M: Muscle<R> + ?Sized> Muscle<R> for alloc::boxed::
     }
 }
 
+/// Composition of two muscles: `first`'s output feeds `second`'s input
+///
+/// Both run within one `execute` on the same context. Successors from
+/// `first` come before those from `second` in the combined output.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pipe<A, B> {
+    /// Muscle run on the pipe's input
+    pub first: A,
+    /// Muscle run on `first`'s output
+    pub second: B,
+}
+
+impl<A, B> Pipe<A, B> {
+    /// Pipe `first` into `second`
+    pub fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<R, A, B> Muscle<R> for Pipe<A, B>
+where
+    R: RngCore + CryptoRng,
+    A: Muscle<R>,
+    B: Muscle<R>,
+    A::PrivateOutput: Into<B::PrivateInput>,
+{
+    type PrivateInput = A::PrivateInput;
+    type PrivateOutput = B::PrivateOutput;
+
+    fn execute(
+        &self,
+        ctx: &mut MuscleContext<R>,
+        private_input: Self::PrivateInput,
+    ) -> Result<MuscleOutput<Self::PrivateOutput>, MuscleError> {
+        let first = self.first.execute(ctx, private_input)?;
+        let mut second = self.second.execute(ctx, first.output.into())?;
+
+        let mut successors = first.successors;
+        successors.append(&mut second.successors);
+        Ok(MuscleOutput {
+            output: second.output,
+            successors,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -164,6 +210,36 @@ mod tests {
         }
     }
 
+    /// Passes input through or increments every byte, leaving a successor
+    /// tagged with its name either way.
+    struct Tagged {
+        name: &'static str,
+        increment: bool,
+    }
+
+    impl<R: RngCore + CryptoRng> Muscle<R> for Tagged {
+        type PrivateInput = alloc::vec::Vec<u8>;
+        type PrivateOutput = alloc::vec::Vec<u8>;
+
+        fn execute(
+            &self,
+            _ctx: &mut MuscleContext<R>,
+            input: Self::PrivateInput,
+        ) -> Result<MuscleOutput<Self::PrivateOutput>, MuscleError> {
+            let output = input
+                .into_iter()
+                .map(|b| b.wrapping_add(u8::from(self.increment)))
+                .collect();
+            Ok(MuscleOutput {
+                output,
+                successors: alloc::vec![MuscleSuccessor {
+                    blob: SealedBlob::new(alloc::vec![], MuscleSalt::new([0; 16]), 1),
+                    metadata: SuccessorMetadata::new(1, self.name.into()),
+                }],
+            })
+        }
+    }
+
     #[test]
     fn test_muscle_trait_implementation() {
         let muscle = TestMuscle;
@@ -195,4 +271,30 @@ mod tests {
 
         assert_eq!(result.output, input);
     }
+
+    #[test]
+    fn test_pipe_composes_outputs_and_successors() {
+        let pipe = Pipe::new(
+            Tagged {
+                name: "identity",
+                increment: false,
+            },
+            Tagged {
+                name: "increment",
+                increment: true,
+            },
+        );
+
+        let blob = SealedBlob::new(alloc::vec![], MuscleSalt::new([0; 16]), 1);
+        let mut ctx = MuscleContext::new(blob, [0u8; 32], OsRng);
+        let result = pipe.execute(&mut ctx, alloc::vec![1, 2, 0xFF]).unwrap();
+
+        assert_eq!(result.output, alloc::vec![2, 3, 0]);
+        let types: alloc::vec::Vec<_> = result
+            .successors
+            .iter()
+            .map(|s| s.metadata.muscle_type.as_str())
+            .collect();
+        assert_eq!(types, ["identity", "increment"]);
+    }
 }
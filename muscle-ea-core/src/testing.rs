@@ -0,0 +1,188 @@
+//! Test doubles for exercising muscle runtime plumbing
+//!
+//! Enabled by the `test-utils` feature. Nothing here is suitable for
+//! production: [`TestRng`] is deterministic and only claims to be a
+//! `CryptoRng` so it can drive a [`MuscleContext`].
+
+use crate::biology::SealedBlob;
+use crate::error::MuscleError;
+use crate::runtime::{Muscle, MuscleContext, MuscleOutput, MuscleSuccessor};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use rand_core::{impls, CryptoRng, RngCore};
+
+/// Deterministic SplitMix64 generator for reproducible tests
+#[derive(Debug, Clone)]
+pub struct TestRng {
+    state: u64,
+}
+
+impl TestRng {
+    /// Create a generator that yields the same stream for the same seed
+    pub fn seed_from_u64(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RngCore for TestRng {
+    fn next_u32(&mut self) -> u32 {
+        impls::next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        impls::fill_bytes_via_next(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+// Test-only: lets deterministic contexts satisfy the `Muscle` bound.
+impl CryptoRng for TestRng {}
+
+impl MuscleContext<TestRng> {
+    /// Context over `blob` and `master_key` with a zero-seeded [`TestRng`]
+    pub fn for_test(blob: SealedBlob, master_key: [u8; 32]) -> Self {
+        Self::new(blob, master_key, TestRng::seed_from_u64(0))
+    }
+}
+
+/// One recorded invocation of a [`MockMuscle`]
+#[derive(Debug, Clone)]
+pub struct MockCall<I> {
+    /// Input the muscle was executed with
+    pub input: I,
+    /// Blob of the context it ran in
+    pub blob: SealedBlob,
+    /// Master key of the context it ran in
+    pub master_key: [u8; 32],
+}
+
+/// Muscle that returns a canned output and records every call
+#[derive(Debug)]
+pub struct MockMuscle<I, O> {
+    output: MuscleOutput<O>,
+    calls: RefCell<Vec<MockCall<I>>>,
+}
+
+impl<I, O> MockMuscle<I, O> {
+    /// Mock returning `output` with no successors
+    pub fn returning(output: O) -> Self {
+        Self::new(MuscleOutput {
+            output,
+            successors: Vec::new(),
+        })
+    }
+
+    /// Mock returning a full canned `output`
+    pub fn new(output: MuscleOutput<O>) -> Self {
+        Self {
+            output,
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Add a successor to the canned output
+    pub fn with_successor(mut self, successor: MuscleSuccessor) -> Self {
+        self.output.successors.push(successor);
+        self
+    }
+
+    /// Number of times the muscle has been executed
+    pub fn call_count(&self) -> usize {
+        self.calls.borrow().len()
+    }
+}
+
+impl<I: Clone, O> MockMuscle<I, O> {
+    /// Recorded calls, oldest first
+    pub fn calls(&self) -> Vec<MockCall<I>> {
+        self.calls.borrow().clone()
+    }
+}
+
+impl<R, I, O> Muscle<R> for MockMuscle<I, O>
+where
+    R: RngCore + CryptoRng,
+    O: Clone,
+{
+    type PrivateInput = I;
+    type PrivateOutput = O;
+
+    fn execute(
+        &self,
+        ctx: &mut MuscleContext<R>,
+        private_input: Self::PrivateInput,
+    ) -> Result<MuscleOutput<Self::PrivateOutput>, MuscleError> {
+        self.calls.borrow_mut().push(MockCall {
+            input: private_input,
+            blob: ctx.current_blob().clone(),
+            master_key: *ctx.master_key(),
+        });
+        Ok(self.output.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::biology::MuscleSalt;
+    use crate::runtime::{Pipe, SuccessorMetadata};
+
+    fn successor(name: &str) -> MuscleSuccessor {
+        MuscleSuccessor {
+            blob: SealedBlob::new(alloc::vec![], MuscleSalt::new([0; 16]), 1),
+            metadata: SuccessorMetadata::new(1, name.into()),
+        }
+    }
+
+    #[test]
+    fn test_rng_is_deterministic() {
+        let mut a = TestRng::seed_from_u64(7);
+        let mut b = TestRng::seed_from_u64(7);
+        let (mut x, mut y) = ([0u8; 13], [0u8; 13]);
+        a.fill_bytes(&mut x);
+        b.fill_bytes(&mut y);
+        assert_eq!(x, y);
+        assert_ne!(a.next_u64(), TestRng::seed_from_u64(8).next_u64());
+    }
+
+    #[test]
+    fn test_pipe_forwards_inputs_and_collects_successors() {
+        let pipe = Pipe::new(
+            MockMuscle::<Vec<u8>, Vec<u8>>::returning(alloc::vec![9, 9])
+                .with_successor(successor("first")),
+            MockMuscle::<Vec<u8>, u32>::returning(7).with_successor(successor("second")),
+        );
+
+        let blob = SealedBlob::new(alloc::vec![0xAB], MuscleSalt::new([1; 16]), 3);
+        let mut ctx = MuscleContext::for_test(blob, [5; 32]);
+        let result = pipe.execute(&mut ctx, alloc::vec![1, 2, 3]).unwrap();
+
+        assert_eq!(result.output, 7);
+        let types: Vec<_> = result
+            .successors
+            .iter()
+            .map(|s| s.metadata.muscle_type.as_str())
+            .collect();
+        assert_eq!(types, ["first", "second"]);
+
+        let first = pipe.first.calls();
+        let second = pipe.second.calls();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].input, [1, 2, 3]);
+        assert_eq!(second[0].input, [9, 9]);
+        assert_eq!(second[0].blob.version(), 3);
+        assert_eq!(second[0].master_key, [5; 32]);
+    }
+}
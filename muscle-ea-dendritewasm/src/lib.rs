@@ -115,6 +115,7 @@ impl<'a, R: RngCore + CryptoRng, R2: RngCore + CryptoRng> Dendrite<'a, R, R2> {
         ctx: &'a mut MuscleContext<R2>,
         inputs: DendriticInput,
     ) -> Result<Self, MuscleError> {
+        ctx.current_blob().validate_for_version()?;
         let mut dendrite = Self {
             muscle,
             ctx,
@@ -284,6 +285,24 @@ mod tests {
         assert_eq!(muscle.learning_rate, 0.01);
     }
 
+    #[test]
+    fn test_oversized_dendrite_blob_is_rejected() {
+        use muscle_ea_core::constants::{DENDRITE_VERSION, MAX_MUSCLE_SIZE};
+
+        let muscle = DendriteWasmMuscle::<OsRng>::default();
+        let blob = SealedBlob::new(
+            vec![0; MAX_MUSCLE_SIZE + 1],
+            MuscleSalt::new([0; 16]),
+            DENDRITE_VERSION,
+        );
+        let mut ctx = MuscleContext::new(blob, [0; 32], OsRng);
+
+        assert!(matches!(
+            muscle.execute(&mut ctx, Vec::new()),
+            Err(MuscleError::InvalidBlob)
+        ));
+    }
+
     #[test]
     fn test_dendrite_integration() {
         let muscle = DendriteWasmMuscle::<OsRng>::default();
@@ -17,12 +17,14 @@ use core::num::NonZeroUsize;
 use lru::LruCache;
 use muscle_ea_core::{
     biology::*,
+    crypto::derive_key,
     error::MuscleError,
     runtime::{Muscle, MuscleContext, MuscleOutput, MuscleSuccessor, SuccessorMetadata},
 };
 use muscle_ea_pathfinder::PathfinderMuscle;
 use rand_core::{CryptoRng, OsRng, RngCore};
 use sha3::{Digest, Sha3_256};
+use subtle::ConstantTimeEq;
 use zeroize::Zeroizing;
 
 /// Execution modes for the NeuroWasm hybrid organ
@@ -68,11 +70,61 @@ pub struct NeuroHeader {
     pub eä_code_length: u32,
 }
 
+/// Default bound on interpreted Eä instructions per execution
+pub const DEFAULT_MAX_STEPS: usize = 1 << 16;
+
+/// Default bound on organelles one hybrid execution may spawn
+pub const DEFAULT_MAX_ORGANELLE_SPAWNS: usize = 16;
+
+/// Key of an interpretation cache entry: SHA3-256 of the Eä bytecode and
+/// the input it ran on, under the interpreter's versioned domain
+pub type CacheKey = [u8; 32];
+
+/// Domain the cache keys are hashed under; bumped whenever interpretation
+/// or key derivation changes so results cached by an older interpreter
+/// never match
+const INTERPRETATION_CACHE_DOMAIN: &[u8] = b"ea-neurowasm:interpretation:v2";
+
+/// Domain of the MAC sealing exported cache snapshots
+const CACHE_SNAPSHOT_DOMAIN: &[u8] = b"ea-neurowasm:cache-snapshot:v1";
+
+fn cache_key(code: &[u8], input: &[u8]) -> CacheKey {
+    let mut hasher = Sha3_256::new();
+    hasher.update(INTERPRETATION_CACHE_DOMAIN);
+    // Length prefix keeps the code/input boundary unambiguous
+    hasher.update((code.len() as u64).to_le_bytes());
+    hasher.update(code);
+    hasher.update(input);
+    hasher.finalize().into()
+}
+
+/// Interpretation cache entries authenticated under the exporting node's
+/// key, produced by [`NeuroWasmMuscle::export_cache`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheSnapshot {
+    /// Cached outputs, least recently used first
+    pub entries: Vec<(CacheKey, Vec<u8>)>,
+    /// HMAC-SHA3-256 over `entries`
+    pub tag: [u8; 32],
+}
+
+fn snapshot_tag(key: &[u8; 32], entries: &[(CacheKey, Vec<u8>)]) -> [u8; 32] {
+    let parts: Vec<&[u8]> = entries
+        .iter()
+        .flat_map(|(hash, output)| [hash.as_slice(), output.as_slice()])
+        .collect();
+    derive_key(key, CACHE_SNAPSHOT_DOMAIN, &parts)
+}
+
 /// The first true hybrid organ — NeuroWasmMuscle v1 "Thalamus"
 pub struct NeuroWasmMuscle<R: RngCore + CryptoRng = OsRng> {
     _phantom: PhantomData<R>,
     /// Cache of interpreted Eä bytecode results (biological computation memory)
-    interpretation_cache: parking_lot::Mutex<LruCache<[u8; 32], Vec<u8>>>,
+    interpretation_cache: parking_lot::Mutex<LruCache<CacheKey, Vec<u8>>>,
+    /// Eä instruction budget per execution — the bytecode analog of WASM fuel
+    max_steps: usize,
+    /// Organelle spawn budget per hybrid execution
+    max_organelle_spawns: usize,
 }
 
 impl<R: RngCore + CryptoRng> Default for NeuroWasmMuscle<R> {
@@ -82,7 +134,78 @@ impl<R: RngCore + CryptoRng> Default for NeuroWasmMuscle<R> {
             interpretation_cache: parking_lot::Mutex::new(LruCache::new(
                 NonZeroUsize::new(64).unwrap(),
             )),
+            max_steps: DEFAULT_MAX_STEPS,
+            max_organelle_spawns: DEFAULT_MAX_ORGANELLE_SPAWNS,
+        }
+    }
+}
+
+impl<R: RngCore + CryptoRng> NeuroWasmMuscle<R> {
+    /// Limit the number of Eä instructions a single execution may run
+    #[must_use]
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Limit the number of organelles a single hybrid execution may spawn
+    #[must_use]
+    pub fn with_max_organelle_spawns(mut self, max_organelle_spawns: usize) -> Self {
+        self.max_organelle_spawns = max_organelle_spawns;
+        self
+    }
+
+    /// Snapshot the interpretation cache, least recently used first, so a
+    /// restarted muscle holding the same `key` can be warmed with
+    /// [`Self::import_cache`]
+    ///
+    /// `key` should be a node-local secret; the snapshot is authenticated
+    /// under it, not encrypted.
+    #[must_use]
+    pub fn export_cache(&self, key: &[u8; 32]) -> CacheSnapshot {
+        let entries: Vec<_> = self
+            .interpretation_cache
+            .lock()
+            .iter()
+            .rev()
+            .map(|(hash, output)| (*hash, output.clone()))
+            .collect();
+        let tag = snapshot_tag(key, &entries);
+        CacheSnapshot { entries, tag }
+    }
+
+    /// Warm the interpretation cache from an [`Self::export_cache`] snapshot,
+    /// returning how many entries were accepted
+    ///
+    /// Entries keyed under an older interpreter version simply never match.
+    /// Entries whose output is longer than this muscle's step budget could
+    /// not have been produced by it and are skipped.
+    ///
+    /// # Errors
+    /// Returns [`MuscleError::Crypto`] without touching the cache if the
+    /// snapshot was not exported under `key` or was altered since.
+    pub fn import_cache(
+        &self,
+        key: &[u8; 32],
+        snapshot: CacheSnapshot,
+    ) -> Result<usize, MuscleError> {
+        let expected = snapshot_tag(key, &snapshot.entries);
+        if expected.ct_eq(&snapshot.tag).unwrap_u8() != 1 {
+            return Err(MuscleError::Crypto(
+                "cache snapshot authentication failed".into(),
+            ));
+        }
+        let mut cache = self.interpretation_cache.lock();
+        let mut imported = 0;
+        for (hash, output) in snapshot.entries {
+            // Every output byte costs at least one instruction
+            if output.len() > self.max_steps {
+                continue;
+            }
+            cache.put(hash, output);
+            imported += 1;
         }
+        Ok(imported)
     }
 }
 
@@ -95,6 +218,8 @@ impl<R: RngCore + CryptoRng> Muscle<R> for NeuroWasmMuscle<R> {
         ctx: &mut MuscleContext<R>,
         private_input: Self::PrivateInput,
     ) -> Result<MuscleOutput<Self::PrivateOutput>, MuscleError> {
+        ctx.current_blob().validate_for_version()?;
+
         // Clone what we need from ctx before mutable operations
         let blob_payload = ctx.current_blob().payload.clone();
         let blob_salt = ctx.current_blob().salt().clone();
@@ -129,11 +254,12 @@ impl<R: RngCore + CryptoRng> NeuroWasmMuscle<R> {
         let eä_code =
             &sealed[core::mem::size_of::<NeuroHeader>()..][..header.eä_code_length as usize];
 
-        // Check cache first (biological short-term memory)
-        let code_hash: [u8; 32] = Sha3_256::digest(eä_code).into();
+        // Check cache first (biological short-term memory); the output
+        // depends on the input as well as the code
+        let cache_key = cache_key(eä_code, input);
         {
             let mut cache = self.interpretation_cache.lock();
-            if let Some(cached) = cache.get(&code_hash) {
+            if let Some(cached) = cache.get(&cache_key) {
                 return Ok(MuscleOutput {
                     output: cached.clone(),
                     successors: Vec::new(),
@@ -142,12 +268,12 @@ impl<R: RngCore + CryptoRng> NeuroWasmMuscle<R> {
         }
 
         // Interpret Eä bytecode safely
-        let result = interpret_eä_bytecode(eä_code, input, ctx)?;
+        let result = interpret_eä_bytecode(eä_code, input, self.max_steps, ctx)?;
 
         // Cache the result (biological learning)
         {
             let mut cache = self.interpretation_cache.lock();
-            cache.put(code_hash, result.output.clone());
+            cache.put(cache_key, result.output.clone());
         }
 
         Ok(result)
@@ -168,7 +294,7 @@ impl<R: RngCore + CryptoRng> NeuroWasmMuscle<R> {
         let wasm_blob = SealedBlob::new(
             sealed[header.wasm_offset as usize..][..header.wasm_length as usize].to_vec(),
             salt.clone(),
-            3, // Pathfinder version
+            muscle_ea_core::constants::PATHFINDER_VERSION,
         );
 
         let mut pathfinder_ctx = MuscleContext::new(wasm_blob, *master_key, OsRng);
@@ -204,8 +330,11 @@ impl<R: RngCore + CryptoRng> NeuroWasmMuscle<R> {
         // Safe interpretation of Eä bytecode with organelle spawning
         let mut pc = 0;
         let mut stack: Vec<u8> = Vec::new();
+        let mut steps_left = self.max_steps;
+        let mut spawns_left = self.max_organelle_spawns;
 
         while pc < code.len() {
+            steps_left = consume(steps_left)?;
             let opcode = code[pc];
             pc += 1;
 
@@ -216,6 +345,7 @@ impl<R: RngCore + CryptoRng> NeuroWasmMuscle<R> {
                 }
                 // Organelle spawn operation (0xFF)
                 0xFF => {
+                    spawns_left = consume(spawns_left)?;
                     vm.spawn_wasm_organelle()?;
                 }
             }
@@ -342,17 +472,25 @@ fn parse_neurowasm_header(sealed: &[u8]) -> Result<NeuroHeader, MuscleError> {
     })
 }
 
-/// Safe interpretation of Eä bytecode
+/// Take one unit from an execution budget, failing once it is spent
+fn consume(budget: usize) -> Result<usize, MuscleError> {
+    budget.checked_sub(1).ok_or(MuscleError::ResourceExhausted)
+}
+
+/// Safe interpretation of Eä bytecode, bounded to `max_steps` instructions
 fn interpret_eä_bytecode(
     code: &[u8],
     input: &[u8],
+    max_steps: usize,
     _ctx: &mut MuscleContext<impl RngCore + CryptoRng>,
 ) -> Result<MuscleOutput<Vec<u8>>, MuscleError> {
     // Simplified bytecode interpreter for demonstration
     let mut output = Vec::new();
     let mut stack: Vec<u8> = Vec::new();
+    let mut steps_left = max_steps;
 
     for &opcode in code {
+        steps_left = consume(steps_left)?;
         match opcode {
             // Push input bytes
             0x10..=0x1F => {
@@ -422,6 +560,25 @@ mod tests {
         assert_eq!(header.eä_code_length, 300);
     }
 
+    #[test]
+    fn test_blob_outside_its_version_size_class_is_rejected() {
+        use muscle_ea_core::constants::{PATHFINDER_HEADER_SIZE, PATHFINDER_VERSION};
+
+        // A well-formed PureEä payload, but too short for the version it claims
+        let payload = vec![0u8; PATHFINDER_HEADER_SIZE / 2];
+        let muscle = NeuroWasmMuscle::<OsRng>::default();
+        let mut ctx = MuscleContext::new(
+            SealedBlob::new(payload, MuscleSalt::new([0u8; 16]), PATHFINDER_VERSION),
+            [0u8; 32],
+            OsRng,
+        );
+
+        assert!(matches!(
+            muscle.execute(&mut ctx, Vec::new()),
+            Err(MuscleError::InvalidBlob)
+        ));
+    }
+
     #[test]
     fn test_hybrid_vm_organelle_spawning() {
         let wasm_blob = vec![0x01, 0x02, 0x03];
@@ -439,4 +596,122 @@ mod tests {
             "evolved_organelle"
         );
     }
+
+    #[test]
+    fn test_step_limit_exhausts_deterministically() {
+        let mut ctx = MuscleContext::new(
+            SealedBlob::new(Vec::new(), MuscleSalt::new([0u8; 16]), 0),
+            [0u8; 32],
+            OsRng,
+        );
+        let code = [0x10, 0x20, 0x10, 0x20];
+        let input = [7u8];
+
+        let result = interpret_eä_bytecode(&code, &input, 4, &mut ctx).unwrap();
+        assert_eq!(result.output, vec![7, 7]);
+        for _ in 0..2 {
+            assert!(matches!(
+                interpret_eä_bytecode(&code, &input, 3, &mut ctx),
+                Err(MuscleError::ResourceExhausted)
+            ));
+        }
+
+        let muscle = NeuroWasmMuscle::<OsRng>::default().with_max_steps(3);
+        let mut vm = HybridVm::new(Vec::new(), Vec::new());
+        assert!(matches!(
+            muscle.interpret_eä_with_organelles(&[0x01, 0x02, 0x80, 0x01], &mut vm),
+            Err(MuscleError::ResourceExhausted)
+        ));
+    }
+
+    #[test]
+    fn test_exported_cache_warms_a_fresh_muscle() {
+        let code = [0x10, 0x20];
+        let mut sealed = vec![0u8; core::mem::size_of::<NeuroHeader>()];
+        sealed.extend_from_slice(&code);
+        let header = NeuroHeader {
+            mode: NeuroMode::PureEä,
+            wasm_offset: 0,
+            wasm_length: 0,
+            successor_count: 0,
+            eä_code_length: 2,
+        };
+        let mut ctx = MuscleContext::new(
+            SealedBlob::new(Vec::new(), MuscleSalt::new([0u8; 16]), 0),
+            [0u8; 32],
+            OsRng,
+        );
+
+        let key = [3u8; 32];
+
+        let warm = NeuroWasmMuscle::<OsRng>::default();
+        let first = warm
+            .execute_native_eä(&sealed, &[7], &mut ctx, &header)
+            .unwrap();
+        assert_eq!(first.output, vec![7]);
+        let snapshot = warm.export_cache(&key);
+        assert_eq!(snapshot.entries, vec![(cache_key(&code, &[7]), vec![7])]);
+
+        let restarted = NeuroWasmMuscle::<OsRng>::default();
+        assert_eq!(restarted.import_cache(&key, snapshot.clone()).unwrap(), 1);
+        let hit = restarted
+            .execute_native_eä(&sealed, &[7], &mut ctx, &header)
+            .unwrap();
+        assert_eq!(hit.output, vec![7]);
+        assert_eq!(restarted.export_cache(&key), snapshot);
+
+        // The same code on a new input is interpreted afresh
+        let miss = restarted
+            .execute_native_eä(&sealed, &[9], &mut ctx, &header)
+            .unwrap();
+        assert_eq!(miss.output, vec![9]);
+        assert_eq!(restarted.export_cache(&key).entries.len(), 2);
+
+        let bounded = NeuroWasmMuscle::<OsRng>::default().with_max_steps(1);
+        let entries = vec![([1u8; 32], vec![0, 0])];
+        let tag = snapshot_tag(&key, &entries);
+        let oversized = CacheSnapshot { entries, tag };
+        assert_eq!(bounded.import_cache(&key, oversized).unwrap(), 0);
+        assert!(bounded.export_cache(&key).entries.is_empty());
+    }
+
+    #[test]
+    fn test_tampered_cache_snapshot_is_rejected() {
+        let key = [3u8; 32];
+        let entries = vec![([1u8; 32], vec![7])];
+        let tag = snapshot_tag(&key, &entries);
+        let snapshot = CacheSnapshot { entries, tag };
+        let muscle = NeuroWasmMuscle::<OsRng>::default();
+
+        let mut forged = snapshot.clone();
+        forged.entries[0].1 = vec![8];
+        assert!(matches!(
+            muscle.import_cache(&key, forged),
+            Err(MuscleError::Crypto(_))
+        ));
+        assert!(matches!(
+            muscle.import_cache(&[4u8; 32], snapshot.clone()),
+            Err(MuscleError::Crypto(_))
+        ));
+        assert!(muscle.export_cache(&key).entries.is_empty());
+
+        assert_eq!(muscle.import_cache(&key, snapshot).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_spawn_limit_exhausts_deterministically() {
+        let muscle = NeuroWasmMuscle::<OsRng>::default().with_max_organelle_spawns(2);
+
+        let mut vm = HybridVm::new(vec![0x01], vec![0x10]);
+        muscle
+            .interpret_eä_with_organelles(&[0xFF, 0xFF], &mut vm)
+            .unwrap();
+        assert_eq!(vm.into_result().successors.len(), 2);
+
+        let mut vm = HybridVm::new(vec![0x01], vec![0x10]);
+        assert!(matches!(
+            muscle.interpret_eä_with_organelles(&[0xFF, 0xFF, 0xFF], &mut vm),
+            Err(MuscleError::ResourceExhausted)
+        ));
+    }
 }
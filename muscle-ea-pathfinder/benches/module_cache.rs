@@ -0,0 +1,38 @@
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use muscle_ea_pathfinder::{ModuleCache, PathfinderMuscle};
+use rand_core::OsRng;
+
+#[allow(dead_code)]
+#[path = "../src/test_organelles.rs"]
+mod test_organelles;
+
+use test_organelles::HELLO_ORGANELLE;
+
+const FIRES: usize = 1000;
+
+fn benchmark_repeated_firing(c: &mut Criterion) {
+    let fresh = PathfinderMuscle::<OsRng>::default();
+    let cache = Arc::new(ModuleCache::new(NonZeroUsize::new(16).unwrap()).unwrap());
+    let cached = PathfinderMuscle::<OsRng>::default().with_module_cache(cache);
+
+    let mut group = c.benchmark_group("pathfinder_fire_1000");
+    group.sample_size(10);
+    for (name, muscle) in [("fresh_compile", &fresh), ("module_cache", &cached)] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                for _ in 0..FIRES {
+                    muscle
+                        .execute_organelle(black_box(HELLO_ORGANELLE), &[])
+                        .unwrap();
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_repeated_firing);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+//! Compiled organelle cache — the muscle memory of the pathfinder.
+//!
+//! Compiling WASM dominates the cost of firing small organelles, and the
+//! same organelle is often fired many times in a row. The cache keeps
+//! compiled modules keyed by the SHA3-256 of their bytes.
+
+use core::fmt;
+use core::num::NonZeroUsize;
+
+use lru::LruCache;
+use muscle_ea_core::error::MuscleError;
+use parking_lot::Mutex;
+use sha3::{Digest, Sha3_256};
+use wasmtime::{Engine, Module};
+
+use crate::pathfinder_engine;
+
+/// Bounded LRU of compiled organelle modules.
+///
+/// A module is only valid for the engine that compiled it, so the cache
+/// owns the engine all of its modules run on.
+pub struct ModuleCache {
+    engine: Engine,
+    modules: Mutex<LruCache<[u8; 32], Module>>,
+}
+
+impl ModuleCache {
+    /// Create a cache holding at most `capacity` compiled modules.
+    ///
+    /// # Errors
+    /// Returns [`MuscleError::IsolationFailure`] if the engine cannot be built.
+    pub fn new(capacity: NonZeroUsize) -> Result<Self, MuscleError> {
+        Ok(Self {
+            engine: pathfinder_engine()?,
+            modules: Mutex::new(LruCache::new(capacity)),
+        })
+    }
+
+    /// Number of compiled modules currently cached.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.modules.lock().len()
+    }
+
+    /// Whether no modules are cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.modules.lock().is_empty()
+    }
+
+    pub(crate) fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    /// Compiled module for `wasm`, compiling and caching it on a miss.
+    pub(crate) fn module(&self, wasm: &[u8]) -> Result<Module, MuscleError> {
+        let key: [u8; 32] = Sha3_256::digest(wasm).into();
+        if let Some(module) = self.modules.lock().get(&key) {
+            return Ok(module.clone());
+        }
+
+        // Compile outside the lock so misses on other organelles don't wait.
+        let module =
+            Module::new(&self.engine, wasm).map_err(|_| MuscleError::MalformedOrganelle)?;
+        self.modules.lock().put(key, module.clone());
+        Ok(module)
+    }
+}
+
+impl fmt::Debug for ModuleCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modules = self.modules.lock();
+        f.debug_struct("ModuleCache")
+            .field("len", &modules.len())
+            .field("capacity", &modules.cap())
+            .finish_non_exhaustive()
+    }
+}
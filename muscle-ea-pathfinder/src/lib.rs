@@ -14,95 +14,229 @@ all Eä biological computing principles.
 
 extern crate alloc;
 
-use aes_gcm::{
-    aead::{generic_array::GenericArray, Aead},
-    Aes256Gcm, KeyInit,
-};
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(test)]
+mod test_organelles;
+
+#[cfg(feature = "std")]
+pub use cache::ModuleCache;
+
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
 use alloc::{format, string::String, vec::Vec};
 use core::marker::PhantomData;
 use hmac::{Hmac, Mac};
 use muscle_ea_core::{
     biology::*,
+    constants::{PATHFINDER_HEADER_SIZE, PATHFINDER_VERSION},
+    crypto::{derive_key, AeadSuite},
     error::MuscleError,
     runtime::{Muscle, MuscleContext, MuscleOutput, MuscleSuccessor, SuccessorMetadata},
 };
 use rand_core::{CryptoRng, RngCore};
-use sha3::{
-    digest::{ExtendableOutput, Update, XofReader},
-    Shake256,
-};
 use subtle::ConstantTimeEq;
 use wasmtime::*;
 use zeroize::Zeroizing;
 
+/// Header version written when sealing; v5 encrypts under a key derived
+/// from the master key, salt and nonce
+const HEADER_VERSION: u32 = 5;
+
+/// Header version that added the AEAD suite id; its payloads are encrypted
+/// under the master key itself
+const SUITE_HEADER_VERSION: u32 = 4;
+
+/// Header version of blobs sealed before suites were recorded; their MAC is
+/// keyed on the master key itself
+const LEGACY_HEADER_VERSION: u32 = 3;
+
 /// Sealed blob header for pathfinder muscles
 #[repr(C)]
 #[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
 struct PathfinderHeader {
-    version: u32,        // 3 for pathfinder v1
+    version: u32,        // HEADER_VERSION
     salt: [u8; 16],      // Muscle salt
-    nonce: [u8; 12],     // AES-GCM nonce
+    nonce: [u8; 12],     // AEAD nonce
     mac: [u8; 16],       // HMAC-SHA3-256 truncated
+    suite: u8,           // AeadSuite id used to seal the payload
+    _reserved: [u8; 7],  // Keeps ciphertext_len aligned
     ciphertext_len: u64, // Length of encrypted payload
 }
 
+const _: () = assert!(core::mem::size_of::<PathfinderHeader>() == PATHFINDER_HEADER_SIZE);
+
 impl PathfinderHeader {
     fn as_bytes(&self) -> &[u8] {
         bytemuck::bytes_of(self)
     }
 
-    fn from_bytes(bytes: &[u8]) -> Option<&Self> {
-        bytemuck::try_from_bytes(bytes).ok()
+    /// Parse the header leading `sealed`, returning it with its length.
+    ///
+    /// v3 headers carry no suite id and are read as AES-256-GCM, the only
+    /// suite that existed when they were sealed. v4 shares the v5 layout.
+    fn parse(sealed: &[u8]) -> Option<(Self, usize)> {
+        let version = u32::from_ne_bytes(sealed.get(..4)?.try_into().ok()?);
+        match version {
+            HEADER_VERSION | SUITE_HEADER_VERSION => {
+                let len = core::mem::size_of::<Self>();
+                Some((bytemuck::pod_read_unaligned(sealed.get(..len)?), len))
+            }
+            LEGACY_HEADER_VERSION => {
+                let len = core::mem::size_of::<LegacyPathfinderHeader>();
+                let legacy: LegacyPathfinderHeader =
+                    bytemuck::pod_read_unaligned(sealed.get(..len)?);
+                let header = Self {
+                    version: legacy.version,
+                    salt: legacy.salt,
+                    nonce: legacy.nonce,
+                    mac: legacy.mac,
+                    suite: AeadSuite::Aes256Gcm.id(),
+                    _reserved: [0u8; 7],
+                    ciphertext_len: legacy.ciphertext_len,
+                };
+                Some((header, len))
+            }
+            _ => None,
+        }
     }
 }
 
+/// Sealed blob header of [`LEGACY_HEADER_VERSION`], before the suite id
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+struct LegacyPathfinderHeader {
+    version: u32,
+    salt: [u8; 16],
+    nonce: [u8; 12],
+    mac: [u8; 16],
+    ciphertext_len: u64,
+}
+
 /// Specialized Pathfinder Muscle — a living organ that speaks WASM natively
 /// while remaining 100% part of the Eä tissue architecture.
 pub struct PathfinderMuscle<R: RngCore + CryptoRng = rand_core::OsRng> {
+    /// Compiled modules shared across executions, if caching is enabled
+    #[cfg(feature = "std")]
+    modules: Option<Arc<ModuleCache>>,
     _phantom: PhantomData<R>,
 }
 
 impl<R: RngCore + CryptoRng> Default for PathfinderMuscle<R> {
     fn default() -> Self {
         Self {
+            #[cfg(feature = "std")]
+            modules: None,
             _phantom: PhantomData,
         }
     }
 }
 
-impl<R: RngCore + CryptoRng> Muscle<R> for PathfinderMuscle<R> {
-    type PrivateInput = Vec<u8>;
-    type PrivateOutput = Vec<u8>;
+impl<R: RngCore + CryptoRng> PathfinderMuscle<R> {
+    /// Reuse compiled organelles from `cache` instead of recompiling the
+    /// WASM on every execution. The cache may be shared between muscles.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn with_module_cache(mut self, cache: Arc<ModuleCache>) -> Self {
+        self.modules = Some(cache);
+        self
+    }
 
-    fn execute(
+    /// Execute an already unsealed organelle with no successor keys.
+    ///
+    /// # Errors
+    /// Fails if the WASM does not compile or link, traps, or exhausts its fuel.
+    pub fn execute_organelle(
         &self,
-        ctx: &mut MuscleContext<R>,
-        private_input: Self::PrivateInput,
-    ) -> Result<MuscleOutput<Self::PrivateOutput>, MuscleError> {
+        wasm: &[u8],
+        private_input: &[u8],
+    ) -> Result<PathfinderResult, MuscleError> {
+        self.fire(wasm, private_input, Vec::new(), AeadSuite::default())
+    }
+
+    /// Unseal and execute the context's blob like [`Muscle::execute`], also
+    /// reporting the fuel the organelle consumed.
+    ///
+    /// # Errors
+    /// Fails if the blob is not a valid pathfinder blob for this master key,
+    /// or if the organelle fails as in [`Self::execute_organelle`].
+    pub fn execute_metered(
+        &self,
+        ctx: &MuscleContext<R>,
+        private_input: &[u8],
+    ) -> Result<PathfinderResult, MuscleError> {
         let sealed = ctx.current_blob();
 
         // Verify this is a pathfinder muscle
-        if sealed.version() != 3 {
+        if sealed.version() != PATHFINDER_VERSION {
             return Err(MuscleError::InvalidBlob);
         }
+        sealed.validate_for_version()?;
 
-        let (wasm_bytes, successor_keys) =
+        let (wasm_bytes, successor_keys, suite) =
             unseal_pathfinder_blob(ctx.master_key(), sealed.salt(), &sealed.payload)?;
 
-        let result = run_pathfinder_isolate(&wasm_bytes, &private_input, successor_keys)?;
+        self.fire(&wasm_bytes, private_input, successor_keys, suite)
+    }
 
-        Ok(MuscleOutput {
-            output: result.output,
-            successors: result.successors,
-        })
+    fn fire(
+        &self,
+        wasm: &[u8],
+        private_input: &[u8],
+        successor_keys: Vec<[u8; 32]>,
+        suite: AeadSuite,
+    ) -> Result<PathfinderResult, MuscleError> {
+        let (engine, module) = self.load_organelle(wasm)?;
+        run_pathfinder_isolate(&engine, &module, private_input, successor_keys, suite)
+    }
+
+    /// Compiled module for `wasm`, from the module cache when one is set.
+    fn load_organelle(&self, wasm: &[u8]) -> Result<(Engine, Module), MuscleError> {
+        #[cfg(feature = "std")]
+        if let Some(cache) = &self.modules {
+            return Ok((cache.engine().clone(), cache.module(wasm)?));
+        }
+
+        let engine = pathfinder_engine()?;
+        let module = Module::new(&engine, wasm).map_err(|_| MuscleError::MalformedOrganelle)?;
+        Ok((engine, module))
     }
 }
 
+impl<R: RngCore + CryptoRng> Muscle<R> for PathfinderMuscle<R> {
+    type PrivateInput = Vec<u8>;
+    type PrivateOutput = Vec<u8>;
+
+    fn execute(
+        &self,
+        ctx: &mut MuscleContext<R>,
+        private_input: Self::PrivateInput,
+    ) -> Result<MuscleOutput<Self::PrivateOutput>, MuscleError> {
+        self.execute_metered(ctx, &private_input).map(Into::into)
+    }
+}
+
+/// Fuel granted to a single organelle execution
+pub const ORGANELLE_FUEL: u64 = 500_000;
+
 /// Result from pathfinder execution
 #[derive(Debug)]
-struct PathfinderResult {
-    output: Vec<u8>,
-    successors: Vec<MuscleSuccessor>,
+pub struct PathfinderResult {
+    /// Bytes the organelle wrote through the output membrane
+    pub output: Vec<u8>,
+    /// Successors sealed during execution
+    pub successors: Vec<MuscleSuccessor>,
+    /// Fuel burned out of [`ORGANELLE_FUEL`], for metering and profiling
+    pub fuel_consumed: u64,
+}
+
+impl From<PathfinderResult> for MuscleOutput<Vec<u8>> {
+    fn from(result: PathfinderResult) -> Self {
+        Self {
+            output: result.output,
+            successors: result.successors,
+        }
+    }
 }
 
 /// Biological cell state — the living cytoplasm of the pathfinder muscle
@@ -111,15 +245,18 @@ struct PathfinderCellData {
     output: Zeroizing<Vec<u8>>,
     successors: Vec<MuscleSuccessor>,
     successor_keys: Vec<[u8; 32]>,
+    /// Suite successors are sealed with, inherited from the parent blob
+    suite: AeadSuite,
 }
 
 impl PathfinderCellData {
-    fn new(input: Vec<u8>, successor_keys: Vec<[u8; 32]>) -> Self {
+    fn new(input: Vec<u8>, successor_keys: Vec<[u8; 32]>, suite: AeadSuite) -> Self {
         Self {
             input: Zeroizing::new(input),
             output: Zeroizing::new(Vec::new()),
             successors: Vec::new(),
             successor_keys,
+            suite,
         }
     }
 
@@ -151,7 +288,7 @@ impl PathfinderCellData {
         let key = self.successor_keys.remove(0);
         let mut rng = rand::thread_rng();
         let salt = MuscleSalt::random(&mut rng);
-        let sealed_blob = seal_pathfinder_blob(&key, &salt, wasm, &mut rng)
+        let sealed_blob = seal_pathfinder_blob(&key, &salt, wasm, self.suite, &mut rng)
             .map_err(|e| anyhow::anyhow!("{}", e))?;
 
         let successor = MuscleSuccessor {
@@ -170,24 +307,17 @@ fn unseal_pathfinder_blob(
     master_key: &[u8; 32],
     salt: &MuscleSalt,
     sealed: &[u8],
-) -> Result<(Vec<u8>, Vec<[u8; 32]>), MuscleError> {
-    if sealed.len() < core::mem::size_of::<PathfinderHeader>() {
-        return Err(MuscleError::InvalidBlob);
-    }
-
-    // Parse header using bytemuck (safe)
-    let header_slice = &sealed[..core::mem::size_of::<PathfinderHeader>()];
-    let header = PathfinderHeader::from_bytes(header_slice).ok_or(MuscleError::InvalidBlob)?;
-
-    if header.version != 3 {
-        return Err(MuscleError::InvalidBlob);
-    }
+) -> Result<(Vec<u8>, Vec<[u8; 32]>, AeadSuite), MuscleError> {
+    // Parse header using bytemuck (safe); unknown versions are rejected
+    let (header, header_len) = PathfinderHeader::parse(sealed).ok_or(MuscleError::InvalidBlob)?;
 
     if header.salt != *salt.as_bytes() {
         return Err(MuscleError::InvalidBlob);
     }
 
-    let ciphertext = &sealed[core::mem::size_of::<PathfinderHeader>()..];
+    let suite = AeadSuite::from_id(header.suite).ok_or(MuscleError::InvalidBlob)?;
+
+    let ciphertext = &sealed[header_len..];
     if ciphertext.len() != header.ciphertext_len as usize {
         return Err(MuscleError::InvalidBlob);
     }
@@ -200,15 +330,14 @@ fn unseal_pathfinder_blob(
         + core::mem::size_of_val(&header.nonce);
     let mut sealed_for_mac = sealed.to_vec();
     sealed_for_mac[mac_offset..mac_offset + 16].fill(0);
-    let expected_mac = compute_pathfinder_hmac(master_key, salt, &sealed_for_mac);
+    let expected_mac = compute_pathfinder_hmac(header.version, master_key, salt, &sealed_for_mac);
     if expected_mac.ct_eq(&header.mac).unwrap_u8() != 1 {
         return Err(MuscleError::InvalidBlob);
     }
 
     // Decrypt
-    let enc_key = derive_pathfinder_key(master_key, salt, &header.nonce);
-    let plaintext = decrypt_pathfinder_aes(&enc_key, &header.nonce, ciphertext)
-        .ok_or(MuscleError::Crypto("decryption failed".to_string()))?;
+    let enc_key = pathfinder_enc_key(header.version, master_key, salt, &header.nonce);
+    let plaintext = suite.cipher().open(&enc_key, &header.nonce, ciphertext)?;
 
     // Parse successor keys
     if plaintext.len() < 4 {
@@ -233,15 +362,15 @@ fn unseal_pathfinder_blob(
         offset += 32;
     }
 
-    Ok((module_bytes, successor_keys))
+    Ok((module_bytes, successor_keys, suite))
 }
 
-fn run_pathfinder_isolate(
-    wasm: &[u8],
-    private_input: &[u8],
-    successor_keys: Vec<[u8; 32]>,
-) -> Result<PathfinderResult, MuscleError> {
-    let engine = Engine::new(
+/// Engine with the fixed pathfinder configuration.
+///
+/// Every organelle runs under this exact `Config`, which is what lets
+/// [`ModuleCache`] hand out modules compiled by a previous execution.
+fn pathfinder_engine() -> Result<Engine, MuscleError> {
+    Engine::new(
         Config::new()
             .consume_fuel(true)
             .epoch_interruption(true)
@@ -249,20 +378,26 @@ fn run_pathfinder_isolate(
             .dynamic_memory_guard_size(0)
             .cranelift_opt_level(wasmtime::OptLevel::Speed),
     )
-    .map_err(|_| MuscleError::IsolationFailure)?;
+    .map_err(|_| MuscleError::IsolationFailure)
+}
 
+fn run_pathfinder_isolate(
+    engine: &Engine,
+    module: &Module,
+    private_input: &[u8],
+    successor_keys: Vec<[u8; 32]>,
+    suite: AeadSuite,
+) -> Result<PathfinderResult, MuscleError> {
     let mut store = Store::new(
-        &engine,
-        PathfinderCellData::new(private_input.to_vec(), successor_keys),
+        engine,
+        PathfinderCellData::new(private_input.to_vec(), successor_keys, suite),
     );
 
     store
-        .set_fuel(500_000)
+        .set_fuel(ORGANELLE_FUEL)
         .map_err(|_| MuscleError::ResourceExhausted)?;
     store.set_epoch_deadline(1);
 
-    let module = Module::new(&engine, wasm).map_err(|_| MuscleError::MalformedOrganelle)?;
-
     // Create host functions for biological membrane interface
     let read_input_func = Func::wrap(
         &mut store,
@@ -328,15 +463,19 @@ fn run_pathfinder_isolate(
             let serialized = serialize_successor_for_guest(&successor)
                 .map_err(|e| anyhow::anyhow!("serialize: {}", e))?;
 
-            // Write serialized data back to guest memory
-            if serialized.len() > 4096 {
-                anyhow::bail!("successor data too large");
+            // Write serialized data back to guest memory, into a buffer the
+            // guest allocates when it opted in by exporting a pathfinder allocator
+            if let Some(allocator) = GuestAllocator::lookup(&mut caller)? {
+                allocator.hand_over(&mut caller, memory, &serialized, out_ptr)?;
+            } else {
+                if serialized.len() > 4096 {
+                    anyhow::bail!("successor data too large");
+                }
+                memory
+                    .write(&mut caller, out_ptr as usize, &serialized)
+                    .map_err(|e| anyhow::anyhow!("memory write: {}", e))?;
             }
 
-            memory
-                .write(&mut caller, out_ptr as usize, &serialized)
-                .map_err(|e| anyhow::anyhow!("memory write: {}", e))?;
-
             // Write length to guest memory
             let len_bytes = (serialized.len() as u32).to_le_bytes();
             memory
@@ -349,7 +488,7 @@ fn run_pathfinder_isolate(
 
     let instance = Instance::new(
         &mut store,
-        &module,
+        module,
         &[
             read_input_func.into(),
             write_output_func.into(),
@@ -371,13 +510,80 @@ fn run_pathfinder_isolate(
         }
     })?;
 
+    let fuel_remaining = store
+        .get_fuel()
+        .map_err(|_| MuscleError::IsolationFailure)?;
     let cell = store.into_data();
     Ok(PathfinderResult {
         output: cell.output.to_vec(),
         successors: cell.successors,
+        fuel_consumed: ORGANELLE_FUEL - fuel_remaining,
     })
 }
 
+/// Export through which a guest opts in to receiving successor data in
+/// its own buffers.
+const GUEST_ALLOC_EXPORT: &str = "pathfinder_alloc";
+
+/// Optional export taking back a buffer the host could not fill.
+const GUEST_DEALLOC_EXPORT: &str = "pathfinder_dealloc";
+
+/// Allocator exported by a guest that manages its own memory.
+///
+/// Organelles exporting `pathfinder_alloc(size) -> ptr` receive host-written
+/// data in a buffer from that allocator, and the host stores the buffer's
+/// address at `out_ptr` instead of the data itself. The dedicated name is the
+/// opt-in: a general-purpose `alloc` export leaves the guest on the
+/// fixed-offset ABI. `pathfinder_dealloc(ptr, size)` is optional and only
+/// used to hand a buffer back when the host cannot fill it.
+struct GuestAllocator {
+    alloc: TypedFunc<u32, u32>,
+    dealloc: Option<TypedFunc<(u32, u32), ()>>,
+}
+
+impl GuestAllocator {
+    fn lookup(caller: &mut Caller<'_, PathfinderCellData>) -> anyhow::Result<Option<Self>> {
+        let Some(alloc) = caller
+            .get_export(GUEST_ALLOC_EXPORT)
+            .and_then(Extern::into_func)
+        else {
+            return Ok(None);
+        };
+        let alloc = alloc
+            .typed::<u32, u32>(&*caller)
+            .map_err(|e| anyhow::anyhow!("{} export: {}", GUEST_ALLOC_EXPORT, e))?;
+        let dealloc = caller
+            .get_export(GUEST_DEALLOC_EXPORT)
+            .and_then(Extern::into_func)
+            .map(|f| f.typed::<(u32, u32), ()>(&*caller))
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("{} export: {}", GUEST_DEALLOC_EXPORT, e))?;
+        Ok(Some(Self { alloc, dealloc }))
+    }
+
+    /// Copy `data` into a fresh guest buffer and store its address at `out_ptr`.
+    fn hand_over(
+        &self,
+        caller: &mut Caller<'_, PathfinderCellData>,
+        memory: Memory,
+        data: &[u8],
+        out_ptr: u32,
+    ) -> anyhow::Result<()> {
+        let size = u32::try_from(data.len())?;
+        let ptr = self.alloc.call(&mut *caller, size)?;
+        let written = memory
+            .write(&mut *caller, ptr as usize, data)
+            .and_then(|()| memory.write(&mut *caller, out_ptr as usize, &ptr.to_le_bytes()));
+        if let Err(e) = written {
+            if let Some(dealloc) = &self.dealloc {
+                dealloc.call(&mut *caller, (ptr, size))?;
+            }
+            anyhow::bail!("memory write: {}", e);
+        }
+        Ok(())
+    }
+}
+
 /// Serialize successor data for passing back to WASM guest
 fn serialize_successor_for_guest(successor: &MuscleSuccessor) -> Result<Vec<u8>, MuscleError> {
     use core::fmt::Write;
@@ -413,22 +619,49 @@ fn serialize_successor_for_guest(successor: &MuscleSuccessor) -> Result<Vec<u8>,
 
 // Cryptographic organelles — biological framing of crypto operations
 fn derive_pathfinder_key(master_key: &[u8; 32], salt: &MuscleSalt, nonce: &[u8; 12]) -> [u8; 32] {
-    let mut shake = Shake256::default();
-    shake.update(b"MUSCLE_PATHFINDER_V1_ENC");
-    shake.update(master_key);
-    shake.update(salt.as_bytes());
-    shake.update(nonce);
-    let mut key = [0u8; 32];
-    shake.finalize_xof().read(&mut key);
-    key
+    derive_key(
+        master_key,
+        b"MUSCLE_PATHFINDER_V1_ENC",
+        &[salt.as_bytes(), nonce],
+    )
 }
 
-fn compute_pathfinder_hmac(master_key: &[u8; 32], salt: &MuscleSalt, data: &[u8]) -> [u8; 16] {
+/// Key the payload of a header `version` blob is encrypted under
+fn pathfinder_enc_key(
+    version: u32,
+    master_key: &[u8; 32],
+    salt: &MuscleSalt,
+    nonce: &[u8; 12],
+) -> Zeroizing<[u8; 32]> {
+    if version >= HEADER_VERSION {
+        Zeroizing::new(derive_pathfinder_key(master_key, salt, nonce))
+    } else {
+        // Sealers before v5 encrypted under the master key itself
+        Zeroizing::new(*master_key)
+    }
+}
+
+fn compute_pathfinder_hmac(
+    version: u32,
+    master_key: &[u8; 32],
+    salt: &MuscleSalt,
+    data: &[u8],
+) -> [u8; 16] {
     type HmacSha3256 = Hmac<sha3::Sha3_256>;
-    let mut mac =
-        <HmacSha3256 as Mac>::new_from_slice(master_key).expect("HMAC key should be valid");
-    Mac::update(&mut mac, b"MUSCLE_PATHFINDER_V1_MAC");
-    Mac::update(&mut mac, salt.as_bytes());
+    let mut mac = if version == LEGACY_HEADER_VERSION {
+        let mut mac =
+            <HmacSha3256 as Mac>::new_from_slice(master_key).expect("HMAC key should be valid");
+        Mac::update(&mut mac, b"MUSCLE_PATHFINDER_V1_MAC");
+        Mac::update(&mut mac, salt.as_bytes());
+        mac
+    } else {
+        let mac_key = Zeroizing::new(derive_key(
+            master_key,
+            b"MUSCLE_PATHFINDER_V1_MAC",
+            &[salt.as_bytes()],
+        ));
+        <HmacSha3256 as Mac>::new_from_slice(&*mac_key).expect("HMAC key should be valid")
+    };
     Mac::update(&mut mac, data);
 
     let result = mac.finalize().into_bytes();
@@ -437,28 +670,18 @@ fn compute_pathfinder_hmac(master_key: &[u8; 32], salt: &MuscleSalt, data: &[u8]
     truncated
 }
 
-fn decrypt_pathfinder_aes(key: &[u8; 32], nonce: &[u8; 12], ciphertext: &[u8]) -> Option<Vec<u8>> {
-    let cipher = Aes256Gcm::new_from_slice(key).ok()?;
-    let nonce = GenericArray::from_slice(nonce);
-    cipher.decrypt(nonce, ciphertext).ok()
-}
-
 fn seal_pathfinder_blob(
     key: &[u8; 32],
     salt: &MuscleSalt,
     payload: &[u8],
+    suite: AeadSuite,
     rng: &mut impl RngCore,
 ) -> Result<SealedBlob, MuscleError> {
-    let cipher = Aes256Gcm::new_from_slice(key)
-        .map_err(|_| MuscleError::Crypto("invalid key".to_string()))?;
-
     let mut nonce = [0u8; 12];
     rng.fill_bytes(&mut nonce);
 
-    let nonce_array = GenericArray::from_slice(&nonce);
-    let ciphertext = cipher
-        .encrypt(nonce_array, payload)
-        .map_err(|_| MuscleError::Crypto("encryption failed".to_string()))?;
+    let enc_key = pathfinder_enc_key(HEADER_VERSION, key, salt, &nonce);
+    let ciphertext = suite.cipher().seal(&enc_key, &nonce, payload)?;
 
     // Build the full sealed data with header
     let mut sealed_data =
@@ -466,10 +689,12 @@ fn seal_pathfinder_blob(
 
     // Create header (MAC will be computed after)
     let header = PathfinderHeader {
-        version: 3,
+        version: HEADER_VERSION,
         salt: *salt.as_bytes(),
         nonce,
         mac: [0u8; 16], // Placeholder - will be set below
+        suite: suite.id(),
+        _reserved: [0u8; 7],
         ciphertext_len: ciphertext.len() as u64,
     };
 
@@ -478,7 +703,7 @@ fn seal_pathfinder_blob(
     sealed_data.extend_from_slice(&ciphertext);
 
     // Compute and set MAC
-    let mac = compute_pathfinder_hmac(key, salt, &sealed_data);
+    let mac = compute_pathfinder_hmac(HEADER_VERSION, key, salt, &sealed_data);
 
     // Update MAC in the sealed data
     let mac_offset = core::mem::size_of_val(&header.version)
@@ -486,12 +711,17 @@ fn seal_pathfinder_blob(
         + core::mem::size_of_val(&header.nonce);
     sealed_data[mac_offset..mac_offset + 16].copy_from_slice(&mac);
 
-    Ok(SealedBlob::new(sealed_data, salt.clone(), 3))
+    Ok(SealedBlob::new(
+        sealed_data,
+        salt.clone(),
+        PATHFINDER_VERSION,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_organelles::{HELLO_ORGANELLE, LOOP_ORGANELLE};
     use rand_core::OsRng;
 
     #[test]
@@ -505,7 +735,7 @@ mod tests {
     fn test_pathfinder_cell_operations() {
         let input = vec![1, 2, 3, 4, 5];
         let keys = vec![[0u8; 32]];
-        let cell = PathfinderCellData::new(input.clone(), keys);
+        let cell = PathfinderCellData::new(input.clone(), keys, AeadSuite::default());
 
         let read_data = cell.read_input(1, 3).unwrap();
         assert_eq!(read_data, vec![2, 3, 4]);
@@ -521,8 +751,10 @@ mod tests {
         assert_eq!(derived.len(), 32);
 
         let data = b"test data";
-        let mac = compute_pathfinder_hmac(&key, &salt, data);
+        let mac = compute_pathfinder_hmac(HEADER_VERSION, &key, &salt, data);
         assert_eq!(mac.len(), 16);
+        let legacy_mac = compute_pathfinder_hmac(LEGACY_HEADER_VERSION, &key, &salt, data);
+        assert_ne!(mac, legacy_mac);
     }
 
     #[test]
@@ -538,4 +770,169 @@ mod tests {
         assert!(!serialized.is_empty());
         assert!(serialized.len() >= 16); // Minimum header size
     }
+
+    #[test]
+    fn test_sealed_header_records_suite() {
+        let key = [1u8; 32];
+        let salt = MuscleSalt::new([2u8; 16]);
+        for suite in [AeadSuite::Aes256Gcm, AeadSuite::ChaCha20Poly1305] {
+            let blob = seal_pathfinder_blob(&key, &salt, b"wasm", suite, &mut OsRng).unwrap();
+            let header_len = core::mem::size_of::<PathfinderHeader>();
+            let header: PathfinderHeader =
+                bytemuck::pod_read_unaligned(&blob.payload[..header_len]);
+            assert_eq!(header.version, HEADER_VERSION);
+            assert_eq!(header.suite, suite.id());
+            assert_eq!(
+                header.ciphertext_len as usize,
+                blob.payload.len() - header_len
+            );
+        }
+    }
+
+    #[test]
+    fn test_sealed_blob_round_trips() {
+        let key = [1u8; 32];
+        let salt = MuscleSalt::new([2u8; 16]);
+        let mut payload = b"wasm".to_vec();
+        payload.extend_from_slice(&0u32.to_le_bytes());
+        for suite in [AeadSuite::Aes256Gcm, AeadSuite::ChaCha20Poly1305] {
+            let blob = seal_pathfinder_blob(&key, &salt, &payload, suite, &mut OsRng).unwrap();
+            let (wasm, keys, opened) = unseal_pathfinder_blob(&key, &salt, &blob.payload).unwrap();
+            assert_eq!(wasm, b"wasm");
+            assert!(keys.is_empty());
+            assert_eq!(opened, suite);
+        }
+    }
+
+    /// v3 blob sealed before AEAD suites existed: `b"wasm"` with no
+    /// successor keys under key `[1; 32]` and salt `[2; 16]`
+    const LEGACY_V3_BLOB: [u8; 80] = hex_literal::hex!(
+        "03000000020202020202020202020202020202024ead86224c40f6faef24b7108d926811edf078f1"
+        "543be181881e8a6718000000000000004b8c598e6578ab192ac51a06a8a3cb556352a903313d5749"
+    );
+
+    #[test]
+    fn test_legacy_header_unseals_as_aes_gcm() {
+        let key = [1u8; 32];
+        let salt = MuscleSalt::new([2u8; 16]);
+        let mut sealed = LEGACY_V3_BLOB.to_vec();
+
+        let (wasm, keys, suite) = unseal_pathfinder_blob(&key, &salt, &sealed).unwrap();
+        assert_eq!(wasm, b"wasm");
+        assert!(keys.is_empty());
+        assert_eq!(suite, AeadSuite::Aes256Gcm);
+
+        // Header versions other than v3 to v5 are rejected outright
+        sealed[..4].copy_from_slice(&2u32.to_ne_bytes());
+        assert!(matches!(
+            unseal_pathfinder_blob(&key, &salt, &sealed),
+            Err(MuscleError::InvalidBlob)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_cached_and_fresh_compiles_match() {
+        let fresh = PathfinderMuscle::<OsRng>::default();
+        let cache = Arc::new(ModuleCache::new(core::num::NonZeroUsize::new(4).unwrap()).unwrap());
+        let cached = PathfinderMuscle::<OsRng>::default().with_module_cache(cache.clone());
+
+        let expected = fresh.execute_organelle(HELLO_ORGANELLE, &[]).unwrap();
+        assert_eq!(expected.output, b"hello");
+        for _ in 0..3 {
+            let out = cached.execute_organelle(HELLO_ORGANELLE, &[]).unwrap();
+            assert_eq!(out.output, expected.output);
+            assert_eq!(out.fuel_consumed, expected.fuel_consumed);
+            assert!(out.successors.is_empty());
+        }
+        assert_eq!(cache.len(), 1);
+
+        assert!(matches!(
+            cached.execute_organelle(b"not wasm", &[]),
+            Err(MuscleError::MalformedOrganelle)
+        ));
+        assert_eq!(cache.len(), 1);
+    }
+
+    /// Organelle with a bump allocator that seals 5000 zero bytes as a
+    /// successor, then echoes the buffer address and the successor data.
+    const ALLOCATING_ORGANELLE: &str = r#"
+        (module
+          (import "env" "read_input" (func (param i32 i32 i32)))
+          (import "env" "write_output" (func $write_output (param i32 i32)))
+          (import "env" "seal_successor" (func $seal (param i32 i32 i32 i32) (result i32)))
+          (memory (export "memory") 1)
+          (global $heap (mut i32) (i32.const 8192))
+          (func (export "pathfinder_alloc") (param $size i32) (result i32)
+            global.get $heap
+            global.get $heap
+            local.get $size
+            i32.add
+            global.set $heap)
+          (func (export "pathfinder_dealloc") (param i32 i32))
+          (func (export "run")
+            (drop (call $seal (i32.const 2048) (i32.const 5000) (i32.const 16) (i32.const 20)))
+            (call $write_output (i32.const 16) (i32.const 4))
+            (call $write_output (i32.load (i32.const 16)) (i32.load (i32.const 20)))))
+    "#;
+
+    #[test]
+    fn test_successor_written_through_guest_allocator() {
+        let wasm = wat::parse_str(ALLOCATING_ORGANELLE).unwrap();
+        let muscle = PathfinderMuscle::<OsRng>::default();
+        let result = muscle
+            .fire(&wasm, &[], vec![[7u8; 32]], AeadSuite::default())
+            .unwrap();
+
+        assert_eq!(result.successors.len(), 1);
+        let expected = serialize_successor_for_guest(&result.successors[0]).unwrap();
+        assert!(expected.len() > 4096);
+        assert_eq!(result.output[..4], 8192u32.to_le_bytes());
+        assert_eq!(result.output[4..], expected[..]);
+    }
+
+    /// Organelle exporting an unrelated `alloc` that traps if called; it
+    /// seals 8 bytes and echoes the successor data at the fixed offset.
+    const PLAIN_ALLOC_ORGANELLE: &str = r#"
+        (module
+          (import "env" "read_input" (func (param i32 i32 i32)))
+          (import "env" "write_output" (func $write_output (param i32 i32)))
+          (import "env" "seal_successor" (func $seal (param i32 i32 i32 i32) (result i32)))
+          (memory (export "memory") 1)
+          (func (export "alloc") (param i32) (result i32)
+            unreachable)
+          (func (export "run")
+            (drop (call $seal (i32.const 2048) (i32.const 8) (i32.const 4096) (i32.const 20)))
+            (call $write_output (i32.const 4096) (i32.load (i32.const 20)))))
+    "#;
+
+    #[test]
+    fn test_plain_alloc_export_keeps_fixed_offset_abi() {
+        let wasm = wat::parse_str(PLAIN_ALLOC_ORGANELLE).unwrap();
+        let muscle = PathfinderMuscle::<OsRng>::default();
+        let result = muscle
+            .fire(&wasm, &[], vec![[7u8; 32]], AeadSuite::default())
+            .unwrap();
+
+        assert_eq!(result.successors.len(), 1);
+        let expected = serialize_successor_for_guest(&result.successors[0]).unwrap();
+        assert_eq!(result.output, expected);
+    }
+
+    #[test]
+    fn test_fuel_consumed_tracks_loop_cost() {
+        let muscle = PathfinderMuscle::<OsRng>::default();
+
+        // Seven metered instructions per iteration, one hundred iterations.
+        let looped = muscle.execute_organelle(LOOP_ORGANELLE, &[]).unwrap();
+        assert!(
+            (700..=720).contains(&looped.fuel_consumed),
+            "unexpected loop cost {}",
+            looped.fuel_consumed
+        );
+
+        let hello = muscle.execute_organelle(HELLO_ORGANELLE, &[]).unwrap();
+        assert!(hello.fuel_consumed > 0);
+        assert!(hello.fuel_consumed < looped.fuel_consumed);
+    }
 }
@@ -0,0 +1,51 @@
+//! Hand-assembled organelle WASM shared by the unit tests and benches.
+
+/// Organelle whose `run` writes "hello" through the output membrane.
+pub const HELLO_ORGANELLE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic + version
+    0x01, 0x17, 0x04, // types
+    0x60, 0x03, 0x7f, 0x7f, 0x7f, 0x00, // read_input
+    0x60, 0x02, 0x7f, 0x7f, 0x00, // write_output
+    0x60, 0x04, 0x7f, 0x7f, 0x7f, 0x7f, 0x01, 0x7f, // seal_successor
+    0x60, 0x00, 0x00, // run
+    0x02, 0x3a, 0x03, // imports
+    0x03, b'e', b'n', b'v', 0x0a, b'r', b'e', b'a', b'd', b'_', b'i', b'n', b'p', b'u', b't',
+    0x00, 0x00, //
+    0x03, b'e', b'n', b'v', 0x0c, b'w', b'r', b'i', b't', b'e', b'_', b'o', b'u', b't', b'p',
+    b'u', b't', 0x00, 0x01, //
+    0x03, b'e', b'n', b'v', 0x0e, b's', b'e', b'a', b'l', b'_', b's', b'u', b'c', b'c', b'e',
+    b's', b's', b'o', b'r', 0x00, 0x02, //
+    0x03, 0x02, 0x01, 0x03, // functions
+    0x05, 0x03, 0x01, 0x00, 0x01, // one page of memory
+    0x07, 0x10, 0x02, // exports
+    0x06, b'm', b'e', b'm', b'o', b'r', b'y', 0x02, 0x00, //
+    0x03, b'r', b'u', b'n', 0x00, 0x03, //
+    0x0a, 0x0a, 0x01, 0x08, 0x00, // code: write_output(0, 5)
+    0x41, 0x00, 0x41, 0x05, 0x10, 0x01, 0x0b, //
+    0x0b, 0x0b, 0x01, 0x00, 0x41, 0x00, 0x0b, 0x05, // data at offset 0
+    b'h', b'e', b'l', b'l', b'o',
+];
+
+/// Organelle whose `run` counts a local from 0 to 100 in a loop.
+pub const LOOP_ORGANELLE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // magic + version
+    0x01, 0x17, 0x04, // types
+    0x60, 0x03, 0x7f, 0x7f, 0x7f, 0x00, // read_input
+    0x60, 0x02, 0x7f, 0x7f, 0x00, // write_output
+    0x60, 0x04, 0x7f, 0x7f, 0x7f, 0x7f, 0x01, 0x7f, // seal_successor
+    0x60, 0x00, 0x00, // run
+    0x02, 0x3a, 0x03, // imports
+    0x03, b'e', b'n', b'v', 0x0a, b'r', b'e', b'a', b'd', b'_', b'i', b'n', b'p', b'u', b't',
+    0x00, 0x00, //
+    0x03, b'e', b'n', b'v', 0x0c, b'w', b'r', b'i', b't', b'e', b'_', b'o', b'u', b't', b'p',
+    b'u', b't', 0x00, 0x01, //
+    0x03, b'e', b'n', b'v', 0x0e, b's', b'e', b'a', b'l', b'_', b's', b'u', b'c', b'c', b'e',
+    b's', b's', b'o', b'r', 0x00, 0x02, //
+    0x03, 0x02, 0x01, 0x03, // functions
+    0x07, 0x07, 0x01, 0x03, b'r', b'u', b'n', 0x00, 0x03, // exports
+    0x0a, 0x16, 0x01, 0x14, 0x01, 0x01, 0x7f, // code: one i32 local
+    0x03, 0x40, // loop
+    0x20, 0x00, 0x41, 0x01, 0x6a, 0x22, 0x00, // local.tee 0 (local.get 0 + 1)
+    0x41, 0xe4, 0x00, 0x49, 0x0d, 0x00, // br_if 0 (local < 100)
+    0x0b, 0x0b, // end loop, end func
+];
@@ -1,5 +1,5 @@
-#![no_std]
-#![no_main]
+#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(test), no_main)]
 
 //! Pre-Nucleus Loader - 2KiB verified loader that boots the Nucleus Muscle
 //!
@@ -11,12 +11,16 @@
 //!
 //! The loader itself is ~2KiB. The nucleus blob lives at NUCLEUS_BLOB_ADDR.
 
+#[cfg(not(test))]
 use core::arch::naked_asm;
+#[cfg(not(test))]
 use core::panic::PanicInfo;
 
 /// Known memory addresses (set by Referee)
+#[cfg_attr(test, allow(dead_code))]
 const NUCLEUS_BLOB_ADDR: u64 = 0x9200_0000;
 const NUCLEUS_BLOB_SIZE: usize = 8192;
+#[cfg_attr(test, allow(dead_code))]
 const VERIFICATION_KEY_ADDR: u64 = 0x9000_0020; // After master key
 
 /// Boot parameters passed from Referee (in X0 register)
@@ -31,6 +35,7 @@ pub struct BootParameters {
 
 /// Entry point called by Referee after verification
 /// Boot parameters pointer passed in X0
+#[cfg(not(test))]
 #[unsafe(naked)]
 #[no_mangle]
 pub extern "C" fn _start() -> ! {
@@ -56,19 +61,29 @@ pub extern "C" fn _start() -> ! {
     )
 }
 
+/// Check that `blob` hashes to `expected` (constant-time comparison)
+fn blob_matches_digest(blob: &[u8], expected: &[u8; 32]) -> bool {
+    // blake3::Hash equality is constant-time
+    blake3::hash(blob) == *expected
+}
+
 /// Verify the Nucleus blob signature at known address
+#[cfg(not(test))]
 #[no_mangle]
 extern "C" fn verify_nucleus_blob() -> u64 {
-    // Read verification key and nucleus blob from known addresses
-    let _key_ptr = VERIFICATION_KEY_ADDR as *const [u8; 32];
-    let _blob_ptr = NUCLEUS_BLOB_ADDR as *const u8;
+    // Read expected digest and nucleus blob from known addresses
+    let (expected, blob) = unsafe {
+        (
+            &*(VERIFICATION_KEY_ADDR as *const [u8; 32]),
+            core::slice::from_raw_parts(NUCLEUS_BLOB_ADDR as *const u8, NUCLEUS_BLOB_SIZE),
+        )
+    };
 
-    // In production: verify BLAKE3 hash against key
-    // For now, return success
-    1
+    blob_matches_digest(blob, expected) as u64
 }
 
 /// Set up execution environment for Nucleus
+#[cfg(not(test))]
 #[no_mangle]
 extern "C" fn setup_nucleus_environment() {
     use core::arch::asm;
@@ -87,6 +102,7 @@ extern "C" fn setup_nucleus_environment() {
 }
 
 /// Halt system on critical failure
+#[cfg(not(test))]
 #[no_mangle]
 extern "C" fn halt_system() -> ! {
     use core::arch::asm;
@@ -98,7 +114,7 @@ extern "C" fn halt_system() -> ! {
 }
 
 // Panic handler only for bare-metal UEFI target
-#[cfg(target_os = "uefi")]
+#[cfg(all(target_os = "uefi", not(test)))]
 #[panic_handler]
 fn panic(_info: &PanicInfo) -> ! {
     halt_system()
@@ -110,3 +126,36 @@ const _: () = assert!(
     core::mem::size_of::<BootParameters>() <= 128,
     "BootParameters too large"
 );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BLAKE3 digest of the empty input (from the reference test vectors)
+    const EMPTY_DIGEST: &str = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+
+    fn digest_from_hex(hex: &str) -> [u8; 32] {
+        let mut out = [0u8; 32];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn known_vector_matches() {
+        let expected = digest_from_hex(EMPTY_DIGEST);
+        assert!(blob_matches_digest(&[], &expected));
+    }
+
+    #[test]
+    fn tampered_blob_is_rejected() {
+        let mut blob = vec![0xEAu8; NUCLEUS_BLOB_SIZE];
+        let expected = *blake3::hash(&blob).as_bytes();
+        assert!(blob_matches_digest(&blob, &expected));
+
+        blob[NUCLEUS_BLOB_SIZE - 1] ^= 1;
+        assert!(!blob_matches_digest(&blob, &expected));
+        assert!(!blob_matches_digest(&[], &expected));
+    }
+}
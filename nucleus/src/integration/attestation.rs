@@ -1,20 +1,60 @@
+use crate::memory::FixedAllocator;
+
+/// Device attestation proof carrying the platform's monotonic boot counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttestationProof {
+    pub counter: u64,
+}
+
+impl AttestationProof {
+    pub const fn new(counter: u64) -> Self {
+        Self { counter }
+    }
+}
+
+// Slot in fixed memory holding the last accepted boot counter
+const COUNTER_SLOT: usize = 0;
+
 #[derive(Debug)]
 pub struct HardwareAttestation {
     verified: bool,
+    counter: FixedAllocator<u64, 1>,
 }
 
 impl HardwareAttestation {
     pub const fn new() -> Self {
-        Self { verified: false }
+        Self {
+            verified: false,
+            counter: FixedAllocator::new(),
+        }
     }
 
-    pub fn verify(&mut self) -> bool {
-        // In production, this would verify TPM/secure boot attestation
-        // For prototype, simulate successful verification
+    /// Accept `proof` only if its counter is newer than the last accepted one
+    pub fn verify(&mut self, proof: &AttestationProof) -> bool {
+        if let Some(last) = self.last_counter() {
+            if proof.counter <= last {
+                return false;
+            }
+        }
+
+        match self.counter.get_mut(COUNTER_SLOT) {
+            Some(stored) => *stored = proof.counter,
+            None => {
+                if self.counter.allocate(proof.counter).is_err() {
+                    return false;
+                }
+            }
+        }
+
         self.verified = true;
         true
     }
 
+    /// Counter of the most recently accepted proof, if any
+    pub fn last_counter(&self) -> Option<u64> {
+        self.counter.get(COUNTER_SLOT).copied()
+    }
+
     pub const fn is_verified(&self) -> bool {
         self.verified
     }
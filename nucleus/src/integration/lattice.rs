@@ -1,4 +1,5 @@
-use ea_ledger::MuscleUpdate;
+use crate::{NucleusError, Result};
+use ea_ledger::{apply_update, MuscleUpdate, GENESIS_ROOT};
 
 #[derive(Debug)]
 pub struct LatticeStream {
@@ -6,14 +7,26 @@ pub struct LatticeStream {
     updates: [Option<MuscleUpdate>; 16],
     head: usize,
     tail: usize,
+    // Root every incoming update is verified against; advanced by each
+    // accepted update
+    root: LatticeRoot,
+    rejected: u64,
 }
 
 impl LatticeStream {
+    /// Create a stream anchored at the genesis root
     pub const fn new() -> Self {
+        Self::with_root(GENESIS_ROOT)
+    }
+
+    /// Create a stream anchored at the given lattice root
+    pub const fn with_root(root: LatticeRoot) -> Self {
         Self {
             updates: [None; 16],
             head: 0,
             tail: 0,
+            root,
+            rejected: 0,
         }
     }
 
@@ -22,14 +35,46 @@ impl LatticeStream {
         true
     }
 
-    pub fn next_update(&mut self) -> Option<MuscleUpdate> {
+    /// Current lattice root used for update verification
+    pub fn root(&self) -> LatticeRoot {
+        self.root
+    }
+
+    /// Re-anchor verification at a new lattice root
+    pub fn set_root(&mut self, root: LatticeRoot) {
+        self.root = root;
+    }
+
+    /// Number of updates dropped because they failed verification
+    pub fn rejected(&self) -> u64 {
+        self.rejected
+    }
+
+    /// Pop the next update, verifying it against the current root.
+    ///
+    /// An accepted update moves the root to the one it commits to, so the
+    /// next update must build on it. Updates that fail verification are
+    /// consumed and reported as `VerificationFailed` so they never reach
+    /// the lattice update rule; the root is left unchanged.
+    pub fn next_update(&mut self) -> Option<Result<MuscleUpdate>> {
         if self.head == self.tail {
             return None;
         }
 
-        let update = self.updates[self.tail];
+        let update = self.updates[self.tail].take();
         self.tail = (self.tail + 1) % 16;
-        update
+        let update = update?;
+
+        match apply_update(self.root, &update) {
+            Some(root) => {
+                self.root = root;
+                Some(Ok(update))
+            }
+            None => {
+                self.rejected = self.rejected.wrapping_add(1);
+                Some(Err(NucleusError::VerificationFailed))
+            }
+        }
     }
 
     pub fn push_update(&mut self, update: MuscleUpdate) -> bool {
@@ -45,5 +90,4 @@ impl LatticeStream {
 }
 
 // Re-export for compatibility if needed, but prefer ea_ledger types
-#[allow(unused_imports)]
 pub use ea_ledger::LatticeRoot;
@@ -1,4 +1,4 @@
-use crate::NucleusError;
+use crate::{NucleusError, MAX_MUSCLES};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Capability {
@@ -6,12 +6,22 @@ pub struct Capability {
     pub permissions: u32,
 }
 
-/// Compile-time capability system
+/// Kernel operations gated by a [`CapabilitySet`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CapabilityKind {
+    LoadMuscle = 0b001,
+    Schedule = 0b010,
+    EmitUpdate = 0b100,
+}
+
+/// Compile-time capability system with per-muscle runtime revocation
 #[derive(Debug, Clone, Copy)]
 pub struct CapabilitySet {
     load_muscle: bool,
-    schedule: u8,       // Bitmap of allowed priorities
-    emit_update: usize, // Max updates allowed
+    schedule: u8,               // Bitmap of allowed priorities
+    emit_update: usize,         // Max updates allowed
+    revoked: [u8; MAX_MUSCLES], // Per-slot bitmaps of withdrawn CapabilityKinds
 }
 
 impl CapabilitySet {
@@ -20,9 +30,35 @@ impl CapabilitySet {
             load_muscle: true,
             schedule: 0b1111_1111, // Allow all priorities
             emit_update: 16,       // Max 16 updates
+            revoked: [0; MAX_MUSCLES],
         }
     }
 
+    /// Withdraw a capability from the muscle in `slot`; its gated
+    /// operations fail until the capability is granted again
+    pub fn revoke(&mut self, slot: usize, cap: CapabilityKind) -> Result<(), NucleusError> {
+        let revoked = self
+            .revoked
+            .get_mut(slot)
+            .ok_or(NucleusError::CapacityExceeded)?;
+        *revoked |= cap as u8;
+        Ok(())
+    }
+
+    /// Reinstate a capability revoked from `slot`, subject to its load-time limits
+    pub fn grant(&mut self, slot: usize, cap: CapabilityKind) -> Result<(), NucleusError> {
+        let revoked = self
+            .revoked
+            .get_mut(slot)
+            .ok_or(NucleusError::CapacityExceeded)?;
+        *revoked &= !(cap as u8);
+        Ok(())
+    }
+
+    pub const fn is_revoked(&self, slot: usize, cap: CapabilityKind) -> bool {
+        slot < MAX_MUSCLES && (self.revoked[slot] & cap as u8) != 0
+    }
+
     pub const fn can_load_muscle(&self) -> bool {
         self.load_muscle
     }
@@ -35,6 +71,21 @@ impl CapabilitySet {
         self.emit_update > 0
     }
 
+    /// Whether a muscle may be loaded into `slot`
+    pub const fn can_load_muscle_for(&self, slot: usize) -> bool {
+        self.can_load_muscle() && !self.is_revoked(slot, CapabilityKind::LoadMuscle)
+    }
+
+    /// Whether the muscle in `slot` may be scheduled at `priority`
+    pub const fn can_schedule_for(&self, slot: usize, priority: u8) -> bool {
+        self.can_schedule(priority) && !self.is_revoked(slot, CapabilityKind::Schedule)
+    }
+
+    /// Whether the muscle in `slot` may emit lattice updates
+    pub const fn can_emit_update_for(&self, slot: usize) -> bool {
+        self.can_emit_update() && !self.is_revoked(slot, CapabilityKind::EmitUpdate)
+    }
+
     pub fn use_emit_capability(&mut self) -> Result<(), NucleusError> {
         if self.emit_update == 0 {
             Err(NucleusError::InvalidCapability)
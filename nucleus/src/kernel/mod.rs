@@ -2,6 +2,6 @@ mod capabilities;
 mod nucleus;
 mod scheduler;
 
-pub use capabilities::{Capability, CapabilitySet};
+pub use capabilities::{Capability, CapabilityKind, CapabilitySet};
 pub use nucleus::MuscleNucleus;
-pub use scheduler::{Priority, Scheduler};
+pub use scheduler::{Priority, Scheduler, AGING_CAP};
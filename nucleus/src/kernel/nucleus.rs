@@ -1,11 +1,12 @@
-use super::capabilities::CapabilitySet;
+use super::capabilities::{CapabilityKind, CapabilitySet};
 use super::scheduler::{Priority, Scheduler};
 use crate::integration::{
-    HardwareAttestation, Heartbeat, LatticeStream, LatticeUpdate, SealedBlob, SymbioteInterface,
+    AttestationProof, HardwareAttestation, Heartbeat, LatticeStream, LatticeUpdate, SealedBlob,
+    SymbioteInterface,
 };
 use crate::memory::manager::MemoryManager;
 use crate::memory::FixedAllocator;
-use crate::rules::{RuleEngine, RuleId};
+use crate::rules::{RuleEngine, RuleKind};
 use crate::syscalls::{Syscall, SyscallArgs, SyscallHandler, SyscallResult};
 use crate::{NucleusError, Result, MAX_MUSCLES, MAX_UPDATES, SYMBIOTE_ID};
 
@@ -13,7 +14,7 @@ use crate::{NucleusError, Result, MAX_MUSCLES, MAX_UPDATES, SYMBIOTE_ID};
 #[repr(C, align(4096))] // Page aligned
 #[derive(Debug)]
 pub struct MuscleNucleus {
-    // Core capabilities - compile-time fixed
+    // Core capabilities - fixed at compile time, revocable per muscle at runtime
     capabilities: CapabilitySet,
 
     // Fixed-size muscle slots
@@ -37,10 +38,15 @@ pub struct MuscleNucleus {
     update_buffer: FixedAllocator<SealedBlob, MAX_UPDATES>,
 
     // Current execution state
-    current_rule: RuleId,
+    current_rule: RuleKind,
     heartbeat_counter: u64,
+    // Slot of the muscle whose syscalls are being dispatched
+    running_slot: usize,
 }
 
+/// Slot the symbiote is loaded into; kernel rules act on its behalf
+const SYMBIOTE_SLOT: usize = 0;
+
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
 pub struct LoadedMuscle {
@@ -63,17 +69,20 @@ impl MuscleNucleus {
             symbiote: SymbioteInterface::new(),
             memory_manager: MemoryManager::new(),
             update_buffer: FixedAllocator::new(),
-            current_rule: RuleId::Boot,
+            current_rule: RuleKind::Boot,
             heartbeat_counter: 0,
+            running_slot: SYMBIOTE_SLOT,
         }
     }
 
     /// Execute the boot rule - this is the kernel entry point
-    pub fn execute_boot_rule(&mut self) -> ! {
-        self.current_rule = RuleId::Boot;
+    ///
+    /// `proof` is the platform's attestation quote for this boot.
+    pub fn execute_boot_rule(&mut self, proof: AttestationProof) -> ! {
+        self.current_rule = RuleKind::Boot;
 
         // 1. Verify hardware attestation
-        if !self.attestation.verify() {
+        if !self.attestation.verify(&proof) {
             self.panic("Hardware attestation failed");
         }
 
@@ -83,12 +92,12 @@ impl MuscleNucleus {
         }
 
         // 3. Load symbiote as highest priority muscle
-        if let Err(_) = self.load_muscle(SYMBIOTE_ID, 0) {
+        if let Err(_) = self.load_muscle(SYMBIOTE_ID, SYMBIOTE_SLOT) {
             self.panic("Failed to load symbiote");
         }
 
         // 4. Schedule symbiote at highest priority
-        if let Err(_) = self.scheduler.schedule(0, Priority::MAX) {
+        if let Err(_) = self.schedule_muscle(SYMBIOTE_SLOT, Priority::MAX) {
             self.panic("Failed to schedule symbiote");
         }
 
@@ -99,10 +108,8 @@ impl MuscleNucleus {
     /// Main event processing loop
     fn event_loop(&mut self) -> ! {
         loop {
-            // Process lattice updates
-            if let Some(update) = self.lattice.next_update() {
-                self.process_lattice_update(update);
-            }
+            // Process lattice updates; unverified ones are dropped
+            let _ = self.process_next_lattice_update();
 
             // Process timer events (1Hz heartbeat)
             if self.timer_elapsed() {
@@ -110,13 +117,45 @@ impl MuscleNucleus {
             }
 
             // Execute scheduled muscles
-            self.scheduler.execute_next();
+            self.run_next_muscle();
         }
     }
 
+    /// Run the next scheduled muscle, dispatching syscalls on its behalf
+    /// until another one runs; returns its slot
+    pub fn run_next_muscle(&mut self) -> Option<usize> {
+        let slot = self.scheduler.execute_next()?;
+        self.running_slot = slot;
+        Some(slot)
+    }
+
+    /// Queue an incoming lattice update for verification and dispatch
+    pub fn enqueue_lattice_update(&mut self, update: LatticeUpdate) -> bool {
+        self.lattice.push_update(update)
+    }
+
+    /// Dispatch the next queued lattice update to the lattice update rule.
+    ///
+    /// Returns `None` when the queue is empty, and `VerificationFailed` when
+    /// the update does not verify against the current lattice root; such
+    /// updates are dropped without running the rule.
+    pub fn process_next_lattice_update(&mut self) -> Option<Result<()>> {
+        let update = match self.lattice.next_update()? {
+            Ok(update) => update,
+            Err(err) => return Some(Err(err)),
+        };
+        self.process_lattice_update(update);
+        Some(Ok(()))
+    }
+
+    /// Rule currently being executed (for testing)
+    pub fn current_rule(&self) -> RuleKind {
+        self.current_rule
+    }
+
     /// Process lattice update rule
     fn process_lattice_update(&mut self, update: LatticeUpdate) {
-        self.current_rule = RuleId::LatticeUpdate;
+        self.current_rule = RuleKind::LatticeUpdate;
 
         if let Some(action) = self.symbiote.process_update(update) {
             if action.is_healing() && self.can_emit_update() {
@@ -129,7 +168,7 @@ impl MuscleNucleus {
 
     /// Process 1Hz heartbeat rule
     fn process_heartbeat(&mut self) {
-        self.current_rule = RuleId::Timer;
+        self.current_rule = RuleKind::Timer;
         self.heartbeat_counter = self.heartbeat_counter.wrapping_add(1);
 
         // Emit heartbeat to lattice
@@ -150,7 +189,7 @@ impl MuscleNucleus {
             return Err(NucleusError::CapacityExceeded);
         }
 
-        if !self.capabilities.can_load_muscle() {
+        if !self.capabilities.can_load_muscle_for(slot) {
             return Err(NucleusError::InvalidCapability);
         }
 
@@ -166,15 +205,37 @@ impl MuscleNucleus {
         Ok(())
     }
 
-    /// Emit an update to the lattice
+    /// Schedule the muscle in `slot` if its live capabilities allow `priority`
+    pub fn schedule_muscle(&mut self, slot: usize, priority: Priority) -> Result<()> {
+        if !self.capabilities.can_schedule_for(slot, priority as u8) {
+            return Err(NucleusError::InvalidCapability);
+        }
+        self.scheduler.schedule(slot, priority)
+    }
+
+    /// Withdraw a capability from the muscle in `slot` at runtime, e.g. to
+    /// contain it after quarantine; other muscles keep theirs. Revoking
+    /// `Schedule` also takes an already scheduled muscle off the ready table.
+    pub fn revoke_capability(&mut self, slot: usize, cap: CapabilityKind) -> Result<()> {
+        self.capabilities.revoke(slot, cap)?;
+        if cap == CapabilityKind::Schedule {
+            self.scheduler.unschedule(slot);
+        }
+        Ok(())
+    }
+
+    /// Reinstate a capability withdrawn with [`Self::revoke_capability`]
+    pub fn grant_capability(&mut self, slot: usize, cap: CapabilityKind) -> Result<()> {
+        self.capabilities.grant(slot, cap)
+    }
+
+    /// Emit an update to the lattice on the symbiote's behalf
     fn emit_update(&mut self, blob: SealedBlob) -> Result<()> {
-        if !self.capabilities.can_emit_update() {
+        if !self.capabilities.can_emit_update_for(SYMBIOTE_SLOT) {
             return Err(NucleusError::InvalidCapability);
         }
 
-        self.update_buffer
-            .allocate(blob)
-            .map_err(|_| NucleusError::CapacityExceeded)?;
+        self.update_buffer.allocate(blob)?;
 
         // In production, this would send to lattice
         Ok(())
@@ -235,8 +296,8 @@ impl SyscallHandler for MuscleNucleus {
             Syscall::LatticeRead => {
                 // args.arg0: position, args.arg1: buffer ptr
                 // In a real system, we'd copy to user buffer.
-                // Here we just verify capability.
-                if !self.capabilities.can_emit_update() {
+                // Here we just verify the calling muscle's capability.
+                if !self.capabilities.can_emit_update_for(self.running_slot) {
                     // Using emit as proxy for lattice access
                     return Err(NucleusError::InvalidCapability);
                 }
@@ -244,7 +305,7 @@ impl SyscallHandler for MuscleNucleus {
             }
             Syscall::LatticeWrite => {
                 // args.arg0: buffer ptr, args.arg1: len
-                if !self.capabilities.can_emit_update() {
+                if !self.capabilities.can_emit_update_for(self.running_slot) {
                     return Err(NucleusError::InvalidCapability);
                 }
                 // Logic to write to lattice would go here
@@ -13,17 +13,42 @@ impl Priority {
     pub const MAX: Self = Self::High;
 }
 
-/// Fixed-size scheduler with compile-time analysis
+/// Highest priority a waiting muscle can age to; `Priority::MAX` stays
+/// reserved for muscles scheduled there (the Symbiote)
+pub const AGING_CAP: u8 = Priority::MAX as u8 - 1;
+
+#[derive(Debug, Clone, Copy)]
+struct ReadyEntry {
+    base: u8,
+    /// Ticks waited since this muscle last ran
+    age: u8,
+}
+
+impl ReadyEntry {
+    const fn effective_priority(&self) -> u8 {
+        if self.base == Priority::MAX as u8 {
+            return self.base;
+        }
+        let aged = self.base.saturating_add(self.age);
+        if aged > AGING_CAP {
+            AGING_CAP
+        } else {
+            aged
+        }
+    }
+}
+
+/// Fixed-size scheduler with priority aging
 #[derive(Debug)]
 pub struct Scheduler {
-    schedule: [Option<usize>; 256], // Muscle slots by priority
+    ready: [Option<ReadyEntry>; MAX_MUSCLES], // Ready muscles by slot
     current_slot: u8,
 }
 
 impl Scheduler {
     pub const fn new() -> Self {
         Self {
-            schedule: [None; 256],
+            ready: [None; MAX_MUSCLES],
             current_slot: 0,
         }
     }
@@ -34,23 +59,60 @@ impl Scheduler {
             return Err(NucleusError::CapacityExceeded);
         }
 
-        let priority_val = priority as u8;
-        self.schedule[priority_val as usize] = Some(muscle_slot);
+        self.ready[muscle_slot] = Some(ReadyEntry {
+            base: priority as u8,
+            age: 0,
+        });
         Ok(())
     }
 
-    /// Execute next scheduled muscle
-    pub fn execute_next(&mut self) {
-        // Round-robin within priority levels
-        for priority in (0..=255).rev() {
-            if let Some(slot) = self.schedule[priority as usize] {
-                // In production, this would context switch to muscle
-                self.execute_muscle(slot);
-                break;
+    /// Remove a muscle from the ready table; it no longer runs until it is
+    /// scheduled again
+    pub fn unschedule(&mut self, muscle_slot: usize) {
+        if let Some(entry) = self.ready.get_mut(muscle_slot) {
+            *entry = None;
+        }
+    }
+
+    /// Priority a ready muscle currently competes at, including aging
+    pub fn effective_priority(&self, muscle_slot: usize) -> Option<u8> {
+        self.ready
+            .get(muscle_slot)
+            .copied()
+            .flatten()
+            .map(|entry| entry.effective_priority())
+    }
+
+    /// Execute next scheduled muscle, returning its slot.
+    ///
+    /// The highest effective priority runs (ties go to the longest waiter,
+    /// then the lowest slot) and its age resets; every other ready muscle
+    /// ages by one tick.
+    pub fn execute_next(&mut self) -> Option<usize> {
+        let next = self
+            .ready
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| entry.map(|e| (slot, e)))
+            .max_by_key(|(slot, e)| (e.effective_priority(), e.age, core::cmp::Reverse(*slot)))
+            .map(|(slot, _)| slot);
+
+        if let Some(slot) = next {
+            for (i, entry) in self.ready.iter_mut().enumerate() {
+                if let Some(entry) = entry {
+                    entry.age = if i == slot {
+                        0
+                    } else {
+                        entry.age.saturating_add(1)
+                    };
+                }
             }
+            // In production, this would context switch to muscle
+            self.execute_muscle(slot);
         }
 
         self.current_slot = self.current_slot.wrapping_add(1);
+        next
     }
 
     /// Execute a specific muscle
@@ -117,7 +117,7 @@ pub mod capability {
 pub use integration::{HardwareAttestation, LatticeStream, SymbioteInterface};
 pub use kernel::MuscleNucleus;
 pub use memory::FixedAllocator;
-pub use rules::{RuleEngine, RuleId};
+pub use rules::{RuleEngine, RuleId, RuleKind};
 
 /// Core error types for the nucleus
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -5,6 +5,7 @@ extern crate alloc;
 
 use core::panic::PanicInfo;
 use linked_list_allocator::LockedHeap;
+use nucleus::integration::AttestationProof;
 use nucleus::kernel::MuscleNucleus;
 
 #[global_allocator]
@@ -25,6 +26,10 @@ pub extern "C" fn _start() -> ! {
     // Initialize the biological kernel
     let mut nucleus = MuscleNucleus::new();
 
+    // In production, the proof is the TPM/secure boot quote for this boot
+    // For prototype, present the first boot counter
+    let proof = AttestationProof::new(1);
+
     // Execute boot rule - this never returns
-    nucleus.execute_boot_rule();
+    nucleus.execute_boot_rule(proof);
 }
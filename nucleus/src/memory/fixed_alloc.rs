@@ -1,8 +1,11 @@
+use crate::{NucleusError, Result};
+
 /// Fixed-size allocator for no-std environments
 #[derive(Debug)]
 pub struct FixedAllocator<T, const N: usize> {
     buffer: [Option<T>; N],
     count: usize,
+    high_water: usize,
 }
 
 impl<T: Copy, const N: usize> FixedAllocator<T, N> {
@@ -10,23 +13,24 @@ impl<T: Copy, const N: usize> FixedAllocator<T, N> {
         Self {
             buffer: [None; N],
             count: 0,
+            high_water: 0,
         }
     }
 
-    pub fn allocate(&mut self, item: T) -> Result<(), ()> {
-        if self.count >= N {
-            return Err(());
-        }
+    /// Store `item` in the first free slot and return its index
+    pub fn allocate(&mut self, item: T) -> Result<usize> {
+        let index = self
+            .buffer
+            .iter()
+            .position(Option::is_none)
+            .ok_or(NucleusError::CapacityExceeded)?;
 
-        for slot in &mut self.buffer {
-            if slot.is_none() {
-                *slot = Some(item);
-                self.count += 1;
-                return Ok(());
-            }
+        self.buffer[index] = Some(item);
+        self.count += 1;
+        if self.count > self.high_water {
+            self.high_water = self.count;
         }
-
-        Err(())
+        Ok(index)
     }
 
     pub fn deallocate(&mut self, index: usize) -> Option<T> {
@@ -39,6 +43,28 @@ impl<T: Copy, const N: usize> FixedAllocator<T, N> {
         None
     }
 
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.buffer.get(index)?.as_ref()
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.buffer.get_mut(index)?.as_mut()
+    }
+
+    /// Number of occupied slots
+    pub const fn used(&self) -> usize {
+        self.count
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Most slots ever occupied at once
+    pub const fn high_water_mark(&self) -> usize {
+        self.high_water
+    }
+
     pub const fn remaining(&self) -> usize {
         N - self.count
     }
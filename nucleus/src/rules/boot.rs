@@ -1,4 +1,4 @@
-use crate::integration::{HardwareAttestation, LatticeStream};
+use crate::integration::{AttestationProof, HardwareAttestation, LatticeStream};
 
 pub struct BootRule;
 
@@ -7,9 +7,13 @@ impl BootRule {
         Self
     }
 
-    pub fn execute(attestation: &mut HardwareAttestation, lattice: &LatticeStream) -> bool {
+    pub fn execute(
+        attestation: &mut HardwareAttestation,
+        proof: &AttestationProof,
+        lattice: &LatticeStream,
+    ) -> bool {
         // 1. Verify hardware attestation
-        if !attestation.verify() {
+        if !attestation.verify(proof) {
             return false;
         }
 
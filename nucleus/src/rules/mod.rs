@@ -6,42 +6,148 @@ pub use boot::BootRule;
 pub use timer::TimerRule;
 pub use updates::LatticeUpdateRule;
 
-/// Rule identifiers for compile-time verification
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum RuleId {
+use crate::{NucleusError, Result, MAX_UPDATES};
+
+/// Built-in rule kinds known at compile time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleKind {
     Boot,
     LatticeUpdate,
     Timer,
 }
 
-/// Fixed-size rule engine
+/// Handle to a registered rule.
+///
+/// Fields are private so ids can only come from [`RuleEngine::register`];
+/// the generation invalidates the id once its slot is freed and reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleId {
+    index: u8,
+    generation: u16,
+}
+
+impl RuleId {
+    /// Slot index backing this id
+    pub const fn index(&self) -> usize {
+        self.index as usize
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct RuleSlot {
+    generation: u16,
+    kind: Option<RuleKind>,
+}
+
+impl RuleSlot {
+    const EMPTY: Self = Self {
+        generation: 0,
+        kind: None,
+    };
+}
+
+/// Fixed-size rule engine with an array-backed slot map of registered rules
 #[derive(Debug)]
 pub struct RuleEngine {
-    current_rule: RuleId,
+    current_rule: RuleKind,
     rule_flags: u8,
+    slots: [RuleSlot; MAX_UPDATES],
+    registered: usize,
 }
 
 impl RuleEngine {
     pub const fn new() -> Self {
         Self {
-            current_rule: RuleId::Boot,
+            current_rule: RuleKind::Boot,
             rule_flags: 0b111, // All rules enabled
+            slots: [RuleSlot::EMPTY; MAX_UPDATES],
+            registered: 0,
         }
     }
 
-    pub const fn is_rule_enabled(&self, rule: RuleId) -> bool {
+    pub const fn is_rule_enabled(&self, rule: RuleKind) -> bool {
         match rule {
-            RuleId::Boot => (self.rule_flags & 0b001) != 0,
-            RuleId::LatticeUpdate => (self.rule_flags & 0b010) != 0,
-            RuleId::Timer => (self.rule_flags & 0b100) != 0,
+            RuleKind::Boot => (self.rule_flags & 0b001) != 0,
+            RuleKind::LatticeUpdate => (self.rule_flags & 0b010) != 0,
+            RuleKind::Timer => (self.rule_flags & 0b100) != 0,
         }
     }
 
-    pub fn set_current_rule(&mut self, rule: RuleId) {
+    pub fn set_current_rule(&mut self, rule: RuleKind) {
         self.current_rule = rule;
     }
 
-    pub fn current_rule(&self) -> RuleId {
+    pub fn current_rule(&self) -> RuleKind {
         self.current_rule
     }
+
+    /// Register a rule in the lowest free slot
+    pub fn register(&mut self, kind: RuleKind) -> Result<RuleId> {
+        let index = self
+            .slots
+            .iter()
+            .position(|slot| slot.kind.is_none())
+            .ok_or(NucleusError::CapacityExceeded)?;
+
+        let slot = &mut self.slots[index];
+        slot.kind = Some(kind);
+        self.registered += 1;
+
+        Ok(RuleId {
+            index: index as u8,
+            generation: slot.generation,
+        })
+    }
+
+    /// Remove a rule, invalidating its id and freeing the slot for reuse
+    pub fn remove(&mut self, id: RuleId) -> Result<RuleKind> {
+        let slot = self.live_slot_mut(id).ok_or(NucleusError::RuleViolation)?;
+        let kind = slot.kind.take().ok_or(NucleusError::RuleViolation)?;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.registered -= 1;
+        Ok(kind)
+    }
+
+    /// Kind of a registered rule, or `None` if the id is stale
+    pub fn get(&self, id: RuleId) -> Option<RuleKind> {
+        let slot = self.slots.get(id.index())?;
+        if slot.generation == id.generation {
+            slot.kind
+        } else {
+            None
+        }
+    }
+
+    pub fn contains(&self, id: RuleId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// Number of registered rules
+    pub const fn len(&self) -> usize {
+        self.registered
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.registered == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        MAX_UPDATES
+    }
+
+    /// Registered rules in slot order
+    pub fn iter(&self) -> impl Iterator<Item = (RuleId, RuleKind)> + '_ {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let id = RuleId {
+                index: index as u8,
+                generation: slot.generation,
+            };
+            slot.kind.map(|kind| (id, kind))
+        })
+    }
+
+    fn live_slot_mut(&mut self, id: RuleId) -> Option<&mut RuleSlot> {
+        let slot = self.slots.get_mut(id.index())?;
+        (slot.generation == id.generation).then_some(slot)
+    }
 }
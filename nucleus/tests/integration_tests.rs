@@ -1,6 +1,6 @@
 #![cfg(test)]
 
-use nucleus::integration::{HardwareAttestation, LatticeStream};
+use nucleus::integration::{AttestationProof, HardwareAttestation, LatticeStream};
 use nucleus::kernel::MuscleNucleus;
 
 #[test]
@@ -9,7 +9,7 @@ fn test_boot_rule_verification() {
     let lattice = LatticeStream::new();
 
     // Boot rule should pass with valid attestation
-    assert!(attestation.verify());
+    assert!(attestation.verify(&AttestationProof::new(1)));
     // Lattice root verification would depend on actual genesis
 }
 
@@ -24,3 +24,102 @@ fn test_nucleus_creation() {
     // Verify capabilities are set using public method
     assert!(nucleus.has_load_muscle_capability());
 }
+
+fn signed_update(tag: u8) -> ea_ledger::MuscleUpdate {
+    let mut blob = [0u8; ea_ledger::MAX_BLOB];
+    blob[100] = tag;
+    ea_ledger::generate_update([0xEA; 32], 1, blob, ea_ledger::GENESIS_ROOT)
+}
+
+#[test]
+fn test_lattice_stream_rejects_tampered_update() {
+    use nucleus::NucleusError;
+
+    let mut lattice = LatticeStream::new();
+    let valid = signed_update(0x42);
+    let mut tampered = signed_update(0x42);
+    tampered.blob[100] = 0x43;
+
+    assert!(lattice.push_update(tampered));
+    assert!(lattice.push_update(valid));
+
+    assert_eq!(
+        lattice.next_update().map(|r| r.err()),
+        Some(Some(NucleusError::VerificationFailed))
+    );
+    assert!(matches!(lattice.next_update(), Some(Ok(u)) if u.blob[100] == 0x42));
+    assert!(lattice.next_update().is_none());
+    assert_eq!(lattice.rejected(), 1);
+}
+
+#[test]
+fn test_lattice_stream_advances_root_on_acceptance() {
+    use ea_ledger::{apply_update, generate_update, GENESIS_ROOT, MAX_BLOB};
+    use nucleus::NucleusError;
+
+    let mut lattice = LatticeStream::new();
+    assert_eq!(lattice.root(), GENESIS_ROOT);
+
+    let first = signed_update(0x01);
+    let after_first = apply_update(GENESIS_ROOT, &first).unwrap();
+    let second = generate_update([0xEA; 32], 2, [0x02; MAX_BLOB], after_first);
+    let after_second = apply_update(after_first, &second).unwrap();
+    // Built against genesis, so stale once `first` is accepted
+    let stale = generate_update([0x01; 32], 1, [0x03; MAX_BLOB], GENESIS_ROOT);
+
+    assert!(lattice.push_update(first));
+    assert!(lattice.push_update(second));
+    assert!(lattice.push_update(stale));
+
+    assert!(matches!(lattice.next_update(), Some(Ok(u)) if u == first));
+    assert_eq!(lattice.root(), after_first);
+    assert!(matches!(lattice.next_update(), Some(Ok(u)) if u == second));
+    assert_eq!(lattice.root(), after_second);
+    assert_eq!(
+        lattice.next_update().map(|r| r.err()),
+        Some(Some(NucleusError::VerificationFailed))
+    );
+    assert_eq!(lattice.root(), after_second);
+    assert_eq!(lattice.rejected(), 1);
+}
+
+#[test]
+fn test_only_verified_updates_trigger_lattice_rule() {
+    use nucleus::{NucleusError, RuleKind};
+
+    let mut nucleus = MuscleNucleus::new();
+    let mut tampered = signed_update(0x10);
+    tampered.blob[100] ^= 0xFF;
+
+    assert!(nucleus.enqueue_lattice_update(tampered));
+    assert!(nucleus.enqueue_lattice_update(signed_update(0x10)));
+
+    assert_eq!(
+        nucleus.process_next_lattice_update(),
+        Some(Err(NucleusError::VerificationFailed))
+    );
+    assert_eq!(nucleus.current_rule(), RuleKind::Boot);
+
+    assert_eq!(nucleus.process_next_lattice_update(), Some(Ok(())));
+    assert_eq!(nucleus.current_rule(), RuleKind::LatticeUpdate);
+    assert_eq!(nucleus.process_next_lattice_update(), None);
+}
+
+#[test]
+fn test_attestation_rejects_replayed_proof() {
+    let mut attestation = HardwareAttestation::new();
+    let proof = AttestationProof::new(7);
+
+    assert!(attestation.verify(&proof));
+    assert_eq!(attestation.last_counter(), Some(7));
+
+    // Replaying the same proof, or an older one, is rejected
+    assert!(!attestation.verify(&proof));
+    assert!(!attestation.verify(&AttestationProof::new(3)));
+    assert_eq!(attestation.last_counter(), Some(7));
+
+    // A newer counter is accepted and advances the stored value
+    assert!(attestation.verify(&AttestationProof::new(8)));
+    assert_eq!(attestation.last_counter(), Some(8));
+    assert!(attestation.is_verified());
+}
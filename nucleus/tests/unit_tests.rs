@@ -13,6 +13,33 @@ fn test_fixed_allocator() {
     assert_eq!(alloc.remaining(), 3);
 }
 
+#[test]
+fn test_fixed_allocator_capacity_and_high_water() {
+    use nucleus::NucleusError;
+
+    let mut alloc: FixedAllocator<u32, 4> = FixedAllocator::new();
+    assert_eq!(alloc.capacity(), 4);
+
+    for i in 0..4 {
+        assert_eq!(alloc.allocate(i), Ok(i as usize));
+    }
+    assert_eq!(alloc.used(), 4);
+    assert_eq!(alloc.high_water_mark(), 4);
+
+    // A full allocator fails without touching its slots
+    assert_eq!(alloc.allocate(99), Err(NucleusError::CapacityExceeded));
+    assert_eq!(alloc.used(), 4);
+    assert!(alloc.is_full());
+
+    // Freeing lowers usage but not the high-water mark
+    assert_eq!(alloc.deallocate(1), Some(1));
+    assert_eq!(alloc.deallocate(2), Some(2));
+    assert_eq!(alloc.used(), 2);
+    assert_eq!(alloc.high_water_mark(), 4);
+    assert_eq!(alloc.allocate(7), Ok(1));
+    assert_eq!(alloc.high_water_mark(), 4);
+}
+
 #[test]
 fn test_capabilities() {
     let caps = CapabilitySet::new();
@@ -21,6 +48,84 @@ fn test_capabilities() {
     assert!(caps.can_emit_update());
 }
 
+#[test]
+fn test_capability_revoke_and_grant() {
+    use nucleus::kernel::CapabilityKind;
+    use nucleus::{NucleusError, MAX_MUSCLES};
+
+    let mut caps = CapabilitySet::new();
+    caps.revoke(1, CapabilityKind::EmitUpdate).unwrap();
+    assert!(caps.is_revoked(1, CapabilityKind::EmitUpdate));
+    assert!(!caps.can_emit_update_for(1));
+
+    // Other slots and capabilities are untouched
+    assert!(caps.can_emit_update_for(2));
+    assert!(caps.can_load_muscle_for(1));
+    assert!(caps.can_schedule_for(1, 0));
+    assert_eq!(
+        caps.revoke(MAX_MUSCLES, CapabilityKind::EmitUpdate),
+        Err(NucleusError::CapacityExceeded)
+    );
+
+    caps.grant(1, CapabilityKind::EmitUpdate).unwrap();
+    assert!(caps.can_emit_update_for(1));
+}
+
+#[test]
+fn test_revoked_muscle_is_contained_while_others_emit() {
+    use nucleus::kernel::{CapabilityKind, MuscleNucleus, Priority};
+    use nucleus::syscalls::{Syscall, SyscallArgs, SyscallHandler};
+    use nucleus::NucleusError;
+
+    let args = || SyscallArgs {
+        arg0: 0,
+        arg1: 0,
+        arg2: 0,
+    };
+    let mut nucleus = MuscleNucleus::new();
+    nucleus.schedule_muscle(1, Priority::Normal).unwrap();
+    nucleus.schedule_muscle(2, Priority::Normal).unwrap();
+
+    nucleus
+        .revoke_capability(1, CapabilityKind::EmitUpdate)
+        .unwrap();
+
+    // Equal priorities alternate: the revoked muscle is refused, its
+    // neighbour still writes to the lattice
+    assert_eq!(nucleus.run_next_muscle(), Some(1));
+    assert_eq!(
+        nucleus.handle_syscall(Syscall::LatticeWrite, args()),
+        Err(NucleusError::InvalidCapability)
+    );
+    assert_eq!(nucleus.run_next_muscle(), Some(2));
+    assert!(nucleus
+        .handle_syscall(Syscall::LatticeWrite, args())
+        .is_ok());
+
+    // Revoking Schedule takes the already scheduled muscle off the CPU
+    nucleus
+        .revoke_capability(1, CapabilityKind::Schedule)
+        .unwrap();
+    assert_eq!(
+        nucleus.schedule_muscle(1, Priority::Normal),
+        Err(NucleusError::InvalidCapability)
+    );
+    assert_eq!(nucleus.run_next_muscle(), Some(2));
+    assert_eq!(nucleus.run_next_muscle(), Some(2));
+
+    nucleus
+        .grant_capability(1, CapabilityKind::Schedule)
+        .unwrap();
+    nucleus
+        .grant_capability(1, CapabilityKind::EmitUpdate)
+        .unwrap();
+    nucleus.schedule_muscle(1, Priority::Normal).unwrap();
+    assert_eq!(nucleus.run_next_muscle(), Some(1));
+    assert!(nucleus
+        .handle_syscall(Syscall::LatticeWrite, args())
+        .is_ok());
+}
+
 #[test]
 fn test_syscalls() {
     use nucleus::kernel::MuscleNucleus;
@@ -37,3 +142,91 @@ fn test_syscalls() {
     let res = nucleus.handle_syscall(Syscall::MuscAlloc, args);
     assert!(res.is_ok());
 }
+
+#[test]
+fn test_rule_registration_capacity() {
+    use nucleus::rules::{RuleEngine, RuleKind};
+    use nucleus::{NucleusError, MAX_UPDATES};
+
+    let mut engine = RuleEngine::new();
+    let ids: Vec<_> = (0..MAX_UPDATES)
+        .map(|_| engine.register(RuleKind::Timer).unwrap())
+        .collect();
+
+    assert_eq!(engine.len(), MAX_UPDATES);
+    assert_eq!(
+        engine.register(RuleKind::Boot),
+        Err(NucleusError::CapacityExceeded)
+    );
+    assert!(ids
+        .iter()
+        .all(|id| engine.get(*id) == Some(RuleKind::Timer)));
+}
+
+#[test]
+fn test_rule_slot_reuse_invalidates_old_id() {
+    use nucleus::rules::{RuleEngine, RuleKind};
+    use nucleus::{NucleusError, MAX_UPDATES};
+
+    let mut engine = RuleEngine::new();
+    let ids: Vec<_> = (0..MAX_UPDATES)
+        .map(|_| engine.register(RuleKind::Timer).unwrap())
+        .collect();
+
+    let removed = ids[3];
+    assert_eq!(engine.remove(removed), Ok(RuleKind::Timer));
+    assert_eq!(engine.len(), MAX_UPDATES - 1);
+    assert!(!engine.contains(removed));
+    assert_eq!(engine.remove(removed), Err(NucleusError::RuleViolation));
+
+    // The freed slot is reused, but under a new id
+    let reused = engine.register(RuleKind::LatticeUpdate).unwrap();
+    assert_eq!(reused.index(), removed.index());
+    assert_ne!(reused, removed);
+    assert_eq!(engine.get(reused), Some(RuleKind::LatticeUpdate));
+    assert_eq!(engine.get(removed), None);
+
+    // Other ids are unaffected
+    assert_eq!(engine.get(ids[4]), Some(RuleKind::Timer));
+    assert_eq!(engine.iter().count(), MAX_UPDATES);
+}
+
+#[test]
+fn test_scheduler_aging_prevents_starvation() {
+    use nucleus::kernel::{Priority, Scheduler};
+    use nucleus::SCHEDULE_SLOTS;
+
+    let mut scheduler = Scheduler::new();
+    scheduler.schedule(1, Priority::Normal).unwrap();
+    scheduler.schedule(2, Priority::Low).unwrap();
+
+    // Both stay ready; the low-priority muscle must still get a turn
+    let ran: Vec<_> = (0..SCHEDULE_SLOTS)
+        .map(|_| scheduler.execute_next().unwrap())
+        .collect();
+    let first_low = ran
+        .iter()
+        .position(|&slot| slot == 2)
+        .expect("low priority muscle starved");
+    assert!(ran[..first_low].iter().all(|&slot| slot == 1));
+
+    // Running resets its age back to the base priority
+    while scheduler.execute_next() != Some(2) {}
+    assert_eq!(scheduler.effective_priority(2), Some(Priority::Low as u8));
+}
+
+#[test]
+fn test_symbiote_always_preempts_aged_muscles() {
+    use nucleus::kernel::{Priority, Scheduler, AGING_CAP};
+
+    let mut scheduler = Scheduler::new();
+    scheduler.schedule(0, Priority::MAX).unwrap(); // Symbiote
+    scheduler.schedule(3, Priority::Min).unwrap();
+
+    for _ in 0..1000 {
+        assert_eq!(scheduler.execute_next(), Some(0));
+    }
+    // The waiting muscle ages only up to just below the reserved priority
+    assert_eq!(scheduler.effective_priority(3), Some(AGING_CAP));
+    assert_eq!(scheduler.effective_priority(0), Some(Priority::MAX as u8));
+}
@@ -18,6 +18,8 @@ impl Error {
     pub const CUSTOM_START: u32 = 1 << 31;
     /// Unknown error code fallback.
     const UNKNOWN: NonZeroU32 = unsafe { NonZeroU32::new_unchecked(Self::INTERNAL_START) };
+    /// Custom code reported when no secure entropy source is available.
+    pub const NO_ENTROPY_SOURCE: u32 = Self::CUSTOM_START + 1;
 
     /// Create a new error from a code.
     pub fn new_custom(code: u32) -> Self {
@@ -51,16 +53,107 @@ impl fmt::Display for Error {
 impl std::error::Error for Error {}
 
 /// When std is enabled, use real random bytes from the OS.
-/// In no_std mode, fills with zeros (deterministic).
+/// In no_std mode, fails with `Error::NO_ENTROPY_SOURCE` unless the
+/// `insecure-zero-rng` feature opts into filling with zeros (deterministic).
 #[cfg(feature = "std")]
 pub fn getrandom(dest: &mut [u8]) -> Result<(), Error> {
-    use std::io::Read;
-    std::fs::File::open("/dev/urandom")
-        .and_then(|mut f| f.read_exact(dest))
-        .map_err(|_| Error::new_custom(1))
+    match os::sys_fill(dest) {
+        Some(result) => result.map_err(|_| Error::new_custom(1)),
+        None => os::urandom_fill(dest),
+    }
 }
 
-#[cfg(not(feature = "std"))]
+#[cfg(feature = "std")]
+mod os {
+    use super::Error;
+    use std::fs::File;
+    use std::io::{self, ErrorKind, Read};
+    use std::sync::OnceLock;
+
+    /// Fill `dest` via `getrandom(2)`.
+    ///
+    /// Returns `None` when the syscall is unavailable (old kernel or seccomp
+    /// filter) so the caller can fall back to `/dev/urandom`.
+    #[cfg(target_os = "linux")]
+    pub fn sys_fill(dest: &mut [u8]) -> Option<io::Result<()>> {
+        extern "C" {
+            fn getrandom(buf: *mut u8, buflen: usize, flags: u32) -> isize;
+        }
+
+        let mut filled = 0;
+        while filled < dest.len() {
+            let rest = &mut dest[filled..];
+            let ret = unsafe { getrandom(rest.as_mut_ptr(), rest.len(), 0) };
+            if ret < 0 {
+                let err = io::Error::last_os_error();
+                match err.kind() {
+                    ErrorKind::Interrupted => continue,
+                    ErrorKind::Unsupported | ErrorKind::PermissionDenied if filled == 0 => {
+                        return None
+                    }
+                    _ => return Some(Err(err)),
+                }
+            }
+            filled += ret as usize;
+        }
+        Some(Ok(()))
+    }
+
+    /// Fill `dest` via `getentropy(3)`, which caps each request at 256 bytes.
+    #[cfg(any(
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    ))]
+    pub fn sys_fill(dest: &mut [u8]) -> Option<io::Result<()>> {
+        extern "C" {
+            fn getentropy(buf: *mut u8, buflen: usize) -> i32;
+        }
+
+        for chunk in dest.chunks_mut(256) {
+            if unsafe { getentropy(chunk.as_mut_ptr(), chunk.len()) } != 0 {
+                return Some(Err(io::Error::last_os_error()));
+            }
+        }
+        Some(Ok(()))
+    }
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "freebsd",
+        target_os = "openbsd"
+    )))]
+    pub fn sys_fill(_dest: &mut [u8]) -> Option<io::Result<()>> {
+        None
+    }
+
+    /// `/dev/urandom`, opened once on first fallback and reused afterwards.
+    static URANDOM: OnceLock<File> = OnceLock::new();
+
+    pub fn urandom_fill(dest: &mut [u8]) -> Result<(), Error> {
+        let file = match URANDOM.get() {
+            Some(file) => file,
+            None => {
+                let file = File::open("/dev/urandom").map_err(|_| Error::new_custom(1))?;
+                URANDOM.get_or_init(|| file)
+            }
+        };
+
+        let mut reader: &File = file;
+        reader.read_exact(dest).map_err(|_| Error::new_custom(1))
+    }
+
+    #[cfg(all(test, unix))]
+    pub fn urandom_fd() -> Option<i32> {
+        use std::os::unix::io::AsRawFd;
+        URANDOM.get().map(AsRawFd::as_raw_fd)
+    }
+}
+
+#[cfg(all(not(feature = "std"), feature = "insecure-zero-rng"))]
 pub fn getrandom(dest: &mut [u8]) -> Result<(), Error> {
     for byte in dest.iter_mut() {
         *byte = 0;
@@ -68,6 +161,11 @@ pub fn getrandom(dest: &mut [u8]) -> Result<(), Error> {
     Ok(())
 }
 
+#[cfg(all(not(feature = "std"), not(feature = "insecure-zero-rng")))]
+pub fn getrandom(_dest: &mut [u8]) -> Result<(), Error> {
+    Err(Error::new_custom(Error::NO_ENTROPY_SOURCE))
+}
+
 /// Placeholder macro with the same signature as the upstream crate so existing
 /// uses of `register_custom_getrandom!` still compile.
 #[macro_export]
@@ -78,3 +176,62 @@ macro_rules! register_custom_getrandom {
         };
     };
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    #[test]
+    fn large_fill_is_random() {
+        let mut first = vec![0u8; 1 << 20];
+        let mut second = vec![0u8; 1 << 20];
+        getrandom(&mut first).unwrap();
+        getrandom(&mut second).unwrap();
+
+        assert!(first.iter().any(|&b| b != 0));
+        assert_ne!(first, second);
+
+        let mut seen = [false; 256];
+        for &b in &first {
+            seen[b as usize] = true;
+        }
+        assert!(seen.iter().all(|&s| s));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn urandom_fallback_reuses_descriptor() {
+        let mut buf = [0u8; 64];
+        os::urandom_fill(&mut buf).unwrap();
+        let fd = os::urandom_fd();
+        assert!(fd.is_some());
+
+        for _ in 0..16 {
+            os::urandom_fill(&mut buf).unwrap();
+            assert_eq!(os::urandom_fd(), fd);
+        }
+    }
+}
+
+#[cfg(all(test, not(feature = "std")))]
+mod no_std_tests {
+    use super::*;
+
+    #[cfg(feature = "insecure-zero-rng")]
+    #[test]
+    fn insecure_feature_zero_fills() {
+        let mut buf = [0xAAu8; 32];
+        assert!(getrandom(&mut buf).is_ok());
+        assert_eq!(buf, [0u8; 32]);
+    }
+
+    #[cfg(not(feature = "insecure-zero-rng"))]
+    #[test]
+    fn without_opt_in_reports_unsupported() {
+        let mut buf = [0xAAu8; 32];
+        let err = getrandom(&mut buf).unwrap_err();
+        assert_eq!(err.code().get(), Error::NO_ENTROPY_SOURCE);
+        assert_eq!(buf, [0xAAu8; 32]);
+    }
+}
@@ -13,7 +13,7 @@ mod crypto;
 mod muscle_loader;
 mod uart;
 
-use crate::muscle_loader::{load_muscle, LoadedMuscle};
+use crate::muscle_loader::{is_plausible_blob, load_muscle, LoadedMuscle};
 use crate::uart::Uart;
 
 const N_MUSCLES: usize = 50;
@@ -120,6 +120,16 @@ fn load_all_muscles(
             continue;
         }
 
+        // Reject regions without a well-formed header before a full load
+        if !is_plausible_blob(blob_data) {
+            log(
+                uart,
+                "WARN",
+                &format!("Muscle {} skipped: malformed blob header", i),
+            );
+            continue;
+        }
+
         // Load and validate muscle
         match load_muscle(boot_services, master_key, blob_data, i) {
             Ok(loaded_muscle) => {
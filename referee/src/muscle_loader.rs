@@ -77,19 +77,37 @@ pub fn load_muscle(
     })
 }
 
+/// v5.0 blob magic and format version
+const BLOB_MAGIC: &[u8; 4] = b"EaM5";
+const BLOB_FORMAT_VERSION: u8 = 5;
+/// 40-byte header plus 8-byte integrity hash
+const MIN_BLOB_LEN: usize = 48;
+
+/// Cheap header sanity check run before attempting a full load
+///
+/// Rejects regions that cannot be a v5.0 blob (wrong magic, version,
+/// architecture code or name length) without hashing or decrypting them.
+pub fn is_plausible_blob(blob: &[u8]) -> bool {
+    blob.len() >= MIN_BLOB_LEN
+        && &blob[0..4] == BLOB_MAGIC
+        && blob[4] == BLOB_FORMAT_VERSION
+        && matches!(blob[5], 1 | 2)
+        && blob.len() >= 40 + blob[6] as usize
+}
+
 /// Parse v5.0 blob header
 fn parse_blob_header(blob: &[u8]) -> Result<(String, String, &[u8]), &'static str> {
-    if blob.len() < 48 {
+    if blob.len() < MIN_BLOB_LEN {
         return Err("blob too small");
     }
 
     // Verify magic
-    if &blob[0..4] != b"EaM5" {
+    if &blob[0..4] != BLOB_MAGIC {
         return Err("invalid magic");
     }
 
     let format_version = blob[4];
-    if format_version != 5 {
+    if format_version != BLOB_FORMAT_VERSION {
         return Err("unsupported format version");
     }
 
@@ -177,4 +195,43 @@ mod tests {
         assert_eq!(calculate_required_pages(4096), 1);
         assert_eq!(calculate_required_pages(4097), 2);
     }
+
+    #[test]
+    fn test_plausible_blob_header() {
+        let mut blob = [0u8; 8192];
+        blob[0..4].copy_from_slice(b"EaM5");
+        blob[4] = 5;
+        blob[5] = 1;
+        blob[6] = 4;
+        blob[8..12].copy_from_slice(b"core");
+        assert!(is_plausible_blob(&blob));
+
+        // Unknown architecture code
+        blob[5] = 9;
+        assert!(!is_plausible_blob(&blob));
+
+        // Truncated region
+        blob[5] = 2;
+        assert!(!is_plausible_blob(&blob[..MIN_BLOB_LEN - 1]));
+    }
+
+    #[test]
+    fn test_zeroed_slot_is_not_plausible() {
+        assert!(!is_plausible_blob(&[0u8; 8192]));
+    }
+
+    #[test]
+    fn test_garbage_is_not_plausible() {
+        // Deterministic pseudo-random fill
+        let mut state = 0x9E37_79B9u32;
+        let garbage: alloc::vec::Vec<u8> = (0..8192)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                state as u8
+            })
+            .collect();
+        assert!(!is_plausible_blob(&garbage));
+    }
 }
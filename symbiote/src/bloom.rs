@@ -0,0 +1,81 @@
+//! Bloom filter over 32-byte hashes
+//!
+//! Keys are expected to already be cryptographic digests, so probe positions
+//! are derived directly from the key bytes by double hashing instead of
+//! rehashing. A negative answer is exact; a positive one must be confirmed.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Bits allocated per expected item (~1% false-positive rate at capacity)
+const BITS_PER_ITEM: usize = 10;
+
+/// Probes per key, optimal for `BITS_PER_ITEM`
+const PROBES: u64 = 7;
+
+/// Fixed-size bloom filter keyed by 32-byte hashes
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+}
+
+impl BloomFilter {
+    /// Create a filter sized for `expected_items` keys
+    pub fn with_capacity(expected_items: usize) -> Self {
+        let words = (expected_items.max(1) * BITS_PER_ITEM).div_ceil(64);
+        Self {
+            bits: vec![0; words],
+        }
+    }
+
+    /// Add a key to the filter
+    pub fn insert(&mut self, key: &[u8; 32]) {
+        for bit in self.positions(key) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Whether `key` may have been inserted; `false` is definitive
+    pub fn may_contain(&self, key: &[u8; 32]) -> bool {
+        self.positions(key)
+            .all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Bit positions probed for `key`
+    #[allow(clippy::cast_possible_truncation)] // reduced modulo a usize bit count
+    fn positions(&self, key: &[u8; 32]) -> impl Iterator<Item = usize> {
+        let mut h1 = [0u8; 8];
+        let mut h2 = [0u8; 8];
+        h1.copy_from_slice(&key[..8]);
+        h2.copy_from_slice(&key[8..16]);
+        let h1 = u64::from_le_bytes(h1);
+        // Odd stride so probes never collapse onto a single bit
+        let h2 = u64::from_le_bytes(h2) | 1;
+        let len = self.bits.len() as u64 * 64;
+        (0..PROBES).map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % len) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(n: u32) -> [u8; 32] {
+        blake3::hash(&n.to_le_bytes()).into()
+    }
+
+    #[test]
+    fn test_no_false_negatives() {
+        let mut filter = BloomFilter::with_capacity(256);
+        for n in 0..512 {
+            filter.insert(&key(n));
+        }
+        assert!((0..512).all(|n| filter.may_contain(&key(n))));
+    }
+
+    #[test]
+    fn test_empty_filter_rejects_everything() {
+        let filter = BloomFilter::with_capacity(0);
+        assert!((0..64).all(|n| !filter.may_contain(&key(n))));
+    }
+}
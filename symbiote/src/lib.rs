@@ -43,32 +43,101 @@
 #![warn(clippy::all, clippy::pedantic)]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use ea_lattice_ledger::{generate_update, verify_update, LatticeRoot, MuscleUpdate, SealedBlob};
 
-use ea_lattice_ledger::{verify_update, LatticeRoot, MuscleUpdate};
+mod bloom;
+pub use bloom::BloomFilter;
 
 mod policy_engine;
-pub use policy_engine::{PolicyAction, PolicyEngine, SecurityPolicy};
+pub use policy_engine::{PolicyAction, PolicyEngine, QuarantineEntry, SecurityPolicy};
 
 pub mod patches;
 
+#[cfg(feature = "transport")]
+pub mod transport;
+
+/// Observer notified whenever the Symbiote decides on a policy action
+pub trait ActionSink {
+    /// Called synchronously with each action as it is produced
+    fn notify(&self, action: &PolicyAction);
+}
+
+/// Destination for healing updates, typically the lattice itself
+pub trait UpdateSink {
+    /// Called once with each healing update to publish
+    fn emit(&self, update: MuscleUpdate);
+}
+
+/// Produces the patched, sealed blob a healing update carries
+pub trait MuscleRebuilder {
+    /// Rebuild `muscle_id` at `vulnerable_version` with `patch` applied,
+    /// or `None` if the muscle cannot be patched
+    fn rebuild(
+        &self,
+        muscle_id: [u8; 32],
+        vulnerable_version: u64,
+        patch: &dyn patches::SecurityPatch,
+    ) -> Option<SealedBlob>;
+}
+
 /// Symbiote core - cryptographic immune system
-#[derive(Debug, Clone)]
 pub struct Symbiote {
     /// Current lattice root for verification
     pub current_root: LatticeRoot,
     /// Policy engine for security decisions
     pub policy_engine: PolicyEngine,
+    /// Runtime configuration
+    pub config: SymbioteConfig,
+    /// Registered action observers, notified in registration order
+    sinks: Vec<Box<dyn ActionSink>>,
+    /// Source of patched blobs; without one no healing update is produced
+    rebuilder: Option<Box<dyn MuscleRebuilder>>,
+}
+
+impl core::fmt::Debug for Symbiote {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Symbiote")
+            .field("current_root", &self.current_root)
+            .field("policy_engine", &self.policy_engine)
+            .field("config", &self.config)
+            .field("sinks", &self.sinks.len())
+            .field("rebuilder", &self.rebuilder.is_some())
+            .finish()
+    }
 }
 
 impl Symbiote {
     /// Create a new Symbiote instance
     pub fn new(current_root: LatticeRoot) -> Self {
+        Self::with_config(current_root, SymbioteConfig::default())
+    }
+
+    /// Create a new Symbiote instance with explicit configuration
+    pub fn with_config(current_root: LatticeRoot, config: SymbioteConfig) -> Self {
         Self {
             current_root,
             policy_engine: PolicyEngine::default(),
+            config,
+            sinks: Vec::new(),
+            rebuilder: None,
         }
     }
 
+    /// Register an observer for every policy action this Symbiote produces
+    pub fn on_action(&mut self, sink: impl ActionSink + 'static) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    /// Install the rebuilder healing actions use to produce patched muscles
+    pub fn set_rebuilder(&mut self, rebuilder: impl MuscleRebuilder + 'static) {
+        self.rebuilder = Some(Box::new(rebuilder));
+    }
+
     /// Process a lattice update and return any required actions
     pub fn process_update(&self, update: &MuscleUpdate) -> Option<PolicyAction> {
         // Verify the update is valid before processing
@@ -77,18 +146,63 @@ impl Symbiote {
         }
 
         // Evaluate against security policies
-        self.policy_engine.evaluate(update)
+        self.evaluate_and_notify(update)
     }
 
     /// Process update without verification (TEST ONLY - bypasses cryptographic checks)
     /// This should only be used in tests where constructing valid proofs is not feasible.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn process_update_unchecked(&self, update: &MuscleUpdate) -> Option<PolicyAction> {
-        self.policy_engine.evaluate(update)
+        self.evaluate_and_notify(update)
+    }
+
+    /// Report the action each verified update would trigger, in order,
+    /// without notifying sinks or executing anything.
+    ///
+    /// Updates that fail verification pair with `None`, as they would be
+    /// dropped by [`Self::process_update`].
+    #[must_use]
+    pub fn dry_run(&self, updates: &[MuscleUpdate]) -> Vec<(MuscleUpdate, Option<PolicyAction>)> {
+        updates
+            .iter()
+            .map(|update| {
+                let action = verify_update(self.current_root, update)
+                    .then(|| self.evaluate(update))
+                    .flatten();
+                (*update, action)
+            })
+            .collect()
+    }
+
+    /// Policy action for `update`
+    ///
+    /// The known-vulnerable check hashes the whole blob and can only ever
+    /// quarantine, so it is skipped when quarantine is disabled.
+    fn evaluate(&self, update: &MuscleUpdate) -> Option<PolicyAction> {
+        if self.config.quarantine {
+            self.policy_engine.evaluate(update)
+        } else {
+            self.policy_engine.evaluate_policies(update)
+        }
+    }
+
+    /// Evaluate policies and fan the resulting action out to registered sinks
+    fn evaluate_and_notify(&self, update: &MuscleUpdate) -> Option<PolicyAction> {
+        let action = self.evaluate(update)?;
+        for sink in &self.sinks {
+            sink.notify(&action);
+        }
+        Some(action)
     }
 
     /// Execute a policy action (typically would emit to lattice)
+    ///
+    /// In dry-run mode the action is logged and skipped.
     pub fn execute_policy_action(&self, action: PolicyAction) -> Option<MuscleUpdate> {
+        if self.config.dry_run {
+            log::info!("Dry run: skipping {action:?}");
+            return None;
+        }
         match action {
             PolicyAction::HealVulnerability {
                 muscle_id,
@@ -109,27 +223,60 @@ impl Symbiote {
         }
     }
 
+    /// Execute a policy action and hand any resulting update to `sink`
+    ///
+    /// Returns whether an update was emitted.
+    pub fn emit_via<T: UpdateSink>(&self, action: PolicyAction, sink: &T) -> bool {
+        match self.execute_policy_action(action) {
+            Some(update) => {
+                sink.emit(update);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Generate a healing update for a vulnerable muscle
+    ///
+    /// The registered rebuilder fetches, patches and reseals the muscle; the
+    /// result is committed as `vulnerable_version + 1` against the current root.
     fn generate_healing_update(
         &self,
-        _muscle_id: [u8; 32],
-        _vulnerable_version: u64,
-        _patch: &dyn patches::SecurityPatch,
+        muscle_id: [u8; 32],
+        vulnerable_version: u64,
+        patch: &dyn patches::SecurityPatch,
     ) -> Option<MuscleUpdate> {
-        // In real implementation, this would:
-        // 1. Fetch current muscle source via introspection capability
-        // 2. Apply the security patch
-        // 3. Recompile and seal the patched muscle
-        // 4. Generate lattice update for version + 1
+        let version = vulnerable_version.checked_add(1)?;
+        let blob = self
+            .rebuilder
+            .as_ref()?
+            .rebuild(muscle_id, vulnerable_version, patch)?;
+        Some(generate_update(muscle_id, version, blob, self.current_root))
+    }
+
+    /// Verify if a muscle should be quarantined at monotonic tick `now`
+    pub fn should_quarantine(&self, muscle_id: [u8; 32], version: u64, now: u64) -> bool {
+        self.policy_engine
+            .should_quarantine(muscle_id, version, now)
+    }
 
-        // For now, return None as placeholder
-        // Full implementation requires integration with muscle compiler
-        None
+    /// Quarantine a muscle version for the configured duration from tick `now`
+    pub fn quarantine(
+        &mut self,
+        muscle_id: [u8; 32],
+        version: u64,
+        reason: &'static str,
+        now: u64,
+    ) {
+        let expires_at = now.saturating_add(self.config.quarantine_duration);
+        self.policy_engine
+            .quarantine_muscle(muscle_id, version, reason, expires_at);
     }
 
-    /// Verify if a muscle should be quarantined
-    pub fn should_quarantine(&self, muscle_id: [u8; 32], version: u64) -> bool {
-        self.policy_engine.should_quarantine(muscle_id, version)
+    /// Record that a muscle was healed to `version`, clearing any quarantine
+    /// on an older version
+    pub fn mark_healed(&mut self, muscle_id: [u8; 32], version: u64) {
+        self.policy_engine.clear_healed(muscle_id, version);
     }
 }
 
@@ -142,6 +289,10 @@ pub struct SymbioteConfig {
     pub quarantine: bool,
     /// Maximum healing attempts per muscle
     pub max_healing_attempts: u32,
+    /// How long a quarantine lasts, in monotonic ticks
+    pub quarantine_duration: u64,
+    /// Log policy actions instead of executing them
+    pub dry_run: bool,
 }
 
 impl Default for SymbioteConfig {
@@ -150,6 +301,8 @@ impl Default for SymbioteConfig {
             auto_heal: true,
             quarantine: true,
             max_healing_attempts: 3,
+            quarantine_duration: 3600,
+            dry_run: false,
         }
     }
 }
@@ -1,11 +1,21 @@
 //! Policy engine for security decision making
 
-use alloc::collections::BTreeMap;
+use alloc::borrow::Cow;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::vec::Vec;
 use ea_lattice_ledger::MuscleUpdate;
 
+use crate::bloom::BloomFilter;
+
+/// Known-vulnerable blob hashes the default bloom filter is sized for
+const DEFAULT_VULNERABLE_CAPACITY: usize = 1024;
+
+/// Quarantine reason for updates whose blob is a known-vulnerable build
+const KNOWN_VULNERABLE_REASON: &str = "Known vulnerable blob";
+
 /// Security policy action
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PolicyAction {
     /// Heal a specific vulnerability
     HealVulnerability {
@@ -21,17 +31,18 @@ pub enum PolicyAction {
         /// Muscle identifier
         muscle_id: [u8; 32],
         /// Reason for quarantine
-        reason: &'static str,
+        reason: Cow<'static, str>,
     },
 }
 
 /// Security policy definition
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SecurityPolicy {
     /// Policy identifier
     pub id: [u8; 32],
     /// Policy name
-    pub name: &'static str,
+    pub name: Cow<'static, str>,
     /// Muscle pattern to match
     pub muscle_pattern: Option<[u8; 32]>,
     /// Version range to match
@@ -42,21 +53,33 @@ pub struct SecurityPolicy {
     pub enabled: bool,
 }
 
+/// Active quarantine of a muscle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuarantineEntry {
+    /// Reason for quarantine
+    pub reason: &'static str,
+    /// Version that was quarantined; only a heal past it lifts the quarantine
+    pub version: u64,
+    /// Monotonic tick at which the quarantine lapses
+    pub expires_at: u64,
+}
+
 /// Policy engine for evaluating security policies
 #[derive(Debug, Clone)]
 pub struct PolicyEngine {
     /// Registered security policies
     policies: Vec<SecurityPolicy>,
-    /// Quarantine list (muscle_id -> reason)
-    quarantine_list: BTreeMap<[u8; 32], &'static str>,
+    /// Quarantine list (muscle_id -> entry)
+    quarantine_list: BTreeMap<[u8; 32], QuarantineEntry>,
+    /// BLAKE3 hashes of known-vulnerable blobs
+    vulnerable_blobs: BTreeSet<[u8; 32]>,
+    /// Fast negative check in front of `vulnerable_blobs`
+    vulnerable_filter: BloomFilter,
 }
 
 impl Default for PolicyEngine {
     fn default() -> Self {
-        let mut engine = Self {
-            policies: Vec::new(),
-            quarantine_list: BTreeMap::new(),
-        };
+        let mut engine = Self::from_parts(Vec::new(), BTreeSet::new());
 
         // Register default policies
         engine.register_default_policies();
@@ -65,8 +88,39 @@ impl Default for PolicyEngine {
 }
 
 impl PolicyEngine {
+    /// Build an engine with no quarantines from a policy set and vulnerability set
+    fn from_parts(policies: Vec<SecurityPolicy>, vulnerable_blobs: BTreeSet<[u8; 32]>) -> Self {
+        let mut vulnerable_filter =
+            BloomFilter::with_capacity(DEFAULT_VULNERABLE_CAPACITY.max(vulnerable_blobs.len()));
+        for blob_hash in &vulnerable_blobs {
+            vulnerable_filter.insert(blob_hash);
+        }
+        Self {
+            policies,
+            quarantine_list: BTreeMap::new(),
+            vulnerable_blobs,
+            vulnerable_filter,
+        }
+    }
+
     /// Evaluate an update against security policies
+    ///
+    /// Updates carrying a known-vulnerable blob are quarantined before any
+    /// policy is consulted.
     pub fn evaluate(&self, update: &MuscleUpdate) -> Option<PolicyAction> {
+        let blob_hash: [u8; 32] = blake3::hash(&update.blob).into();
+        if self.is_known_vulnerable(&blob_hash) {
+            return Some(PolicyAction::QuarantineMuscle {
+                muscle_id: update.muscle_id,
+                reason: Cow::Borrowed(KNOWN_VULNERABLE_REASON),
+            });
+        }
+        self.evaluate_policies(update)
+    }
+
+    /// Evaluate an update against the registered policies only, without
+    /// hashing its blob for the known-vulnerable check
+    pub fn evaluate_policies(&self, update: &MuscleUpdate) -> Option<PolicyAction> {
         for policy in &self.policies {
             if !policy.enabled {
                 continue;
@@ -80,9 +134,14 @@ impl PolicyEngine {
         None
     }
 
-    /// Check if a muscle should be quarantined
-    pub fn should_quarantine(&self, muscle_id: [u8; 32], _version: u64) -> bool {
-        self.quarantine_list.contains_key(&muscle_id)
+    /// Check if a muscle is under an unexpired quarantine at tick `now`
+    ///
+    /// The quarantine covers every version: a muscle cannot escape it by
+    /// bumping its version, only by expiry or [`Self::clear_healed`].
+    pub fn should_quarantine(&self, muscle_id: [u8; 32], _version: u64, now: u64) -> bool {
+        self.quarantine_list
+            .get(&muscle_id)
+            .is_some_and(|entry| now < entry.expires_at)
     }
 
     /// Register a new security policy
@@ -90,9 +149,58 @@ impl PolicyEngine {
         self.policies.push(policy);
     }
 
-    /// Add muscle to quarantine list
-    pub fn quarantine_muscle(&mut self, muscle_id: [u8; 32], reason: &'static str) {
-        self.quarantine_list.insert(muscle_id, reason);
+    /// Record the BLAKE3 hash of a blob known to be vulnerable
+    pub fn register_vulnerable_blob(&mut self, blob_hash: [u8; 32]) {
+        self.vulnerable_filter.insert(&blob_hash);
+        self.vulnerable_blobs.insert(blob_hash);
+    }
+
+    /// Check a blob hash against the vulnerability set
+    ///
+    /// The bloom filter rejects almost every clean hash; only its hits pay
+    /// for the exact lookup, so false positives never trigger a heal.
+    pub fn is_known_vulnerable(&self, blob_hash: &[u8; 32]) -> bool {
+        self.vulnerable_filter.may_contain(blob_hash) && self.vulnerable_blobs.contains(blob_hash)
+    }
+
+    /// Add muscle to quarantine list until tick `expires_at`
+    pub fn quarantine_muscle(
+        &mut self,
+        muscle_id: [u8; 32],
+        version: u64,
+        reason: &'static str,
+        expires_at: u64,
+    ) {
+        self.quarantine_list.insert(
+            muscle_id,
+            QuarantineEntry {
+                reason,
+                version,
+                expires_at,
+            },
+        );
+    }
+
+    /// Lift a quarantine once a higher version of the muscle has been healed
+    pub fn clear_healed(&mut self, muscle_id: [u8; 32], healed_version: u64) {
+        if self
+            .quarantine_list
+            .get(&muscle_id)
+            .is_some_and(|entry| entry.version < healed_version)
+        {
+            self.quarantine_list.remove(&muscle_id);
+        }
+    }
+
+    /// Drop quarantine entries that have lapsed by tick `now`
+    pub fn release_expired(&mut self, now: u64) {
+        self.quarantine_list
+            .retain(|_, entry| now < entry.expires_at);
+    }
+
+    /// Look up the quarantine entry for a muscle, expired or not
+    pub fn quarantine_entry(&self, muscle_id: [u8; 32]) -> Option<&QuarantineEntry> {
+        self.quarantine_list.get(&muscle_id)
     }
 
     /// Get number of active policies
@@ -124,7 +232,7 @@ impl PolicyEngine {
         // Example policy: Heal known vulnerability in specific muscle version
         let heal_policy = SecurityPolicy {
             id: blake3::hash(b"heal_cve_2026_01").into(),
-            name: "Heal CVE-2026-01 in muscle 0xEA...",
+            name: Cow::Borrowed("Heal CVE-2026-01 in muscle 0xEA..."),
             muscle_pattern: Some([0xEA; 32]), // Example muscle ID
             version_range: Some((42, 42)),    // Specific vulnerable version
             action: PolicyAction::HealVulnerability {
@@ -138,12 +246,12 @@ impl PolicyEngine {
         // Example policy: Quarantine known malicious patterns
         let quarantine_policy = SecurityPolicy {
             id: blake3::hash(b"quarantine_parasite").into(),
-            name: "Quarantine parasite muscles",
+            name: Cow::Borrowed("Quarantine parasite muscles"),
             muscle_pattern: None, // Match any muscle with "parasite" in name
             version_range: None,
             action: PolicyAction::QuarantineMuscle {
                 muscle_id: [0; 32], // Will be filled at match time
-                reason: "Contains parasite pattern",
+                reason: Cow::Borrowed("Contains parasite pattern"),
             },
             enabled: true,
         };
@@ -153,6 +261,67 @@ impl PolicyEngine {
     }
 }
 
+/// Wire form of a [`PolicyEngine`]: the policy set and known-vulnerable
+/// blobs. Quarantines are node-local runtime state and are not distributed.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PolicyBundle<'a> {
+    policies: Cow<'a, [SecurityPolicy]>,
+    vulnerable_blobs: Cow<'a, BTreeSet<[u8; 32]>>,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PolicyEngine {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        PolicyBundle {
+            policies: Cow::Borrowed(&self.policies),
+            vulnerable_blobs: Cow::Borrowed(&self.vulnerable_blobs),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PolicyEngine {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let bundle = PolicyBundle::deserialize(deserializer)?;
+        Ok(Self::from_parts(
+            bundle.policies.into_owned(),
+            bundle.vulnerable_blobs.into_owned(),
+        ))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl PolicyEngine {
+    /// Encode the policy bundle for distribution, e.g. as a `MuscleUpdate` blob
+    ///
+    /// The encoding is deterministic, so equal bundles produce equal bytes.
+    ///
+    /// # Errors
+    /// Returns the encoder error if the bundle cannot be serialized.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, postcard::Error> {
+        postcard::to_allocvec(self)
+    }
+
+    /// Decode a bundle produced by [`PolicyEngine::to_bytes`]
+    ///
+    /// # Errors
+    /// Returns the decoder error if `bytes` is not a valid bundle.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, postcard::Error> {
+        postcard::from_bytes(bytes)
+    }
+
+    /// BLAKE3 hash of [`PolicyEngine::to_bytes`], for checking that a node
+    /// runs the expected bundle
+    ///
+    /// # Errors
+    /// Returns the encoder error if the bundle cannot be serialized.
+    pub fn bundle_hash(&self) -> Result<[u8; 32], postcard::Error> {
+        Ok(blake3::hash(&self.to_bytes()?).into())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -186,9 +355,126 @@ mod tests {
     #[test]
     fn test_quarantine() {
         let mut engine = PolicyEngine::default();
-        engine.quarantine_muscle([0x42; 32], "Test quarantine");
+        engine.quarantine_muscle([0x42; 32], 1, "Test quarantine", 100);
+
+        assert!(engine.should_quarantine([0x42; 32], 1, 0));
+        assert!(!engine.should_quarantine([0x43; 32], 1, 0));
+    }
+
+    #[test]
+    fn test_quarantine_release() {
+        let mut engine = PolicyEngine::default();
+        engine.quarantine_muscle([0x42; 32], 3, "Test quarantine", 100);
+
+        assert!(engine.should_quarantine([0x42; 32], 3, 99));
+        assert!(!engine.should_quarantine([0x42; 32], 3, 100));
+        // A higher version is still held until it is healed
+        assert!(engine.should_quarantine([0x42; 32], 4, 0));
+        engine.clear_healed([0x42; 32], 4);
+        assert!(!engine.should_quarantine([0x42; 32], 4, 0));
+
+        engine.release_expired(100);
+        assert!(engine.quarantine_entry([0x42; 32]).is_none());
+    }
+
+    fn update_with_blob(fill: u8) -> MuscleUpdate {
+        MuscleUpdate {
+            muscle_id: [0x11; 32],
+            version: 1,
+            blob: [fill; 8256],
+            proof: [0; 48],
+        }
+    }
+
+    #[test]
+    fn test_known_vulnerable_blob_is_quarantined() {
+        let mut engine = PolicyEngine::default();
+        let update = update_with_blob(7);
+        engine.register_vulnerable_blob(blake3::hash(&update.blob).into());
+
+        assert_eq!(
+            engine.evaluate(&update),
+            Some(PolicyAction::QuarantineMuscle {
+                muscle_id: [0x11; 32],
+                reason: Cow::Borrowed(KNOWN_VULNERABLE_REASON),
+            })
+        );
+    }
+
+    #[test]
+    fn test_clean_update_skips_exact_lookup() {
+        let mut engine = PolicyEngine::default();
+        let update = update_with_blob(9);
+        let blob_hash: [u8; 32] = blake3::hash(&update.blob).into();
+        // Present in the exact set but not the filter: only reachable if the
+        // exact set is consulted without a bloom hit.
+        engine.vulnerable_blobs.insert(blob_hash);
+
+        assert!(!engine.vulnerable_filter.may_contain(&blob_hash));
+        assert!(!engine.is_known_vulnerable(&blob_hash));
+        assert!(!matches!(
+            engine.evaluate(&update),
+            Some(PolicyAction::QuarantineMuscle { reason, .. }) if reason == KNOWN_VULNERABLE_REASON
+        ));
+    }
+
+    #[cfg(feature = "serde")]
+    fn custom_engine() -> PolicyEngine {
+        let mut engine = PolicyEngine::default();
+        engine.register_policy(SecurityPolicy {
+            id: [0x33; 32],
+            name: Cow::Owned("Quarantine v1-3 of muscle 0x22".into()),
+            muscle_pattern: Some([0x22; 32]),
+            version_range: Some((1, 3)),
+            action: PolicyAction::QuarantineMuscle {
+                muscle_id: [0x22; 32],
+                reason: Cow::Borrowed("Deprecated release"),
+            },
+            enabled: false,
+        });
+        engine.register_vulnerable_blob(blake3::hash(&update_with_blob(5).blob).into());
+        engine.quarantine_muscle([0x42; 32], 1, "Local only", 100);
+        engine
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_policy_bundle_round_trip() {
+        let engine = custom_engine();
+        let decoded = PolicyEngine::from_bytes(&engine.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(decoded.policy_count(), engine.policy_count());
+        for (ours, theirs) in engine.policies.iter().zip(&decoded.policies) {
+            assert_eq!(ours.id, theirs.id);
+            assert_eq!(ours.name, theirs.name);
+            assert_eq!(ours.muscle_pattern, theirs.muscle_pattern);
+            assert_eq!(ours.version_range, theirs.version_range);
+            assert_eq!(ours.action, theirs.action);
+            assert_eq!(ours.enabled, theirs.enabled);
+        }
+        // The bloom filter is rebuilt, so vulnerable blobs still match
+        assert!(decoded.evaluate(&update_with_blob(5)).is_some());
+        // Quarantines stay node-local
+        assert!(decoded.quarantine_entry([0x42; 32]).is_none());
+        assert!(PolicyEngine::from_bytes(&[0xFF; 3]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_bundle_hash_is_stable_across_serialization() {
+        let engine = custom_engine();
+        let bytes = engine.to_bytes().unwrap();
+        let hash = engine.bundle_hash().unwrap();
+        assert_eq!(hash, <[u8; 32]>::from(blake3::hash(&bytes)));
+
+        let decoded = PolicyEngine::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), bytes);
+        assert_eq!(decoded.bundle_hash().unwrap(), hash);
 
-        assert!(engine.should_quarantine([0x42; 32], 1));
-        assert!(!engine.should_quarantine([0x43; 32], 1));
+        // Node-local quarantines don't affect the bundle
+        let mut quarantined = decoded.clone();
+        quarantined.quarantine_muscle([0x99; 32], 2, "Local only", 10);
+        assert_eq!(quarantined.bundle_hash().unwrap(), hash);
+        assert_ne!(PolicyEngine::default().bundle_hash().unwrap(), hash);
     }
 }
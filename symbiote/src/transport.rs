@@ -0,0 +1,135 @@
+//! Lattice emission over the ledger transport.
+//!
+//! [`TransportSink`] wraps each healing update in a signed [`Envelope`] and
+//! appends it through a [`LogInspect`] transport. Appends run on a single
+//! background task so envelopes keep their hash chain in emission order.
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ea_lattice_ledger::MuscleUpdate;
+use ed25519_dalek::SigningKey;
+use ledger_core::{signing, AppendError};
+use ledger_spec::{
+    envelope_hash, hash_body_for, Envelope, EnvelopeBody, EnvelopeHeader, Hash, ValidationError,
+};
+use ledger_transport::{LogInspect, TransportError};
+use tokio::sync::mpsc;
+
+use crate::UpdateSink;
+
+/// Payload type tag carried by healing update envelopes
+pub const HEALING_UPDATE_PAYLOAD_TYPE: &str = "ea.symbiote.healing_update.v1";
+
+/// Envelope schema version written by [`TransportSink`]
+const SCHEMA_VERSION: u16 = 1;
+
+/// [`UpdateSink`] that appends healing updates onto a ledger channel
+///
+/// The sink starts from the channel's current head and chains each envelope
+/// onto the previous one it appended. If another writer has moved the head
+/// in between, the append fails with `ChainMismatch` and the sink re-signs
+/// the update onto the new head.
+#[derive(Debug, Clone)]
+pub struct TransportSink {
+    updates: mpsc::UnboundedSender<MuscleUpdate>,
+}
+
+impl TransportSink {
+    /// Start appending updates to `channel`, signed with `signer`
+    ///
+    /// # Panics
+    /// Panics if called outside a Tokio runtime.
+    pub fn spawn(
+        transport: Arc<dyn LogInspect>,
+        channel: impl Into<String>,
+        signer: SigningKey,
+    ) -> Self {
+        let channel = channel.into();
+        let (updates, mut rx) = mpsc::unbounded_channel::<MuscleUpdate>();
+        tokio::spawn(async move {
+            let mut prev = transport.channel_head(&channel);
+            while let Some(update) = rx.recv().await {
+                let mut envelope = healing_envelope(&channel, prev, &update, &signer);
+                let mut result = transport.append(envelope.clone()).await;
+                if result.as_ref().is_err_and(is_chain_mismatch) {
+                    prev = transport.channel_head(&channel);
+                    envelope = healing_envelope(&channel, prev, &update, &signer);
+                    result = transport.append(envelope.clone()).await;
+                }
+                match result {
+                    Ok(()) => prev = Some(envelope_hash(&envelope)),
+                    Err(err) => log::error!(
+                        "dropping healing update for muscle {} v{}: {err}",
+                        hex::encode(update.muscle_id),
+                        update.version
+                    ),
+                }
+            }
+        });
+        Self { updates }
+    }
+}
+
+/// Whether `err` is the log refusing an envelope whose `prev` is not the
+/// channel head.
+fn is_chain_mismatch(err: &TransportError) -> bool {
+    matches!(
+        err,
+        TransportError::Other(inner) if matches!(
+            inner.downcast_ref::<AppendError>(),
+            Some(AppendError::Validation(ValidationError::ChainMismatch))
+        )
+    )
+}
+
+impl UpdateSink for TransportSink {
+    fn emit(&self, update: MuscleUpdate) {
+        if self.updates.send(update).is_err() {
+            log::error!("transport sink stopped; healing update not emitted");
+        }
+    }
+}
+
+/// Build the signed envelope carrying `update` on `channel`
+#[must_use]
+pub fn healing_envelope(
+    channel: &str,
+    prev: Option<Hash>,
+    update: &MuscleUpdate,
+    signer: &SigningKey,
+) -> Envelope {
+    let body = EnvelopeBody {
+        payload: serde_json::json!({
+            "muscle_id": hex::encode(update.muscle_id),
+            "version": update.version,
+            "blob": hex::encode(update.blob),
+            "proof": hex::encode(update.proof),
+        }),
+        payload_type: Some(HEALING_UPDATE_PAYLOAD_TYPE.into()),
+    };
+    let mut envelope = Envelope {
+        header: EnvelopeHeader {
+            channel: channel.into(),
+            version: SCHEMA_VERSION,
+            prev,
+            body_hash: hash_body_for(&body, SCHEMA_VERSION),
+            timestamp: now_millis(),
+        },
+        body,
+        signatures: Vec::new(),
+        attestations: Vec::new(),
+    };
+    signing::sign_envelope(&mut envelope, signer);
+    envelope
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX)
+        })
+}
@@ -46,7 +46,40 @@ fn test_quarantine_functionality() {
     let symbiote = Symbiote::new(root);
 
     // Test quarantine check
-    assert!(!symbiote.should_quarantine([0x42; 32], 1));
+    assert!(!symbiote.should_quarantine([0x42; 32], 1, 0));
+}
+
+#[test]
+fn test_quarantine_expires_after_duration() {
+    use ea_symbiote::SymbioteConfig;
+
+    let config = SymbioteConfig {
+        quarantine_duration: 10,
+        ..SymbioteConfig::default()
+    };
+    let mut symbiote = Symbiote::with_config([0u8; 32], config);
+    symbiote.quarantine([0x42; 32], 1, "Test quarantine", 5);
+
+    assert!(symbiote.should_quarantine([0x42; 32], 1, 5));
+    assert!(symbiote.should_quarantine([0x42; 32], 1, 14));
+    assert!(!symbiote.should_quarantine([0x42; 32], 1, 15));
+}
+
+#[test]
+fn test_healing_clears_quarantine_early() {
+    let mut symbiote = Symbiote::new([0u8; 32]);
+    symbiote.quarantine([0x42; 32], 1, "Test quarantine", 0);
+
+    // Healing to the same version does not lift it
+    symbiote.mark_healed([0x42; 32], 1);
+    assert!(symbiote.should_quarantine([0x42; 32], 1, 1));
+
+    symbiote.mark_healed([0x42; 32], 2);
+    assert!(!symbiote.should_quarantine([0x42; 32], 1, 1));
+    assert!(symbiote
+        .policy_engine
+        .quarantine_entry([0x42; 32])
+        .is_none());
 }
 
 #[test]
@@ -74,6 +107,225 @@ fn test_symbiote_config() {
     assert!(config.auto_heal);
     assert!(config.quarantine);
     assert_eq!(config.max_healing_attempts, 3);
+    assert_eq!(config.quarantine_duration, 3600);
+    assert!(!config.dry_run);
+}
+
+#[test]
+fn test_vulnerable_blob_check_follows_quarantine_config() {
+    use ea_symbiote::SymbioteConfig;
+
+    let update = MuscleUpdate {
+        muscle_id: [0xEA; 32],
+        version: 42,
+        blob: [7u8; 8256],
+        proof: [0u8; 48],
+    };
+    let blob_hash: [u8; 32] = blake3::hash(&update.blob).into();
+
+    let mut symbiote = Symbiote::new([0u8; 32]);
+    symbiote.policy_engine.register_vulnerable_blob(blob_hash);
+    assert!(matches!(
+        symbiote.process_update_unchecked(&update),
+        Some(PolicyAction::QuarantineMuscle { .. })
+    ));
+
+    let config = SymbioteConfig {
+        quarantine: false,
+        ..SymbioteConfig::default()
+    };
+    let mut symbiote = Symbiote::with_config([0u8; 32], config);
+    symbiote.policy_engine.register_vulnerable_blob(blob_hash);
+    assert!(matches!(
+        symbiote.process_update_unchecked(&update),
+        Some(PolicyAction::HealVulnerability { .. })
+    ));
+}
+
+#[test]
+fn test_dry_run_reports_actions() {
+    use ea_lattice_ledger::generate_update;
+
+    let root = [0u8; 32];
+    let symbiote = Symbiote::new(root);
+    let vulnerable = generate_update([0xEA; 32], 42, [0u8; 8256], root);
+    let other = generate_update([0x01; 32], 1, [0u8; 8256], root);
+    let mut forged = vulnerable;
+    forged.proof[0] ^= 0xFF;
+
+    let report = symbiote.dry_run(&[vulnerable, other, forged]);
+    assert_eq!(report.len(), 3);
+    assert_eq!(report[0].0, vulnerable);
+    assert!(matches!(
+        report[0].1,
+        Some(PolicyAction::HealVulnerability {
+            vulnerable_version: 42,
+            ..
+        })
+    ));
+    assert!(matches!(
+        report[1].1,
+        Some(PolicyAction::QuarantineMuscle { .. })
+    ));
+    assert_eq!(report[2].1, None);
+}
+
+#[test]
+fn test_dry_run_config_skips_healing() {
+    use ea_symbiote::SymbioteConfig;
+
+    let config = SymbioteConfig {
+        dry_run: true,
+        ..SymbioteConfig::default()
+    };
+    let symbiote = Symbiote::with_config([0u8; 32], config);
+    let update = MuscleUpdate {
+        muscle_id: [0xEA; 32],
+        version: 42,
+        blob: [0u8; 8256],
+        proof: [0u8; 48],
+    };
+    let action = symbiote
+        .process_update_unchecked(&update)
+        .expect("default policy heals this version");
+    assert!(matches!(action, PolicyAction::HealVulnerability { .. }));
+    assert!(symbiote.execute_policy_action(action).is_none());
+}
+
+#[test]
+fn test_action_sink_receives_actions_in_order() {
+    use ea_symbiote::ActionSink;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct RecordingSink(Rc<RefCell<Vec<PolicyAction>>>);
+
+    impl ActionSink for RecordingSink {
+        fn notify(&self, action: &PolicyAction) {
+            self.0.borrow_mut().push(action.clone());
+        }
+    }
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut symbiote = Symbiote::new([0u8; 32]);
+    symbiote.on_action(RecordingSink(Rc::clone(&seen)));
+
+    let vulnerable = MuscleUpdate {
+        muscle_id: [0xEA; 32],
+        version: 42,
+        blob: [0u8; 8256],
+        proof: [0u8; 48],
+    };
+    let other = MuscleUpdate {
+        muscle_id: [0x01; 32],
+        ..vulnerable
+    };
+    let heal = symbiote.process_update_unchecked(&vulnerable);
+    let quarantine = symbiote.process_update_unchecked(&other);
+
+    let seen = seen.borrow();
+    assert_eq!(seen.len(), 2);
+    assert_eq!(Some(&seen[0]), heal.as_ref());
+    assert_eq!(Some(&seen[1]), quarantine.as_ref());
+    assert!(matches!(seen[0], PolicyAction::HealVulnerability { .. }));
+    assert!(matches!(seen[1], PolicyAction::QuarantineMuscle { .. }));
+}
+
+#[test]
+fn test_healing_action_emits_next_version() {
+    use ea_lattice_ledger::{verify_update, SealedBlob};
+    use ea_symbiote::patches::SecurityPatch;
+    use ea_symbiote::{MuscleRebuilder, UpdateSink};
+    use std::cell::RefCell;
+
+    struct PatchedBlob;
+
+    impl MuscleRebuilder for PatchedBlob {
+        fn rebuild(
+            &self,
+            _muscle_id: [u8; 32],
+            _vulnerable_version: u64,
+            _patch: &dyn SecurityPatch,
+        ) -> Option<SealedBlob> {
+            Some([0x5A; 8256])
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingSink(RefCell<Vec<MuscleUpdate>>);
+
+    impl UpdateSink for RecordingSink {
+        fn emit(&self, update: MuscleUpdate) {
+            self.0.borrow_mut().push(update);
+        }
+    }
+
+    let root = [0u8; 32];
+    let mut symbiote = Symbiote::new(root);
+    symbiote.set_rebuilder(PatchedBlob);
+
+    let vulnerable = MuscleUpdate {
+        muscle_id: [0xEA; 32],
+        version: 42,
+        blob: [0u8; 8256],
+        proof: [0u8; 48],
+    };
+    let action = symbiote
+        .process_update_unchecked(&vulnerable)
+        .expect("default policy heals this version");
+
+    let sink = RecordingSink::default();
+    assert!(symbiote.emit_via(action, &sink));
+
+    let emitted = sink.0.borrow();
+    assert_eq!(emitted.len(), 1);
+    assert_eq!(emitted[0].muscle_id, vulnerable.muscle_id);
+    assert_eq!(emitted[0].version, vulnerable.version + 1);
+    assert!(verify_update(root, &emitted[0]));
+}
+
+#[cfg(feature = "transport")]
+#[tokio::test]
+async fn test_transport_sink_extends_an_existing_channel() {
+    use ea_lattice_ledger::generate_update;
+    use ea_symbiote::transport::{healing_envelope, TransportSink};
+    use ea_symbiote::UpdateSink;
+    use ed25519_dalek::SigningKey;
+    use ledger_spec::{envelope_hash, Envelope};
+    use ledger_transport::{InVmQueue, LogInspect, Transport};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    async fn appended(queue: &InVmQueue, index: usize) -> Envelope {
+        for _ in 0..200 {
+            if queue.log_len() > index {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        queue.read(index, 1).await.unwrap().remove(0)
+    }
+
+    let channel = "symbiote.healing";
+    let signer = SigningKey::from_bytes(&[7; 32]);
+    let update = generate_update([0xEA; 32], 1, [0u8; 8256], [0u8; 32]);
+    let queue = Arc::new(InVmQueue::new().unwrap());
+    let existing = healing_envelope(channel, None, &update, &signer);
+    queue.append(existing.clone()).await.unwrap();
+
+    // The sink picks up the head already on the channel.
+    let sink = TransportSink::spawn(queue.clone(), channel, signer.clone());
+    sink.emit(update);
+    let first = appended(&queue, 1).await;
+    assert_eq!(first.header.prev, Some(envelope_hash(&existing)));
+
+    // Another writer moves the head; the sink re-signs onto it.
+    let foreign = healing_envelope(channel, Some(envelope_hash(&first)), &update, &signer);
+    queue.append(foreign.clone()).await.unwrap();
+    sink.emit(update);
+    let second = appended(&queue, 3).await;
+    assert_eq!(second.header.prev, Some(envelope_hash(&foreign)));
+    assert_eq!(queue.channel_head(channel), Some(envelope_hash(&second)));
 }
 
 // Property-based tests